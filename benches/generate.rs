@@ -0,0 +1,58 @@
+//! `cargo bench --bench generate`: time `StatusLineGenerator::generate` on
+//! fixed segment data, so no network or cache is involved.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use mycode::config::{Config, SegmentConfig};
+use mycode::core::segments::SegmentData;
+use mycode::core::StatusLineGenerator;
+use mycode::ui::themes::ThemePresets;
+use std::collections::HashMap;
+
+/// Every segment of `config`, enabled and filled with sample text
+fn sample_segments(config: &Config) -> Vec<(SegmentConfig, SegmentData)> {
+    config
+        .segments
+        .iter()
+        .map(|segment| {
+            let mut segment = segment.clone();
+            segment.enabled = true;
+            let mut metadata = HashMap::new();
+            metadata.insert("percentage".to_string(), "42".to_string());
+            let data = SegmentData {
+                primary: "$10.38 / $30".into(),
+                secondary: "专业版 - 周期第14/30天".into(),
+                metadata,
+            };
+            (segment, data)
+        })
+        .collect()
+}
+
+fn bench_generate(c: &mut Criterion) {
+    for theme in ["default", "powerline-dark"] {
+        let config = ThemePresets::get_theme(theme);
+        let segments = sample_segments(&config);
+
+        c.bench_function(&format!("generate/{}", theme), |b| {
+            let generator = StatusLineGenerator::new(config.clone());
+            b.iter_batched(
+                || segments.clone(),
+                |segments| black_box(generator.generate(segments)),
+                BatchSize::SmallInput,
+            )
+        });
+
+        // Narrow terminals go through the trimming path
+        c.bench_function(&format!("generate/{}/width-80", theme), |b| {
+            let generator = StatusLineGenerator::new(config.clone()).with_available_width(Some(80));
+            b.iter_batched(
+                || segments.clone(),
+                |segments| black_box(generator.generate(segments)),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+}
+
+criterion_group!(benches, bench_generate);
+criterion_main!(benches);
@@ -0,0 +1,32 @@
+/*
+ * C interface of the uucode statusline engine.
+ *
+ * Build: cargo rustc --lib --profile release-ffi --features ffi --crate-type cdylib
+ * (release-ffi keeps panics unwinding so they come back as {"error": ...})
+ * All strings are NUL-terminated UTF-8.
+ */
+
+#ifndef UUCODE_H
+#define UUCODE_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * Render Claude Code's statusline JSON payload.
+ *
+ * config_path may be NULL to use ~/.claude/uucode/config.toml.
+ * Returns {"ansi": ..., "text": ..., "spans": [...]} or {"error": ...} as
+ * JSON, or NULL if input is NULL. Free the result with uucode_free_string.
+ */
+char *uucode_render_json(const char *input, const char *config_path);
+
+/* Free a string returned by uucode_render_json. NULL is ignored. */
+void uucode_free_string(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* UUCODE_H */
@@ -0,0 +1,216 @@
+//! 按接口共享的熔断器（`cache/breakers.json`）
+//!
+//! 同一接口连续失败 [`FAILURE_THRESHOLD`] 次后熔断（open），冷却期内所有段都不再请求，
+//! 直接回退到缓存；冷却结束后放行一次探测请求（half-open），成功则恢复（closed），
+//! 失败则加倍冷却时间重新熔断。状态跨渲染持久化，取代各段进程内的失败计数器。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+/// 连续失败多少次后熔断
+pub const FAILURE_THRESHOLD: u32 = 3;
+/// 首次熔断的冷却时间
+const BASE_COOLDOWN_SECS: i64 = 60;
+/// 冷却时间上限
+const MAX_COOLDOWN_SECS: i64 = 1800;
+/// 探测请求的最长等待，超时后（如进程被中断）允许再次探测
+const PROBE_TIMEOUT_SECS: i64 = 30;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum State {
+    #[default]
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl State {
+    pub fn label(&self) -> &'static str {
+        match self {
+            State::Closed => "closed",
+            State::Open => "open",
+            State::HalfOpen => "half-open",
+        }
+    }
+}
+
+/// 单个接口的熔断状态
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Breaker {
+    pub state: State,
+    /// 连续失败次数
+    pub failures: u32,
+    /// open 时为下次探测时间，half-open 时为探测超时时间（Unix 秒）
+    #[serde(default)]
+    pub retry_at: i64,
+    #[serde(default)]
+    pub cooldown_secs: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+impl Breaker {
+    /// 是否放行一次请求；冷却结束时转为 half-open 并放行探测
+    fn allow(&mut self, now: i64) -> bool {
+        match self.state {
+            State::Closed => true,
+            State::Open | State::HalfOpen if now >= self.retry_at => {
+                self.state = State::HalfOpen;
+                self.retry_at = now + PROBE_TIMEOUT_SECS;
+                true
+            }
+            State::Open | State::HalfOpen => false,
+        }
+    }
+
+    fn succeed(&mut self) {
+        *self = Breaker::default();
+    }
+
+    fn fail(&mut self, now: i64, error: String) {
+        self.failures += 1;
+        self.last_error = Some(error);
+        let cooldown = match self.state {
+            State::HalfOpen => {
+                (self.cooldown_secs * 2).clamp(BASE_COOLDOWN_SECS, MAX_COOLDOWN_SECS)
+            }
+            _ if self.failures >= FAILURE_THRESHOLD => BASE_COOLDOWN_SECS,
+            _ => return,
+        };
+        self.state = State::Open;
+        self.cooldown_secs = cooldown;
+        self.retry_at = now + cooldown;
+    }
+}
+
+/// 熔断期间跳过的请求
+#[derive(Debug)]
+pub struct CircuitOpen {
+    pub endpoint: String,
+    pub retry_in: i64,
+}
+
+impl fmt::Display for CircuitOpen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} 已熔断，{} 秒后重新探测",
+            self.endpoint,
+            self.retry_in.max(0)
+        )
+    }
+}
+
+impl std::error::Error for CircuitOpen {}
+
+impl From<CircuitOpen> for String {
+    fn from(open: CircuitOpen) -> Self {
+        open.to_string()
+    }
+}
+
+fn breakers_path() -> Option<PathBuf> {
+    crate::utils::paths::cache_dir().map(|dir| dir.join("breakers.json"))
+}
+
+/// 所有接口的熔断状态，键为 [`endpoint`]
+pub fn load() -> HashMap<String, Breaker> {
+    breakers_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// 重新读取后修改单个接口的状态，避免覆盖其他段同时写入的结果
+fn update(
+    endpoint: &str,
+    change: impl FnOnce(&mut Breaker),
+) -> Result<(), Box<dyn std::error::Error>> {
+    let path = breakers_path().ok_or("Could not find home directory")?;
+    let mut breakers = load();
+    let breaker = breakers.entry(endpoint.to_string()).or_default();
+    change(breaker);
+    if breaker.state == State::Closed && breaker.failures == 0 {
+        breakers.remove(endpoint);
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    crate::utils::atomic_write(path, serde_json::to_string_pretty(&breakers)?)?;
+    Ok(())
+}
+
+/// 接口标识：去掉协议和查询参数的 URL
+///
+/// ```
+/// use mycode::api::breaker::endpoint;
+///
+/// assert_eq!(
+///     endpoint("https://cubence.com/api/v1/user/subscription?x=1"),
+///     "cubence.com/api/v1/user/subscription"
+/// );
+/// ```
+pub fn endpoint(url: &str) -> String {
+    let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let url = url.split(['?', '#']).next().unwrap_or(url);
+    url.trim_end_matches('/').to_string()
+}
+
+/// 经过 `url` 对应接口的熔断器发起请求：熔断期间不调用 `request`，直接返回 [`CircuitOpen`]
+pub fn call<T, E>(url: &str, request: impl FnOnce() -> Result<T, E>) -> Result<T, E>
+where
+    E: From<CircuitOpen> + fmt::Display,
+{
+    // 确定性渲染不能依赖之前的请求结果
+    if crate::utils::clock::is_deterministic() {
+        return request();
+    }
+
+    let endpoint = endpoint(url);
+    let now = crate::utils::clock::unix_now();
+    let breaker = load().remove(&endpoint).unwrap_or_default();
+    let mut probe = breaker.clone();
+    if !probe.allow(now) {
+        return Err(CircuitOpen {
+            retry_in: breaker.retry_at - now,
+            endpoint,
+        }
+        .into());
+    }
+
+    let mut saved = Ok(());
+    if probe.state == State::HalfOpen {
+        crate::debug_log!("circuit breaker probing {}", endpoint);
+        saved = update(&endpoint, |b| *b = probe);
+    }
+
+    let result = request();
+    let now = crate::utils::clock::unix_now();
+    match &result {
+        Ok(_) if breaker.failures > 0 || breaker.state != State::Closed => {
+            saved = saved.and(update(&endpoint, Breaker::succeed));
+        }
+        Ok(_) => {}
+        Err(e) => {
+            let error = e.to_string();
+            saved = saved.and(update(&endpoint, |b| b.fail(now, error)));
+        }
+    }
+    if let Err(e) = saved {
+        crate::debug_log!("failed to save circuit breakers: {}", e);
+    }
+    result
+}
+
+/// 清除 `url` 对应接口的熔断状态（如更换凭据后），下次渲染立即重新请求
+pub fn reset(url: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let endpoint = endpoint(url);
+    if !load().contains_key(&endpoint) {
+        return Ok(());
+    }
+    update(&endpoint, Breaker::succeed)
+}
@@ -1,25 +1,25 @@
-use super::{CubenceData, SubscriptionData, UsageData, VendorType};
+use super::{
+    vendor::VendorUsage, CubenceData, OneApiData, SubscriptionData, UsageData, VendorType,
+};
+use crate::error::UucodeError;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
-use std::time::SystemTime;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// 缓存有效期：5分钟
-const CACHE_FRESH_SECONDS: u64 = 300;
+pub const CACHE_FRESH_SECONDS: u64 = 300;
 
 /// 获取缓存文件路径（按厂商区分）
 /// 缓存目录结构: ~/.claude/mycode/cache/{vendor}/{cache_type}.json
 fn get_vendor_cache_file(vendor: &VendorType, cache_type: &str) -> Option<PathBuf> {
-    let home = dirs::home_dir()?;
-    let vendor_name = match vendor {
-        VendorType::Uucode => "uucode",
-        VendorType::Cubence => "cubence",
-        VendorType::Unknown => "unknown",
-    };
-    let cache_dir = home
-        .join(".claude")
-        .join("mycode")
-        .join("cache")
-        .join(vendor_name);
+    get_cache_file_in(vendor.id(), cache_type)
+}
+
+/// vendors.d 中的服务商按各自的 id 分目录: ~/.claude/mycode/cache/{id}/{cache_type}.json
+fn get_cache_file_in(vendor_id: &str, cache_type: &str) -> Option<PathBuf> {
+    let cache_dir = get_cache_root()?.join(vendor_id);
 
     // 确保缓存目录存在
     fs::create_dir_all(&cache_dir).ok()?;
@@ -27,6 +27,197 @@ fn get_vendor_cache_file(vendor: &VendorType, cache_type: &str) -> Option<PathBu
     Some(cache_dir.join(format!("{}.json", cache_type)))
 }
 
+/// 缓存根目录: ~/.claude/mycode/cache
+fn get_cache_root() -> Option<PathBuf> {
+    crate::utils::paths::cache_dir()
+}
+
+/// 区分同时运行的会话：Claude 配置目录和进程环境变量中的 ANTHROPIC_BASE_URL
+/// 不同的会话各自记录上次的服务商，互不触发缓存清理
+fn session_key() -> String {
+    let dir = crate::utils::paths::claude_dir()
+        .map(|dir| dir.display().to_string())
+        .unwrap_or_default();
+    let url = std::env::var("ANTHROPIC_BASE_URL").unwrap_or_default();
+    let digest = crate::utils::download::sha256_hex(format!("{}\n{}", dir, url).as_bytes());
+    digest[..16].to_string()
+}
+
+/// 本会话的服务商状态文件: ~/.claude/mycode/cache/sessions/{key}.{name}
+fn session_file(name: &str) -> Option<PathBuf> {
+    Some(
+        get_cache_root()?
+            .join("sessions")
+            .join(format!("{}.{}", session_key(), name)),
+    )
+}
+
+/// 读取本会话上次检测到的服务商
+pub fn get_last_vendor() -> Option<VendorType> {
+    let content = fs::read_to_string(session_file("last_vendor")?).ok()?;
+    VendorType::from_id(content.trim())
+}
+
+/// 记录本会话检测到的服务商
+pub fn save_last_vendor(vendor: VendorType) -> Result<(), UucodeError> {
+    let path = session_file("last_vendor").ok_or_else(UucodeError::no_home)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    crate::utils::atomic_write(path, vendor.id())?;
+    Ok(())
+}
+
+/// 服务商变化时按 `vendor_themes` 切换到的主题，`replaces` 是当时配置中的主题
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VendorTheme {
+    pub theme: String,
+    pub replaces: String,
+}
+
+/// 读取本会话服务商切换时记录的主题
+pub fn get_vendor_theme() -> Option<VendorTheme> {
+    let content = fs::read_to_string(session_file("vendor_theme.json")?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// 记录本会话服务商切换到的主题，`None` 表示不再切换
+pub fn save_vendor_theme(theme: Option<&VendorTheme>) -> Result<(), UucodeError> {
+    let path = session_file("vendor_theme.json").ok_or_else(UucodeError::no_home)?;
+    match theme {
+        Some(theme) => {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            save_cache(&path, theme)
+        }
+        None if path.exists() => Ok(fs::remove_file(path)?),
+        None => Ok(()),
+    }
+}
+
+/// 保存最近一次 Claude Code 传入的状态栏 JSON，供 `uucode top` 等独立视图复用
+pub fn save_last_input(raw: &str) -> Result<(), UucodeError> {
+    let root = get_cache_root().ok_or_else(UucodeError::no_home)?;
+    fs::create_dir_all(&root)?;
+    crate::utils::atomic_write(root.join("last_input.json"), raw)?;
+    Ok(())
+}
+
+/// 最近一次状态栏 JSON 的写入时间
+pub fn last_input_modified() -> Option<SystemTime> {
+    fs::metadata(get_cache_root()?.join("last_input.json"))
+        .ok()?
+        .modified()
+        .ok()
+}
+
+/// 读取最近一次的状态栏 JSON
+pub fn load_last_input() -> Option<String> {
+    fs::read_to_string(get_cache_root()?.join("last_input.json")).ok()
+}
+
+/// 缓存目录（含各服务商子目录）中最新的修改时间，用于 `--follow` 检测变化
+pub fn latest_modification() -> Option<SystemTime> {
+    let root = get_cache_root()?;
+    let mut latest = None;
+    let mut dirs = vec![root];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                dirs.push(entry.path());
+            } else if let Ok(modified) = metadata.modified() {
+                latest = latest.max(Some(modified));
+            }
+        }
+    }
+    latest
+}
+
+/// 服务商检测缓存有效期：即使 settings.json 未变化也定期重新检测
+const VENDOR_DETECTION_FRESH_SECONDS: u64 = 600;
+
+/// settings.json 的路径、修改时间和大小，任一变化即视为配置已修改
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SettingsStamp {
+    path: String,
+    modified_nanos: u128,
+    len: u64,
+}
+
+impl SettingsStamp {
+    /// 当前 settings.json 的标记，文件不存在时返回 None
+    pub fn current() -> Option<Self> {
+        let path = super::get_claude_settings_path()?;
+        let metadata = fs::metadata(&path).ok()?;
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(Self {
+            path: path.display().to_string(),
+            modified_nanos: modified.as_nanos(),
+            len: metadata.len(),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct VendorDetection {
+    stamp: SettingsStamp,
+    vendor: String,
+}
+
+/// 读取 `stamp` 对应的服务商检测结果，settings.json 变化或缓存过期时返回 None
+pub fn get_cached_vendor(stamp: &SettingsStamp) -> Option<VendorType> {
+    let cache_file = get_cache_root()?.join("vendor_detection.json");
+    if cache_age_secs(&cache_file)? >= VENDOR_DETECTION_FRESH_SECONDS {
+        return None;
+    }
+    let detection: VendorDetection = read_cache(&cache_file)?;
+    if &detection.stamp != stamp {
+        return None;
+    }
+    VendorType::from_id(&detection.vendor)
+}
+
+/// 记录基于 `stamp` 检测到的服务商
+pub fn save_cached_vendor(stamp: &SettingsStamp, vendor: VendorType) -> Result<(), UucodeError> {
+    let root = get_cache_root().ok_or_else(UucodeError::no_home)?;
+    fs::create_dir_all(&root)?;
+    let detection = VendorDetection {
+        stamp: stamp.clone(),
+        vendor: vendor.id().to_string(),
+    };
+    save_cache(&root.join("vendor_detection.json"), &detection)
+}
+
+/// 删除其他服务商的用量/订阅缓存，避免切换服务商后短暂显示旧数据
+/// 注意：只删除数据缓存，cookie.json 等凭据文件保留
+pub fn invalidate_other_vendors(current: VendorType) {
+    let current_id = match current {
+        VendorType::Custom => super::vendor::current().map(|v| v.id()),
+        _ => Some(current.id()),
+    };
+    let ids = super::vendor::registry()
+        .iter()
+        .map(|v| v.id())
+        .chain([VendorType::Unknown.id()]);
+    for id in ids {
+        if Some(id) == current_id {
+            continue;
+        }
+        for cache_type in ["usage", "subscriptions"] {
+            if let Some(cache_file) = get_cache_file_in(id, cache_type) {
+                let _ = fs::remove_file(cache_file);
+            }
+        }
+    }
+}
+
 /// 获取缓存文件路径（旧版兼容，使用 uucode 目录）
 /// 已废弃，仅用于兼容旧代码
 fn get_cache_file(cache_type: &str) -> Option<PathBuf> {
@@ -45,19 +236,55 @@ fn is_cache_fresh(cache_file: &PathBuf) -> bool {
     false
 }
 
+/// 缓存文件距上次写入的秒数；确定性模式下一律视为刚写入
+fn cache_age_secs(cache_file: &PathBuf) -> Option<u64> {
+    let modified = fs::metadata(cache_file).ok()?.modified().ok()?;
+    if crate::utils::clock::is_deterministic() {
+        return Some(0);
+    }
+    SystemTime::now()
+        .duration_since(modified)
+        .ok()
+        .map(|d| d.as_secs())
+}
+
+/// uucode 用量缓存的年龄（秒）
+pub fn usage_cache_age() -> Option<u64> {
+    cache_age_secs(&get_cache_file("usage")?)
+}
+
+/// Cubence 用量缓存的年龄（秒）
+pub fn cubence_usage_cache_age() -> Option<u64> {
+    cache_age_secs(&get_vendor_cache_file(&VendorType::Cubence, "usage")?)
+}
+
+/// one-api 用量缓存的年龄（秒）
+pub fn oneapi_usage_cache_age() -> Option<u64> {
+    cache_age_secs(&get_vendor_cache_file(&VendorType::OneApi, "usage")?)
+}
+
+static HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+
+/// 本进程读取缓存的次数：(命中, 未命中)，文件不存在或无法解析算未命中
+pub fn read_stats() -> (u64, u64) {
+    (HITS.load(Ordering::Relaxed), MISSES.load(Ordering::Relaxed))
+}
+
 /// 读取缓存文件
 fn read_cache<T: serde::de::DeserializeOwned>(cache_file: &PathBuf) -> Option<T> {
-    let content = fs::read_to_string(cache_file).ok()?;
-    serde_json::from_str(&content).ok()
+    let cached = fs::read_to_string(cache_file)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok());
+    let counter = if cached.is_some() { &HITS } else { &MISSES };
+    counter.fetch_add(1, Ordering::Relaxed);
+    cached
 }
 
 /// 保存缓存文件（覆盖旧缓存）
-fn save_cache<T: serde::Serialize>(
-    cache_file: &PathBuf,
-    data: &T,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let json = serde_json::to_string(data)?;
-    fs::write(cache_file, json)?;
+fn save_cache<T: serde::Serialize>(cache_file: &PathBuf, data: &T) -> Result<(), UucodeError> {
+    let json = serde_json::to_string(data).map_err(std::io::Error::from)?;
+    crate::utils::atomic_write(cache_file, json)?;
     Ok(())
 }
 
@@ -94,9 +321,7 @@ pub fn get_cached_subscriptions() -> (Option<Vec<SubscriptionData>>, bool) {
 }
 
 /// 保存订阅数据到缓存（覆盖旧缓存）
-pub fn save_cached_subscriptions(
-    data: &Vec<SubscriptionData>,
-) -> Result<(), Box<dyn std::error::Error>> {
+pub fn save_cached_subscriptions(data: &Vec<SubscriptionData>) -> Result<(), UucodeError> {
     if let Some(cache_file) = get_cache_file("subscriptions") {
         save_cache(&cache_file, data)?;
     }
@@ -129,7 +354,7 @@ pub fn get_cached_usage() -> (Option<UsageData>, bool) {
 }
 
 /// 保存使用量数据到缓存（覆盖旧缓存）
-pub fn save_cached_usage(data: &UsageData) -> Result<(), Box<dyn std::error::Error>> {
+pub fn save_cached_usage(data: &UsageData) -> Result<(), UucodeError> {
     if let Some(cache_file) = get_cache_file("usage") {
         save_cache(&cache_file, data)?;
     }
@@ -148,6 +373,8 @@ pub fn spawn_background_subscription_update(api_key: String) {
             usage_url: "https://api.uucode.org/account/billing".to_string(),
             auto_cookie: false,
             cookie: None,
+            base_url: String::new(),
+            user_id: None,
         };
 
         if let Ok(client) = super::client::ApiClient::new(api_config) {
@@ -170,6 +397,8 @@ pub fn spawn_background_usage_update(api_key: String) {
             usage_url: "https://api.uucode.org/account/billing".to_string(),
             auto_cookie: false,
             cookie: None,
+            base_url: String::new(),
+            user_id: None,
         };
 
         if let Ok(client) = super::client::ApiClient::new(api_config) {
@@ -208,7 +437,7 @@ pub fn get_cached_cubence_usage() -> (Option<CubenceData>, bool) {
 }
 
 /// 保存 Cubence 使用量数据到缓存
-pub fn save_cached_cubence_usage(data: &CubenceData) -> Result<(), Box<dyn std::error::Error>> {
+pub fn save_cached_cubence_usage(data: &CubenceData) -> Result<(), UucodeError> {
     if let Some(cache_file) = get_vendor_cache_file(&VendorType::Cubence, "usage") {
         save_cache(&cache_file, data)?;
     }
@@ -227,6 +456,8 @@ pub fn spawn_background_cubence_usage_update(api_key: String) {
             usage_url: "https://cubence.com/api/v1/user/subscription-info".to_string(),
             auto_cookie: true, // Cubence 需要 Cookie
             cookie: None,
+            base_url: String::new(),
+            user_id: None,
         };
 
         if let Ok(client) = super::client::ApiClient::new(api_config) {
@@ -238,3 +469,38 @@ pub fn spawn_background_cubence_usage_update(api_key: String) {
         }
     });
 }
+
+// ============== one-api 缓存支持 ==============
+
+/// 读取 one-api 用量缓存
+pub fn get_cached_oneapi_usage() -> Option<OneApiData> {
+    read_cache(&get_vendor_cache_file(&VendorType::OneApi, "usage")?)
+}
+
+/// 保存 one-api 用量缓存
+pub fn save_cached_oneapi_usage(data: &OneApiData) -> Result<(), UucodeError> {
+    if let Some(cache_file) = get_vendor_cache_file(&VendorType::OneApi, "usage") {
+        save_cache(&cache_file, data)?;
+    }
+    Ok(())
+}
+
+// ============== vendors.d 服务商缓存支持 ==============
+
+/// 读取 vendors.d 中服务商的用量缓存
+pub fn get_cached_custom_usage(vendor_id: &str) -> Option<VendorUsage> {
+    read_cache(&get_cache_file_in(vendor_id, "usage")?)
+}
+
+/// 保存 vendors.d 中服务商的用量缓存
+pub fn save_cached_custom_usage(vendor_id: &str, data: &VendorUsage) -> Result<(), UucodeError> {
+    if let Some(cache_file) = get_cache_file_in(vendor_id, "usage") {
+        save_cache(&cache_file, data)?;
+    }
+    Ok(())
+}
+
+/// vendors.d 中服务商用量缓存的年龄（秒）
+pub fn custom_usage_cache_age(vendor_id: &str) -> Option<u64> {
+    cache_age_secs(&get_cache_file_in(vendor_id, "usage")?)
+}
@@ -1,7 +1,61 @@
-use super::{ApiConfig, CubenceData, CubenceResponse, SubscriptionData, UsageData};
+use super::{ApiConfig, OneApiData, SubscriptionData, UsageData};
+use crate::error::UucodeError;
 use reqwest::blocking::Client;
+use serde::Deserialize;
 use std::time::Duration;
 
+/// one-api / new-api 返回当前用户信息（含额度）的接口
+pub const ONEAPI_SELF_PATH: &str = "/api/user/self";
+
+/// 网关地址对应的用户信息接口
+pub fn oneapi_usage_url(base_url: &str) -> String {
+    format!("{}{}", base_url.trim_end_matches('/'), ONEAPI_SELF_PATH)
+}
+
+#[derive(Deserialize)]
+struct OneApiResponse {
+    success: bool,
+    #[serde(default)]
+    message: String,
+    data: Option<OneApiData>,
+}
+
+/// 解析 one-api / new-api 的 `/api/user/self` 响应；`success` 为 false 时
+/// 返回网关给出的原因（通常是访问令牌无效）
+///
+/// ```
+/// use mycode::api::client::parse_oneapi_self;
+///
+/// let data = parse_oneapi_self(
+///     r#"{"success":true,"message":"","data":{"id":1,"username":"dev",
+///        "quota":3750000,"used_quota":1250000,"request_count":42,"group":"vip"}}"#,
+/// )
+/// .unwrap();
+/// assert_eq!(data.remaining_usd(), 7.5);
+/// assert_eq!(data.used_usd(), 2.5);
+/// assert_eq!(data.percentage_used(), 25.0);
+/// assert_eq!(data.group, "vip");
+///
+/// let err = parse_oneapi_self(r#"{"success":false,"message":"无权进行此操作，access token 无效"}"#)
+///     .unwrap_err();
+/// assert!(err.to_string().contains("access token"));
+/// ```
+pub fn parse_oneapi_self(text: &str) -> Result<OneApiData, UucodeError> {
+    let response: OneApiResponse = serde_json::from_str(text)
+        .map_err(|e| UucodeError::Vendor(format!("Unexpected one-api response: {}", e)))?;
+    match response.data {
+        Some(data) if response.success => Ok(data),
+        _ => Err(UucodeError::Vendor(format!(
+            "one-api: {}",
+            if response.message.is_empty() {
+                "request failed"
+            } else {
+                &response.message
+            }
+        ))),
+    }
+}
+
 pub struct ApiClient {
     config: ApiConfig,
     client: Client,
@@ -10,11 +64,8 @@ pub struct ApiClient {
 }
 
 impl ApiClient {
-    pub fn new(config: ApiConfig) -> Result<Self, Box<dyn std::error::Error>> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(10))
-            .user_agent("uucode/1.0.0")
-            .build()?;
+    pub fn new(config: ApiConfig) -> Result<Self, UucodeError> {
+        let client = super::http::client_for_url(&config.usage_url, Duration::from_secs(10))?;
 
         // 获取 Cookie：使用手动配置
         let cached_cookie = config.cookie.clone();
@@ -31,9 +82,11 @@ impl ApiClient {
         self.cached_cookie.as_deref()
     }
 
-    pub fn get_usage(&self) -> Result<UsageData, Box<dyn std::error::Error>> {
+    pub fn get_usage(&self) -> Result<UsageData, UucodeError> {
         // 根据 URL 判断是哪个服务商
-        if self.config.usage_url.contains("cubence.com") {
+        if !self.config.base_url.is_empty() {
+            self.get_oneapi_usage()
+        } else if self.config.usage_url.contains("cubence.com") {
             self.get_cubence_usage()
         } else {
             self.get_uucode_usage()
@@ -41,7 +94,7 @@ impl ApiClient {
     }
 
     /// 获取 uucode 用量数据
-    fn get_uucode_usage(&self) -> Result<UsageData, Box<dyn std::error::Error>> {
+    fn get_uucode_usage(&self) -> Result<UsageData, UucodeError> {
         let response = self
             .client
             .get(&self.config.usage_url)
@@ -49,28 +102,23 @@ impl ApiClient {
             .send()?;
 
         if !response.status().is_success() {
-            return Err(format!("Usage API request failed: {}", response.status()).into());
+            return Err(UucodeError::Vendor(format!(
+                "Usage API request failed: {}",
+                response.status()
+            )));
         }
 
-        let response_text = response.text()?;
+        let response_text = super::http::read_text(response)?;
 
-        let mut usage: UsageData = {
-            let resp: super::NewVendorResponse =
-                serde_json::from_str(&response_text).map_err(|e| {
-                    format!(
-                        "uucode JSON parse error: {} | Response: {}",
-                        e, response_text
-                    )
-                })?;
-            UsageData::NewVendor(resp.data)
-        };
+        let parsed = super::schema::parse_uucode_usage(&response_text)?;
+        let mut usage = UsageData::NewVendor(parsed.data);
 
         usage.calculate();
         Ok(usage)
     }
 
     /// 获取 Cubence 用量数据
-    fn get_cubence_usage(&self) -> Result<UsageData, Box<dyn std::error::Error>> {
+    fn get_cubence_usage(&self) -> Result<UsageData, UucodeError> {
         let mut request = self
             .client
             .get(&self.config.usage_url)
@@ -84,23 +132,43 @@ impl ApiClient {
         let response = request.send()?;
 
         if !response.status().is_success() {
-            return Err(format!("Cubence API request failed: {}", response.status()).into());
+            return Err(UucodeError::Vendor(format!(
+                "Cubence API request failed: {}",
+                response.status()
+            )));
         }
 
-        let response_text = response.text()?;
+        let response_text = super::http::read_text(response)?;
 
-        let resp: CubenceResponse = serde_json::from_str(&response_text).map_err(|e| {
-            format!(
-                "Cubence JSON parse error: {} | Response: {}",
-                e, response_text
-            )
-        })?;
+        let parsed = super::schema::parse_cubence_usage(&response_text)?;
+        Ok(UsageData::Cubence(parsed.data))
+    }
+
+    /// 获取 one-api / new-api 用量数据
+    fn get_oneapi_usage(&self) -> Result<UsageData, UucodeError> {
+        let mut request = self
+            .client
+            .get(oneapi_usage_url(&self.config.base_url))
+            .header("Authorization", format!("Bearer {}", self.config.api_key));
+        if let Some(user_id) = self.config.user_id {
+            request = request.header("New-Api-User", user_id.to_string());
+        }
+
+        let response = request.send()?;
+        let status = response.status();
+        let response_text = super::http::read_text(response)?;
+        // 令牌无效时 one-api 仍返回 200，原因在 message 中
+        if !status.is_success() && !response_text.contains("\"message\"") {
+            return Err(UucodeError::Vendor(format!(
+                "one-api API request failed: {}",
+                status
+            )));
+        }
 
-        let data = CubenceData::from_response(resp);
-        Ok(UsageData::Cubence(data))
+        Ok(UsageData::OneApi(parse_oneapi_self(&response_text)?))
     }
 
-    pub fn get_subscriptions(&self) -> Result<Vec<SubscriptionData>, Box<dyn std::error::Error>> {
+    pub fn get_subscriptions(&self) -> Result<Vec<SubscriptionData>, UucodeError> {
         let response = self
             .client
             .post(&self.config.subscription_url)
@@ -109,11 +177,16 @@ impl ApiClient {
             .send()?;
 
         if !response.status().is_success() {
-            return Err(format!("Subscription API request failed: {}", response.status()).into());
+            return Err(UucodeError::Vendor(format!(
+                "Subscription API request failed: {}",
+                response.status()
+            )));
         }
 
         // API返回的是数组,返回所有订阅
-        let mut subscriptions: Vec<SubscriptionData> = response.json()?;
+        let response_text = super::http::read_text(response)?;
+        let mut subscriptions: Vec<SubscriptionData> = serde_json::from_str(&response_text)
+            .map_err(|e| UucodeError::Vendor(format!("Unexpected subscription response: {}", e)))?;
 
         // 格式化每个订阅的显示数据
         for subscription in &mut subscriptions {
@@ -123,7 +196,7 @@ impl ApiClient {
         Ok(subscriptions)
     }
 
-    pub fn check_token_limit(&self) -> Result<bool, Box<dyn std::error::Error>> {
+    pub fn check_token_limit(&self) -> Result<bool, UucodeError> {
         let usage = self.get_usage()?;
         Ok(usage.get_remaining_tokens() == 0)
     }
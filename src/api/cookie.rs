@@ -0,0 +1,169 @@
+//! Cubence 网站 Cookie（`cache/cubence/cookie.json`）
+//!
+//! 负载、订阅和倍率接口不接受 API Key，需要登录网站后的 `token` Cookie。
+//! 可以手动编辑 cookie.json，也可以从浏览器复制后执行 `uucode cubence set-cookie`。
+
+use crate::error::UucodeError;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// 过期前多久开始在段中提醒续期
+pub const RENEW_WARNING_SECS: i64 = 24 * 3600;
+
+/// Cookie 配置文件结构
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CookieConfig {
+    /// Cookie 值，为空表示未配置
+    pub cookie: String,
+    /// 配置说明
+    #[serde(default = "default_description")]
+    pub description: String,
+}
+
+fn default_description() -> String {
+    "请将 Cubence 网站的 Cookie 粘贴到 cookie 字段中，或执行 uucode cubence set-cookie".to_string()
+}
+
+impl Default for CookieConfig {
+    fn default() -> Self {
+        Self {
+            cookie: String::new(),
+            description: default_description(),
+        }
+    }
+}
+
+/// cookie.json 路径
+pub fn path() -> Option<PathBuf> {
+    crate::utils::paths::cache_dir().map(|dir| dir.join("cubence").join("cookie.json"))
+}
+
+/// 读取 cookie 配置
+/// 返回: Ok(Some(cookie)) - cookie 已配置
+///       Ok(None) - 文件不存在或 cookie 为空（未配置）
+///       Err - 文件读取或解析失败
+pub fn load() -> Result<Option<String>, String> {
+    let path = path().ok_or("无法找到主目录")?;
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let config: CookieConfig = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+
+    let cookie = config.cookie.trim();
+    Ok((!cookie.is_empty()).then(|| cookie.to_string()))
+}
+
+/// 确保配置文件存在，不存在时写入模板
+pub fn ensure_exists() -> Option<PathBuf> {
+    let path = path()?;
+    if path.exists() {
+        return Some(path);
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).ok()?;
+    }
+    let content = serde_json::to_string_pretty(&CookieConfig::default()).ok()?;
+    crate::utils::atomic_write(&path, content).ok()?;
+    Some(path)
+}
+
+/// 写入 cookie，返回文件路径
+pub fn save(cookie: &str) -> Result<PathBuf, UucodeError> {
+    let path = path().ok_or_else(UucodeError::no_home)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let config = CookieConfig {
+        cookie: cookie.to_string(),
+        ..CookieConfig::default()
+    };
+    let json = serde_json::to_string_pretty(&config).map_err(std::io::Error::from)?;
+    crate::utils::atomic_write(&path, json)?;
+    Ok(path)
+}
+
+/// Bearer 接口使用的 token：cookie 中有 `token=xxx` 时取其值，否则整个当作 token
+///
+/// ```
+/// use mycode::api::cookie::token;
+///
+/// assert_eq!(token("token=eyJhbGci"), "eyJhbGci");
+/// assert_eq!(token("theme=dark; token=eyJhbGci"), "eyJhbGci");
+/// assert_eq!(token("eyJhbGci"), "eyJhbGci");
+/// ```
+pub fn token(cookie: &str) -> &str {
+    cookie
+        .split(';')
+        .find_map(|pair| pair.trim().strip_prefix("token="))
+        .unwrap_or(cookie.trim())
+}
+
+/// 整理从浏览器复制的内容：去掉 `Cookie:` 前缀、引号和换行；
+/// 只复制了 token 的值时补上 `token=`
+///
+/// ```
+/// use mycode::api::cookie::normalize;
+///
+/// assert_eq!(normalize("Cookie: token=abc; theme=dark\n").as_deref(), Some("token=abc; theme=dark"));
+/// assert_eq!(normalize("\"eyJhbGci\"").as_deref(), Some("token=eyJhbGci"));
+/// assert_eq!(normalize("  \n"), None);
+/// ```
+pub fn normalize(text: &str) -> Option<String> {
+    let text: String = text.lines().map(str::trim).collect::<Vec<_>>().join("");
+    let mut text = text.as_str();
+    if text
+        .get(..7)
+        .is_some_and(|p| p.eq_ignore_ascii_case("cookie:"))
+    {
+        text = &text[7..];
+    }
+    let text = text.trim().trim_matches(|c| c == '"' || c == '\'').trim();
+    if text.is_empty() {
+        None
+    } else if text.contains('=') {
+        Some(text.to_string())
+    } else {
+        Some(format!("token={}", text))
+    }
+}
+
+/// token 为 JWT 时读取其 `exp`（Unix 秒）；不是 JWT 或没有过期时间时返回 None
+///
+/// ```
+/// use base64::Engine;
+/// use mycode::api::cookie::expires_at;
+///
+/// let b64 = base64::engine::general_purpose::URL_SAFE_NO_PAD;
+/// let jwt = format!(
+///     "{}.{}.sig",
+///     b64.encode(r#"{"alg":"HS256"}"#),
+///     b64.encode(r#"{"sub":"42","exp":1767225600}"#)
+/// );
+/// assert_eq!(expires_at(&format!("token={}; theme=dark", jwt)), Some(1767225600));
+/// assert_eq!(expires_at("token=opaque-session-id"), None);
+/// ```
+pub fn expires_at(cookie: &str) -> Option<i64> {
+    let mut parts = token(cookie).split('.');
+    let (_, payload, _) = (parts.next()?, parts.next()?, parts.next()?);
+    let payload = payload.trim_end_matches('=');
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(payload)
+        .ok()?;
+    let claims: serde_json::Value = serde_json::from_slice(&bytes).ok()?;
+    claims.get("exp")?.as_f64().map(|exp| exp as i64)
+}
+
+/// 剩余有效期的简短描述，如 `18h`、`35m`
+pub fn format_remaining(secs: i64) -> String {
+    if secs >= 2 * 24 * 3600 {
+        format!("{}d", secs / (24 * 3600))
+    } else if secs >= 3600 {
+        format!("{}h", secs / 3600)
+    } else {
+        format!("{}m", (secs / 60).max(1))
+    }
+}
@@ -0,0 +1,289 @@
+//! 服务商 API 密钥解析链
+//!
+//! 优先级（从高到低）：
+//! 1. 进程环境变量 `ANTHROPIC_AUTH_TOKEN` / `ANTHROPIC_API_KEY`
+//! 2. settings.json 的 env 块
+//! 3. settings.json 的 `apiKeyHelper` 脚本输出
+//! 4. ~/.claude/uucode/api_keys.toml
+//! 5. 系统钥匙串（macOS Keychain / Linux secret-tool，服务名 `uucode`）
+//!
+//! 前三项属于当前 ANTHROPIC_BASE_URL，只有当前服务商就是目标服务商时才使用。
+
+use super::VendorType;
+use crate::config::ApiKeys;
+use crate::utils::subprocess::Subprocess;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// 读取系统钥匙串的超时
+const KEYRING_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 密钥来源
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CredentialSource {
+    Environment,
+    SettingsEnv,
+    ApiKeyHelper,
+    ApiKeysFile,
+    Keyring,
+}
+
+impl CredentialSource {
+    pub const ALL: [CredentialSource; 5] = [
+        CredentialSource::Environment,
+        CredentialSource::SettingsEnv,
+        CredentialSource::ApiKeyHelper,
+        CredentialSource::ApiKeysFile,
+        CredentialSource::Keyring,
+    ];
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            CredentialSource::Environment => "环境变量",
+            CredentialSource::SettingsEnv => "settings.json env",
+            CredentialSource::ApiKeyHelper => "apiKeyHelper",
+            CredentialSource::ApiKeysFile => "api_keys.toml",
+            CredentialSource::Keyring => "系统钥匙串",
+        }
+    }
+}
+
+const TOKEN_VARS: [&str; 2] = ["ANTHROPIC_AUTH_TOKEN", "ANTHROPIC_API_KEY"];
+
+/// 按优先级解析服务商的 API 密钥，返回密钥和来源
+pub fn resolve(vendor: VendorType) -> Option<(String, CredentialSource)> {
+    CredentialSource::ALL
+        .iter()
+        .find_map(|source| lookup(*source, vendor).map(|token| (token, *source)))
+}
+
+/// 解析服务商的 API 密钥
+pub fn resolve_api_key(vendor: VendorType) -> Option<String> {
+    resolve(vendor).map(|(token, _)| token)
+}
+
+/// 从单个来源读取密钥（doctor 用来展示每个来源的情况）
+pub fn lookup(source: CredentialSource, vendor: VendorType) -> Option<String> {
+    let current_vendor = super::detect_vendor_from_claude_settings() == vendor;
+
+    let token = match source {
+        CredentialSource::Environment if current_vendor => {
+            TOKEN_VARS.iter().find_map(|name| std::env::var(name).ok())
+        }
+        CredentialSource::SettingsEnv if current_vendor => TOKEN_VARS
+            .iter()
+            .find_map(|name| super::get_claude_env_var(name)),
+        CredentialSource::ApiKeyHelper if current_vendor => run_api_key_helper(),
+        CredentialSource::ApiKeysFile => ApiKeys::load().ok()?.token_for(&account(vendor)?),
+        CredentialSource::Keyring => read_keyring(&account(vendor)?),
+        _ => None,
+    };
+
+    token
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+}
+
+/// apiKeyHelper 输出的默认有效期，与 Claude Code 相同
+const API_KEY_HELPER_TTL: Duration = Duration::from_secs(300);
+
+/// apiKeyHelper 的执行超时，可能需要联网换取令牌，给足时间
+const API_KEY_HELPER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 一次 apiKeyHelper 执行的结果（包括失败），保存在 cache/api_key_helper.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HelperToken {
+    /// 命令的 sha256，命令变化后立即重新执行
+    command: String,
+    /// 执行时间（毫秒时间戳）
+    at_ms: u64,
+    token: Option<String>,
+}
+
+impl HelperToken {
+    fn is_fresh(&self, command: &str, ttl: Duration) -> bool {
+        self.command == command && now_ms().saturating_sub(self.at_ms) < ttl.as_millis() as u64
+    }
+}
+
+/// 上一次执行的 apiKeyHelper；执行期间一直持有锁，并行采集的段不会重复执行
+static API_KEY_HELPER: Mutex<Option<HelperToken>> = Mutex::new(None);
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn helper_token_path() -> Option<PathBuf> {
+    crate::utils::paths::cache_dir().map(|dir| dir.join("api_key_helper.json"))
+}
+
+fn load_helper_token() -> Option<HelperToken> {
+    let content = fs::read_to_string(helper_token_path()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// 令牌只允许当前用户读取（Unix 下为 0600）
+fn save_helper_token(entry: &HelperToken) -> Result<(), Box<dyn std::error::Error>> {
+    let path = helper_token_path().ok_or("Could not find home directory")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // atomic_write 沿用目标文件的权限，先把目标文件收紧，临时文件写入内容前就是 0600
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .mode(0o600)
+            .open(&path)?;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+    }
+    crate::utils::atomic_write(&path, serde_json::to_string(entry)?)?;
+    Ok(())
+}
+
+/// 跨进程的执行锁（cache/api_key_helper.lock）：同时启动的渲染只有一个执行
+/// apiKeyHelper，其余等它写入结果后直接读取
+struct HelperLock(PathBuf);
+
+impl HelperLock {
+    /// 等待超过一次执行的时间仍拿不到锁时返回 None，调用方不加锁直接执行
+    fn acquire() -> Option<Self> {
+        let path = crate::utils::paths::cache_dir()?.join("api_key_helper.lock");
+        fs::create_dir_all(path.parent()?).ok()?;
+        let wait = API_KEY_HELPER_TIMEOUT + Duration::from_secs(1);
+        let started = Instant::now();
+        loop {
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(_) => return Some(Self(path)),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    // 持有者被杀死时锁文件会残留，超过一次执行的时间即视为失效
+                    let stale = fs::metadata(&path)
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .and_then(|at| at.elapsed().ok())
+                        .is_some_and(|age| age > wait);
+                    if stale {
+                        let _ = fs::remove_file(&path);
+                        continue;
+                    }
+                    if started.elapsed() > wait {
+                        return None;
+                    }
+                    std::thread::sleep(Duration::from_millis(50));
+                }
+                Err(_) => return None,
+            }
+        }
+    }
+}
+
+impl Drop for HelperLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// 执行 settings.json 中配置的 apiKeyHelper 命令，取其标准输出
+///
+/// 每次渲染都是新进程，所以结果（包括失败）连同执行时间保存在缓存目录，有效期内
+/// 不再重复执行；有效期可用 Claude Code 的 `CLAUDE_CODE_API_KEY_HELPER_TTL_MS`
+/// 调整，命令变化后立即重新执行。同一时刻只有一个线程、一个进程在执行
+fn run_api_key_helper() -> Option<String> {
+    let helper = super::load_claude_settings()
+        .ok()?
+        .get("apiKeyHelper")?
+        .as_str()?
+        .to_string();
+    let command = crate::utils::download::sha256_hex(helper.as_bytes());
+
+    let ttl = std::env::var("CLAUDE_CODE_API_KEY_HELPER_TTL_MS")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .map(Duration::from_millis)
+        .unwrap_or(API_KEY_HELPER_TTL);
+    let mut memo = API_KEY_HELPER.lock().ok()?;
+    if let Some(entry) = memo.as_ref().filter(|e| e.is_fresh(&command, ttl)) {
+        return entry.token.clone();
+    }
+    if let Some(entry) = load_helper_token().filter(|e| e.is_fresh(&command, ttl)) {
+        let token = entry.token.clone();
+        *memo = Some(entry);
+        return token;
+    }
+
+    let _lock = HelperLock::acquire();
+    // 等锁期间另一个进程可能已经执行完
+    if let Some(entry) = load_helper_token().filter(|e| e.is_fresh(&command, ttl)) {
+        let token = entry.token.clone();
+        *memo = Some(entry);
+        return token;
+    }
+
+    let token = match Subprocess::shell(&helper)
+        .with_timeout(API_KEY_HELPER_TIMEOUT)
+        .run()
+    {
+        Ok(output) if output.success() => Some(output.stdout),
+        Ok(output) => {
+            crate::debug_log!("apiKeyHelper exited with {}", output.status);
+            None
+        }
+        Err(_) => None,
+    };
+    let entry = HelperToken {
+        command,
+        at_ms: now_ms(),
+        token: token.clone(),
+    };
+    if let Err(e) = save_helper_token(&entry) {
+        crate::debug_log!("failed to save the apiKeyHelper token: {}", e);
+    }
+    *memo = Some(entry);
+    token
+}
+
+/// api_keys.toml 和钥匙串中的账户名；vendors.d 中的服务商使用各自的 id
+fn account(vendor: VendorType) -> Option<String> {
+    match vendor {
+        VendorType::Custom => super::vendor::current().map(|v| v.id().to_string()),
+        _ => Some(vendor.id().to_string()),
+    }
+}
+
+/// 从系统钥匙串读取（service = uucode, account = 服务商标识）
+fn read_keyring(account: &str) -> Option<String> {
+    let command = if cfg!(target_os = "macos") {
+        Subprocess::new("security").args([
+            "find-generic-password",
+            "-s",
+            "uucode",
+            "-a",
+            account,
+            "-w",
+        ])
+    } else if cfg!(target_os = "linux") {
+        Subprocess::new("secret-tool").args(["lookup", "service", "uucode", "account", account])
+    } else {
+        return None;
+    };
+    // 钥匙串锁定时可能等待解锁，不能拖住渲染
+    let output = command.with_timeout(KEYRING_TIMEOUT).run().ok()?;
+
+    if !output.success() {
+        return None;
+    }
+    Some(output.stdout)
+}
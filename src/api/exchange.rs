@@ -0,0 +1,122 @@
+//! 汇率获取与缓存
+//! 默认使用 `[format].cny_rate` 静态汇率；配置 `exchange_rate_url` 后每天拉取一次并缓存，
+//! 拉取失败时回退到旧缓存或静态汇率，并在一小时内不再重试
+
+use crate::config::FormatConfig;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 汇率缓存有效期：1天
+const RATE_FRESH_SECONDS: u64 = 86_400;
+
+/// 拉取汇率的超时时间，避免阻塞状态栏渲染
+const RATE_FETCH_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 拉取失败后的重试间隔，离线时不必每次渲染都等待超时
+const RATE_RETRY_SECONDS: u64 = 3_600;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RateCache {
+    url: String,
+    /// 最近一次成功拉取的汇率，从未成功时为空
+    usd_cny: Option<f64>,
+    fetched_at: u64,
+    /// 最近一次拉取失败的时间
+    #[serde(default)]
+    failed_at: u64,
+}
+
+fn get_rate_cache_file() -> Option<PathBuf> {
+    let cache_dir = crate::utils::paths::cache_dir()?;
+    fs::create_dir_all(&cache_dir).ok()?;
+    Some(cache_dir.join("exchange_rate.json"))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// 获取当前生效的 USD -> CNY 汇率
+pub fn usd_cny_rate(format: &FormatConfig) -> f64 {
+    let url = match format
+        .exchange_rate_url
+        .as_deref()
+        .filter(|u| !u.is_empty())
+    {
+        Some(url) => url,
+        None => return format.cny_rate,
+    };
+
+    let cache_file = get_rate_cache_file();
+    let cached: Option<RateCache> = cache_file
+        .as_ref()
+        .and_then(|f| fs::read_to_string(f).ok())
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .filter(|c: &RateCache| c.url == url);
+    let fallback = cached
+        .as_ref()
+        .and_then(|c| c.usd_cny)
+        .unwrap_or(format.cny_rate);
+
+    if let Some(ref c) = cached {
+        let now = now_secs();
+        if c.usd_cny.is_some() && now.saturating_sub(c.fetched_at) < RATE_FRESH_SECONDS {
+            return fallback;
+        }
+        // 上次拉取失败且仍在重试间隔内：直接使用过期缓存或静态汇率
+        if now.saturating_sub(c.failed_at) < RATE_RETRY_SECONDS {
+            return fallback;
+        }
+    }
+
+    let fetched = fetch_rate(url);
+    if let Some(ref f) = cache_file {
+        let entry = match fetched {
+            Some(rate) => RateCache {
+                url: url.to_string(),
+                usd_cny: Some(rate),
+                fetched_at: now_secs(),
+                failed_at: 0,
+            },
+            // 记录失败时间，保留上次成功的汇率
+            None => RateCache {
+                url: url.to_string(),
+                usd_cny: cached.as_ref().and_then(|c| c.usd_cny),
+                fetched_at: cached.as_ref().map(|c| c.fetched_at).unwrap_or(0),
+                failed_at: now_secs(),
+            },
+        };
+        if let Ok(json) = serde_json::to_string(&entry) {
+            let _ = crate::utils::atomic_write(f, json);
+        }
+    }
+    // 拉取失败：优先使用过期缓存，再回退到静态汇率
+    fetched.unwrap_or(fallback)
+}
+
+/// 用当前生效汇率更新格式配置，之后的格式化都使用同一汇率
+pub fn resolve_rate(format: &mut FormatConfig) {
+    format.cny_rate = usd_cny_rate(format);
+}
+
+fn fetch_rate(url: &str) -> Option<f64> {
+    let client = super::http::client(None, RATE_FETCH_TIMEOUT).ok()?;
+    let text = super::http::read_text(client.get(url).send().ok()?).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&text).ok()?;
+    parse_rate(&json).filter(|r| r.is_finite() && *r > 0.0)
+}
+
+/// 兼容常见汇率接口：`{"rates":{"CNY":7.1}}`、`{"CNY":7.1}`、`{"rate":7.1}`
+fn parse_rate(json: &serde_json::Value) -> Option<f64> {
+    json.get("rates")
+        .and_then(|r| r.get("CNY"))
+        .or_else(|| json.get("CNY"))
+        .or_else(|| json.get("cny"))
+        .or_else(|| json.get("rate"))
+        .and_then(|v| v.as_f64())
+}
@@ -0,0 +1,205 @@
+//! 请求用量接口，失败时回退到缓存，并标记数据状态
+//!
+//! `uucode daemon` 运行时由它定时刷新缓存，渲染时不再请求接口（见 [`crate::daemon`]）
+
+use super::vendor::{self, Vendor, VendorUsage};
+use super::{breaker, cache, client::ApiClient, ApiConfig, CubenceData, OneApiData, UsageData};
+use crate::error::UucodeError;
+use std::collections::HashMap;
+
+/// 获取到的数据及其来源
+#[derive(Debug)]
+pub struct Fetched<T> {
+    pub data: T,
+    /// 请求失败且缓存已超过有效期，或接口结构不匹配
+    pub stale: bool,
+    /// 数据来自缓存时的缓存年龄（秒）
+    pub cache_age: Option<u64>,
+    /// 请求成功但响应结构无法识别（接口可能已变更）
+    pub schema_mismatch: bool,
+}
+
+impl<T> Fetched<T> {
+    fn fresh(data: T) -> Self {
+        Self {
+            data,
+            stale: false,
+            cache_age: None,
+            schema_mismatch: false,
+        }
+    }
+
+    fn cached(data: T, cache_age: Option<u64>, error: &UucodeError) -> Self {
+        let schema_mismatch = matches!(error, UucodeError::Schema(_));
+        if schema_mismatch {
+            crate::debug_log!("{}", error);
+        } else {
+            crate::debug_log!("request failed, using cache: {}", error);
+        }
+        // 缓存仍在有效期内时照常显示，不算过期
+        let expired = cache_age.is_none_or(|age| age >= cache::CACHE_FRESH_SECONDS);
+        Self {
+            data,
+            stale: expired || schema_mismatch,
+            cache_age,
+            schema_mismatch,
+        }
+    }
+
+    /// 后台 daemon 运行时直接使用缓存，超过有效期才算过期
+    fn from_daemon(data: T, cache_age: Option<u64>) -> Self {
+        Self {
+            data,
+            stale: cache_age.is_none_or(|age| age >= cache::CACHE_FRESH_SECONDS),
+            cache_age,
+            schema_mismatch: false,
+        }
+    }
+
+    /// 把数据状态写入段 metadata，供渲染时标记
+    pub fn annotate(&self, metadata: &mut HashMap<String, String>) {
+        if let Some(age) = self.cache_age {
+            metadata.insert("cache_age_secs".to_string(), age.to_string());
+        }
+        if self.stale {
+            metadata.insert("stale".to_string(), "true".to_string());
+        }
+        if self.schema_mismatch {
+            metadata.insert("schema_mismatch".to_string(), "true".to_string());
+        }
+    }
+}
+
+fn usage_client(api_key: &str, usage_url: &str, auto_cookie: bool) -> Option<ApiClient> {
+    let api_config = ApiConfig {
+        enabled: true,
+        api_key: api_key.to_string(),
+        usage_url: usage_url.to_string(),
+        subscription_url: String::new(),
+        auto_cookie,
+        cookie: None,
+        base_url: String::new(),
+        user_id: None,
+    };
+    ApiClient::new(api_config).ok()
+}
+
+/// 获取 uucode 用量：每次先请求接口，失败或熔断时回退到本地缓存（无论是否过期）；
+/// 后台 daemon 运行时只读缓存
+pub fn uucode_usage(api_key: &str, usage_url: &str) -> Option<Fetched<UsageData>> {
+    let (cached, _) = cache::get_cached_usage();
+    let cache_age = cache::usage_cache_age();
+    if crate::daemon::cache_only() {
+        let mut cached = cached?;
+        cached.calculate();
+        return Some(Fetched::from_daemon(cached, cache_age));
+    }
+    let client = usage_client(api_key, usage_url, false)?;
+
+    match breaker::call(usage_url, || client.get_usage()) {
+        Ok(mut fresh) => {
+            fresh.calculate();
+            let _ = cache::save_cached_usage(&fresh);
+            Some(Fetched::fresh(fresh))
+        }
+        Err(e) => {
+            let mut cached = cached?;
+            cached.calculate();
+            Some(Fetched::cached(cached, cache_age, &e))
+        }
+    }
+}
+
+/// 获取 Cubence 用量：每次先请求接口，失败或熔断时回退到本地缓存（无论是否过期）；
+/// 后台 daemon 运行时只读缓存
+pub fn cubence_usage(api_key: &str, usage_url: &str) -> Option<Fetched<CubenceData>> {
+    let (cached, _) = cache::get_cached_cubence_usage();
+    let cache_age = cache::cubence_usage_cache_age();
+    if crate::daemon::cache_only() {
+        let mut cached = cached?;
+        cached.calculate();
+        return Some(Fetched::from_daemon(cached, cache_age));
+    }
+    // Cubence 需要 Cookie
+    let client = usage_client(api_key, usage_url, true)?;
+
+    let result = breaker::call(usage_url, || client.get_usage()).and_then(|usage| {
+        usage
+            .as_cubence()
+            .cloned()
+            .ok_or_else(|| UucodeError::Vendor("unexpected usage type".to_string()))
+    });
+
+    match result {
+        Ok(mut fresh) => {
+            fresh.calculate();
+            let _ = cache::save_cached_cubence_usage(&fresh);
+            Some(Fetched::fresh(fresh))
+        }
+        Err(e) => {
+            let mut cached = cached?;
+            cached.calculate();
+            Some(Fetched::cached(cached, cache_age, &e))
+        }
+    }
+}
+
+/// 获取 one-api / new-api 网关的用量：每次先请求接口，失败或熔断时回退到本地缓存；
+/// 后台 daemon 运行时只读缓存
+pub fn oneapi_usage(
+    base_url: &str,
+    access_token: &str,
+    user_id: Option<u64>,
+) -> Option<Fetched<OneApiData>> {
+    let cached = cache::get_cached_oneapi_usage();
+    let cache_age = cache::oneapi_usage_cache_age();
+    if crate::daemon::cache_only() {
+        return Some(Fetched::from_daemon(cached?, cache_age));
+    }
+    let usage_url = super::client::oneapi_usage_url(base_url);
+    let client = ApiClient::new(ApiConfig {
+        enabled: true,
+        api_key: access_token.to_string(),
+        usage_url: usage_url.clone(),
+        subscription_url: String::new(),
+        auto_cookie: false,
+        cookie: None,
+        base_url: base_url.to_string(),
+        user_id,
+    })
+    .ok()?;
+
+    let result = breaker::call(&usage_url, || client.get_usage()).and_then(|usage| {
+        usage
+            .as_oneapi()
+            .cloned()
+            .ok_or_else(|| UucodeError::Vendor("unexpected usage type".to_string()))
+    });
+
+    match result {
+        Ok(fresh) => {
+            let _ = cache::save_cached_oneapi_usage(&fresh);
+            Some(Fetched::fresh(fresh))
+        }
+        Err(e) => Some(Fetched::cached(cached?, cache_age, &e)),
+    }
+}
+
+/// 获取 vendors.d 中服务商的用量：每次先请求接口，失败或熔断时回退到本地缓存；
+/// 后台 daemon 运行时只读缓存
+pub fn custom_usage(vendor: &dyn Vendor, api_key: &str) -> Option<Fetched<VendorUsage>> {
+    let usage_url = vendor.usage_url()?;
+    let cached = cache::get_cached_custom_usage(vendor.id());
+    let cache_age = cache::custom_usage_cache_age(vendor.id());
+    if crate::daemon::cache_only() {
+        return Some(Fetched::from_daemon(cached?, cache_age));
+    }
+
+    match breaker::call(usage_url, || vendor::fetch_usage(vendor, api_key)) {
+        Ok(fresh) => {
+            let _ = cache::save_cached_custom_usage(vendor.id(), &fresh);
+            Some(Fetched::fresh(fresh))
+        }
+        Err(e) => Some(Fetched::cached(cached?, cache_age, &e)),
+    }
+}
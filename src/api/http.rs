@@ -0,0 +1,233 @@
+//! 共享的 HTTP 客户端设置（config.toml 的 `[http]`）
+//!
+//! 服务商接口、汇率、告警、webhook 等所有请求都从这里创建客户端，统一应用
+//! User-Agent、附加请求头、IP 协议偏好和静态 DNS；`[http.vendors.<id>]` 中的
+//! 设置只用于该服务商的接口，并覆盖全局设置。部分中转站会拦截默认 UA 或要求
+//! 特定请求头；IPv6 不通的网络可以固定走 IPv4，避免每次连接都等到超时。
+//! 连接超时和响应超时分开设置，响应体用 [`read_text`] 读取并限制大小，
+//! 异常接口返回的超大响应不会拖住渲染。
+//!
+//! [`client`] 按服务商和超时复用同一个客户端，`--watch`、`--follow`、`serve`
+//! 等长时间运行的模式因此能复用已建立的连接（keep-alive / HTTP/2）。
+
+use super::VendorType;
+use crate::config::{HttpConfig, HttpOverrides, IpFamily};
+use crate::error::UucodeError;
+use reqwest::blocking::{Client, ClientBuilder, Response};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// 未配置 `user_agent` 时使用
+pub const DEFAULT_USER_AGENT: &str = concat!("uucode/", env!("CARGO_PKG_VERSION"));
+/// 未配置 `max_response_kb` 时的响应体上限，接口返回的 JSON 远小于此
+pub const DEFAULT_MAX_RESPONSE_KB: u64 = 1024;
+
+static CONFIG: OnceLock<HttpConfig> = OnceLock::new();
+
+/// 决定客户端配置的全部因素；请求级的密钥、Cookie 不在其中
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Fingerprint {
+    vendor: Option<VendorType>,
+    timeout: Duration,
+}
+
+static POOL: OnceLock<Mutex<HashMap<Fingerprint, Client>>> = OnceLock::new();
+
+/// 设置后所有客户端都经由该代理，`uucode simulate` 用它拦截请求
+static PROXY_OVERRIDE: OnceLock<String> = OnceLock::new();
+
+/// 让之后创建的所有客户端都走 `proxy_url`，忽略系统代理；只能设置一次，
+/// 须在第一个请求之前调用
+pub fn route_through(proxy_url: &str) {
+    let _ = PROXY_OVERRIDE.set(proxy_url.to_string());
+}
+
+/// `[http]`，不加载完整配置（段里的临时客户端拿不到 Config）
+pub fn config() -> &'static HttpConfig {
+    CONFIG.get_or_init(|| {
+        crate::utils::paths::raw_config_value("http")
+            .and_then(|value| value.try_into().ok())
+            .unwrap_or_default()
+    })
+}
+
+/// 全局设置叠加服务商设置；同名请求头（不区分大小写）和同一主机的 DNS 以服务商的为准
+///
+/// ```
+/// use mycode::api::{http::resolve, VendorType};
+/// use mycode::config::{HttpConfig, IpFamily};
+///
+/// let config: HttpConfig = toml::from_str(r#"
+///     headers = { "X-Client" = "uucode", "X-Relay" = "global" }
+///     ip_family = "ipv4"
+///     connect_timeout = 5
+///     max_response_kb = 256
+///     [vendors.cubence]
+///     connect_timeout = 2
+///     user_agent = "Mozilla/5.0"
+///     headers = { "x-relay" = "cubence" }
+///     dns = { "api.cubence.com" = "203.0.113.7" }
+/// "#).unwrap();
+///
+/// let cubence = resolve(&config, Some(VendorType::Cubence));
+/// assert_eq!(cubence.user_agent.as_deref(), Some("Mozilla/5.0"));
+/// assert_eq!(cubence.headers.len(), 2);
+/// assert_eq!(cubence.headers["x-relay"], "cubence");
+/// assert_eq!(cubence.ip_family, Some(IpFamily::Ipv4));
+/// assert_eq!(cubence.dns["api.cubence.com"], "203.0.113.7");
+/// assert_eq!(cubence.connect_timeout, Some(2.0));
+/// assert_eq!(cubence.max_response_kb, Some(256));
+///
+/// let other = resolve(&config, None);
+/// assert_eq!(other.user_agent, None);
+/// assert_eq!(other.headers["X-Relay"], "global");
+/// assert!(other.dns.is_empty());
+/// assert_eq!(other.connect_timeout, Some(5.0));
+/// ```
+pub fn resolve(config: &HttpConfig, vendor: Option<VendorType>) -> HttpOverrides {
+    let mut merged = config.global.clone();
+    // URL 中的主机名是小写的
+    merged.dns = merged
+        .dns
+        .into_iter()
+        .map(|(host, ip)| (host.to_ascii_lowercase(), ip))
+        .collect();
+    let Some(overrides) = vendor.and_then(|v| config.vendors.get(v.id())) else {
+        return merged;
+    };
+    if overrides.user_agent.is_some() {
+        merged.user_agent = overrides.user_agent.clone();
+    }
+    for (name, value) in &overrides.headers {
+        merged.headers.retain(|n, _| !n.eq_ignore_ascii_case(name));
+        merged.headers.insert(name.clone(), value.clone());
+    }
+    if overrides.ip_family.is_some() {
+        merged.ip_family = overrides.ip_family;
+    }
+    for (host, ip) in &overrides.dns {
+        merged.dns.insert(host.to_ascii_lowercase(), ip.clone());
+    }
+    merged.connect_timeout = overrides.connect_timeout.or(merged.connect_timeout);
+    merged.read_timeout = overrides.read_timeout.or(merged.read_timeout);
+    merged.max_response_kb = overrides.max_response_kb.or(merged.max_response_kb);
+    merged
+}
+
+fn header_map(overrides: &HttpOverrides) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for (name, value) in &overrides.headers {
+        match (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            (Ok(name), Ok(value)) => {
+                headers.insert(name, value);
+            }
+            _ => crate::debug_log!("ignoring invalid header in [http]: {}", name),
+        }
+    }
+    headers
+}
+
+fn seconds(secs: Option<f64>) -> Option<Duration> {
+    secs.and_then(|s| Duration::try_from_secs_f64(s).ok())
+        .filter(|d| !d.is_zero())
+}
+
+/// 应用了 `[http]` 设置的客户端构建器；`vendor` 为 `None` 时只应用全局设置。
+/// `timeout` 是调用方的默认响应超时，配置了 `read_timeout` 时以配置为准；
+/// 请求上单独设置的同名请求头优先。
+pub fn builder(vendor: Option<VendorType>, timeout: Duration) -> ClientBuilder {
+    let overrides = resolve(config(), vendor);
+    let mut builder = reqwest::blocking::Client::builder()
+        .timeout(seconds(overrides.read_timeout).unwrap_or(timeout))
+        .user_agent(
+            overrides
+                .user_agent
+                .as_deref()
+                .unwrap_or(DEFAULT_USER_AGENT),
+        )
+        .default_headers(header_map(&overrides));
+    if let Some(connect_timeout) = seconds(overrides.connect_timeout) {
+        builder = builder.connect_timeout(connect_timeout);
+    }
+    if let Some(proxy) = PROXY_OVERRIDE.get() {
+        match reqwest::Proxy::all(proxy) {
+            Ok(proxy) => builder = builder.proxy(proxy),
+            Err(e) => crate::debug_log!("ignoring invalid proxy {}: {}", proxy, e),
+        }
+    }
+
+    // 绑定某一协议的本地地址后，只连接解析结果中同协议的地址
+    match overrides.ip_family.unwrap_or_default() {
+        IpFamily::Auto => {}
+        IpFamily::Ipv4 => builder = builder.local_address(IpAddr::V4(Ipv4Addr::UNSPECIFIED)),
+        IpFamily::Ipv6 => builder = builder.local_address(IpAddr::V6(Ipv6Addr::UNSPECIFIED)),
+    }
+    for (host, ip) in &overrides.dns {
+        match ip.parse::<IpAddr>() {
+            // 端口取自 URL，这里的端口会被忽略
+            Ok(ip) => builder = builder.resolve(host, SocketAddr::new(ip, 0)),
+            Err(_) => crate::debug_log!("ignoring invalid IP for {} in [http.dns]: {}", host, ip),
+        }
+    }
+    builder
+}
+
+fn vendor_of(url: &str) -> Option<VendorType> {
+    let vendor = super::detect_vendor_from_url(url);
+    vendor.is_supported().then_some(vendor)
+}
+
+/// 按 URL 判断服务商的 [`builder`]
+pub fn builder_for_url(url: &str, timeout: Duration) -> ClientBuilder {
+    builder(vendor_of(url), timeout)
+}
+
+/// 共享的客户端：同一服务商、同一超时的请求复用一个客户端及其连接池
+pub fn client(vendor: Option<VendorType>, timeout: Duration) -> Result<Client, reqwest::Error> {
+    let key = Fingerprint { vendor, timeout };
+    let pool = POOL.get_or_init(Default::default);
+    if let Some(client) = pool.lock().ok().and_then(|p| p.get(&key).cloned()) {
+        return Ok(client);
+    }
+    let client = builder(vendor, timeout).build()?;
+    if let Ok(mut pool) = pool.lock() {
+        pool.entry(key).or_insert_with(|| client.clone());
+    }
+    Ok(client)
+}
+
+/// 按 URL 判断服务商的 [`client`]
+pub fn client_for_url(url: &str, timeout: Duration) -> Result<Client, reqwest::Error> {
+    client(vendor_of(url), timeout)
+}
+
+/// 读取文本响应体；超过 `max_response_kb` 时报错，多余部分不再读取
+pub fn read_text(response: Response) -> Result<String, UucodeError> {
+    let url = response.url().as_str();
+    let limit_kb = resolve(config(), vendor_of(url))
+        .max_response_kb
+        .unwrap_or(DEFAULT_MAX_RESPONSE_KB);
+    let limit = limit_kb.saturating_mul(1024);
+    let too_large = UucodeError::Vendor(format!(
+        "{} 的响应超过 {} KB",
+        super::breaker::endpoint(url),
+        limit_kb
+    ));
+    if response.content_length().is_some_and(|len| len > limit) {
+        return Err(too_large);
+    }
+
+    let mut body = Vec::new();
+    response.take(limit + 1).read_to_end(&mut body)?;
+    if body.len() as u64 > limit {
+        return Err(too_large);
+    }
+    String::from_utf8(body).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e).into())
+}
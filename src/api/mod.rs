@@ -1,16 +1,29 @@
+pub mod breaker;
 pub mod cache;
 pub mod client;
+pub mod cookie;
+pub mod credentials;
+pub mod exchange;
+pub mod fetch;
+pub mod http;
+pub mod schema;
+pub mod vendor;
 
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
 // ============== 厂商类型定义 ==============
 
 /// 检测当前配置的服务商类型
-#[derive(Debug, Clone, PartialEq, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
 pub enum VendorType {
     Uucode,
     Cubence,
+    /// `~/.claude/uucode/vendors.d` 中描述的服务商，见 [`vendor::current`]
+    Custom,
+    /// 自建的 one-api / new-api 网关（config.toml 的 `[oneapi]`）
+    OneApi,
     Unknown,
 }
 
@@ -20,6 +33,8 @@ impl VendorType {
         match self {
             VendorType::Uucode => "uucode",
             VendorType::Cubence => "Cubence",
+            VendorType::Custom => "自定义",
+            VendorType::OneApi => "one-api",
             VendorType::Unknown => "未知",
         }
     }
@@ -28,6 +43,29 @@ impl VendorType {
     pub fn is_supported(&self) -> bool {
         !matches!(self, VendorType::Unknown)
     }
+
+    /// 服务商标识（用于缓存目录和配置键）
+    pub fn id(&self) -> &'static str {
+        match self {
+            VendorType::Uucode => "uucode",
+            VendorType::Cubence => "cubence",
+            VendorType::Custom => "custom",
+            VendorType::OneApi => "oneapi",
+            VendorType::Unknown => "unknown",
+        }
+    }
+
+    /// 从服务商标识解析
+    pub fn from_id(id: &str) -> Option<VendorType> {
+        match id {
+            "uucode" => Some(VendorType::Uucode),
+            "cubence" => Some(VendorType::Cubence),
+            "custom" => Some(VendorType::Custom),
+            "oneapi" => Some(VendorType::OneApi),
+            "unknown" => Some(VendorType::Unknown),
+            _ => None,
+        }
+    }
 }
 
 /// 厂商 URL 模式配置
@@ -36,14 +74,16 @@ pub struct VendorUrlPatterns {
     pub vendor_type: VendorType,
     pub display_name: &'static str,
     pub url_patterns: &'static [&'static str],
+    pub usage_url: &'static str,
 }
 
-/// 支持的厂商及其 URL 模式
+/// 内置的厂商及其 URL 模式，其他厂商见 [`vendor`] 注册表
 pub const VENDOR_CONFIGS: &[VendorUrlPatterns] = &[
     VendorUrlPatterns {
         vendor_type: VendorType::Uucode,
         display_name: "uucode",
         url_patterns: &["uucode.org", "cometix.cn"],
+        usage_url: "https://api.uucode.org/account/billing",
     },
     VendorUrlPatterns {
         vendor_type: VendorType::Cubence,
@@ -55,48 +95,40 @@ pub const VENDOR_CONFIGS: &[VendorUrlPatterns] = &[
             "api-bwg.cubence.com",
             "api-cf.cubence.com",
         ],
+        usage_url: "https://cubence.com/api/v1/user/subscription-info",
     },
 ];
 
-/// 获取支持的服务商列表字符串（用于提示信息）
+/// 获取支持的服务商列表字符串（用于提示信息，含 vendors.d 中的服务商）
 pub fn get_supported_vendors_str() -> String {
-    VENDOR_CONFIGS
+    vendor::registry()
         .iter()
-        .map(|v| v.display_name)
+        .map(|v| v.display_name())
         .collect::<Vec<_>>()
         .join(", ")
 }
 
 /// 获取所有支持的 URL 模式字符串（用于详细提示）
 pub fn get_all_supported_urls_str() -> String {
-    VENDOR_CONFIGS
+    vendor::registry()
         .iter()
-        .flat_map(|v| v.url_patterns.iter())
-        .copied()
+        .flat_map(|v| v.url_patterns())
         .collect::<Vec<_>>()
         .join(", ")
 }
 
 /// 根据 URL 检测厂商类型
 pub fn detect_vendor_from_url(url: &str) -> VendorType {
-    for config in VENDOR_CONFIGS {
-        for pattern in config.url_patterns {
-            if url.contains(pattern) {
-                return config.vendor_type;
-            }
-        }
-    }
-    VendorType::Unknown
+    vendor::for_url(url)
+        .map(|v| v.vendor_type())
+        .unwrap_or(VendorType::Unknown)
 }
 
 /// 检查 URL 是否属于指定厂商
 pub fn url_matches_vendor(url: &str, vendor: &VendorType) -> bool {
-    for config in VENDOR_CONFIGS {
-        if &config.vendor_type == vendor {
-            return config.url_patterns.iter().any(|p| url.contains(p));
-        }
-    }
-    false
+    vendor::registry()
+        .iter()
+        .any(|v| &v.vendor_type() == vendor && v.matches(url))
 }
 
 // ============== API 配置 ==============
@@ -113,6 +145,12 @@ pub struct ApiConfig {
     /// 手动配置的 Cookie（优先级高于自动读取）
     #[serde(default)]
     pub cookie: Option<String>,
+    /// one-api / new-api 网关地址，设置后按其 `/api/user/self` 请求用量
+    #[serde(default)]
+    pub base_url: String,
+    /// new-api 要求的 `New-Api-User` 请求头
+    #[serde(default)]
+    pub user_id: Option<u64>,
 }
 
 impl Default for ApiConfig {
@@ -124,6 +162,8 @@ impl Default for ApiConfig {
             subscription_url: String::new(),
             auto_cookie: false,
             cookie: None,
+            base_url: String::new(),
+            user_id: None,
         }
     }
 }
@@ -134,6 +174,8 @@ pub enum UsageData {
     NewVendor(NewVendorData),
     /// Cubence 计费接口
     Cubence(CubenceData),
+    /// one-api / new-api 用户信息接口
+    OneApi(OneApiData),
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -166,6 +208,7 @@ impl UsageData {
         match self {
             UsageData::NewVendor(data) => data.calculate(),
             UsageData::Cubence(data) => data.calculate(),
+            UsageData::OneApi(_) => {}
         }
     }
 
@@ -173,6 +216,7 @@ impl UsageData {
         match self {
             UsageData::NewVendor(data) => data.is_exhausted(),
             UsageData::Cubence(data) => data.is_exhausted(),
+            UsageData::OneApi(data) => data.is_exhausted(),
         }
     }
 
@@ -181,6 +225,7 @@ impl UsageData {
             UsageData::NewVendor(data) => data.used_tokens,
             // Cubence 使用5小时窗口的已用量
             UsageData::Cubence(data) => data.five_hour_used as u64,
+            UsageData::OneApi(data) => data.used_quota.max(0) as u64,
         }
     }
 
@@ -189,6 +234,7 @@ impl UsageData {
             UsageData::NewVendor(data) => data.remaining_tokens,
             // Cubence 使用5小时窗口的剩余量
             UsageData::Cubence(data) => data.five_hour_remaining as u64,
+            UsageData::OneApi(data) => data.quota.max(0) as u64,
         }
     }
 
@@ -197,6 +243,7 @@ impl UsageData {
             UsageData::NewVendor(data) => data.credit_limit,
             // Cubence 返回账户余额
             UsageData::Cubence(data) => data.balance_usd,
+            UsageData::OneApi(data) => data.total_usd(),
         }
     }
 
@@ -250,6 +297,14 @@ impl UsageData {
             _ => None,
         }
     }
+
+    /// 获取 one-api 数据（如果是 one-api 类型）
+    pub fn as_oneapi(&self) -> Option<&OneApiData> {
+        match self {
+            UsageData::OneApi(data) => Some(data),
+            _ => None,
+        }
+    }
 }
 
 impl NewVendorData {
@@ -422,36 +477,75 @@ impl CubenceData {
         self.is_five_hour_exhausted() && self.is_weekly_exhausted()
     }
 
-    /// 格式化 token 数量为美元字符串（1M = $1）
+    /// 格式化 token 数量为美元字符串（1M = $1），使用默认格式
     pub fn format_tokens(tokens: i64) -> String {
-        if tokens >= 1_000_000 {
-            format!("${:.1}", tokens as f64 / 1_000_000.0)
-        } else if tokens >= 1_000 {
-            format!("${:.2}", tokens as f64 / 1_000_000.0)
-        } else {
-            format!("${:.3}", tokens as f64 / 1_000_000.0)
-        }
+        crate::utils::format::tokens_as_money(tokens, &crate::config::FormatConfig::default())
     }
 
     /// 计算重置剩余时间（秒）
     pub fn get_five_hour_reset_seconds(&self) -> i64 {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map(|d| d.as_secs() as i64)
-            .unwrap_or(0);
+        let now = crate::utils::clock::unix_now();
         (self.five_hour_reset_at - now).max(0)
     }
 
     /// 计算周重置剩余时间（秒）
     pub fn get_weekly_reset_seconds(&self) -> i64 {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map(|d| d.as_secs() as i64)
-            .unwrap_or(0);
+        let now = crate::utils::clock::unix_now();
         (self.weekly_reset_at - now).max(0)
     }
 }
 
+// ============== one-api 数据结构 ==============
+
+/// one-api / new-api `/api/user/self` 中的额度信息
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct OneApiData {
+    #[serde(default)]
+    pub username: String,
+    /// 剩余额度（quota 单位）
+    pub quota: i64,
+    /// 已用额度（quota 单位）
+    #[serde(default)]
+    pub used_quota: i64,
+    #[serde(default)]
+    pub request_count: i64,
+    /// 用户分组，决定计费倍率
+    #[serde(default)]
+    pub group: String,
+}
+
+impl OneApiData {
+    /// one-api 默认的 QuotaPerUnit：500000 quota = $1
+    pub const QUOTA_PER_USD: f64 = 500_000.0;
+
+    pub fn remaining_usd(&self) -> f64 {
+        self.quota.max(0) as f64 / Self::QUOTA_PER_USD
+    }
+
+    pub fn used_usd(&self) -> f64 {
+        self.used_quota.max(0) as f64 / Self::QUOTA_PER_USD
+    }
+
+    /// 已用 + 剩余
+    pub fn total_usd(&self) -> f64 {
+        self.used_usd() + self.remaining_usd()
+    }
+
+    /// 已用额度百分比
+    pub fn percentage_used(&self) -> f64 {
+        let total = self.total_usd();
+        if total > 0.0 {
+            (self.used_usd() / total * 100.0).clamp(0.0, 100.0)
+        } else {
+            0.0
+        }
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.quota <= 0
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SubscriptionData {
     #[serde(rename = "subscriptionPlanName")]
@@ -482,45 +576,59 @@ impl SubscriptionData {
     }
 }
 
-/// Claude settings.json structure for reading API key
-#[derive(Debug, Deserialize)]
-struct ClaudeSettings {
-    env: Option<ClaudeEnv>,
-}
-
-#[derive(Debug, Deserialize)]
-struct ClaudeEnv {
-    #[serde(rename = "ANTHROPIC_AUTH_TOKEN")]
-    auth_token: Option<String>,
-    #[serde(rename = "ANTHROPIC_BASE_URL")]
-    base_url: Option<String>,
-}
+/// settings.json 解析结果及其对应的文件标记（文件不存在时为 None），标记变化后重新读取
+static CLAUDE_SETTINGS: Mutex<Option<(Option<cache::SettingsStamp>, Arc<serde_json::Value>)>> =
+    Mutex::new(None);
 
 /// Get the path to Claude settings.json (cross-platform)
-fn get_claude_settings_path() -> Option<PathBuf> {
-    dirs::home_dir().map(|home| home.join(".claude").join("settings.json"))
+pub fn get_claude_settings_path() -> Option<PathBuf> {
+    crate::utils::paths::claude_settings_path()
 }
 
-/// Read API key from Claude settings.json for supported vendors
-/// 支持所有在 VENDOR_CONFIGS 中配置的厂商
-pub fn get_api_key_from_claude_settings() -> Option<String> {
-    let settings_path = get_claude_settings_path()?;
+/// 解析 Claude settings.json（支持注释和尾随逗号），结果按文件的修改时间和大小在进程内缓存
+/// 文件不存在时视为空配置；解析失败不缓存，原因会输出到 --debug 并返回给 doctor
+pub fn load_claude_settings() -> Result<Arc<serde_json::Value>, String> {
+    let stamp = cache::SettingsStamp::current();
+    if let Ok(cached) = CLAUDE_SETTINGS.lock() {
+        if let Some((_, settings)) = cached.as_ref().filter(|(s, _)| *s == stamp) {
+            return Ok(settings.clone());
+        }
+    }
 
-    if !settings_path.exists() {
-        return None;
+    let settings = Arc::new(read_claude_settings().inspect_err(|e| crate::debug_log!("{}", e))?);
+    if let Ok(mut cached) = CLAUDE_SETTINGS.lock() {
+        *cached = Some((stamp, settings.clone()));
     }
+    Ok(settings)
+}
 
-    let content = std::fs::read_to_string(settings_path).ok()?;
-    let settings: ClaudeSettings = serde_json::from_str(&content).ok()?;
+fn read_claude_settings() -> Result<serde_json::Value, String> {
+    let path = get_claude_settings_path().ok_or("无法定位 home 目录")?;
+    if !path.exists() {
+        return Ok(serde_json::json!({}));
+    }
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?;
+    crate::utils::jsonc::parse(&content).map_err(|e| format!("解析 {} 失败: {}", path.display(), e))
+}
 
-    let env = settings.env?;
+/// 读取 Claude settings.json 中 env 块的任意变量
+pub fn get_claude_env_var(name: &str) -> Option<String> {
+    load_claude_settings()
+        .ok()?
+        .get("env")?
+        .get(name)?
+        .as_str()
+        .map(|s| s.to_string())
+}
 
+/// Read API key from Claude settings.json for supported vendors
+/// 支持注册表中的所有厂商（含 vendors.d）
+pub fn get_api_key_from_claude_settings() -> Option<String> {
     // 当 ANTHROPIC_BASE_URL 指向支持的厂商时读取
-    if let Some(base_url) = env.base_url {
-        let vendor = detect_vendor_from_url(&base_url);
-        if vendor.is_supported() {
-            return env.auth_token;
-        }
+    let base_url = get_current_base_url()?;
+    if detect_vendor_from_url(&base_url).is_supported() {
+        return get_claude_env_var("ANTHROPIC_AUTH_TOKEN");
     }
 
     None
@@ -528,22 +636,10 @@ pub fn get_api_key_from_claude_settings() -> Option<String> {
 
 /// Read API key from Claude settings.json specifically for Cubence
 pub fn get_cubence_api_key_from_claude_settings() -> Option<String> {
-    let settings_path = get_claude_settings_path()?;
-
-    if !settings_path.exists() {
-        return None;
-    }
-
-    let content = std::fs::read_to_string(settings_path).ok()?;
-    let settings: ClaudeSettings = serde_json::from_str(&content).ok()?;
-
-    let env = settings.env?;
-
     // 仅当 ANTHROPIC_BASE_URL 指向 Cubence 时读取
-    if let Some(base_url) = env.base_url {
-        if url_matches_vendor(&base_url, &VendorType::Cubence) {
-            return env.auth_token;
-        }
+    let base_url = get_current_base_url()?;
+    if url_matches_vendor(&base_url, &VendorType::Cubence) {
+        return get_claude_env_var("ANTHROPIC_AUTH_TOKEN");
     }
 
     None
@@ -551,68 +647,60 @@ pub fn get_cubence_api_key_from_claude_settings() -> Option<String> {
 
 /// Get usage_url from Claude settings.json based on ANTHROPIC_BASE_URL
 pub fn get_usage_url_from_claude_settings() -> Option<String> {
-    let settings_path = get_claude_settings_path()?;
-
-    if !settings_path.exists() {
-        return None;
-    }
-
-    let content = std::fs::read_to_string(settings_path).ok()?;
-    let settings = serde_json::from_str::<serde_json::Value>(&content).ok()?;
-
-    let base_url = settings.get("env")?.get("ANTHROPIC_BASE_URL")?.as_str()?;
+    let base_url = get_current_base_url()?;
 
-    let vendor = detect_vendor_from_url(base_url);
-    match vendor {
-        VendorType::Uucode => Some("https://api.uucode.org/account/billing".to_string()),
-        VendorType::Cubence => {
-            Some("https://cubence.com/api/v1/user/subscription-info".to_string())
-        }
-        VendorType::Unknown => None,
-    }
+    vendor::for_url(&base_url)?.usage_url().map(str::to_string)
 }
 
+/// 检测到的服务商及其所依据的 settings.json 标记
+static DETECTED_VENDOR: Mutex<Option<(Option<cache::SettingsStamp>, VendorType)>> =
+    Mutex::new(None);
+
 /// 从 Claude settings.json 检测服务商类型
+///
+/// 结果按 settings.json 的修改时间在进程内缓存并缓存到文件，
+/// settings.json 未变化时后续渲染无需再读取和解析它，变化后重新检测
 pub fn detect_vendor_from_claude_settings() -> VendorType {
-    let settings_path = match get_claude_settings_path() {
-        Some(p) => p,
-        None => return VendorType::Unknown,
-    };
-
-    if !settings_path.exists() {
-        return VendorType::Unknown;
+    // 进程环境变量优先，与 settings.json 无关
+    if let Some(url) = std::env::var("ANTHROPIC_BASE_URL")
+        .ok()
+        .filter(|s| !s.is_empty())
+    {
+        return detect_vendor_from_url(&url);
+    }
+
+    let stamp = cache::SettingsStamp::current();
+    if let Ok(detected) = DETECTED_VENDOR.lock() {
+        if let Some((_, vendor)) = detected.as_ref().filter(|(s, _)| *s == stamp) {
+            return *vendor;
+        }
     }
 
-    let content = match std::fs::read_to_string(&settings_path) {
-        Ok(c) => c,
-        Err(_) => return VendorType::Unknown,
-    };
-
-    let settings: serde_json::Value = match serde_json::from_str(&content) {
-        Ok(s) => s,
-        Err(_) => return VendorType::Unknown,
+    let vendor = match stamp.as_ref().and_then(cache::get_cached_vendor) {
+        Some(vendor) => vendor,
+        None => {
+            // 使用新的多 URL 模式检测
+            let vendor = detect_vendor_from_url(&get_current_base_url().unwrap_or_default());
+            if let Some(stamp) = &stamp {
+                if let Err(e) = cache::save_cached_vendor(stamp, vendor) {
+                    crate::debug_log!("failed to cache vendor detection: {}", e);
+                }
+            }
+            vendor
+        }
     };
-
-    let base_url = settings
-        .get("env")
-        .and_then(|e| e.get("ANTHROPIC_BASE_URL"))
-        .and_then(|u| u.as_str())
-        .unwrap_or("");
-
-    // 使用新的多 URL 模式检测
-    detect_vendor_from_url(base_url)
+    if let Ok(mut detected) = DETECTED_VENDOR.lock() {
+        *detected = Some((stamp, vendor));
+    }
+    vendor
 }
 
-/// 获取当前 base_url（用于错误提示）
+/// 获取当前 base_url：进程环境变量优先，其次 settings.json
 pub fn get_current_base_url() -> Option<String> {
-    let settings_path = get_claude_settings_path()?;
-    let content = std::fs::read_to_string(&settings_path).ok()?;
-    let settings: serde_json::Value = serde_json::from_str(&content).ok()?;
-    settings
-        .get("env")?
-        .get("ANTHROPIC_BASE_URL")?
-        .as_str()
-        .map(|s| s.to_string())
+    std::env::var("ANTHROPIC_BASE_URL")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .or_else(|| get_claude_env_var("ANTHROPIC_BASE_URL"))
 }
 
 /// 检查当前服务商是否支持，返回错误信息（如果不支持）
@@ -623,7 +711,7 @@ pub fn check_vendor_support() -> Result<VendorType, String> {
     } else {
         let current_url = get_current_base_url().unwrap_or_else(|| "未配置".to_string());
         Err(format!(
-            "mycode 不支持当前厂商，请检查你的 ANTHROPIC_BASE_URL。当前: {}，支持: {}",
+            "mycode 不支持当前厂商，请检查你的 ANTHROPIC_BASE_URL 或运行 `uucode vendor switch <name>`。当前: {}，支持: {}",
             current_url,
             get_supported_vendors_str()
         ))
@@ -0,0 +1,145 @@
+//! 版本化的响应解析
+//!
+//! 服务商调整接口结构时，先尝试当前结构，再依次尝试已知的旧结构和宽松解析；
+//! 全部失败时返回 [`SchemaMismatch`]，调用方据此回退到缓存并标记数据已过期。
+
+use super::{CubenceData, CubenceResponse, NewVendorData, NewVendorResponse};
+use serde_json::Value;
+use std::fmt;
+
+/// 所有已知结构都无法解析响应
+#[derive(Debug)]
+pub struct SchemaMismatch {
+    pub vendor: &'static str,
+    pub detail: String,
+}
+
+impl fmt::Display for SchemaMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} 接口结构不匹配: {}", self.vendor, self.detail)
+    }
+}
+
+impl std::error::Error for SchemaMismatch {}
+
+/// 解析结果及命中的结构版本
+#[derive(Debug)]
+pub struct Parsed<T> {
+    pub data: T,
+    pub version: &'static str,
+}
+
+impl<T> Parsed<T> {
+    /// 是否使用了当前结构以外的解析器
+    pub fn is_fallback(&self) -> bool {
+        self.version != "current"
+    }
+}
+
+type Parser<T> = fn(&str) -> Result<T, String>;
+
+fn try_parsers<T>(
+    vendor: &'static str,
+    text: &str,
+    parsers: &[(&'static str, Parser<T>)],
+) -> Result<Parsed<T>, SchemaMismatch> {
+    let mut errors = Vec::new();
+    for (version, parser) in parsers {
+        match parser(text) {
+            Ok(data) => {
+                if *version != "current" {
+                    crate::debug_log!("{} response parsed with '{}' schema", vendor, version);
+                }
+                return Ok(Parsed { data, version });
+            }
+            Err(e) => errors.push(format!("{}: {}", version, e)),
+        }
+    }
+    Err(SchemaMismatch {
+        vendor,
+        detail: errors.join("; "),
+    })
+}
+
+/// 解析 uucode /account/billing 响应
+///
+/// ```
+/// use mycode::api::schema::parse_uucode_usage;
+///
+/// // 数字金额、缺少可选字段：由宽松解析兜底
+/// let parsed = parse_uucode_usage(r#"{"data":{"usage_usd":1.5,"limit_usd":"20"}}"#).unwrap();
+/// assert_eq!(parsed.version, "lenient");
+/// assert_eq!(parsed.data.usage_usd, "1.5");
+///
+/// assert!(parse_uucode_usage(r#"{"error":"gone"}"#).is_err());
+/// ```
+pub fn parse_uucode_usage(text: &str) -> Result<Parsed<NewVendorData>, SchemaMismatch> {
+    try_parsers(
+        "uucode",
+        text,
+        &[
+            ("current", |t| {
+                serde_json::from_str::<NewVendorResponse>(t)
+                    .map(|r| r.data)
+                    .map_err(|e| e.to_string())
+            }),
+            // 早期接口没有 data 包裹层
+            ("flat", |t| {
+                serde_json::from_str::<NewVendorData>(t).map_err(|e| e.to_string())
+            }),
+            ("lenient", parse_uucode_lenient),
+        ],
+    )
+}
+
+/// 宽松解析：金额字段接受数字或字符串，非关键字段缺失时使用默认值
+fn parse_uucode_lenient(text: &str) -> Result<NewVendorData, String> {
+    let root: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    let obj = root.get("data").unwrap_or(&root);
+
+    let amount = |key: &str| -> Option<String> {
+        match obj.get(key)? {
+            Value::String(s) => Some(s.clone()),
+            Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        }
+    };
+
+    let mut data = NewVendorData::default_placeholder();
+    data.usage_usd = amount("usage_usd").ok_or("missing usage_usd")?;
+    data.limit_usd = amount("limit_usd").ok_or("missing limit_usd")?;
+    data.payg_balance_usd = amount("payg_balance_usd").unwrap_or_default();
+    data.subscription_name = obj
+        .get("subscription_name")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+    data.remaining_seconds = obj
+        .get("remaining_seconds")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(0);
+    Ok(data)
+}
+
+/// 解析 Cubence subscription-info 响应
+pub fn parse_cubence_usage(text: &str) -> Result<Parsed<CubenceData>, SchemaMismatch> {
+    try_parsers(
+        "Cubence",
+        text,
+        &[
+            ("current", |t| {
+                serde_json::from_str::<CubenceResponse>(t)
+                    .map(CubenceData::from_response)
+                    .map_err(|e| e.to_string())
+            }),
+            // 统一响应格式 { "data": { ... } }
+            ("wrapped", |t| {
+                let root: Value = serde_json::from_str(t).map_err(|e| e.to_string())?;
+                let data = root.get("data").cloned().ok_or("missing data")?;
+                serde_json::from_value::<CubenceResponse>(data)
+                    .map(CubenceData::from_response)
+                    .map_err(|e| e.to_string())
+            }),
+        ],
+    )
+}
@@ -0,0 +1,524 @@
+//! 服务商注册表
+//!
+//! 每个服务商实现 [`Vendor`]：用哪些 URL 识别、如何携带密钥、用量接口地址以及
+//! 如何把响应解析为统一的 [`VendorUsage`]。uucode 和 Cubence 内置；其他中转站
+//! 可以在 `~/.claude/uucode/vendors.d/*.toml` 中描述，无需重新编译：
+//!
+//! ```toml
+//! id = "acme"
+//! name = "Acme Relay"
+//! url_patterns = ["acme-relay.com"]
+//! usage_url = "https://acme-relay.com/api/usage"
+//! auth = "header"            # bearer（默认）/ raw / header
+//! auth_header = "X-API-Key"  # auth = "header" 时使用
+//!
+//! # 响应字段的 JSON Pointer，金额乘以 scale 后按美元显示
+//! [usage]
+//! used = "/data/used"
+//! limit = "/data/limit"
+//! balance = "/data/balance"
+//! resets_at = "/data/reset_at"
+//! plan = "/data/plan_name"
+//! scale = 1.0
+//! ```
+//!
+//! 识别时先匹配内置服务商和 config.toml `[oneapi]` 配置的网关，再按文件名顺序
+//! 匹配 vendors.d 中的服务商；与内置服务商同名的文件会被忽略。vendors.d 中的服务商识别为 [`VendorType::Custom`]，
+//! 用量由 `vendor_usage` 段显示。
+
+use super::{VendorType, VendorUrlPatterns, VENDOR_CONFIGS};
+use crate::error::UucodeError;
+use reqwest::blocking::RequestBuilder;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+const USAGE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 请求用量接口时携带密钥的方式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthStyle {
+    /// `Authorization: Bearer <key>`
+    Bearer,
+    /// `Authorization: <key>`
+    Raw,
+    /// 自定义请求头，例如 `X-API-Key: <key>`
+    Header(String),
+}
+
+impl AuthStyle {
+    /// 把密钥加到请求上
+    pub fn apply(&self, request: RequestBuilder, api_key: &str) -> RequestBuilder {
+        match self {
+            AuthStyle::Bearer => request.header("Authorization", format!("Bearer {}", api_key)),
+            AuthStyle::Raw => request.header("Authorization", api_key),
+            AuthStyle::Header(name) => request.header(name.as_str(), api_key),
+        }
+    }
+}
+
+/// 统一的用量数据，金额单位为美元
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VendorUsage {
+    /// 当前周期已用
+    pub used: f64,
+    /// 当前周期额度
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<f64>,
+    /// 账户余额
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance: Option<f64>,
+    /// 额度重置时间（Unix 秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resets_at: Option<i64>,
+    /// 套餐名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan: Option<String>,
+}
+
+impl VendorUsage {
+    /// 已用额度百分比，没有额度时返回 None
+    pub fn percentage(&self) -> Option<f64> {
+        self.limit
+            .filter(|limit| *limit > 0.0)
+            .map(|limit| (self.used / limit * 100.0).clamp(0.0, 100.0))
+    }
+}
+
+/// 一个服务商：如何识别、如何请求用量接口、如何解析响应
+pub trait Vendor: Send + Sync {
+    /// 标识，用于缓存目录和配置键
+    fn id(&self) -> &str;
+    fn display_name(&self) -> &str;
+    /// ANTHROPIC_BASE_URL 包含其中任一项即视为该服务商
+    fn url_patterns(&self) -> Vec<&str>;
+    fn auth(&self) -> AuthStyle;
+    /// 用量接口地址，没有时不显示用量
+    fn usage_url(&self) -> Option<&str>;
+    fn parse_usage(&self, body: &str) -> Result<VendorUsage, UucodeError>;
+
+    /// 识别出的服务商类型，vendors.d 中的服务商都是 [`VendorType::Custom`]
+    fn vendor_type(&self) -> VendorType {
+        VendorType::Custom
+    }
+
+    fn matches(&self, url: &str) -> bool {
+        self.url_patterns()
+            .iter()
+            .any(|pattern| !pattern.is_empty() && url.contains(pattern))
+    }
+}
+
+// ============== 内置服务商 ==============
+
+/// [`VENDOR_CONFIGS`] 中的内置服务商
+struct Builtin(&'static VendorUrlPatterns);
+
+impl Vendor for Builtin {
+    fn id(&self) -> &str {
+        self.0.vendor_type.id()
+    }
+
+    fn display_name(&self) -> &str {
+        self.0.display_name
+    }
+
+    fn url_patterns(&self) -> Vec<&str> {
+        self.0.url_patterns.to_vec()
+    }
+
+    fn auth(&self) -> AuthStyle {
+        match self.0.vendor_type {
+            VendorType::Cubence => AuthStyle::Raw,
+            _ => AuthStyle::Header("X-API-Key".to_string()),
+        }
+    }
+
+    fn usage_url(&self) -> Option<&str> {
+        Some(self.0.usage_url)
+    }
+
+    fn parse_usage(&self, body: &str) -> Result<VendorUsage, UucodeError> {
+        match self.0.vendor_type {
+            VendorType::Cubence => {
+                let data = super::schema::parse_cubence_usage(body)?.data;
+                Ok(VendorUsage {
+                    // 1M tokens = $1
+                    used: data.five_hour_used as f64 / 1_000_000.0,
+                    limit: Some(data.five_hour_limit as f64 / 1_000_000.0),
+                    balance: Some(data.balance_usd),
+                    resets_at: Some(data.five_hour_reset_at).filter(|t| *t > 0),
+                    plan: None,
+                })
+            }
+            _ => {
+                let data = super::schema::parse_uucode_usage(body)?.data;
+                let amount = |s: &str| s.parse::<f64>().ok();
+                Ok(VendorUsage {
+                    used: amount(&data.usage_usd).unwrap_or(0.0),
+                    limit: amount(&data.limit_usd),
+                    balance: amount(&data.payg_balance_usd),
+                    resets_at: (data.remaining_seconds > 0)
+                        .then(|| crate::utils::clock::unix_now() + data.remaining_seconds),
+                    plan: Some(data.subscription_name).filter(|s| !s.is_empty()),
+                })
+            }
+        }
+    }
+
+    fn vendor_type(&self) -> VendorType {
+        self.0.vendor_type
+    }
+}
+
+// ============== one-api 网关 ==============
+
+/// config.toml `[oneapi]` 配置的自建 one-api / new-api 网关
+struct OneApi {
+    /// 去掉协议和末尾斜杠的网关地址，用于匹配 ANTHROPIC_BASE_URL
+    host: String,
+    usage_url: String,
+}
+
+impl OneApi {
+    fn from_config() -> Option<Self> {
+        let config: crate::config::OneApiConfig = crate::utils::paths::raw_config_value("oneapi")?
+            .try_into()
+            .ok()?;
+        let base_url = config.base_url.trim().trim_end_matches('/');
+        let host = base_url
+            .split_once("://")
+            .map_or(base_url, |(_, rest)| rest);
+        if host.is_empty() {
+            return None;
+        }
+        Some(Self {
+            host: host.to_string(),
+            usage_url: super::client::oneapi_usage_url(base_url),
+        })
+    }
+}
+
+impl Vendor for OneApi {
+    fn id(&self) -> &str {
+        VendorType::OneApi.id()
+    }
+
+    fn display_name(&self) -> &str {
+        VendorType::OneApi.display_name()
+    }
+
+    fn url_patterns(&self) -> Vec<&str> {
+        vec![self.host.as_str()]
+    }
+
+    fn auth(&self) -> AuthStyle {
+        AuthStyle::Bearer
+    }
+
+    fn usage_url(&self) -> Option<&str> {
+        Some(&self.usage_url)
+    }
+
+    fn parse_usage(&self, body: &str) -> Result<VendorUsage, UucodeError> {
+        let data = super::client::parse_oneapi_self(body)?;
+        Ok(VendorUsage {
+            used: data.used_usd(),
+            limit: Some(data.total_usd()),
+            balance: Some(data.remaining_usd()),
+            resets_at: None,
+            plan: Some(data.group).filter(|g| !g.is_empty()),
+        })
+    }
+
+    fn vendor_type(&self) -> VendorType {
+        VendorType::OneApi
+    }
+}
+
+// ============== vendors.d 中的服务商 ==============
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum AuthKind {
+    #[default]
+    Bearer,
+    Raw,
+    Header,
+}
+
+fn default_scale() -> f64 {
+    1.0
+}
+
+/// `[usage]`：各字段在响应中的 JSON Pointer
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UsageMapping {
+    pub used: String,
+    #[serde(default)]
+    pub limit: Option<String>,
+    #[serde(default)]
+    pub balance: Option<String>,
+    #[serde(default)]
+    pub resets_at: Option<String>,
+    #[serde(default)]
+    pub plan: Option<String>,
+    /// 金额换算为美元的倍数，例如接口以分为单位时为 0.01
+    #[serde(default = "default_scale")]
+    pub scale: f64,
+}
+
+/// vendors.d 中一个 TOML 文件描述的服务商
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TomlVendor {
+    pub id: String,
+    pub name: String,
+    pub url_patterns: Vec<String>,
+    #[serde(default)]
+    pub usage_url: Option<String>,
+    #[serde(default)]
+    auth: AuthKind,
+    #[serde(default)]
+    auth_header: Option<String>,
+    #[serde(default)]
+    pub usage: Option<UsageMapping>,
+}
+
+/// 数字或数字字符串
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Unix 秒、Unix 毫秒或 RFC 3339 时间
+fn timestamp(value: &Value) -> Option<i64> {
+    if let Value::String(s) = value {
+        if let Ok(time) = chrono::DateTime::parse_from_rfc3339(s.trim()) {
+            return Some(time.timestamp());
+        }
+    }
+    let secs = number(value)? as i64;
+    Some(if secs > 100_000_000_000 {
+        secs / 1000
+    } else {
+        secs
+    })
+}
+
+impl TomlVendor {
+    /// 解析并校验 vendors.d 中的一个文件
+    ///
+    /// ```
+    /// use mycode::api::vendor::{TomlVendor, Vendor};
+    ///
+    /// let vendor = TomlVendor::parse(r#"
+    ///     id = "acme"
+    ///     name = "Acme Relay"
+    ///     url_patterns = ["acme-relay.com"]
+    ///     usage_url = "https://acme-relay.com/api/usage"
+    ///     [usage]
+    ///     used = "/data/used_cents"
+    ///     limit = "/data/quota_cents"
+    ///     resets_at = "/data/reset_at"
+    ///     plan = "/data/plan"
+    ///     scale = 0.01
+    /// "#).unwrap();
+    /// assert!(vendor.matches("https://api.acme-relay.com/v1"));
+    ///
+    /// let usage = vendor
+    ///     .parse_usage(r#"{"data":{"used_cents":"1250","quota_cents":5000,
+    ///                  "reset_at":"2026-01-01T00:00:00Z","plan":"Pro"}}"#)
+    ///     .unwrap();
+    /// assert_eq!(usage.used, 12.5);
+    /// assert_eq!(usage.limit, Some(50.0));
+    /// assert_eq!(usage.percentage(), Some(25.0));
+    /// assert_eq!(usage.resets_at, Some(1767225600));
+    /// assert_eq!(usage.plan.as_deref(), Some("Pro"));
+    ///
+    /// assert!(vendor.parse_usage(r#"{"data":{}}"#).is_err());
+    /// assert!(TomlVendor::parse(r#"id = "acme""#).is_err());
+    /// ```
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let vendor: TomlVendor = toml::from_str(text).map_err(|e| e.to_string())?;
+        if vendor.id.is_empty()
+            || !vendor
+                .id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!("id 只能包含字母、数字、- 和 _: {:?}", vendor.id));
+        }
+        if vendor.url_patterns.iter().all(|p| p.is_empty()) {
+            return Err("url_patterns 不能为空".to_string());
+        }
+        if vendor.auth == AuthKind::Header && vendor.auth_header.is_none() {
+            return Err("auth = \"header\" 时需要设置 auth_header".to_string());
+        }
+        if vendor.usage_url.is_some() && vendor.usage.is_none() {
+            return Err("设置了 usage_url 时需要 [usage] 字段映射".to_string());
+        }
+        Ok(vendor)
+    }
+}
+
+impl Vendor for TomlVendor {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn display_name(&self) -> &str {
+        &self.name
+    }
+
+    fn url_patterns(&self) -> Vec<&str> {
+        self.url_patterns.iter().map(String::as_str).collect()
+    }
+
+    fn auth(&self) -> AuthStyle {
+        match self.auth {
+            AuthKind::Bearer => AuthStyle::Bearer,
+            AuthKind::Raw => AuthStyle::Raw,
+            AuthKind::Header => AuthStyle::Header(self.auth_header.clone().unwrap_or_default()),
+        }
+    }
+
+    fn usage_url(&self) -> Option<&str> {
+        self.usage_url.as_deref()
+    }
+
+    fn parse_usage(&self, body: &str) -> Result<VendorUsage, UucodeError> {
+        let mapping = self
+            .usage
+            .as_ref()
+            .ok_or_else(|| UucodeError::Config(format!("{} 没有 [usage] 字段映射", self.id)))?;
+        let root: Value = serde_json::from_str(body)
+            .map_err(|e| UucodeError::Vendor(format!("{} 用量响应不是 JSON: {}", self.name, e)))?;
+        let field = |pointer: &Option<String>| pointer.as_deref().and_then(|p| root.pointer(p));
+        let amount =
+            |pointer: &Option<String>| field(pointer).and_then(number).map(|n| n * mapping.scale);
+
+        let used = root
+            .pointer(&mapping.used)
+            .and_then(number)
+            .ok_or_else(|| {
+                UucodeError::Vendor(format!("{} 用量响应中没有 {}", self.name, mapping.used))
+            })?;
+        Ok(VendorUsage {
+            used: used * mapping.scale,
+            limit: amount(&mapping.limit),
+            balance: amount(&mapping.balance),
+            resets_at: field(&mapping.resets_at).and_then(timestamp),
+            plan: field(&mapping.plan)
+                .and_then(Value::as_str)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string),
+        })
+    }
+}
+
+// ============== 注册表 ==============
+
+/// 自定义服务商目录: ~/.claude/uucode/vendors.d
+pub fn vendors_dir() -> Option<PathBuf> {
+    crate::utils::paths::uucode_dir().map(|dir| dir.join("vendors.d"))
+}
+
+/// 读取目录中的 `*.toml`（按文件名排序），返回成功解析的服务商和每个失败文件的原因
+pub fn load_dir(dir: &Path) -> (Vec<TomlVendor>, Vec<String>) {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+
+    let mut vendors: Vec<TomlVendor> = Vec::new();
+    let mut errors = Vec::new();
+    for path in files {
+        let name = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        let parsed = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| TomlVendor::parse(&text));
+        match parsed {
+            Ok(vendor) if VendorType::from_id(&vendor.id).is_some() => {
+                errors.push(format!("{}: id {} 与内置服务商重名", name, vendor.id));
+            }
+            Ok(vendor) if vendors.iter().any(|v| v.id == vendor.id) => {
+                errors.push(format!("{}: id {} 重复", name, vendor.id));
+            }
+            Ok(vendor) => vendors.push(vendor),
+            Err(e) => errors.push(format!("{}: {}", name, e)),
+        }
+    }
+    (vendors, errors)
+}
+
+static REGISTRY: OnceLock<Vec<Box<dyn Vendor>>> = OnceLock::new();
+
+/// 内置服务商和 vendors.d 中的服务商，每个进程只加载一次
+pub fn registry() -> &'static [Box<dyn Vendor>] {
+    REGISTRY.get_or_init(|| {
+        let mut vendors: Vec<Box<dyn Vendor>> = VENDOR_CONFIGS
+            .iter()
+            .map(|config| Box::new(Builtin(config)) as Box<dyn Vendor>)
+            .collect();
+        if let Some(oneapi) = OneApi::from_config() {
+            vendors.push(Box::new(oneapi));
+        }
+        if let Some(dir) = vendors_dir() {
+            let (custom, errors) = load_dir(&dir);
+            for error in errors {
+                crate::debug_log!("ignoring vendors.d/{}", error);
+            }
+            vendors.extend(custom.into_iter().map(|v| Box::new(v) as Box<dyn Vendor>));
+        }
+        vendors
+    })
+}
+
+/// URL 所属的服务商
+pub fn for_url(url: &str) -> Option<&'static dyn Vendor> {
+    registry()
+        .iter()
+        .find(|vendor| vendor.matches(url))
+        .map(|vendor| vendor.as_ref())
+}
+
+/// 当前 ANTHROPIC_BASE_URL 所属的服务商
+pub fn current() -> Option<&'static dyn Vendor> {
+    for_url(&super::get_current_base_url()?)
+}
+
+/// 请求服务商的用量接口
+pub fn fetch_usage(vendor: &dyn Vendor, api_key: &str) -> Result<VendorUsage, UucodeError> {
+    let url = vendor
+        .usage_url()
+        .ok_or_else(|| UucodeError::Vendor(format!("{} 没有用量接口", vendor.display_name())))?;
+    let client = super::http::client(Some(vendor.vendor_type()), USAGE_TIMEOUT)?;
+    let response = vendor.auth().apply(client.get(url), api_key).send()?;
+    if !response.status().is_success() {
+        return Err(UucodeError::Vendor(format!(
+            "{} usage API request failed: {}",
+            vendor.display_name(),
+            response.status()
+        )));
+    }
+    let body = super::http::read_text(response)?;
+    vendor.parse_usage(&body)
+}
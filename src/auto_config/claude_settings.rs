@@ -8,13 +8,15 @@ pub struct ClaudeSettingsConfigurator;
 impl ClaudeSettingsConfigurator {
     /// 获取 Claude settings.json 的路径
     pub fn get_settings_path() -> Option<PathBuf> {
-        dirs::home_dir().map(|home| home.join(".claude").join("settings.json"))
+        crate::utils::paths::claude_settings_path()
     }
 
-    /// 获取当前二进制文件的绝对路径
+    /// 获取当前二进制文件的绝对路径；包管理器安装时使用升级后仍有效的稳定路径
     pub fn get_binary_path() -> Result<String, Box<dyn std::error::Error>> {
-        let exe_path = std::env::current_exe()?;
-        let absolute_path = fs::canonicalize(exe_path)?;
+        let absolute_path = match super::install::detect() {
+            Some((_, stable)) => stable,
+            None => fs::canonicalize(std::env::current_exe()?)?,
+        };
 
         // 在 Windows 上处理路径
         #[cfg(target_os = "windows")]
@@ -47,7 +49,7 @@ impl ClaudeSettingsConfigurator {
         // 如果文件不存在，创建默认配置
         let mut settings: Value = if settings_path.exists() {
             let content = fs::read_to_string(&settings_path)?;
-            serde_json::from_str(&content)?
+            crate::utils::jsonc::parse(&content)?
         } else {
             json!({})
         };
@@ -55,7 +57,7 @@ impl ClaudeSettingsConfigurator {
         // 获取当前二进制路径
         let binary_path = Self::get_binary_path()?;
 
-        let mut modified = false;
+        let mut change = None;
 
         // 检查 statusLine 字段是否存在
         if let Some(obj) = settings.as_object_mut() {
@@ -69,8 +71,7 @@ impl ClaudeSettingsConfigurator {
                         "padding": 0
                     }),
                 );
-                println!("✓ 已添加 statusLine 配置到 settings.json");
-                modified = true;
+                change = Some("✓ 已添加 statusLine 配置到 settings.json");
             } else {
                 // 已存在，检查 command 路径
                 let mut needs_update = false;
@@ -96,8 +97,7 @@ impl ClaudeSettingsConfigurator {
                     if let Some(status_line) = obj.get_mut("statusLine") {
                         if let Some(sl_obj) = status_line.as_object_mut() {
                             sl_obj.insert("command".to_string(), json!(binary_path));
-                            println!("✓ 已更新 statusLine.command 路径");
-                            modified = true;
+                            change = Some("✓ 已更新 statusLine.command 路径");
                         }
                     }
                 }
@@ -105,9 +105,22 @@ impl ClaudeSettingsConfigurator {
         }
 
         // 只在有修改时写回文件
-        if modified {
-            let formatted = serde_json::to_string_pretty(&settings)?;
-            fs::write(&settings_path, formatted)?;
+        let Some(change) = change else {
+            return Ok(());
+        };
+        let written =
+            super::settings::write(&settings_path, &settings, "修改 settings.json", || {
+                let snippet = serde_json::to_string_pretty(&settings["statusLine"])
+                    .unwrap_or_default()
+                    .replace('\n', "\n  ");
+                format!(
+                    "  请在 {} 中加入:\n  \"statusLine\": {}",
+                    settings_path.display(),
+                    snippet
+                )
+            })?;
+        if written {
+            println!("{}", change);
             println!("✓ Claude settings.json 配置完成");
             println!("  路径: {}", settings_path.display());
         }
@@ -0,0 +1,216 @@
+//! 管理 Claude Code settings.json 中的 `hooks`
+//!
+//! `uucode hooks install` 写入的每条 hook 都执行 `uucode hooks run <name>`，
+//! 据此识别哪些条目由 uucode 管理；卸载时只删除这些条目，用户自己的 hook 保持不变。
+
+use super::ClaudeSettingsConfigurator;
+use serde_json::{json, Map, Value};
+use std::fs;
+
+/// uucode 管理的一条 hook
+pub struct ManagedHook {
+    /// Claude Code 的事件名
+    pub event: &'static str,
+    /// `uucode hooks run` 的参数
+    pub name: &'static str,
+    pub matcher: Option<&'static str>,
+    /// 超时（秒），超时后 Claude Code 不再等待
+    pub timeout: u64,
+    pub description: &'static str,
+}
+
+impl ManagedHook {
+    /// 是否是执行这条 hook 的命令
+    fn runs(&self, command: &str) -> bool {
+        command
+            .trim_end()
+            .ends_with(&format!(" hooks run {}", self.name))
+    }
+}
+
+pub const MANAGED_HOOKS: &[ManagedHook] = &[
+    ManagedHook {
+        event: "SessionEnd",
+        name: "session-end",
+        matcher: None,
+        timeout: 30,
+        description: "会话结束时把会话记录追加到 ledger",
+    },
+    ManagedHook {
+        event: "PreToolUse",
+        name: "pre-tool-use",
+        matcher: Some("*"),
+        timeout: 10,
+        description: "调用工具前刷新服务商用量缓存",
+    },
+];
+
+/// hook 命令是否由 uucode 写入
+///
+/// ```
+/// use mycode::auto_config::hooks::is_managed;
+///
+/// assert!(is_managed("/usr/local/bin/uucode hooks run session-end"));
+/// assert!(!is_managed("notify-send 'Claude is done'"));
+/// ```
+pub fn is_managed(command: &str) -> bool {
+    MANAGED_HOOKS.iter().any(|hook| hook.runs(command))
+}
+
+/// 删除 uucode 管理的 hook，并清理因此变空的分组和事件；返回删除的条数
+pub fn remove(settings: &mut Value) -> usize {
+    let Some(events) = settings.get_mut("hooks").and_then(Value::as_object_mut) else {
+        return 0;
+    };
+
+    let mut removed = 0;
+    for groups in events.values_mut().filter_map(Value::as_array_mut) {
+        for group in groups.iter_mut() {
+            if let Some(hooks) = group.get_mut("hooks").and_then(Value::as_array_mut) {
+                let before = hooks.len();
+                hooks.retain(|hook| {
+                    !hook
+                        .get("command")
+                        .and_then(Value::as_str)
+                        .is_some_and(is_managed)
+                });
+                removed += before - hooks.len();
+            }
+        }
+        groups.retain(|group| {
+            group
+                .get("hooks")
+                .and_then(Value::as_array)
+                .is_none_or(|hooks| !hooks.is_empty())
+        });
+    }
+    events.retain(|_, groups| groups.as_array().is_none_or(|groups| !groups.is_empty()));
+
+    if events.is_empty() {
+        if let Some(obj) = settings.as_object_mut() {
+            obj.remove("hooks");
+        }
+    }
+    removed
+}
+
+/// 写入全部 uucode hook（先删除旧条目，重复安装时更新二进制路径）
+///
+/// ```
+/// use mycode::auto_config::hooks::{add, remove};
+/// use serde_json::json;
+///
+/// let mine = json!({"hooks": {"Stop": [{"hooks": [{"type": "command", "command": "say done"}]}]}});
+/// let mut settings = mine.clone();
+/// add(&mut settings, "/usr/local/bin/uucode");
+/// assert_eq!(
+///     settings["hooks"]["SessionEnd"][0]["hooks"][0]["command"],
+///     "/usr/local/bin/uucode hooks run session-end"
+/// );
+/// assert_eq!(remove(&mut settings), 2);
+/// assert_eq!(settings, mine);
+/// ```
+pub fn add(settings: &mut Value, binary: &str) {
+    remove(settings);
+    if !settings.is_object() {
+        *settings = json!({});
+    }
+    if !settings["hooks"].is_object() {
+        settings["hooks"] = Value::Object(Map::new());
+    }
+    let Some(events) = settings["hooks"].as_object_mut() else {
+        return;
+    };
+
+    for hook in MANAGED_HOOKS {
+        let mut group = json!({
+            "hooks": [{
+                "type": "command",
+                "command": format!("{} hooks run {}", binary, hook.name),
+                "timeout": hook.timeout,
+            }]
+        });
+        if let Some(matcher) = hook.matcher {
+            group["matcher"] = json!(matcher);
+        }
+        let groups = events
+            .entry(hook.event)
+            .or_insert_with(|| Value::Array(Vec::new()));
+        if let Some(groups) = groups.as_array_mut() {
+            groups.push(group);
+        }
+    }
+}
+
+/// settings.json 中已安装的 uucode hook
+pub fn installed(settings: &Value) -> Vec<&'static ManagedHook> {
+    MANAGED_HOOKS
+        .iter()
+        .filter(|hook| {
+            settings
+                .pointer(&format!("/hooks/{}", hook.event))
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|group| group.get("hooks").and_then(Value::as_array))
+                .flatten()
+                .filter_map(|h| h.get("command").and_then(Value::as_str))
+                .any(|command| hook.runs(command))
+        })
+        .collect()
+}
+
+/// 读取 settings.json；文件不存在时返回空对象
+pub fn load_settings() -> Result<Value, Box<dyn std::error::Error>> {
+    let path = ClaudeSettingsConfigurator::get_settings_path()
+        .ok_or("无法找到 Claude settings.json 路径")?;
+    if !path.exists() {
+        return Ok(json!({}));
+    }
+    Ok(crate::utils::jsonc::parse(&fs::read_to_string(&path)?)?)
+}
+
+/// 安装（`install = true`）或卸载 uucode hook 并写回 settings.json
+pub fn configure(install: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let settings_path = ClaudeSettingsConfigurator::get_settings_path()
+        .ok_or("无法找到 Claude settings.json 路径")?;
+    let mut settings = load_settings()?;
+
+    let removed = if install {
+        let binary = ClaudeSettingsConfigurator::get_binary_path()?;
+        add(&mut settings, &binary);
+        0
+    } else {
+        remove(&mut settings)
+    };
+    if !install && removed == 0 {
+        println!("settings.json 中没有 uucode 管理的 hook");
+        return Ok(());
+    }
+
+    let written =
+        super::settings::write(&settings_path, &settings, "修改 settings.json", || {
+            let snippet =
+                serde_json::to_string_pretty(&settings.get("hooks").unwrap_or(&json!({})))
+                    .unwrap_or_default()
+                    .replace('\n', "\n  ");
+            format!(
+                "  请将 {} 中的 hooks 改为:\n  \"hooks\": {}",
+                settings_path.display(),
+                snippet
+            )
+        })?;
+    if !written {
+        return Ok(());
+    }
+
+    if install {
+        for hook in MANAGED_HOOKS {
+            println!("✓ {}: {}", hook.event, hook.description);
+        }
+        println!("  已写入 {}", settings_path.display());
+    } else {
+        println!("✓ 已从 settings.json 删除 {} 条 uucode hook", removed);
+    }
+    Ok(())
+}
@@ -0,0 +1,76 @@
+//! 包管理器安装的识别
+//!
+//! Homebrew 和 Scoop 把二进制放在带版本号的目录里（`Cellar/uucode/1.0.2`、
+//! `apps/uucode/1.0.2`），升级后旧目录会被删除。写入 settings.json 或建立链接时
+//! 改用它们提供的稳定路径（`<prefix>/bin`、`scoop/shims`）。
+
+use std::path::{Component, Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageManager {
+    Homebrew,
+    Scoop,
+}
+
+impl PackageManager {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PackageManager::Homebrew => "Homebrew",
+            PackageManager::Scoop => "Scoop",
+        }
+    }
+}
+
+/// 根据已解析符号链接的二进制路径推出包管理器的稳定路径（不检查是否存在）
+///
+/// ```
+/// use mycode::auto_config::install::{stable_path, PackageManager};
+/// use std::path::Path;
+///
+/// let brew = Path::new("/opt/homebrew/Cellar/uucode/1.0.2/bin/uucode");
+/// assert_eq!(
+///     stable_path(brew),
+///     Some((PackageManager::Homebrew, "/opt/homebrew/bin/uucode".into()))
+/// );
+///
+/// let scoop = Path::new("/c/Users/me/scoop/apps/uucode/1.0.2/uucode.exe");
+/// assert_eq!(
+///     stable_path(scoop),
+///     Some((PackageManager::Scoop, "/c/Users/me/scoop/shims/uucode.exe".into()))
+/// );
+///
+/// assert_eq!(stable_path(Path::new("/home/me/.claude/uucode/uucode")), None);
+/// ```
+pub fn stable_path(exe: &Path) -> Option<(PackageManager, PathBuf)> {
+    let file_name = exe.file_name()?;
+    let parts: Vec<Component> = exe.components().collect();
+    let prefix = |end: usize| parts[..end].iter().collect::<PathBuf>();
+
+    // <prefix>/Cellar/<formula>/<version>/bin/<file>
+    if let Some(i) = parts.iter().position(|c| c.as_os_str() == "Cellar") {
+        if i > 0 && parts.len() > i + 3 {
+            return Some((
+                PackageManager::Homebrew,
+                prefix(i).join("bin").join(file_name),
+            ));
+        }
+    }
+
+    // <root>/apps/<app>/<version>/<file>
+    if let Some(i) = parts.iter().rposition(|c| c.as_os_str() == "apps") {
+        if i > 0 && parts.len() == i + 4 && parts[i + 2].as_os_str() != "current" {
+            return Some((
+                PackageManager::Scoop,
+                prefix(i).join("shims").join(file_name),
+            ));
+        }
+    }
+
+    None
+}
+
+/// 当前二进制由包管理器安装时，返回包管理器及其稳定路径
+pub fn detect() -> Option<(PackageManager, PathBuf)> {
+    let exe = std::fs::canonicalize(std::env::current_exe().ok()?).ok()?;
+    stable_path(&exe).filter(|(_, path)| path.exists())
+}
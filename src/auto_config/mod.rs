@@ -1,8 +1,11 @@
-use crate::config::Config;
+use crate::config::{Config, InstallStrategy};
 use std::fs;
 use std::path::PathBuf;
 
 pub mod claude_settings;
+pub mod hooks;
+pub mod install;
+pub mod settings;
 pub use claude_settings::ClaudeSettingsConfigurator;
 
 pub struct AutoConfigurator {
@@ -72,6 +75,8 @@ impl AutoConfigurator {
                     background: Some(AnsiColor::Color256 { c256: 236 }),
                 },
                 styles: TextStyleConfig { text_bold: false },
+                priority: None,
+                icon_rules: Vec::new(),
                 options,
             });
             println!("✓ 已添加 uucode 用量监控段");
@@ -99,6 +104,8 @@ impl AutoConfigurator {
                     background: Some(AnsiColor::Color256 { c256: 236 }),
                 },
                 styles: TextStyleConfig { text_bold: false },
+                priority: None,
+                icon_rules: Vec::new(),
                 options,
             });
             println!("✓ 已添加 uucode 订阅信息段");
@@ -107,51 +114,96 @@ impl AutoConfigurator {
         // Save config
         let config_path = self.config_dir.join("config.toml");
         let toml_string = toml::to_string_pretty(&config)?;
-        fs::write(&config_path, toml_string)?;
+        if crate::utils::readonly::intercept("写入 config.toml", || {
+            format!(
+                "  如需保存，请手动将以下内容写入 {}:\n\n{}",
+                config_path.display(),
+                toml_string
+            )
+        }) {
+            return Ok(());
+        }
+        crate::config::backup::snapshot_config();
+        crate::utils::atomic_write(&config_path, toml_string)?;
         println!("✓ Configuration saved to: {}", config_path.display());
 
-        // Save API keys to separate config file
+        // Save API keys to separate config file, keeping other stored keys
         if api_key.is_some() {
-            use serde::{Deserialize, Serialize};
-
-            #[derive(Serialize, Deserialize)]
-            struct ApiKeys {
-                #[serde(skip_serializing_if = "Option::is_none")]
-                uucode_api_key: Option<String>,
+            let mut keys = crate::config::ApiKeys::load().unwrap_or_default();
+            keys.uucode_api_key = api_key;
+            keys.save()?;
+            if let Some(keys_path) = crate::config::ApiKeys::path() {
+                println!("✓ API keys saved to: {}", keys_path.display());
             }
-
-            let keys = ApiKeys {
-                uucode_api_key: api_key,
-            };
-
-            let keys_path = self.config_dir.join("api_keys.toml");
-            let keys_toml = toml::to_string_pretty(&keys)?;
-            fs::write(&keys_path, keys_toml)?;
-            println!("✓ API keys saved to: {}", keys_path.display());
         }
 
         Ok(())
     }
 
+    /// Put the binary where `[install] strategy` says, see [`InstallStrategy`]
     pub fn install_binary(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let current_exe = std::env::current_exe()?;
+        let managed = install::detect();
+        let strategy = match Config::load().unwrap_or_default().install.strategy {
+            InstallStrategy::Auto if managed.is_some() => InstallStrategy::InPlace,
+            InstallStrategy::Auto => InstallStrategy::Copy,
+            strategy => strategy,
+        };
+        // Link to the package manager's stable path so upgrades carry over
+        let source = match &managed {
+            Some((_, stable)) => stable.clone(),
+            None => std::env::current_exe()?,
+        };
+
+        if strategy == InstallStrategy::InPlace {
+            match &managed {
+                Some((manager, _)) => println!(
+                    "✓ Installed by {}, using it in place: {}",
+                    manager.name(),
+                    source.display()
+                ),
+                None => println!("✓ Using the binary in place: {}", source.display()),
+            }
+            return Ok(());
+        }
+
         let target_path = self.config_dir.join(if cfg!(windows) {
             "uucode.exe"
         } else {
             "uucode"
         });
 
-        if target_path.exists() {
+        if crate::utils::readonly::intercept("安装二进制", || match strategy {
+            InstallStrategy::Symlink => {
+                format!("  ln -sf {} {}", source.display(), target_path.display())
+            }
+            _ => format!("  cp {} {}", source.display(), target_path.display()),
+        }) {
+            return Ok(());
+        }
+
+        if fs::canonicalize(&target_path).ok() == fs::canonicalize(&source).ok() {
+            println!("✓ Already running from: {}", target_path.display());
+            return Ok(());
+        }
+
+        if target_path.symlink_metadata().is_ok() {
             println!("Binary already exists at: {}", target_path.display());
-            println!("Do you want to overwrite? (y/n)");
-            let mut input = String::new();
-            std::io::stdin().read_line(&mut input)?;
-            if !input.trim().eq_ignore_ascii_case("y") {
+            if !crate::utils::prompt::confirm("Do you want to overwrite? [y/N] ")? {
                 return Ok(());
             }
+            fs::remove_file(&target_path)?;
+        }
+
+        if strategy == InstallStrategy::Symlink {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(&source, &target_path)?;
+            #[cfg(windows)]
+            std::os::windows::fs::symlink_file(&source, &target_path)?;
+            println!("✓ Linked {} -> {}", target_path.display(), source.display());
+            return Ok(());
         }
 
-        fs::copy(&current_exe, &target_path)?;
+        fs::copy(&source, &target_path)?;
 
         // Set executable permissions on Unix
         #[cfg(unix)]
@@ -0,0 +1,119 @@
+//! 写入 Claude Code settings.json 前先显示差异并确认
+//!
+//! `--init`、`hooks install|uninstall` 和 `vendor switch` 都经过 [`write`]：
+//! 先打印将要写入的 JSON 差异，`--dry-run` 时到此为止；否则请求确认
+//! （`--yes` 时自动确认）后再写入。密钥在差异中以 `***` 显示。
+//!
+//! 写回的是格式化后的 JSON，原文件中的注释和尾随逗号会丢失：这种情况下
+//! 先提示，写入前把原文件备份为 `settings.json.bak`。
+
+use crate::config::diff::ConfigDiff;
+use crate::utils::{prompt, readonly};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// 差异中变更行前后保留的行数
+const DIFF_CONTEXT: usize = 2;
+
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+/// 本进程只显示 settings.json 的修改，不写入
+pub fn enable_dry_run() {
+    DRY_RUN.store(true, Ordering::Relaxed);
+}
+
+pub fn is_dry_run() -> bool {
+    DRY_RUN.load(Ordering::Relaxed)
+}
+
+/// 把密钥类字段（键名含 TOKEN、KEY、SECRET）的值替换为 `***`
+///
+/// ```
+/// use mycode::auto_config::settings::mask_secret;
+///
+/// assert_eq!(
+///     mask_secret(r#"    "ANTHROPIC_AUTH_TOKEN": "sk-live-123","#),
+///     r#"    "ANTHROPIC_AUTH_TOKEN": "***","#
+/// );
+/// assert_eq!(
+///     mask_secret(r#"    "ANTHROPIC_BASE_URL": "https://api.uucode.org""#),
+///     r#"    "ANTHROPIC_BASE_URL": "https://api.uucode.org""#
+/// );
+/// ```
+pub fn mask_secret(line: &str) -> String {
+    let trimmed = line.trim_start();
+    let Some(rest) = trimmed.strip_prefix('"') else {
+        return line.to_string();
+    };
+    let Some((key, _)) = rest.split_once("\":") else {
+        return line.to_string();
+    };
+    let upper = key.to_ascii_uppercase();
+    if !["TOKEN", "KEY", "SECRET"].iter().any(|s| upper.contains(s)) {
+        return line.to_string();
+    }
+    let indent = &line[..line.len() - trimmed.len()];
+    let comma = if line.trim_end().ends_with(',') {
+        ","
+    } else {
+        ""
+    };
+    format!("{}\"{}\": \"***\"{}", indent, key, comma)
+}
+
+/// 显示把 `path` 改为 `settings` 的差异，确认后写入；返回是否已写入。
+///
+/// 没有变化、`--dry-run`、只读模式（打印 `instructions` 供手动修改）或
+/// 用户取消时不写入。
+pub fn write(
+    path: &Path,
+    settings: &Value,
+    action: &str,
+    instructions: impl FnOnce() -> String,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let current = fs::read_to_string(path).unwrap_or_default();
+    let new = serde_json::to_string_pretty(settings)?;
+
+    let mut diff = ConfigDiff::between(&current, &new);
+    if !diff.has_changes() {
+        println!("settings.json 无需修改");
+        return Ok(false);
+    }
+    for line in &mut diff.lines {
+        line.text = mask_secret(&line.text);
+    }
+
+    println!("{}:", path.display());
+    print!("{}", diff.to_ansi(DIFF_CONTEXT));
+    if is_dry_run() {
+        println!("--dry-run：未写入 settings.json");
+        return Ok(false);
+    }
+    if readonly::intercept(action, instructions) {
+        return Ok(false);
+    }
+    let lossy = crate::utils::jsonc::has_extensions(&current);
+    let backup = path.with_extension("json.bak");
+    if lossy {
+        println!(
+            "⚠ {} 含有注释或尾随逗号，写入后会丢失；原文件将备份到 {}",
+            path.display(),
+            backup.display()
+        );
+    }
+    if !prompt::confirm("写入 settings.json？[y/N] ")? {
+        println!("已取消");
+        return Ok(false);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if lossy {
+        fs::copy(path, &backup)?;
+    }
+    crate::utils::atomic_write(path, new)?;
+    Ok(true)
+}
@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
 #[derive(Parser, Debug)]
 #[command(name = "uucode")]
@@ -32,9 +32,359 @@ pub struct Cli {
     #[arg(long = "patch")]
     pub patch: Option<String>,
 
+    /// Take the spinner tip patch out of Claude Code cli.js again
+    #[arg(long = "unpatch-spinner", conflicts_with = "patch")]
+    pub unpatch_spinner: Option<String>,
+
     /// Start uucode wrapper mode (inject into Claude Code)
     #[arg(long = "wrap")]
     pub wrap: bool,
+
+    /// Use the named directory profile for the whole wrapped session
+    #[arg(long = "profile", requires = "wrap")]
+    pub profile: Option<String>,
+
+    /// Render once from a synthetic payload without recording anything;
+    /// run by `--wrap` before Claude Code starts
+    #[arg(long = "self-test", hide = true)]
+    pub self_test: bool,
+
+    /// Print the statusline for another consumer instead of the terminal;
+    /// without stdin the last payload from Claude Code is reused
+    #[arg(long = "output", value_enum)]
+    pub output: Option<crate::config::OutputFormat>,
+
+    /// Keep running and print a new line whenever the cached data changes
+    #[arg(long = "follow")]
+    pub follow: bool,
+
+    /// Keep running and redraw the statusline in place when anything changes
+    #[arg(long = "watch", conflicts_with = "follow")]
+    pub watch: bool,
+
+    /// Freeze countdowns and clocks at the time of the payload and drop
+    /// randomness, for reproducible screenshots and golden tests
+    #[arg(long = "deterministic", global = true)]
+    pub deterministic: bool,
+
+    /// Print diagnostics to stderr
+    #[arg(long = "debug", global = true)]
+    pub debug: bool,
+
+    /// Never write outside the cache directory; print the changes to make
+    /// by hand instead
+    #[arg(long = "no-write", global = true)]
+    pub no_write: bool,
+
+    /// Show the settings.json changes of --init, hooks and vendor switch
+    /// without writing them
+    #[arg(long = "dry-run", global = true)]
+    pub dry_run: bool,
+
+    /// Answer yes to every prompt (overwrite, import, trust)
+    #[arg(long = "yes", short = 'y', global = true)]
+    pub yes: bool,
+
+    /// Never prompt; take the safe default (no) instead
+    #[arg(long = "non-interactive", global = true)]
+    pub non_interactive: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Commands {
+    /// Diagnose configuration and environment problems
+    Doctor {
+        /// Leave safe mode after fixing what made renders fail
+        #[arg(long)]
+        reset: bool,
+    },
+    /// Explain each element of the last rendered statusline
+    Explain,
+    /// Hide usage and cost segments while focusing
+    Focus {
+        #[command(subcommand)]
+        action: Option<FocusAction>,
+    },
+    /// Full-screen dashboard with live quota, context, cost and latency
+    Top {
+        /// Refresh interval in seconds
+        #[arg(long, default_value_t = 5)]
+        interval: u64,
+    },
+    /// Serve a small web dashboard with a JSON state endpoint
+    Serve {
+        /// Port to listen on
+        #[arg(long, default_value_t = 8787)]
+        port: u16,
+        /// Address to bind, use 0.0.0.0 to allow other devices
+        #[arg(long, default_value = "127.0.0.1")]
+        bind: String,
+    },
+    /// Inspect or switch the API provider
+    Vendor {
+        #[command(subcommand)]
+        action: VendorAction,
+    },
+    /// Inspect segment collection times
+    Perf {
+        #[command(subcommand)]
+        action: PerfAction,
+    },
+    /// Replay recorded payloads through the full pipeline with the network mocked
+    Simulate {
+        /// Directory of Claude Code statusline payloads (*.json)
+        dir: String,
+        /// Renders per second
+        #[arg(long, default_value_t = 2.0)]
+        rate: f64,
+        /// Total renders, cycling through the payloads [default: one per payload]
+        #[arg(long)]
+        count: Option<usize>,
+        /// Delay before the mocked network answers, in milliseconds
+        #[arg(long, default_value_t = 0)]
+        latency: u64,
+    },
+    /// Show recorded history
+    Stats {
+        #[command(subcommand)]
+        action: StatsAction,
+    },
+    /// Manage config.toml
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Browse and install community segments and themes
+    Segment {
+        #[command(subcommand)]
+        action: SegmentAction,
+    },
+    /// Stop collecting a segment, e.g. while its API is flapping
+    Mute {
+        /// Segment id as in config.toml, e.g. cubence_load_status
+        segment: String,
+        /// Only for this long, e.g. 30m, 2h or 1d; otherwise until unmuted
+        #[arg(long = "for")]
+        duration: Option<String>,
+    },
+    /// Collect a muted segment again
+    Unmute {
+        /// Segment id as in config.toml
+        segment: String,
+    },
+    /// Manage the Claude Code hooks uucode adds to settings.json
+    Hooks {
+        #[command(subcommand)]
+        action: HooksAction,
+    },
+    /// Cubence account helpers
+    Cubence {
+        #[command(subcommand)]
+        action: CubenceAction,
+    },
+    /// Refresh vendor usage in the background so renders never wait for
+    /// the network
+    Daemon {
+        #[command(subcommand)]
+        action: Option<DaemonAction>,
+    },
+    /// Browse the available themes
+    Theme {
+        #[command(subcommand)]
+        action: ThemeAction,
+    },
+    /// Install a Nerd Font so the Nerd Font icons stop showing as boxes
+    Font {
+        #[command(subcommand)]
+        action: FontAction,
+    },
+    /// Allow the .uucode.toml of a project to run commands in the statusline
+    Trust {
+        /// Project directory; defaults to the current directory
+        path: Option<String>,
+        /// List trusted .uucode.toml files
+        #[arg(long, conflicts_with = "revoke")]
+        list: bool,
+        /// Withdraw trust instead of granting it
+        #[arg(long)]
+        revoke: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    /// Replace config.toml with another file after reviewing the changes
+    Import {
+        /// Path or HTTPS URL of the TOML config to import
+        file: String,
+        /// Expected SHA-256 of a downloaded config; with
+        /// `marketplace.minisign_key` set, `<url>.minisig` is checked too
+        #[arg(long)]
+        sha256: Option<String>,
+    },
+    /// Put back a config.toml saved before an earlier rewrite
+    Restore {
+        /// Only list the available backups
+        #[arg(long)]
+        list: bool,
+        /// Backup number from --list (1 is the newest) or its timestamp;
+        /// defaults to the newest
+        backup: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HooksAction {
+    /// Add a SessionEnd hook that appends the session to the ledger and a
+    /// PreToolUse hook that refreshes the usage cache
+    Install,
+    /// Remove the hooks added by install, leaving other hooks untouched
+    Uninstall,
+    /// Show which of uucode's hooks are installed
+    Status,
+    /// Handle a hook event; run by Claude Code
+    #[command(hide = true)]
+    Run { name: String },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CubenceAction {
+    /// Save the cubence.com login cookie for the load, subscription and
+    /// multiplier segments, after checking it against the load endpoint
+    SetCookie {
+        /// Cookie header or token value; read from the clipboard when omitted
+        cookie: Option<String>,
+        /// Save without checking, e.g. while offline
+        #[arg(long)]
+        no_verify: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DaemonAction {
+    /// Start refreshing in a background process
+    Start {
+        /// Seconds between refreshes
+        #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u64).range(10..=300))]
+        interval: u64,
+    },
+    /// Refresh in the foreground, e.g. under systemd or launchd
+    Run {
+        /// Seconds between refreshes
+        #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u64).range(10..=300))]
+        interval: u64,
+    },
+    /// Stop the running daemon
+    Stop,
+    /// Show whether a daemon is running (default)
+    Status,
+    /// Refresh once; run by the daemon for each round
+    #[command(hide = true)]
+    Refresh,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ThemeAction {
+    /// Render the statusline once under every theme, built-in and custom
+    PreviewAll {
+        /// Columns to render for instead of the payload or terminal width
+        #[arg(long)]
+        width: Option<usize>,
+        /// Use sample data instead of the last Claude Code payload
+        #[arg(long)]
+        sample: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum FontAction {
+    /// Download a Nerd Font, verify its checksum and install it for the
+    /// current user
+    Install {
+        /// Font name from `uucode font list`
+        #[arg(default_value = "JetBrainsMono")]
+        name: String,
+    },
+    /// List the fonts that can be installed
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SegmentAction {
+    /// List the community index and install entries interactively
+    Browse {
+        /// Skip the index signature check (for testing private indexes)
+        #[arg(long)]
+        insecure: bool,
+    },
+    /// Install one entry from the index by name
+    Install {
+        name: String,
+        /// Skip the index signature check (for testing private indexes)
+        #[arg(long)]
+        insecure: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum FocusAction {
+    /// Turn focus mode on until turned off
+    On,
+    /// Turn focus mode off, ignoring the schedule
+    Off,
+    /// Follow the [focus] schedule again
+    Auto,
+    /// Show whether focus mode is on and why (default)
+    Status,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PerfAction {
+    /// List segments by average collection time and flag the skipped ones
+    Show,
+    /// Forget recorded timings so skipped segments are collected again
+    Reset,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum StatsAction {
+    /// Hourly latency percentiles of the vendor endpoints
+    Latency {
+        /// How far back to look
+        #[arg(long, default_value_t = 24)]
+        hours: u64,
+        /// Only this endpoint host
+        #[arg(long)]
+        endpoint: Option<String>,
+    },
+    /// Write the session ledger to stdout for invoicing or spreadsheets
+    Export {
+        #[arg(long, value_enum, default_value = "csv")]
+        format: ExportFormat,
+        /// Only sessions started in this month (YYYY-MM, local time)
+        #[arg(long)]
+        month: Option<String>,
+    },
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum VendorAction {
+    /// Show detected vendor, base URL, key presence and reachability
+    Status,
+    /// Point Claude Code at another vendor using stored credentials
+    Switch {
+        /// Vendor id (uucode, cubence)
+        name: String,
+    },
 }
 
 impl Cli {
@@ -0,0 +1,194 @@
+//! `uucode config import|restore`
+
+use crate::api;
+use crate::cli::ConfigAction;
+use crate::config::backup::{self, Backup};
+use crate::config::diff::ConfigDiff;
+use crate::config::{Config, InputData};
+use crate::core::{collect_all_segments, StatusLineGenerator};
+use crate::utils::download::{self, Integrity};
+use crate::utils::{prompt, readonly};
+use std::fs;
+
+/// 差异中每处改动前后保留的未改动行数
+const DIFF_CONTEXT: usize = 2;
+
+/// 从 URL 导入的配置大小上限
+const MAX_IMPORT_BYTES: u64 = 1024 * 1024;
+
+pub fn run(action: &ConfigAction) -> Result<(), Box<dyn std::error::Error>> {
+    match action {
+        ConfigAction::Import { file, sha256 } => import(file, sha256.as_deref()),
+        ConfigAction::Restore { list: true, .. } => {
+            list_backups();
+            Ok(())
+        }
+        ConfigAction::Restore { backup, .. } => restore(backup.as_deref()),
+    }
+}
+
+fn import(file: &str, sha256: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let content = if file.starts_with("https://") || file.starts_with("http://") {
+        download_config(file, sha256)?
+    } else {
+        fs::read_to_string(file)?
+    };
+    let new: Config =
+        toml::from_str(&content).map_err(|e| format!("{} 不是有效的配置: {}", file, e))?;
+
+    let diff = ConfigDiff::against_disk(&new)?;
+    if !review(&diff, &new)? {
+        return Ok(());
+    }
+
+    if readonly::intercept("写入 config.toml", || {
+        format!(
+            "  如需导入，请手动将 {} 复制为 {}",
+            file,
+            Config::get_config_path().display()
+        )
+    }) {
+        return Ok(());
+    }
+
+    new.save()?;
+    println!("✓ 已写入 {}", Config::get_config_path().display());
+    Ok(())
+}
+
+/// 下载要导入的配置，按 --sha256 和 marketplace.minisign_key 校验
+fn download_config(url: &str, sha256: Option<&str>) -> Result<String, Box<dyn std::error::Error>> {
+    let current = Config::load().unwrap_or_else(|_| Config::default());
+    let minisign_key = current.marketplace.minisign_key.as_deref();
+    if sha256.is_none() && minisign_key.is_none() {
+        eprintln!("⚠ 未指定 --sha256，也未配置 marketplace.minisign_key，下载的配置不会校验完整性");
+    }
+    let body = download::fetch_verified(
+        url,
+        MAX_IMPORT_BYTES,
+        download::DEFAULT_TIMEOUT,
+        &Integrity {
+            sha256,
+            minisign_key,
+        },
+    )
+    .map_err(|e| format!("下载 {} 失败: {}", url, e))?;
+    if sha256.is_some() || minisign_key.is_some() {
+        println!("✓ 已校验 {}", url);
+    }
+    Ok(String::from_utf8(body).map_err(|_| format!("{} 不是 UTF-8 文本", url))?)
+}
+
+fn list_backups() {
+    let backups = backup::list();
+    if backups.is_empty() {
+        println!("还没有备份，config.toml 被改写时会自动备份");
+        return;
+    }
+    for (index, backup) in backups.iter().enumerate() {
+        println!(
+            "{:>3}  {}  {}",
+            index + 1,
+            backup.taken_at,
+            backup.path.display()
+        );
+    }
+    println!(
+        "\n最多保留 {} 份，使用 `uucode config restore <编号>` 恢复",
+        backup::MAX_BACKUPS
+    );
+}
+
+fn restore(selector: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let backups = backup::list();
+    let chosen = select_backup(&backups, selector)?;
+
+    let content = fs::read_to_string(&chosen.path)?;
+    let restored: Config = toml::from_str(&content)
+        .map_err(|e| format!("备份 {} 无法解析: {}", chosen.taken_at, e))?;
+
+    let diff = ConfigDiff::against_disk_text(&content);
+    if !review(&diff, &restored)? {
+        return Ok(());
+    }
+
+    let config_path = Config::get_config_path();
+    if readonly::intercept("恢复 config.toml", || {
+        format!("  cp {} {}", chosen.path.display(), config_path.display())
+    }) {
+        return Ok(());
+    }
+
+    // 恢复本身也可以撤销
+    backup::snapshot_config();
+    if let Some(parent) = config_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    crate::utils::atomic_write(&config_path, content)?;
+    println!(
+        "✓ 已从 {} 的备份恢复 {}",
+        chosen.taken_at,
+        config_path.display()
+    );
+    Ok(())
+}
+
+fn select_backup<'a>(
+    backups: &'a [Backup],
+    selector: Option<&str>,
+) -> Result<&'a Backup, Box<dyn std::error::Error>> {
+    let found = match selector {
+        None => backups.first(),
+        Some(text) => match text.parse::<usize>() {
+            Ok(number) => number.checked_sub(1).and_then(|i| backups.get(i)),
+            Err(_) => backups.iter().find(|b| b.taken_at.starts_with(text)),
+        },
+    };
+    found.ok_or_else(|| match selector {
+        None => "还没有备份".into(),
+        Some(text) => format!(
+            "没有找到备份 {}，可用 `uucode config restore --list` 查看",
+            text
+        )
+        .into(),
+    })
+}
+
+/// 打印差异和状态栏预览并请求确认；没有变化或用户取消时返回 false
+fn review(diff: &ConfigDiff, new: &Config) -> Result<bool, Box<dyn std::error::Error>> {
+    if !diff.has_changes() {
+        println!("与当前 config.toml 相同，无需写入");
+        return Ok(false);
+    }
+
+    print!("{}", diff.to_ansi(DIFF_CONTEXT));
+    print_preview(diff.current.as_ref(), new);
+
+    if !prompt::confirm("\n写入 config.toml？[y/N] ")? {
+        println!("已取消");
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+/// 用最近一次 Claude Code 输入分别渲染修改前后的状态栏
+fn print_preview(current: Option<&Config>, new: &Config) {
+    let Some(input) =
+        api::cache::load_last_input().and_then(|raw| serde_json::from_str::<InputData>(&raw).ok())
+    else {
+        println!("\n（状态栏尚未渲染过，跳过预览）");
+        return;
+    };
+
+    let render = |config: &Config| {
+        StatusLineGenerator::new(config.clone()).generate(collect_all_segments(config, &input))
+    };
+
+    println!("\n修改前:");
+    match current {
+        Some(config) => println!("{}", render(config)),
+        None => println!("（没有可读取的 config.toml）"),
+    }
+    println!("修改后:");
+    println!("{}", render(new));
+}
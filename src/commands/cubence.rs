@@ -0,0 +1,111 @@
+//! `uucode cubence set-cookie`
+
+use crate::api::{breaker, cookie};
+use crate::cli::CubenceAction;
+use crate::core::segments::{cubence_load_status, cubence_multiplier, cubence_subscription};
+use crate::error::UucodeError;
+
+pub fn run(action: &CubenceAction) -> Result<(), Box<dyn std::error::Error>> {
+    match action {
+        CubenceAction::SetCookie { cookie, no_verify } => set_cookie(cookie.as_deref(), *no_verify),
+    }
+}
+
+#[cfg(feature = "clipboard")]
+fn read_clipboard() -> Result<String, UucodeError> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(|e| {
+            UucodeError::Config(format!(
+                "无法读取剪贴板: {}（可改为 uucode cubence set-cookie '<cookie>'）",
+                e
+            ))
+        })
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn read_clipboard() -> Result<String, UucodeError> {
+    Err(UucodeError::Config(
+        "未启用 clipboard 功能，请把 Cookie 作为参数传入: uucode cubence set-cookie '<cookie>'"
+            .to_string(),
+    ))
+}
+
+/// Show only the ends of the token so the cookie never lands in scrollback
+fn mask(cookie: &str) -> String {
+    let token: Vec<char> = cookie::token(cookie).chars().collect();
+    if token.len() <= 12 {
+        return format!("{} 个字符", token.len());
+    }
+    let head: String = token[..6].iter().collect();
+    let tail: String = token[token.len() - 4..].iter().collect();
+    format!("{}…{}（{} 个字符）", head, tail, token.len())
+}
+
+fn set_cookie(arg: Option<&str>, no_verify: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let text = match arg {
+        Some(text) => text.to_string(),
+        None => {
+            println!(
+                "从剪贴板读取 Cookie（浏览器开发者工具 → 应用 → Cookie → cubence.com → token）"
+            );
+            read_clipboard()?
+        }
+    };
+    let value = cookie::normalize(&text)
+        .ok_or_else(|| UucodeError::Config("Cookie 为空，请先复制 cubence.com 的 token".into()))?;
+    println!("Cookie: {}", mask(&value));
+
+    if no_verify {
+        println!("⚠ 已跳过校验");
+    } else {
+        match cubence_load_status::fetch_load_status(&value) {
+            Ok((load, level)) => println!(
+                "✓ Cookie 有效，当前负载 {}%（{}）",
+                (load * 100.0).round() as i64,
+                level
+            ),
+            Err(e) => {
+                return Err(UucodeError::Vendor(format!(
+                    "Cookie 校验失败: {}（确认已登录 cubence.com 并复制了最新的 token，未保存）",
+                    e
+                ))
+                .into())
+            }
+        }
+    }
+
+    if crate::utils::readonly::intercept("写入 cookie.json", || {
+        format!(
+            "  请将 {} 中的 cookie 改为:\n  \"cookie\": {}",
+            cookie::path()
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|| "cookie.json".to_string()),
+            serde_json::Value::String(value.clone())
+        )
+    }) {
+        return Ok(());
+    }
+    let path = cookie::save(&value)?;
+
+    // Failures with the old cookie must not hold back the new one
+    for url in [
+        cubence_load_status::LOAD_STATUS_URL,
+        cubence_subscription::SUBSCRIPTION_URL,
+        cubence_multiplier::OVERVIEW_URL,
+    ] {
+        if let Err(e) = breaker::reset(url) {
+            crate::debug_log!("failed to reset circuit breaker: {}", e);
+        }
+    }
+    println!("✓ 已保存到 {}", path.display());
+    if let Some(at) =
+        cookie::expires_at(&value).and_then(|exp| chrono::DateTime::from_timestamp(exp, 0))
+    {
+        println!(
+            "  有效期至 {}，过期前一天负载段会提醒续期",
+            crate::utils::clock::to_local(at).format("%Y-%m-%d %H:%M")
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,111 @@
+//! `uucode daemon start|run|stop|status`
+
+use crate::cli::DaemonAction;
+use crate::daemon;
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub fn run(action: Option<&DaemonAction>) -> Result<(), Box<dyn std::error::Error>> {
+    match action {
+        Some(DaemonAction::Start { interval }) => start(*interval),
+        Some(DaemonAction::Run { interval }) => {
+            if let Some(state) = daemon::running() {
+                println!("已有 daemon 在运行（PID {}），将由本进程接替", state.pid);
+            }
+            println!("每 {} 秒刷新用量缓存，Ctrl+C 退出", interval);
+            daemon::run(Duration::from_secs(*interval))
+        }
+        Some(DaemonAction::Stop) => {
+            match daemon::stop()? {
+                Some(state) => println!("✓ 已通知 daemon（PID {}）退出", state.pid),
+                None => println!("daemon 未运行"),
+            }
+            Ok(())
+        }
+        Some(DaemonAction::Refresh) => Ok(daemon::refresh::refresh()?),
+        Some(DaemonAction::Status) | None => {
+            status();
+            Ok(())
+        }
+    }
+}
+
+fn start(interval: u64) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(state) = daemon::running() {
+        println!(
+            "daemon 已在运行（PID {}，每 {} 秒刷新）",
+            state.pid, state.interval_secs
+        );
+        return Ok(());
+    }
+
+    let mut command = Command::new(std::env::current_exe()?);
+    command
+        .args(["daemon", "run", "--interval", &interval.to_string()])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // Leave the terminal's process group so closing it or Ctrl+C in it
+    // does not take the daemon along
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const DETACHED_PROCESS: u32 = 0x0000_0008;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(DETACHED_PROCESS | CREATE_NO_WINDOW);
+    }
+    let child = command.spawn()?;
+
+    // The daemon writes its state file before the first refresh
+    for _ in 0..20 {
+        if daemon::load().is_some_and(|state| state.pid == child.id()) {
+            println!(
+                "✓ daemon 已启动（PID {}），每 {} 秒刷新用量缓存",
+                child.id(),
+                interval
+            );
+            println!("  渲染状态栏时将只读取缓存 · 停止: uucode daemon stop");
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    Err(format!(
+        "daemon（PID {}）未能启动，可运行 uucode daemon run 查看原因",
+        child.id()
+    )
+    .into())
+}
+
+fn status() {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    match daemon::load() {
+        Some(state) if state.is_alive(now) => {
+            println!(
+                "daemon 运行中（PID {}），每 {} 秒刷新，上次刷新 {} 秒前",
+                state.pid,
+                state.interval_secs,
+                (now - state.heartbeat).max(0)
+            );
+            if state.failures > 0 {
+                println!(
+                    "  ⚠ 最近 {} 次刷新失败，状态栏显示的可能是旧数据（uucode daemon run --debug 查看原因）",
+                    state.failures
+                );
+            }
+        }
+        Some(state) => println!(
+            "daemon 未运行（PID {} 已 {} 秒没有刷新），状态栏会直接请求接口",
+            state.pid,
+            (now - state.heartbeat).max(0)
+        ),
+        None => println!("daemon 未运行，状态栏会直接请求接口 · 启动: uucode daemon start"),
+    }
+}
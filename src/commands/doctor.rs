@@ -0,0 +1,333 @@
+//! `uucode doctor` - diagnose configuration problems
+
+use crate::api::{self, credentials::CredentialSource};
+use crate::config::{Config, EmojiWidth};
+use crate::utils::ansi::visible_width;
+use std::fs;
+
+/// Pad a label to a fixed column width (CJK labels are double-width)
+fn pad(label: &str) -> String {
+    let width = visible_width(label, EmojiWidth::Wide);
+    format!("{}{}", label, " ".repeat(16usize.saturating_sub(width)))
+}
+
+fn ok(label: &str, detail: impl std::fmt::Display) {
+    println!("  ✓ {} {}", pad(label), detail);
+}
+
+fn warn(label: &str, detail: impl std::fmt::Display) {
+    println!("  ⚠ {} {}", pad(label), detail);
+}
+
+fn fail(label: &str, detail: impl std::fmt::Display) {
+    println!("  ✗ {} {}", pad(label), detail);
+}
+
+pub fn run(reset: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if reset {
+        if crate::core::safe_mode::reset()? {
+            println!("已退出安全模式，下次渲染恢复完整状态栏");
+        } else {
+            println!("当前不在安全模式");
+        }
+        return Ok(());
+    }
+
+    println!("uucode doctor\n");
+
+    println!("配置");
+    check_runtime();
+    check_config();
+    check_crashes();
+    check_mutes();
+
+    println!("\nClaude Code");
+    check_claude_settings();
+
+    println!("\n服务商");
+    check_vendor();
+
+    Ok(())
+}
+
+/// WSL, containers and remote hosts each have their own ~/.claude
+fn check_runtime() {
+    let current_dir = std::env::current_dir()
+        .map(|dir| dir.display().to_string())
+        .unwrap_or_default();
+    let runtime = crate::core::runtime::detect(&current_dir);
+    let claude_dir = crate::utils::paths::claude_dir()
+        .map(|dir| dir.display().to_string())
+        .unwrap_or_else(|| "?".to_string());
+    ok(
+        "运行环境",
+        format!("{}，使用 {} 下的配置", runtime.describe(), claude_dir),
+    );
+}
+
+fn check_config() {
+    let path = Config::get_config_path();
+    if !path.exists() {
+        warn(
+            "config.toml",
+            format!("{} 不存在，使用默认配置", path.display()),
+        );
+        return;
+    }
+
+    match Config::load() {
+        Ok(config) => match config.check() {
+            Ok(()) => ok(
+                "config.toml",
+                format!("{} (主题 {})", path.display(), config.theme),
+            ),
+            Err(e) => fail("config.toml", e),
+        },
+        Err(e) => fail("config.toml", format!("{}: {}", path.display(), e)),
+    }
+}
+
+fn check_crashes() {
+    let safe_mode = crate::core::safe_mode::SafeMode::load();
+    if let Some(since) = &safe_mode.since {
+        let since = chrono::DateTime::parse_from_rfc3339(since)
+            .map(|t| t.format("%m-%d %H:%M").to_string())
+            .unwrap_or_else(|_| since.clone());
+        fail(
+            "安全模式",
+            format!(
+                "{} 起（{} 次渲染未完成），状态栏只显示模型和目录，{} 分钟后自动恢复；排查后也可以运行 uucode doctor --reset",
+                since,
+                safe_mode.unfinished,
+                crate::core::safe_mode::DURATION_SECS / 60
+            ),
+        );
+    } else {
+        let unfinished = crate::core::safe_mode::unfinished();
+        if unfinished > 0 {
+            warn(
+                "未完成渲染",
+                format!(
+                    "最近 {} 分钟内 {} 次，达到 {} 次后进入安全模式",
+                    crate::core::safe_mode::WINDOW_SECS / 60,
+                    unfinished,
+                    crate::core::safe_mode::MAX_UNFINISHED
+                ),
+            );
+        }
+    }
+
+    let reports = crate::utils::crash::list();
+    if let Some(latest) = reports.first() {
+        warn(
+            "崩溃报告",
+            format!(
+                "{} 份，最近一次: {}（提交问题时请附上）",
+                reports.len(),
+                latest.display()
+            ),
+        );
+    }
+}
+
+/// Muted segments are silently missing from the statusline
+fn check_mutes() {
+    let mutes = crate::core::mute::Mutes::load();
+    for (id, mute) in mutes.active() {
+        let name = serde_json::to_value(id)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default();
+        let until = match mute
+            .until
+            .and_then(|until| chrono::DateTime::from_timestamp(until, 0))
+        {
+            Some(until) => format!(
+                "至 {}",
+                crate::utils::clock::to_local(until).format("%m-%d %H:%M")
+            ),
+            None => "直到取消".to_string(),
+        };
+        warn(
+            "已静音",
+            format!("{} {}，取消: uucode unmute {}", name, until, name),
+        );
+    }
+}
+
+fn check_claude_settings() {
+    let path = match api::get_claude_settings_path() {
+        Some(p) => p,
+        None => {
+            fail("settings.json", "无法定位 home 目录");
+            return;
+        }
+    };
+
+    if !path.exists() {
+        warn("settings.json", format!("{} 不存在", path.display()));
+        return;
+    }
+
+    let settings = match api::load_claude_settings() {
+        Ok(s) => s,
+        Err(e) => {
+            fail("settings.json", e);
+            return;
+        }
+    };
+
+    // 严格 JSON 解析失败说明文件使用了注释或尾随逗号，Claude Code 本身可能不接受
+    let strict = fs::read_to_string(&path)
+        .ok()
+        .map(|c| serde_json::from_str::<serde_json::Value>(&c).is_ok())
+        .unwrap_or(true);
+    if strict {
+        ok("settings.json", path.display());
+    } else {
+        warn(
+            "settings.json",
+            format!("{} 包含注释或尾随逗号（已兼容解析）", path.display()),
+        );
+    }
+
+    match settings
+        .pointer("/statusLine/command")
+        .and_then(|v| v.as_str())
+    {
+        Some(command) => ok("statusLine", command),
+        None => warn("statusLine", "未配置，运行 `uucode --init` 自动配置"),
+    }
+}
+
+fn check_vendor() {
+    let vendor = api::detect_vendor_from_claude_settings();
+    let base_url = api::get_current_base_url().unwrap_or_else(|| "未配置".to_string());
+
+    if vendor.is_supported() {
+        ok(
+            "服务商",
+            format!("{} ({})", vendor.display_name(), base_url),
+        );
+    } else {
+        warn(
+            "服务商",
+            format!(
+                "未识别 ({})，支持: {}",
+                base_url,
+                api::get_supported_vendors_str()
+            ),
+        );
+    }
+
+    check_credentials(vendor);
+    if vendor == api::VendorType::Cubence {
+        check_cubence_cookie();
+    }
+    check_breakers();
+}
+
+/// The load and subscription segments need the cubence.com login cookie
+fn check_cubence_cookie() {
+    let cookie = match api::cookie::load() {
+        Ok(Some(cookie)) => cookie,
+        Ok(None) => {
+            warn(
+                "Cookie",
+                "未配置，负载和订阅段不可用，运行 `uucode cubence set-cookie`",
+            );
+            return;
+        }
+        Err(e) => {
+            fail("Cookie", format!("cookie.json 无法读取: {}", e));
+            return;
+        }
+    };
+    let Some(expires_at) = api::cookie::expires_at(&cookie) else {
+        ok("Cookie", "已配置");
+        return;
+    };
+    let remaining = expires_at - crate::utils::clock::unix_now();
+    let until = chrono::DateTime::from_timestamp(expires_at, 0)
+        .map(|at| {
+            crate::utils::clock::to_local(at)
+                .format("%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default();
+    if remaining <= 0 {
+        fail(
+            "Cookie",
+            format!("已于 {} 过期，运行 `uucode cubence set-cookie`", until),
+        );
+    } else if remaining <= api::cookie::RENEW_WARNING_SECS {
+        warn(
+            "Cookie",
+            format!(
+                "{} 后过期（{}），运行 `uucode cubence set-cookie` 续期",
+                api::cookie::format_remaining(remaining),
+                until
+            ),
+        );
+    } else {
+        ok("Cookie", format!("有效期至 {}", until));
+    }
+}
+
+/// 熔断中的接口不会被请求，段只显示缓存
+fn check_breakers() {
+    let now = crate::utils::clock::unix_now();
+    let mut breakers: Vec<_> = api::breaker::load().into_iter().collect();
+    breakers.sort_by(|a, b| a.0.cmp(&b.0));
+    for (endpoint, breaker) in breakers {
+        let error = breaker.last_error.as_deref().unwrap_or("未知错误");
+        match breaker.state {
+            api::breaker::State::Closed => warn(
+                "接口失败",
+                format!("{} 连续失败 {} 次: {}", endpoint, breaker.failures, error),
+            ),
+            state => fail(
+                "接口熔断",
+                format!(
+                    "{} {}，{} 秒后重新探测: {}",
+                    endpoint,
+                    state.label(),
+                    (breaker.retry_at - now).max(0),
+                    error
+                ),
+            ),
+        }
+    }
+}
+
+/// 展示密钥解析链中每个来源的情况，以及最终生效的来源
+fn check_credentials(vendor: api::VendorType) {
+    if !vendor.is_supported() {
+        return;
+    }
+
+    let winner = api::credentials::resolve(vendor).map(|(_, source)| source);
+    match winner {
+        Some(source) => ok("密钥", format!("来自 {}", source.description())),
+        None => {
+            fail("密钥", "所有来源均未找到密钥");
+        }
+    }
+
+    for source in CredentialSource::ALL {
+        let found = api::credentials::lookup(source, vendor).is_some();
+        let marker = if Some(source) == winner {
+            "←"
+        } else if found {
+            "（被覆盖）"
+        } else {
+            ""
+        };
+        println!(
+            "      {} {} {}",
+            if found { "●" } else { "○" },
+            source.description(),
+            marker
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! `uucode explain`：逐段解释最近一次渲染的状态栏
+
+use crate::config::SegmentId;
+use crate::core::render_log::{RenderLog, RenderedSegment};
+
+pub fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let Some(log) = RenderLog::load() else {
+        println!("还没有渲染记录，让 Claude Code 显示一次状态栏后再试");
+        return Ok(());
+    };
+
+    let rendered_at = chrono::DateTime::parse_from_rfc3339(&log.rendered_at)
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or(log.rendered_at.clone());
+    println!("最近一次渲染（{}）:", rendered_at);
+    println!("  {}\n", log.text);
+
+    for segment in &log.segments {
+        let shown = [&segment.icon, &segment.primary, &segment.secondary]
+            .iter()
+            .filter(|part| !part.is_empty())
+            .map(|part| part.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        println!("{}", shown);
+        println!("  → {}", describe(segment));
+        for note in notes(segment) {
+            println!("    {}", note);
+        }
+    }
+
+    Ok(())
+}
+
+/// 一句话说明该段显示的是什么
+fn describe(segment: &RenderedSegment) -> String {
+    let get = |key: &str| segment.metadata.get(key).map(String::as_str).unwrap_or("?");
+    let number = |key: &str| {
+        segment
+            .metadata
+            .get(key)
+            .and_then(|v| v.parse::<f64>().ok())
+    };
+
+    match segment.id {
+        SegmentId::Model if segment.primary.contains("(x") => format!(
+            "当前模型（{}）；括号里的 (xN) 是 Cubence 当前的计费倍率",
+            get("model_id")
+        ),
+        SegmentId::Model => format!("当前模型（{}）", get("model_id")),
+        SegmentId::Directory => match number("roots").filter(|roots| *roots > 1.0) {
+            Some(roots) => format!(
+                "当前工作目录 {}，会话另外还添加了 {} 个目录（+{}）",
+                get("full_path"),
+                roots - 1.0,
+                roots - 1.0
+            ),
+            None => format!("当前工作目录 {}", get("full_path")),
+        },
+        SegmentId::Git => {
+            let status = match get("status") {
+                "Clean" => "工作区干净",
+                "Dirty" => "有未提交的改动（●）",
+                "Conflicts" => "存在合并冲突",
+                _ => "状态未知",
+            };
+            format!(
+                "Git 分支 {}，{}；领先 {} / 落后 {} 个提交",
+                get("branch"),
+                status,
+                get("ahead"),
+                get("behind")
+            )
+        }
+        SegmentId::ContextWindow => match number("percentage") {
+            Some(percentage) => {
+                let mut text = format!(
+                    "上下文窗口已用 {:.1}%（{} / {} tokens），满了之后 Claude Code 会自动压缩",
+                    percentage,
+                    get("tokens"),
+                    get("limit")
+                );
+                if let Some(turns) = segment.metadata.get("turns_until_compaction") {
+                    text.push_str(&format!(
+                        "；按最近几轮的增长速度，约 {} 条消息后触发压缩",
+                        turns
+                    ));
+                }
+                text
+            }
+            None => "上下文窗口用量，本会话还没有数据".to_string(),
+        },
+        SegmentId::ContextAdvisor => match get("advice") {
+            "clear" => "上下文快满了，建议 /clear 开始新对话".to_string(),
+            _ => "上下文偏大，建议运行 /compact 压缩对话".to_string(),
+        },
+        SegmentId::ApiErrors => format!(
+            "最近 {} 分钟内有 {} 次 API 请求失败（529 过载 {} 次，429 限流 {} 次）",
+            get("window_minutes"),
+            get("count"),
+            get("overloaded"),
+            get("rate_limit")
+        ),
+        SegmentId::Summary => format!(
+            "智能摘要：按规则挑出当前最需要关注的一项（来自 {} 段）",
+            get("source")
+        ),
+        SegmentId::QuotaPacing => format!(
+            "{} 窗口额度消耗节奏：已用 {}%，已过时间 {}%；用量跑在时间前面说明会提前用完",
+            get("window"),
+            get("used_percentage"),
+            get("time_percentage")
+        ),
+        SegmentId::Usage if get("mode") == "admin" => {
+            let mut text = format!(
+                "Anthropic 组织今日花费 ${}、{} tokens，本月累计 ${}（UTC 日期，来自 Admin API 用量 / 成本报表）",
+                get("today_cost"),
+                get("today_tokens"),
+                get("month_cost")
+            );
+            if let Some(percentage) = number("used_percentage") {
+                text.push_str(&format!("；已用月度预算的 {:.0}%", percentage));
+            }
+            text
+        }
+        SegmentId::Usage => {
+            "Anthropic 官方订阅用量：5 小时窗口百分比，后面是重置时间；图标圆圈表示 7 天用量"
+                .to_string()
+        }
+        SegmentId::Cost => match (number("cost"), number("estimated_cost")) {
+            (Some(cost), _) => format!("本会话 Claude Code 统计的花费 ${:.2}", cost),
+            (None, Some(cost)) => format!("按 token 单价估算的本会话花费 ${:.2}", cost),
+            (None, None) => "本会话花费".to_string(),
+        },
+        SegmentId::DailyCost => {
+            let mut text = format!(
+                "按本机对话记录和模型单价估算：今日花费 ${}（{} tokens），本周（周一起）${}",
+                get("today_cost"),
+                get("today_tokens"),
+                get("week_cost")
+            );
+            if let Some(tokens) = segment.metadata.get("unpriced_tokens") {
+                text.push_str(&format!(
+                    "；{} tokens 来自没有单价的模型，未计入（可在 models.toml 中设置 pricing）",
+                    tokens
+                ));
+            }
+            if segment.metadata.contains_key("scanning") {
+                text.push_str("；首次扫描尚未完成，数字偏小（…）");
+            }
+            text
+        }
+        SegmentId::BlockUsage if get("active") != "true" => {
+            "近 5 小时没有对话，当前没有进行中的计费窗口".to_string()
+        }
+        SegmentId::BlockUsage => {
+            let mut text = format!(
+                "按本机对话记录估算：当前 5 小时计费窗口已用 {} tokens（约 ${}），{}后重置",
+                get("block_tokens"),
+                get("block_cost"),
+                get("resets_in_secs")
+                    .parse::<u64>()
+                    .map(|secs| format!("{} 分钟", secs.div_ceil(60)))
+                    .unwrap_or_default()
+            );
+            match segment.metadata.get("limit_source").map(String::as_str) {
+                Some("option") => text.push_str(&format!(
+                    "；进度条以 token_limit {} 为上限（{}%）",
+                    get("token_limit"),
+                    get("used_percentage")
+                )),
+                Some(_) => text.push_str(&format!(
+                    "；进度条以近几天最大的一个窗口 {} tokens 为上限（{}%）",
+                    get("token_limit"),
+                    get("used_percentage")
+                )),
+                None => text.push_str("；进度条为窗口已过去的时间"),
+            }
+            text
+        }
+        SegmentId::Session => "本会话时长，以及新增 / 删除的代码行数".to_string(),
+        SegmentId::OutputStyle => "当前的输出风格（/output-style）".to_string(),
+        SegmentId::Runtime => format!(
+            "当前运行环境 {}，这里生效的是 {} 和 {}（各环境的配置和 Cookie 互相独立）",
+            segment.primary,
+            get("config_path"),
+            get("claude_dir")
+        ),
+        SegmentId::Update => "有新版本可用".to_string(),
+        SegmentId::UucodeUsage => {
+            let mut text = match (number("used"), number("total")) {
+                (Some(used), Some(total)) if total > 0.0 => format!(
+                    "uucode 本周期已用额度 ${:.2} / 总额度 ${:.0}（{:.0}%），剩余 ${}",
+                    used,
+                    total,
+                    used / total * 100.0,
+                    get("remaining")
+                ),
+                _ => "uucode 本周期额度用量".to_string(),
+            };
+            if segment.metadata.contains_key("cycle_day") {
+                text.push_str(&format!(
+                    "；计费周期第 {} / {} 天",
+                    get("cycle_day"),
+                    get("cycle_days")
+                ));
+            }
+            if let Some(projected) = number("projected_usd") {
+                text.push_str(&format!("，按目前速度周期结束时约用 ${:.0}", projected));
+            }
+            text
+        }
+        SegmentId::UucodeSubscription => "uucode 订阅套餐及剩余天数".to_string(),
+        SegmentId::UucodeStatus => "uucode 服务状态".to_string(),
+        SegmentId::CubenceStatus => "Cubence 服务状态".to_string(),
+        SegmentId::CubenceUsage => "Cubence 额度：5 小时窗口和每周窗口的已用 / 上限".to_string(),
+        SegmentId::CubenceBalance => "Cubence 按量付费余额".to_string(),
+        SegmentId::CubenceSubscription => "Cubence 订阅套餐及剩余时间".to_string(),
+        SegmentId::CubenceFiveHour => {
+            "Cubence 5 小时窗口：进度条是用量，金额是已用 / 上限，括号里是距离重置的时间"
+                .to_string()
+        }
+        SegmentId::CubenceWeekly => {
+            "Cubence 每周窗口：进度条是用量，金额是已用 / 上限，括号里是距离重置的时间".to_string()
+        }
+        SegmentId::CubenceLoadStatus => format!(
+            "Cubence Claude Pool 当前负载 {}%，越高越容易排队或限流",
+            get("load_percentage")
+        ),
+        SegmentId::CubenceLatency => match segment.metadata.get("latency_ms") {
+            Some(ms) => format!("到 Cubence 的请求延迟 {} ms", ms),
+            None => "到 Cubence 的请求失败".to_string(),
+        },
+        SegmentId::CubenceMultiplier => "Cubence 当前的计费倍率".to_string(),
+        SegmentId::OneApiUsage => format!(
+            "one-api 网关额度：已用 / 总额度（已用 + 剩余），剩余 ${}，分组 {}",
+            get("remaining"),
+            get("group")
+        ),
+        SegmentId::VendorUsage => format!(
+            "vendors.d 中服务商 {} 的用量：已用 / 额度，后面是套餐、余额和重置倒计时",
+            get("vendor")
+        ),
+        SegmentId::Custom => match segment.metadata.get("trust") {
+            Some(_) => {
+                "项目 .uucode.toml 中的命令尚未信任，运行 uucode trust 查看并允许".to_string()
+            }
+            None => "自定义命令的输出".to_string(),
+        },
+    }
+}
+
+/// 缓存、告警等附加说明
+fn notes(segment: &RenderedSegment) -> Vec<String> {
+    let mut notes = Vec::new();
+    match segment.metadata.get("level").map(String::as_str) {
+        Some("warning") => notes.push("⚠ 已进入警告区间".to_string()),
+        Some("critical") => notes.push("🛑 已接近或超过上限".to_string()),
+        _ => {}
+    }
+    if segment.metadata.get("stale").is_some_and(|v| v == "true") {
+        let age = segment
+            .metadata
+            .get("cache_age_secs")
+            .and_then(|v| v.parse::<u64>().ok())
+            .map(|secs| format!("（{} 分钟前）", secs / 60))
+            .unwrap_or_default();
+        notes.push(format!("⟳ 接口暂时不可用，显示的是缓存数据{}", age));
+    }
+    if segment
+        .metadata
+        .get("schema_mismatch")
+        .is_some_and(|v| v == "true")
+    {
+        notes.push("接口返回格式有变化，数据可能不完整".to_string());
+    }
+    notes
+}
@@ -0,0 +1,81 @@
+//! `uucode focus on|off|auto|status`
+
+use crate::cli::FocusAction;
+use crate::config::Config;
+use crate::core::focus::{self, Toggle};
+
+pub fn run(action: Option<&FocusAction>) -> Result<(), Box<dyn std::error::Error>> {
+    match action {
+        Some(FocusAction::On) => {
+            focus::set_toggle(Some(Toggle::On))?;
+            println!("🎯 专注模式已开启，用量和花费段将被隐藏");
+            println!("   关闭: uucode focus off · 恢复按计划: uucode focus auto");
+        }
+        Some(FocusAction::Off) => {
+            focus::set_toggle(Some(Toggle::Off))?;
+            println!("专注模式已关闭（计划时段也不会开启），恢复按计划: uucode focus auto");
+        }
+        Some(FocusAction::Auto) => {
+            focus::set_toggle(None)?;
+            println!("专注模式将按 config.toml 中 [focus] schedule 的时段自动开关");
+        }
+        Some(FocusAction::Status) | None => return status(),
+    }
+    println!();
+    status()
+}
+
+fn status() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load().unwrap_or_else(|_| Config::default());
+    let state = focus::load_state();
+
+    match focus::current(&config.focus) {
+        Some(current) => {
+            let source = if current.source == "manual" {
+                "手动开启".to_string()
+            } else {
+                format!("计划时段 {}", current.source)
+            };
+            println!("当前: 专注中（{}）", source);
+            let hidden: Vec<String> = current
+                .hide
+                .iter()
+                .filter_map(|id| serde_json::to_value(id).ok())
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect();
+            if !hidden.is_empty() {
+                println!("  隐藏的段: {}", hidden.join(", "));
+            }
+            if current.mute_alerts {
+                println!("  告警通知: 暂停");
+            }
+        }
+        None if state.as_ref().is_some_and(|s| s.mode == Toggle::Off) => {
+            println!("当前: 已手动关闭");
+        }
+        None => println!("当前: 未开启"),
+    }
+
+    if config.focus.schedule.is_empty() {
+        println!("计划: 未设置，可在 config.toml 的 [[focus.schedule]] 中添加 start/end 时段");
+    } else {
+        println!("计划:");
+        for window in &config.focus.schedule {
+            let mut notes = Vec::new();
+            if window.hide.as_ref().is_some_and(|hide| hide.is_empty()) {
+                notes.push("不隐藏段");
+            }
+            if window.mute_alerts.unwrap_or(config.focus.mute_alerts) {
+                notes.push("暂停告警");
+            }
+            let notes = if notes.is_empty() {
+                String::new()
+            } else {
+                format!("（{}）", notes.join("，"))
+            };
+            println!("  {}-{}{}", window.start, window.end, notes);
+        }
+    }
+
+    Ok(())
+}
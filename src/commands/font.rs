@@ -0,0 +1,279 @@
+//! `uucode font install|list`: download a Nerd Font into the user font
+//! directory so the Nerd Font icon set stops rendering as boxes.
+//!
+//! Archives come from a pinned Nerd Fonts release and are checked against
+//! the `SHA-256.txt` published with it before anything is unpacked. The
+//! system `tar` unpacks them (bsdtar on macOS and Windows 10+, GNU tar on
+//! Linux), which avoids pulling an archive library into the statusline.
+
+use crate::cli::FontAction;
+use crate::utils::download::{self, checksum_for};
+use crate::utils::subprocess::Subprocess;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const RELEASE: &str = "v3.2.1";
+const RELEASE_URL: &str = "https://github.com/ryanoasis/nerd-fonts/releases/download";
+
+/// Font archives are a few MB; anything far larger is not a font
+const MAX_ARCHIVE_BYTES: u64 = 64 * 1024 * 1024;
+
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+const UNPACK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Archive name in the release and the family name terminals list
+const FONTS: &[(&str, &str)] = &[
+    ("JetBrainsMono", "JetBrainsMono Nerd Font"),
+    ("FiraCode", "FiraCode Nerd Font"),
+    ("Hack", "Hack Nerd Font"),
+    ("CascadiaCode", "CaskaydiaCove Nerd Font"),
+    ("Meslo", "MesloLGS Nerd Font"),
+    ("SourceCodePro", "SauceCodePro Nerd Font"),
+    ("UbuntuMono", "UbuntuMono Nerd Font"),
+];
+
+pub fn run(action: &FontAction) -> Result<(), Box<dyn std::error::Error>> {
+    match action {
+        FontAction::Install { name } => install(name),
+        FontAction::List => {
+            list();
+            Ok(())
+        }
+    }
+}
+
+fn lookup(name: &str) -> Option<(&'static str, &'static str)> {
+    FONTS
+        .iter()
+        .find(|(archive, _)| archive.eq_ignore_ascii_case(name))
+        .copied()
+}
+
+/// Where per-user fonts go; fonts are installed in a subdirectory on Linux
+/// so they can be removed together
+fn install_dir(archive: &str) -> Option<PathBuf> {
+    if cfg!(windows) {
+        return dirs::data_local_dir()
+            .map(|dir| dir.join("Microsoft").join("Windows").join("Fonts"));
+    }
+    let dir = dirs::font_dir()?;
+    if cfg!(target_os = "linux") {
+        Some(dir.join(format!("{}NerdFont", archive)))
+    } else {
+        Some(dir)
+    }
+}
+
+fn is_font_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("ttf") || ext.eq_ignore_ascii_case("otf"))
+}
+
+/// Font files of `family` already in the install directory
+fn installed_files(archive: &str, family: &str) -> usize {
+    let Some(dir) = install_dir(archive) else {
+        return 0;
+    };
+    // Files are named after the family, e.g. CaskaydiaCoveNerdFont-Regular.ttf
+    let prefix = family.replace(' ', "").to_lowercase();
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| is_font_file(&entry.path()))
+                .filter(|entry| {
+                    entry
+                        .file_name()
+                        .to_string_lossy()
+                        .to_lowercase()
+                        .starts_with(&prefix)
+                })
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+fn list() {
+    println!("可安装的 Nerd Font（{}）:", RELEASE);
+    for (archive, family) in FONTS {
+        let status = match installed_files(archive, family) {
+            0 => String::new(),
+            n => format!("  ✓ 已安装 {} 个文件", n),
+        };
+        println!("  {:<15} {}{}", archive, family, status);
+    }
+    println!("\n安装: uucode font install <名称>");
+}
+
+fn fetch(url: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    download::fetch(url, MAX_ARCHIVE_BYTES, DOWNLOAD_TIMEOUT)
+}
+
+fn install(name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let (archive, family) = lookup(name).ok_or_else(|| {
+        let names: Vec<&str> = FONTS.iter().map(|(archive, _)| *archive).collect();
+        format!("未知字体: {}（可选: {}）", name, names.join(", "))
+    })?;
+    crate::utils::readonly::ensure_writable("installing fonts")?;
+    let dir = install_dir(archive).ok_or("无法确定字体目录")?;
+    let file = format!("{}.tar.xz", archive);
+
+    println!("下载 {} {} …", family, RELEASE);
+    let sums = fetch(&format!("{}/{}/SHA-256.txt", RELEASE_URL, RELEASE))?;
+    let expected = checksum_for(&String::from_utf8_lossy(&sums), &file)
+        .map(str::to_string)
+        .ok_or_else(|| format!("SHA-256.txt 中没有 {}", file))?;
+    let body = fetch(&format!("{}/{}/{}", RELEASE_URL, RELEASE, file))?;
+    download::verify_sha256(&body, &expected).map_err(|e| format!("{} 校验失败: {}", file, e))?;
+    println!("✓ SHA-256 校验通过");
+
+    let work = crate::utils::paths::cache_dir()
+        .ok_or("Could not find home directory")?
+        .join("font-install");
+    let _ = fs::remove_dir_all(&work);
+    fs::create_dir_all(&work)?;
+    let result = unpack_and_copy(&work, &file, &body, &dir);
+    let _ = fs::remove_dir_all(&work);
+    let installed = result?;
+    println!(
+        "✓ 已安装 {} 个字体文件到 {}",
+        installed.len(),
+        dir.display()
+    );
+
+    register(&dir, &installed);
+    print_instructions(family);
+    Ok(())
+}
+
+/// Unpack the archive in `work` and copy its font files into `dir`
+fn unpack_and_copy(
+    work: &Path,
+    file: &str,
+    body: &[u8],
+    dir: &Path,
+) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let archive_path = work.join(file);
+    crate::utils::atomic_write(&archive_path, body)?;
+    let unpacked = work.join("files");
+    fs::create_dir_all(&unpacked)?;
+
+    let output = Subprocess::new("tar")
+        .arg("-xf")
+        .arg(&archive_path)
+        .arg("-C")
+        .arg(&unpacked)
+        .with_timeout(UNPACK_TIMEOUT)
+        .run()
+        .map_err(|e| format!("无法运行 tar: {}", e))?;
+    if !output.success() {
+        return Err(format!("解压失败: {}", output.stderr.trim()).into());
+    }
+
+    fs::create_dir_all(dir)?;
+    let mut installed = Vec::new();
+    for entry in fs::read_dir(&unpacked)?.flatten() {
+        let path = entry.path();
+        if !is_font_file(&path) {
+            continue;
+        }
+        let target = dir.join(entry.file_name());
+        crate::utils::atomic_write(&target, fs::read(&path)?)?;
+        installed.push(target);
+    }
+    if installed.is_empty() {
+        return Err(format!("{} 中没有字体文件", file).into());
+    }
+    Ok(installed)
+}
+
+/// Make the new files visible to applications without logging out
+fn register(dir: &Path, installed: &[PathBuf]) {
+    if cfg!(target_os = "linux") {
+        match Subprocess::new("fc-cache")
+            .arg("-f")
+            .arg(dir)
+            .with_timeout(UNPACK_TIMEOUT)
+            .run()
+        {
+            Ok(output) if output.success() => println!("✓ 已刷新字体缓存"),
+            _ => println!("⚠ fc-cache 运行失败，可能需要重新登录后字体才会出现"),
+        }
+    } else if cfg!(windows) {
+        // Per-user fonts only load when listed under HKCU
+        let failed = installed.iter().any(|path| {
+            let stem = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let registered = Subprocess::new("reg")
+                .args([
+                    "add",
+                    r"HKCU\Software\Microsoft\Windows NT\CurrentVersion\Fonts",
+                    "/v",
+                    &format!("{} (TrueType)", stem),
+                    "/t",
+                    "REG_SZ",
+                    "/d",
+                    &path.to_string_lossy(),
+                    "/f",
+                ])
+                .run();
+            !registered.is_ok_and(|output| output.success())
+        });
+        if failed {
+            println!("⚠ 部分字体未能注册，可在文件夹中双击字体文件手动安装");
+        }
+    }
+}
+
+/// How to switch the current terminal to the new font
+fn print_instructions(family: &str) {
+    let term_program = std::env::var("TERM_PROGRAM").unwrap_or_default();
+    let term = std::env::var("TERM").unwrap_or_default();
+    let env = |name: &str| std::env::var_os(name).is_some();
+
+    println!();
+    let step = if env("WT_SESSION") {
+        format!(
+            "Windows Terminal: 设置 → 配置文件 → 默认值 → 外观 → 字体，选择 \"{}\"",
+            family
+        )
+    } else if term_program == "vscode" {
+        format!(
+            "VS Code: 在 settings.json 中设置 \"terminal.integrated.fontFamily\": \"{}\"",
+            family
+        )
+    } else if term_program == "iTerm.app" {
+        format!(
+            "iTerm2: Settings → Profiles → Text → Font，选择 \"{}\"",
+            family
+        )
+    } else if term_program == "Apple_Terminal" {
+        format!("终端: 设置 → 描述文件 → 文本 → 字体，选择 \"{}\"", family)
+    } else if term_program == "WezTerm" {
+        format!(
+            "WezTerm: 在 wezterm.lua 中设置 config.font = wezterm.font(\"{}\")",
+            family
+        )
+    } else if term == "xterm-kitty" {
+        format!("kitty: 在 kitty.conf 中设置 font_family {}", family)
+    } else if term == "alacritty" || env("ALACRITTY_SOCKET") {
+        format!(
+            "Alacritty: 在 alacritty.toml 中设置 [font.normal] family = \"{}\"",
+            family
+        )
+    } else if env("VTE_VERSION") {
+        format!(
+            "GNOME 终端: 首选项 → 配置文件 → 文本 → 自定义字体，选择 \"{}\"",
+            family
+        )
+    } else {
+        format!("在终端的字体设置中选择 \"{}\"", family)
+    };
+    println!("下一步:");
+    println!("  1. {}", step);
+    println!("  2. 重启终端，然后在 config.toml 的 [style] 中设置 icon_set = \"nerd-v3\"");
+}
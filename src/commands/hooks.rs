@@ -0,0 +1,112 @@
+//! `uucode hooks install|uninstall|status`, and `uucode hooks run <name>`
+//! which Claude Code calls for each installed hook
+
+use crate::api::{self, cache, VendorType};
+use crate::auto_config::hooks::{self, MANAGED_HOOKS};
+use crate::cli::HooksAction;
+use crate::config::{Config, InputData};
+use crate::core::ledger::SessionRecord;
+use serde::Deserialize;
+use std::io::Read;
+
+/// Usage caches younger than this are not refreshed before a tool call
+const REFRESH_AFTER_SECS: u64 = 60;
+
+/// The part of Claude Code's hook payload (stdin) we use
+#[derive(Debug, Default, Deserialize)]
+struct HookInput {
+    #[serde(default)]
+    transcript_path: String,
+}
+
+pub fn run(action: &HooksAction) -> Result<(), Box<dyn std::error::Error>> {
+    match action {
+        HooksAction::Install => hooks::configure(true),
+        HooksAction::Uninstall => hooks::configure(false),
+        HooksAction::Status => status(),
+        HooksAction::Run { name } => {
+            handle(name);
+            Ok(())
+        }
+    }
+}
+
+fn status() -> Result<(), Box<dyn std::error::Error>> {
+    let settings = hooks::load_settings()?;
+    let installed = hooks::installed(&settings);
+    for hook in MANAGED_HOOKS {
+        let mark = if installed.iter().any(|h| h.name == hook.name) {
+            "✓"
+        } else {
+            "✗"
+        };
+        println!("{} {:<12} {}", mark, hook.event, hook.description);
+    }
+    if installed.is_empty() {
+        println!("\n使用 `uucode hooks install` 安装");
+    }
+    Ok(())
+}
+
+/// Hooks must never get in Claude Code's way, so failures are only logged
+fn handle(name: &str) {
+    let mut raw = String::new();
+    let _ = std::io::stdin().read_to_string(&mut raw);
+    let input: HookInput = serde_json::from_str(&raw).unwrap_or_default();
+
+    let result = match name {
+        "session-end" => session_end(&input),
+        "pre-tool-use" => refresh_usage(),
+        _ => Err(format!("unknown hook: {}", name).into()),
+    };
+    if let Err(e) = result {
+        crate::debug_log!("hook {} failed: {}", name, e);
+    }
+}
+
+fn session_end(input: &HookInput) -> Result<(), Box<dyn std::error::Error>> {
+    // The wrapper writes its own record when Claude Code exits
+    if crate::wrapper::passed_through("UUCODE_WRAPPER").is_some() {
+        return Ok(());
+    }
+    let Some(record) = SessionRecord::collect_for_transcript(&input.transcript_path) else {
+        return Ok(());
+    };
+    record.append()?;
+
+    let config = Config::load().unwrap_or_default();
+    if let Some(url) = &config.wrapper.session_webhook {
+        record.post(url, &config)?;
+    }
+    Ok(())
+}
+
+/// Collect the current vendor's segments from the last payload, which
+/// refreshes their caches, unless the usage cache is still recent
+fn refresh_usage() -> Result<(), Box<dyn std::error::Error>> {
+    let vendor = api::detect_vendor_from_claude_settings();
+    let age = match vendor {
+        VendorType::Uucode => cache::usage_cache_age(),
+        VendorType::Cubence => cache::cubence_usage_cache_age(),
+        VendorType::OneApi => cache::oneapi_usage_cache_age(),
+        VendorType::Custom => {
+            api::vendor::current().and_then(|v| cache::custom_usage_cache_age(v.id()))
+        }
+        VendorType::Unknown => return Ok(()),
+    };
+    if age.is_some_and(|age| age < REFRESH_AFTER_SECS) {
+        return Ok(());
+    }
+
+    let Some(input) =
+        cache::load_last_input().and_then(|raw| serde_json::from_str::<InputData>(&raw).ok())
+    else {
+        return Ok(());
+    };
+    let mut config = Config::load().unwrap_or_default();
+    config
+        .segments
+        .retain(|s| s.enabled && s.id.vendor() == Some(vendor));
+    crate::core::collect_all_segments(&config, &input);
+    Ok(())
+}
@@ -0,0 +1,22 @@
+//! Implementations of `uucode <subcommand>`
+
+pub mod config;
+#[cfg(feature = "cubence")]
+pub mod cubence;
+pub mod daemon;
+pub mod doctor;
+pub mod explain;
+pub mod focus;
+pub mod font;
+pub mod hooks;
+pub mod mute;
+pub mod perf;
+pub mod segment;
+pub mod serve;
+pub mod simulate;
+pub mod stats;
+pub mod theme;
+#[cfg(feature = "tui")]
+pub mod top;
+pub mod trust;
+pub mod vendor;
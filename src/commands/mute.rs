@@ -0,0 +1,51 @@
+//! `uucode mute <segment> [--for 2h]` and `uucode unmute <segment>`
+
+use crate::config::SegmentId;
+use crate::core::mute;
+
+/// Segment id from its config.toml name
+fn parse_segment(name: &str) -> Result<SegmentId, Box<dyn std::error::Error>> {
+    serde_json::from_value(serde_json::Value::String(name.to_string())).map_err(|_| {
+        format!(
+            "未知的段: {}（使用 config.toml 中的 id，如 cubence_load_status）",
+            name
+        )
+        .into()
+    })
+}
+
+pub fn run(segment: &str, duration: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let id = parse_segment(segment)?;
+    let secs = match duration {
+        Some(text) => Some(
+            mute::parse_duration(text)
+                .ok_or_else(|| format!("无法识别的时长: {}（示例: 30m、2h、1d）", text))?,
+        ),
+        None => None,
+    };
+
+    let muted = mute::mute(id, secs)?;
+    match muted
+        .until
+        .and_then(|until| chrono::DateTime::from_timestamp(until, 0))
+    {
+        Some(until) => println!(
+            "🔇 {} 已静音至 {}",
+            segment,
+            crate::utils::clock::to_local(until).format("%m-%d %H:%M")
+        ),
+        None => println!("🔇 {} 已静音", segment),
+    }
+    println!("   取消: uucode unmute {}", segment);
+    Ok(())
+}
+
+pub fn unmute(segment: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let id = parse_segment(segment)?;
+    if mute::unmute(id)? {
+        println!("🔊 {} 已取消静音，下次渲染时重新采集", segment);
+    } else {
+        println!("{} 没有被静音", segment);
+    }
+    Ok(())
+}
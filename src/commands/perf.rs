@@ -0,0 +1,77 @@
+//! `uucode perf show|reset`
+
+use crate::cli::PerfAction;
+use crate::config::Config;
+use crate::core::perf::PerfStats;
+
+pub fn run(action: &PerfAction) -> Result<(), Box<dyn std::error::Error>> {
+    match action {
+        PerfAction::Show => show(),
+        PerfAction::Reset => {
+            PerfStats::reset()?;
+            println!("已清除段耗时记录，所有段将在下次渲染时重新采集");
+            Ok(())
+        }
+    }
+}
+
+fn show() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load().unwrap_or_else(|_| Config::default());
+    let budget = config.perf.budget_ms;
+    let stats = PerfStats::load();
+
+    if stats.segments.is_empty() {
+        println!("还没有耗时记录，状态栏渲染一次后再试");
+        return Ok(());
+    }
+
+    let mut timings: Vec<_> = stats.segments.iter().collect();
+    timings.sort_by(|a, b| b.1.average_ms.total_cmp(&a.1.average_ms));
+
+    println!(
+        "预算: {} ms/段 · 自动跳过: {}\n",
+        budget,
+        if config.perf.auto_skip { "开" } else { "关" }
+    );
+    println!(
+        "{:<22} {:>10} {:>10} {:>8}  状态",
+        "段", "平均 ms", "最近 ms", "次数"
+    );
+    for (id, timing) in timings {
+        let name = serde_json::to_value(id)?
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let status = if !timing.is_over(budget) {
+            "✓"
+        } else if stats.should_skip(*id, &config.perf) {
+            "⏱ 已跳过"
+        } else {
+            "⚠ 超出预算"
+        };
+        println!(
+            "{:<22} {:>10.0} {:>10.0} {:>8}  {}",
+            name, timing.average_ms, timing.last_ms, timing.samples, status
+        );
+    }
+
+    let offenders = stats
+        .segments
+        .values()
+        .filter(|timing| timing.is_over(budget))
+        .count();
+    if offenders > 0 && config.perf.auto_skip {
+        println!(
+            "\n{} 个段超出预算，可在 config.toml 的 [perf] 中调整 budget_ms 或关闭 auto_skip",
+            offenders
+        );
+    } else if offenders > 0 {
+        println!(
+            "\n{} 个段超出预算，在 config.toml 的 [perf] 中设置 auto_skip = true 后，测量满 {} 次且平均耗时超出预算的段会被跳过",
+            offenders,
+            crate::core::perf::MIN_SAMPLES
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,293 @@
+//! `uucode segment browse|install`：社区段脚本和主题
+
+use crate::cli::SegmentAction;
+use crate::config::{Config, MarketplaceConfig};
+use crate::core::marketplace::{self, EntryKind, MarketEntry, MarketIndex};
+
+pub fn run(action: &SegmentAction) -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load().unwrap_or_else(|_| Config::default());
+
+    match action {
+        SegmentAction::Browse { insecure } => {
+            let index = fetch(&config, *insecure)?;
+            browse(index, &config.marketplace)
+        }
+        SegmentAction::Install { name, insecure } => {
+            let index = fetch(&config, *insecure)?;
+            let entry = index
+                .entries
+                .iter()
+                .find(|e| e.name == *name)
+                .ok_or_else(|| format!("索引中没有名为 {} 的条目", name))?;
+            if crate::utils::readonly::intercept("安装", || {
+                format!(
+                    "  下载 {}\n  确认 SHA-256 为 {}\n  保存到 {}",
+                    entry.url,
+                    entry.sha256,
+                    entry
+                        .install_path()
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_default()
+                )
+            }) {
+                return Ok(());
+            }
+            let path = marketplace::install(entry, &config.marketplace)?;
+            println!("✓ 已安装 {} 到 {}", entry.name, path.display());
+            print_next_step(entry);
+            Ok(())
+        }
+    }
+}
+
+fn fetch(config: &Config, insecure: bool) -> Result<MarketIndex, Box<dyn std::error::Error>> {
+    if config.marketplace.is_unverifiable_default() {
+        return Err(
+            "官方社区索引尚未发布签名公钥，暂时无法浏览和安装；使用自建索引时在 config.toml 的 [marketplace] 中设置 index_url 和 public_key（或 minisign_key）"
+                .into(),
+        );
+    }
+    if insecure {
+        eprintln!("⚠ 已跳过索引签名校验，仅在测试私有索引时使用 --insecure");
+    }
+    marketplace::fetch_index(&config.marketplace, insecure)
+        .map_err(|e| format!("获取索引失败 ({}): {}", config.marketplace.index_url, e).into())
+}
+
+fn kind_label(kind: EntryKind) -> &'static str {
+    match kind {
+        EntryKind::Segment => "段",
+        EntryKind::Theme => "主题",
+    }
+}
+
+fn print_next_step(entry: &MarketEntry) {
+    match entry.kind {
+        EntryKind::Theme => println!("  使用 `uucode --theme {}` 切换到该主题", entry.name),
+        EntryKind::Segment => {
+            if let Some(file) = entry
+                .install_path()
+                .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+            {
+                println!(
+                    "  在 config.toml 中启用 custom 段并设置 script = \"{}\"",
+                    file
+                );
+            }
+        }
+    }
+}
+
+/// Full-screen picker when prompts are allowed, a plain list otherwise
+#[cfg_attr(not(feature = "tui"), allow(unused_variables))]
+fn browse(
+    index: MarketIndex,
+    marketplace: &MarketplaceConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(feature = "tui")]
+    if crate::utils::prompt::is_interactive() {
+        return picker::run(index, marketplace.clone());
+    }
+    list(index)
+}
+
+fn list(index: MarketIndex) -> Result<(), Box<dyn std::error::Error>> {
+    if index.entries.is_empty() {
+        println!("索引为空");
+        return Ok(());
+    }
+    for entry in &index.entries {
+        println!(
+            "{} {:<24} [{}] {}",
+            if entry.is_installed() { "✓" } else { " " },
+            entry.name,
+            kind_label(entry.kind),
+            entry.description
+        );
+    }
+    println!("\n使用 `uucode segment install <name>` 安装");
+    Ok(())
+}
+
+/// 全屏浏览索引：↑↓ 选择，Enter 安装，q 退出
+#[cfg(feature = "tui")]
+mod picker {
+    use super::kind_label;
+    use crate::config::MarketplaceConfig;
+    use crate::core::marketplace::{self, MarketIndex};
+    use crossterm::{
+        event::{self, Event, KeyCode, KeyEventKind},
+        execute,
+        terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    };
+    use ratatui::{
+        backend::CrosstermBackend,
+        layout::{Constraint, Direction, Layout},
+        style::{Color, Modifier, Style},
+        text::Line,
+        widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+        Frame, Terminal,
+    };
+    use std::io;
+
+    struct Picker {
+        index: MarketIndex,
+        marketplace: MarketplaceConfig,
+        state: ListState,
+        status: String,
+    }
+
+    pub fn run(
+        index: MarketIndex,
+        marketplace: MarketplaceConfig,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut state = ListState::default();
+        if !index.entries.is_empty() {
+            state.select(Some(0));
+        }
+        let mut picker = Picker {
+            index,
+            marketplace,
+            state,
+            status: "↑↓ 选择 · Enter 安装 · q 退出".to_string(),
+        };
+
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen)?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+        let result = picker.event_loop(&mut terminal);
+
+        disable_raw_mode()?;
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+        terminal.show_cursor()?;
+
+        result
+    }
+
+    impl Picker {
+        fn event_loop(
+            &mut self,
+            terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            loop {
+                terminal.draw(|f| self.ui(f))?;
+
+                if let Event::Key(key) = event::read()? {
+                    if key.kind != KeyEventKind::Press {
+                        continue;
+                    }
+                    match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                        KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+                        KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+                        KeyCode::Enter => {
+                            // 下载是阻塞的，先把提示画出来
+                            self.status = "正在下载…".to_string();
+                            terminal.draw(|f| self.ui(f))?;
+                            self.install_selected();
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        fn move_selection(&mut self, delta: isize) {
+            let len = self.index.entries.len();
+            if len == 0 {
+                return;
+            }
+            let current = self.state.selected().unwrap_or(0) as isize;
+            let next = (current + delta).rem_euclid(len as isize) as usize;
+            self.state.select(Some(next));
+        }
+
+        fn install_selected(&mut self) {
+            let Some(entry) = self
+                .state
+                .selected()
+                .and_then(|i| self.index.entries.get(i))
+            else {
+                return;
+            };
+            self.status = match marketplace::install(entry, &self.marketplace) {
+                Ok(path) => format!("✓ 已安装 {} 到 {}", entry.name, path.display()),
+                Err(e) => format!("✗ 安装 {} 失败: {}", entry.name, e),
+            };
+        }
+
+        fn ui(&mut self, f: &mut Frame) {
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(5), Constraint::Length(1)])
+                .split(f.area());
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .split(rows[0]);
+
+            let items: Vec<ListItem> = self
+                .index
+                .entries
+                .iter()
+                .map(|entry| {
+                    ListItem::new(format!(
+                        "{} [{}] {}",
+                        if entry.is_installed() { "✓" } else { " " },
+                        kind_label(entry.kind),
+                        entry.name
+                    ))
+                })
+                .collect();
+            let list = List::new(items)
+                .block(Block::default().borders(Borders::ALL).title("社区段与主题"))
+                .highlight_style(
+                    Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .highlight_symbol("▶ ");
+            f.render_stateful_widget(list, columns[0], &mut self.state);
+
+            let details = match self
+                .state
+                .selected()
+                .and_then(|i| self.index.entries.get(i))
+            {
+                Some(entry) => {
+                    let mut lines = vec![
+                        Line::from(entry.description.clone()),
+                        Line::from(""),
+                        Line::from(format!("类型: {}", kind_label(entry.kind))),
+                    ];
+                    if let Some(author) = &entry.author {
+                        lines.push(Line::from(format!("作者: {}", author)));
+                    }
+                    if let Some(version) = &entry.version {
+                        lines.push(Line::from(format!("版本: {}", version)));
+                    }
+                    lines.push(Line::from(format!("来源: {}", entry.url)));
+                    lines.push(Line::from(format!("SHA-256: {}", entry.sha256)));
+                    if let Some(path) = entry.install_path() {
+                        lines.push(Line::from(format!("安装到: {}", path.display())));
+                    }
+                    lines
+                }
+                None => vec![Line::from("索引为空")],
+            };
+            f.render_widget(
+                Paragraph::new(details)
+                    .wrap(Wrap { trim: false })
+                    .block(Block::default().borders(Borders::ALL).title("详情")),
+                columns[1],
+            );
+
+            f.render_widget(
+                Paragraph::new(self.status.as_str()).style(Style::default().fg(Color::DarkGray)),
+                rows[1],
+            );
+        }
+    }
+}
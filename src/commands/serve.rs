@@ -0,0 +1,152 @@
+//! `uucode serve`：极简 HTTP 面板，方便在手机或另一台机器上查看用量
+//!
+//! - `GET /`           HTML 页面，定时轮询 `/api/state`
+//! - `GET /api/state`  当前段数据 + 历史采样（JSON）
+
+use crate::api;
+use crate::config::{Config, InputData};
+use crate::core::{collect_all_segments, history, output, StatusLineGenerator};
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+
+/// 两次采集之间的最短间隔，避免每个请求都打一次上游 API
+const STATE_TTL: Duration = Duration::from_secs(5);
+
+const INDEX_HTML: &str = include_str!("serve_index.html");
+
+pub fn run(bind: &str, port: u16) -> Result<(), Box<dyn std::error::Error>> {
+    let mut config = Config::load().unwrap_or_else(|_| Config::default());
+    api::exchange::resolve_rate(&mut config.format);
+
+    let listener = TcpListener::bind((bind, port))?;
+    println!("uucode 面板已启动: http://{}:{}/", bind, port);
+    if bind == "127.0.0.1" || bind == "localhost" {
+        println!("  仅本机可访问，如需从其他设备访问请使用 --bind 0.0.0.0");
+    }
+
+    let mut cached: Option<(Instant, String)> = None;
+
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                crate::debug_log!("accept failed: {}", e);
+                continue;
+            }
+        };
+        let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+
+        let Some(path) = read_request_path(&stream) else {
+            continue;
+        };
+        crate::debug_log!("GET {}", path);
+
+        let result = match path.as_str() {
+            "/" | "/index.html" => respond(
+                &mut stream,
+                "200 OK",
+                "text/html; charset=utf-8",
+                INDEX_HTML,
+            ),
+            "/api/state" => {
+                let body = match &cached {
+                    Some((at, body)) if at.elapsed() < STATE_TTL => body.clone(),
+                    _ => {
+                        let body = state(&config).to_string();
+                        cached = Some((Instant::now(), body.clone()));
+                        body
+                    }
+                };
+                respond(&mut stream, "200 OK", "application/json", &body)
+            }
+            _ => respond(
+                &mut stream,
+                "404 Not Found",
+                "text/plain; charset=utf-8",
+                "not found",
+            ),
+        };
+        if let Err(e) = result {
+            crate::debug_log!("failed to write response: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+/// 读取请求行并返回路径（忽略查询串），只接受 GET
+fn read_request_path(stream: &TcpStream) -> Option<String> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).ok()?;
+
+    // 读完请求头，否则部分客户端会在响应前收到 RST
+    let mut header = String::new();
+    while reader.read_line(&mut header).ok()? > 2 {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    if parts.next()? != "GET" {
+        return None;
+    }
+    let target = parts.next()?;
+    Some(target.split('?').next().unwrap_or("/").to_string())
+}
+
+fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/// 用状态栏的采集器生成当前状态
+fn state(config: &Config) -> Value {
+    let vendor = api::detect_vendor_from_claude_settings();
+    let input =
+        api::cache::load_last_input().and_then(|raw| serde_json::from_str::<InputData>(&raw).ok());
+
+    let history = history::load();
+
+    let Some(input) = input else {
+        return json!({
+            "vendor": vendor.id(),
+            "available": false,
+            "history": history,
+        });
+    };
+
+    let segments = collect_all_segments(config, &input);
+    let statusline = StatusLineGenerator::new(config.clone()).generate(segments.clone());
+
+    let quotas: Vec<Value> = history::quotas(&segments)
+        .into_iter()
+        .map(|(label, percentage)| json!({ "label": label, "percentage": percentage }))
+        .collect();
+
+    json!({
+        "vendor": vendor.id(),
+        "available": true,
+        "model": input.model.display_name,
+        "directory": input.workspace.current_dir,
+        "statusline": crate::utils::ansi::strip(&statusline),
+        "cost_usd": input.cost.as_ref().and_then(|c| c.total_cost_usd),
+        "context_percentage": history::context_percentage(&segments),
+        "quotas": quotas,
+        "segments": output::segments_json(&segments),
+        "history": history,
+    })
+}
@@ -0,0 +1,287 @@
+//! `uucode simulate`: replay recorded statusline payloads at a fixed rate
+//! through the full render pipeline and report throughput, render latency,
+//! cache hit rate and the API calls that would have gone out.
+//!
+//! The run is isolated from the real installation. `HOME` points at a
+//! scratch copy of `~/.claude/uucode` and the cache dir, so cache writes and
+//! circuit breakers opened by failing requests never touch the user's
+//! state; Claude Code's own dir (settings.json, transcripts) is read in
+//! place. Every HTTP client is routed through a local proxy that records
+//! the host and answers 503, so no request leaves the machine.
+
+use crate::config::{Config, InputData};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Executables installed next to the config; never needed for a render
+const SKIPPED_FILES: [&str; 4] = ["uucode", "uucode.exe", "mycode", "mycode.exe"];
+
+type Requests = Arc<Mutex<BTreeMap<String, usize>>>;
+
+pub fn run(
+    dir: &str,
+    rate: f64,
+    count: Option<usize>,
+    latency_ms: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if !(rate > 0.0 && rate.is_finite()) {
+        return Err("--rate 必须大于 0".into());
+    }
+    let payloads = load_payloads(Path::new(dir))?;
+    if payloads.is_empty() {
+        return Err(format!("{} 下没有可用的状态栏输入（*.json）", dir).into());
+    }
+    let count = count.unwrap_or(payloads.len());
+
+    let sandbox = Sandbox::enter()?;
+    let requests = Requests::default();
+    let proxy = start_mock_proxy(Arc::clone(&requests), Duration::from_millis(latency_ms))?;
+    crate::api::http::route_through(&proxy);
+
+    let config = Config::load()?;
+    config.check()?;
+
+    println!(
+        "回放 {} 个输入，共 {} 次渲染，目标 {:.1} 次/秒（网络已模拟{}）\n",
+        payloads.len(),
+        count,
+        rate,
+        if latency_ms > 0 {
+            format!("，延迟 {} ms", latency_ms)
+        } else {
+            String::new()
+        }
+    );
+
+    let interval = Duration::from_secs_f64(1.0 / rate);
+    let started = Instant::now();
+    let mut latencies = Vec::with_capacity(count);
+    let mut late = 0;
+    for i in 0..count {
+        let due = started + interval.mul_f64(i as f64);
+        match due.checked_duration_since(Instant::now()) {
+            Some(wait) => std::thread::sleep(wait),
+            None if i > 0 => late += 1,
+            None => {}
+        }
+        let render_started = Instant::now();
+        crate::render(&config, &payloads[i % payloads.len()]);
+        latencies.push(render_started.elapsed());
+    }
+    let elapsed = started.elapsed();
+    drop(sandbox);
+
+    report(&latencies, elapsed, interval, late, &requests);
+    Ok(())
+}
+
+/// Every `*.json` payload in `dir`, sorted by file name
+fn load_payloads(dir: &Path) -> Result<Vec<InputData>, Box<dyn std::error::Error>> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| format!("无法读取 {}: {}", dir.display(), e))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+
+    let mut payloads = Vec::new();
+    for file in files {
+        let parsed = fs::read_to_string(&file)
+            .map_err(|e| e.to_string())
+            .and_then(|content| serde_json::from_str(&content).map_err(|e| e.to_string()));
+        match parsed {
+            Ok(input) => payloads.push(input),
+            Err(e) => eprintln!("跳过 {}: {}", file.display(), e),
+        }
+    }
+    Ok(payloads)
+}
+
+/// Scratch `HOME` for the run, removed on drop
+struct Sandbox {
+    root: PathBuf,
+}
+
+impl Sandbox {
+    fn enter() -> Result<Self, Box<dyn std::error::Error>> {
+        // Resolve Claude Code's dir against the real home before it moves
+        let claude_dir = crate::utils::paths::claude_dir();
+        let uucode_dir = crate::utils::paths::uucode_dir();
+        let cache_dir = crate::utils::paths::cache_dir();
+
+        let root = std::env::temp_dir().join(format!("uucode-simulate-{}", std::process::id()));
+        let sandbox = Self { root };
+        let _ = fs::remove_dir_all(&sandbox.root);
+        let home_claude = sandbox.root.join(".claude");
+        if let Some(dir) = uucode_dir.filter(|dir| dir.is_dir()) {
+            copy_tree(&dir, &home_claude.join("uucode"))?;
+        }
+        if let Some(dir) = cache_dir.filter(|dir| dir.is_dir()) {
+            copy_tree(&dir, &home_claude.join("mycode").join("cache"))?;
+        }
+
+        std::env::set_var("HOME", &sandbox.root);
+        if let Some(dir) = claude_dir {
+            std::env::set_var("CLAUDE_CONFIG_DIR", dir);
+        }
+        // dirs ignores HOME on Windows; refuse rather than write to the real caches
+        if dirs::home_dir().as_deref() != Some(sandbox.root.as_path()) {
+            return Err("无法把 HOME 指向临时目录，当前平台不支持 simulate".into());
+        }
+        Ok(sandbox)
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+fn copy_tree(from: &Path, to: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)?.flatten() {
+        let path = entry.path();
+        let target = to.join(entry.file_name());
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            copy_tree(&path, &target)?;
+        } else if file_type.is_file()
+            && !SKIPPED_FILES.contains(&entry.file_name().to_string_lossy().as_ref())
+        {
+            fs::copy(&path, &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Start a proxy on a free local port that records each request's host and
+/// refuses it. Returns the proxy URL.
+fn start_mock_proxy(requests: Requests, latency: Duration) -> std::io::Result<String> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("http://{}", listener.local_addr()?);
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let requests = Arc::clone(&requests);
+            std::thread::spawn(move || refuse(stream, &requests, latency));
+        }
+    });
+    Ok(url)
+}
+
+fn refuse(mut stream: TcpStream, requests: &Requests, latency: Duration) {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+    let mut request_line = String::new();
+    let mut reader = BufReader::new(&stream);
+    if reader.read_line(&mut request_line).is_err() {
+        return;
+    }
+    // Drain the headers so the client sees the response, not a reset
+    let mut header = String::new();
+    while reader.read_line(&mut header).is_ok_and(|read| read > 2) {
+        header.clear();
+    }
+
+    if let Some(host) = request_host(&request_line) {
+        if let Ok(mut requests) = requests.lock() {
+            *requests.entry(host).or_default() += 1;
+        }
+    }
+    std::thread::sleep(latency);
+    let _ = stream.write_all(
+        b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+    );
+}
+
+/// Host of a proxied request line: `CONNECT host:443 HTTP/1.1` for HTTPS,
+/// `GET http://host/path HTTP/1.1` for plain HTTP
+fn request_host(line: &str) -> Option<String> {
+    let target = line.split_whitespace().nth(1)?;
+    let authority = match target.split_once("://") {
+        Some((_, rest)) => rest.split('/').next()?,
+        None => target,
+    };
+    let host = match authority.rsplit_once(':') {
+        Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => host,
+        _ => authority,
+    };
+    Some(host.to_string())
+}
+
+fn report(
+    latencies: &[Duration],
+    elapsed: Duration,
+    interval: Duration,
+    late: usize,
+    requests: &Requests,
+) {
+    let mut sorted = latencies.to_vec();
+    sorted.sort();
+    let percentile = |p: f64| {
+        let index = ((sorted.len() as f64 * p).ceil() as usize).clamp(1, sorted.len()) - 1;
+        sorted[index].as_secs_f64() * 1000.0
+    };
+    let renders = latencies.len();
+
+    println!(
+        "吞吐: {:.2} 次/秒（{} 次，用时 {:.1} s）",
+        renders as f64 / elapsed.as_secs_f64(),
+        renders,
+        elapsed.as_secs_f64()
+    );
+    println!(
+        "渲染耗时: p50 {:.0} ms · p95 {:.0} ms · 最大 {:.0} ms",
+        percentile(0.5),
+        percentile(0.95),
+        percentile(1.0)
+    );
+    if late > 0 {
+        println!("⚠ {} 次渲染因上一次未完成而推迟", late);
+    }
+
+    let (hits, misses) = crate::api::cache::read_stats();
+    match hits + misses {
+        0 => println!("缓存: 未读取"),
+        reads => println!(
+            "缓存: 命中 {} · 未命中 {}（命中率 {:.0}%）",
+            hits,
+            misses,
+            hits as f64 / reads as f64 * 100.0
+        ),
+    }
+
+    let requests = requests.lock().map(|r| r.clone()).unwrap_or_default();
+    let total: usize = requests.values().sum();
+    if total == 0 {
+        println!("接口请求: 无");
+    } else {
+        println!(
+            "接口请求: {} 次（平均每次渲染 {:.2} 次，均已拦截）",
+            total,
+            total as f64 / renders as f64
+        );
+        for (host, calls) in &requests {
+            println!("  {:<32} {:>6}", host, calls);
+        }
+    }
+
+    if percentile(0.95) > interval.as_secs_f64() * 1000.0 {
+        println!(
+            "\n✗ p95 超过 {:.0} ms 的刷新间隔，以这个频率自动刷新会积压渲染",
+            interval.as_secs_f64() * 1000.0
+        );
+    } else {
+        println!(
+            "\n✓ 渲染能跟上 {:.0} ms 的刷新间隔",
+            interval.as_secs_f64() * 1000.0
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! `uucode stats latency`：线路延迟历史，按小时汇总
+//! `uucode stats export`：导出会话账本（CSV / JSON）
+
+use crate::cli::{ExportFormat, StatsAction};
+use crate::core::latency::{self, LatencySample, LatencyStats};
+use crate::core::ledger;
+use chrono::TimeZone;
+use std::collections::BTreeMap;
+
+pub fn run(action: &StatsAction) -> Result<(), Box<dyn std::error::Error>> {
+    match action {
+        StatsAction::Latency { hours, endpoint } => show_latency(*hours, endpoint.as_deref()),
+        StatsAction::Export { format, month } => export(*format, month.as_deref()),
+    }
+}
+
+fn export(format: ExportFormat, month: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(month) = month {
+        chrono::NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d")
+            .map_err(|_| format!("月份格式应为 YYYY-MM：{}", month))?;
+    }
+    let records: Vec<_> = ledger::load()
+        .into_iter()
+        .filter(|r| month.is_none_or(|m| r.month() == m))
+        .collect();
+
+    match format {
+        ExportFormat::Csv => print!("{}", ledger::to_csv(&records)),
+        ExportFormat::Json => println!("{}", serde_json::to_string_pretty(&records)?),
+    }
+    if records.is_empty() {
+        eprintln!("没有符合条件的会话记录");
+    }
+    Ok(())
+}
+
+fn show_latency(hours: u64, endpoint: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let since =
+        (chrono::Utc::now().timestamp().max(0) as u64).saturating_sub(hours.saturating_mul(3600));
+    let samples: Vec<LatencySample> = latency::load()
+        .into_iter()
+        .filter(|s| s.timestamp >= since)
+        .filter(|s| endpoint.is_none_or(|e| s.endpoint == e))
+        .collect();
+
+    if samples.is_empty() {
+        println!("近 {} 小时没有延迟记录", hours);
+        println!("延迟由 cubence_latency 段在每次渲染时测量，确认该段已启用");
+        return Ok(());
+    }
+
+    let mut by_endpoint: BTreeMap<&str, Vec<&LatencySample>> = BTreeMap::new();
+    for sample in &samples {
+        by_endpoint
+            .entry(&sample.endpoint)
+            .or_default()
+            .push(sample);
+    }
+
+    println!("线路延迟（近 {} 小时）", hours);
+    for (endpoint, samples) in by_endpoint {
+        let stats = LatencyStats::from_samples(samples.iter().copied());
+        println!("\n{}", endpoint);
+        println!("  {}", summary(&stats));
+
+        let mut by_hour: BTreeMap<u64, Vec<&LatencySample>> = BTreeMap::new();
+        for sample in &samples {
+            by_hour
+                .entry(sample.timestamp / 3600 * 3600)
+                .or_default()
+                .push(sample);
+        }
+
+        println!(
+            "\n  {:<12} {:>6} {:>6} {:>8} {:>8} {:>8}",
+            "时间", "样本", "失败", "p50", "p95", "最大"
+        );
+        for (hour, samples) in by_hour {
+            let stats = LatencyStats::from_samples(samples.iter().copied());
+            let time = chrono::Local
+                .timestamp_opt(hour as i64, 0)
+                .single()
+                .map(|t| t.format("%m-%d %H:00").to_string())
+                .unwrap_or_default();
+            println!(
+                "  {:<12} {:>6} {:>6} {:>8} {:>8} {:>8}",
+                time,
+                stats.samples,
+                stats.failures,
+                ms(stats.p50_ms),
+                ms(stats.p95_ms),
+                ms(stats.max_ms)
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn summary(stats: &LatencyStats) -> String {
+    format!(
+        "样本 {} · 失败 {} ({:.1}%) · p50 {} ms · p95 {} ms · 最大 {} ms",
+        stats.samples,
+        stats.failures,
+        stats.failures as f64 / stats.samples.max(1) as f64 * 100.0,
+        ms(stats.p50_ms),
+        ms(stats.p95_ms),
+        ms(stats.max_ms)
+    )
+}
+
+fn ms(value: Option<u64>) -> String {
+    value
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
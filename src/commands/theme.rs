@@ -0,0 +1,87 @@
+//! `uucode theme preview-all`: render one statusline per theme so a theme
+//! can be picked without opening the TUI.
+
+use crate::api;
+use crate::cli::ThemeAction;
+use crate::config::{Config, Cost, InputData};
+use crate::ui::themes::ThemePresets;
+
+pub fn run(action: &ThemeAction) -> Result<(), Box<dyn std::error::Error>> {
+    match action {
+        ThemeAction::PreviewAll { width, sample } => preview_all(*width, *sample),
+    }
+}
+
+/// Payload for the gallery when Claude Code has not rendered yet, with
+/// enough cost data for the cost and session segments to show up
+fn sample_input() -> InputData {
+    let current_dir = std::env::current_dir()
+        .map(|dir| dir.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| ".".to_string());
+    InputData::new("claude-sonnet-4-5", &current_dir)
+        .with_display_name("Sonnet 4.5")
+        .with_cost(Cost {
+            total_cost_usd: Some(0.42),
+            total_duration_ms: Some(754_000),
+            total_api_duration_ms: Some(312_000),
+            total_lines_added: Some(120),
+            total_lines_removed: Some(30),
+        })
+}
+
+fn preview_all(width: Option<usize>, sample: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let last = (!sample)
+        .then(api::cache::load_last_input)
+        .flatten()
+        .and_then(|raw| serde_json::from_str::<InputData>(&raw).ok());
+    let from_last = last.is_some();
+    let mut input = last.unwrap_or_else(sample_input);
+    if let Some(width) = width {
+        input = input.with_width(width);
+    }
+
+    let config = Config::load().unwrap_or_default();
+    let builtin = ThemePresets::get_available_themes();
+    println!(
+        "{}",
+        if from_last {
+            "使用最近一次 Claude Code 输入渲染（--sample 改用示例数据）"
+        } else {
+            "使用示例数据渲染"
+        }
+    );
+
+    for name in ThemePresets::list_available_themes() {
+        let description = builtin.iter().find(|(id, _)| *id == name);
+        // Built-in names fall back to the preset; custom files must load
+        let theme = match ThemePresets::load_theme_from_file(&name) {
+            Ok(theme) => theme,
+            Err(_) if description.is_some() => ThemePresets::get_theme(&name),
+            Err(e) => {
+                println!("\n\x1b[1m{}\x1b[0m", name);
+                println!("⚠ 无法加载: {}", e);
+                continue;
+            }
+        };
+
+        let mut themed = config.clone();
+        themed.apply_theme(theme);
+        let line = crate::render(&themed, &input);
+
+        let current = if name == config.theme {
+            "（当前）"
+        } else {
+            ""
+        };
+        match description {
+            Some((_, description)) => {
+                println!("\n\x1b[1m{}\x1b[0m{} - {}", name, current, description)
+            }
+            None => println!("\n\x1b[1m{}\x1b[0m{}", name, current),
+        }
+        println!("{}", line.ansi);
+    }
+
+    println!("\n用 uucode --config 选择主题，或在 settings.json 的状态栏命令后加 --theme <名称>");
+    Ok(())
+}
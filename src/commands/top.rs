@@ -0,0 +1,301 @@
+//! `uucode top`：独立的全屏监控面板
+//!
+//! 复用状态栏的采集器，数据来源是状态栏最近一次收到的 Claude Code JSON，
+//! 因此需要 Claude Code 至少渲染过一次状态栏。
+
+use crate::api::{self, VendorType};
+use crate::config::{Config, InputData, SegmentConfig, SegmentId};
+use crate::core::segments::SegmentData;
+use crate::core::{collect_all_segments, history, StatusLineGenerator};
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Gauge, Paragraph, Sparkline},
+    Frame, Terminal,
+};
+use std::collections::VecDeque;
+use std::io;
+use std::time::{Duration, Instant};
+
+/// 趋势图保留的采样点数量
+const HISTORY_LEN: usize = 120;
+
+pub fn run(interval: u64) -> Result<(), Box<dyn std::error::Error>> {
+    let mut dashboard = Dashboard::new(Duration::from_secs(interval.max(1)));
+    dashboard.refresh();
+
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+    let result = dashboard.event_loop(&mut terminal);
+
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    result
+}
+
+struct Dashboard {
+    config: Config,
+    interval: Duration,
+    vendor: VendorType,
+    input: Option<InputData>,
+    segments: Vec<(SegmentConfig, SegmentData)>,
+    /// 会话费用，单位：美分
+    cost_history: VecDeque<u64>,
+    /// API 延迟，单位：毫秒
+    latency_history: VecDeque<u64>,
+    latency: Result<Duration, String>,
+    last_refresh: Instant,
+}
+
+impl Dashboard {
+    fn new(interval: Duration) -> Self {
+        let mut config = Config::load().unwrap_or_else(|_| Config::default());
+        api::exchange::resolve_rate(&mut config.format);
+
+        Self {
+            config,
+            interval,
+            vendor: VendorType::Unknown,
+            input: None,
+            segments: Vec::new(),
+            cost_history: VecDeque::with_capacity(HISTORY_LEN),
+            latency_history: VecDeque::with_capacity(HISTORY_LEN),
+            latency: Err("未检测".to_string()),
+            last_refresh: Instant::now(),
+        }
+    }
+
+    fn event_loop(
+        &mut self,
+        terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        loop {
+            terminal.draw(|f| self.ui(f))?;
+
+            let timeout = self.interval.saturating_sub(self.last_refresh.elapsed());
+            if event::poll(timeout)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind != KeyEventKind::Press {
+                        continue;
+                    }
+                    match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                        KeyCode::Char('r') => self.refresh(),
+                        _ => {}
+                    }
+                }
+            }
+
+            if self.last_refresh.elapsed() >= self.interval {
+                self.refresh();
+            }
+        }
+    }
+
+    /// 重新采集所有段数据并追加趋势采样
+    fn refresh(&mut self) {
+        self.last_refresh = Instant::now();
+        self.vendor = api::detect_vendor_from_claude_settings();
+
+        self.input = api::cache::load_last_input()
+            .and_then(|raw| serde_json::from_str::<InputData>(&raw).ok());
+
+        if let Some(input) = &self.input {
+            self.segments = collect_all_segments(&self.config, input);
+
+            if let Some(cost) = input.cost.as_ref().and_then(|c| c.total_cost_usd) {
+                push_sample(&mut self.cost_history, (cost * 100.0).round() as u64);
+            }
+        }
+
+        self.latency = match api::get_current_base_url() {
+            Some(url) => super::vendor::check_reachable(&url).map(|(_, elapsed)| elapsed),
+            None => Err("未配置 base URL".to_string()),
+        };
+        if let Ok(elapsed) = &self.latency {
+            push_sample(&mut self.latency_history, elapsed.as_millis() as u64);
+        }
+    }
+
+    fn ui(&self, f: &mut Frame) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Min(6),
+                Constraint::Length(7),
+                Constraint::Length(1),
+            ])
+            .split(f.area());
+
+        self.render_header(f, rows[0]);
+        self.render_statusline(f, rows[1]);
+
+        let middle = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(rows[2]);
+        self.render_quotas(f, middle[0]);
+        self.render_context(f, middle[1]);
+
+        let bottom = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(rows[3]);
+        self.render_cost(f, bottom[0]);
+        self.render_latency(f, bottom[1]);
+
+        let footer = Paragraph::new(format!(
+            "q 退出 · r 立即刷新 · 每 {} 秒刷新",
+            self.interval.as_secs()
+        ))
+        .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(footer, rows[4]);
+    }
+
+    fn render_header(&self, f: &mut Frame, area: Rect) {
+        let text = match &self.input {
+            Some(input) => format!(
+                "服务商: {}  模型: {}  目录: {}",
+                self.vendor.id(),
+                input.model.display_name,
+                input.workspace.current_dir
+            ),
+            None => "尚无状态栏数据：请先在 Claude Code 中运行一次状态栏".to_string(),
+        };
+        let header =
+            Paragraph::new(text).block(Block::default().borders(Borders::ALL).title("uucode top"));
+        f.render_widget(header, area);
+    }
+
+    fn render_statusline(&self, f: &mut Frame, area: Rect) {
+        let line = if self.segments.is_empty() {
+            Line::from("")
+        } else {
+            StatusLineGenerator::new(self.config.clone()).generate_for_tui(self.segments.clone())
+        };
+        let widget =
+            Paragraph::new(line).block(Block::default().borders(Borders::ALL).title("状态栏"));
+        f.render_widget(widget, area);
+    }
+
+    fn render_quotas(&self, f: &mut Frame, area: Rect) {
+        let block = Block::default().borders(Borders::ALL).title("额度");
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let quotas = history::quotas(&self.segments);
+
+        if quotas.is_empty() {
+            f.render_widget(Paragraph::new("当前服务商没有额度数据"), inner);
+            return;
+        }
+
+        let slots = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Length(2); quotas.len()])
+            .split(inner);
+        for ((label, percentage), slot) in quotas.iter().zip(slots.iter()) {
+            f.render_widget(percent_gauge(label, *percentage), *slot);
+        }
+    }
+
+    fn render_context(&self, f: &mut Frame, area: Rect) {
+        let block = Block::default().borders(Borders::ALL).title("上下文");
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let context = self
+            .segments
+            .iter()
+            .find(|(config, _)| config.id == SegmentId::ContextWindow)
+            .map(|(_, data)| data);
+        let Some(data) = context else {
+            f.render_widget(Paragraph::new("上下文窗口段未启用"), inner);
+            return;
+        };
+
+        let parts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(2), Constraint::Min(1)])
+            .split(inner);
+        let percentage = history::context_percentage(&self.segments).unwrap_or(0.0);
+        f.render_widget(percent_gauge(&data.primary, percentage), parts[0]);
+
+        let advice = self
+            .segments
+            .iter()
+            .find(|(config, _)| config.id == SegmentId::ContextAdvisor)
+            .map(|(_, data)| format!("{} ({})", data.primary, data.secondary))
+            .unwrap_or_default();
+        f.render_widget(Paragraph::new(advice), parts[1]);
+    }
+
+    fn render_cost(&self, f: &mut Frame, area: Rect) {
+        let current = self
+            .input
+            .as_ref()
+            .and_then(|input| input.cost.as_ref())
+            .and_then(|cost| cost.total_cost_usd)
+            .map(|usd| crate::utils::format::money(usd, 2, &self.config.format))
+            .unwrap_or_else(|| "-".to_string());
+        let data: Vec<u64> = self.cost_history.iter().copied().collect();
+        let sparkline = Sparkline::default()
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("会话费用 {}", current)),
+            )
+            .data(&data)
+            .style(Style::default().fg(Color::Yellow));
+        f.render_widget(sparkline, area);
+    }
+
+    fn render_latency(&self, f: &mut Frame, area: Rect) {
+        let title = match &self.latency {
+            Ok(elapsed) => format!("API 延迟 {}ms", elapsed.as_millis()),
+            Err(e) => format!("API 延迟 ✗ {}", e),
+        };
+        let data: Vec<u64> = self.latency_history.iter().copied().collect();
+        let sparkline = Sparkline::default()
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .data(&data)
+            .style(Style::default().fg(Color::Cyan));
+        f.render_widget(sparkline, area);
+    }
+}
+
+fn push_sample(history: &mut VecDeque<u64>, value: u64) {
+    if history.len() == HISTORY_LEN {
+        history.pop_front();
+    }
+    history.push_back(value);
+}
+
+fn percent_gauge(label: &str, percentage: f64) -> Gauge<'static> {
+    let clamped = percentage.clamp(0.0, 100.0);
+    let color = if clamped >= 90.0 {
+        Color::Red
+    } else if clamped >= 70.0 {
+        Color::Yellow
+    } else {
+        Color::Green
+    };
+    Gauge::default()
+        .gauge_style(Style::default().fg(color))
+        .ratio(clamped / 100.0)
+        .label(format!("{} {:.1}%", label, percentage))
+}
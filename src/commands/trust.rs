@@ -0,0 +1,109 @@
+//! `uucode trust`：允许项目 .uucode.toml 中的命令在状态栏中执行
+
+use crate::config::project::{self, ProjectConfig};
+use crate::utils::trust::{self, Trust, TrustStore};
+use crate::utils::{prompt, readonly};
+use std::path::PathBuf;
+
+pub fn run(path: Option<&str>, list: bool, revoke: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if list {
+        list_trusted();
+        return Ok(());
+    }
+
+    let dir = match path {
+        Some(p) => PathBuf::from(p),
+        None => std::env::current_dir()?,
+    };
+    let file = if dir.is_file() {
+        dir
+    } else {
+        project::find(&dir).ok_or_else(|| {
+            format!(
+                "{} 及其上级目录中没有 {}",
+                dir.display(),
+                project::FILE_NAME
+            )
+        })?
+    };
+
+    if revoke {
+        return revoke_trust(&file);
+    }
+
+    let state = trust::check(&file);
+    if state == Trust::Trusted {
+        println!("{} 已受信任", file.display());
+        return Ok(());
+    }
+
+    let content = std::fs::read_to_string(&file)?;
+    let project =
+        ProjectConfig::load(&file).map_err(|e| format!("{} 无法解析: {}", file.display(), e))?;
+
+    if state == Trust::Changed {
+        println!("⚠ {} 在上次信任后被修改过", file.display());
+    }
+    println!("{}:\n", file.display());
+    for line in content.lines() {
+        println!("  │ {}", line);
+    }
+    let commands = project.commands();
+    if commands.is_empty() {
+        println!("\n该文件没有定义要执行的命令");
+    } else {
+        println!("\n信任后状态栏每次刷新都会在本机执行:");
+        for command in &commands {
+            println!("  $ {}", command);
+        }
+    }
+
+    if !prompt::confirm("\n信任该文件？文件内容变化后需要重新信任 [y/N] ")? {
+        println!("已取消");
+        return Ok(());
+    }
+
+    if readonly::intercept("写入信任列表", String::new) {
+        return Ok(());
+    }
+    let mut store = TrustStore::load();
+    store.trust(&file)?;
+    store.save()?;
+    println!("✓ 已信任 {}", file.display());
+    Ok(())
+}
+
+fn revoke_trust(file: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    let mut store = TrustStore::load();
+    if !store.revoke(file) {
+        println!("{} 不在信任列表中", file.display());
+        return Ok(());
+    }
+    if readonly::intercept("写入信任列表", String::new) {
+        return Ok(());
+    }
+    store.save()?;
+    println!("✓ 已撤销对 {} 的信任", file.display());
+    Ok(())
+}
+
+fn list_trusted() {
+    let store = TrustStore::load();
+    if store.workspaces.is_empty() {
+        println!("信任列表为空，使用 `uucode trust [目录]` 信任项目的 .uucode.toml");
+        return;
+    }
+    for workspace in &store.workspaces {
+        let mark = match store.check(&workspace.path) {
+            Trust::Trusted => "✓",
+            Trust::Changed => "⚠ 已修改",
+            Trust::Untrusted => "✗ 文件不存在",
+        };
+        println!(
+            "{}  {}  {}",
+            workspace.trusted_at,
+            workspace.path.display(),
+            mark
+        );
+    }
+}
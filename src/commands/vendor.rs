@@ -0,0 +1,191 @@
+//! `uucode vendor status|switch`
+
+use crate::api::{self, vendor::Vendor, VendorType};
+use crate::auto_config::ClaudeSettingsConfigurator;
+use crate::cli::VendorAction;
+use crate::config::{ApiKeys, VendorCredentials};
+use crate::error::UucodeError;
+use serde_json::{json, Value};
+use std::fs;
+use std::time::{Duration, Instant};
+
+/// 各服务商默认的 ANTHROPIC_BASE_URL
+fn default_base_url(vendor: VendorType) -> Option<&'static str> {
+    match vendor {
+        VendorType::Uucode => Some("https://api.uucode.org"),
+        VendorType::Cubence => Some("https://api.cubence.com"),
+        // one-api 网关和 vendors.d 中的服务商需要在 api_keys.toml 中填写 base_url
+        VendorType::OneApi | VendorType::Custom | VendorType::Unknown => None,
+    }
+}
+
+pub fn run(action: &VendorAction) -> Result<(), Box<dyn std::error::Error>> {
+    match action {
+        VendorAction::Status => status(),
+        VendorAction::Switch { name } => switch(name),
+    }
+}
+
+fn status() -> Result<(), Box<dyn std::error::Error>> {
+    let vendor = api::detect_vendor_from_claude_settings();
+    let base_url = api::get_current_base_url();
+    let token = api::get_claude_env_var("ANTHROPIC_AUTH_TOKEN");
+
+    let name = match api::vendor::current() {
+        Some(current) if vendor == VendorType::Custom => current.display_name(),
+        _ => vendor.display_name(),
+    };
+    println!("服务商:   {}", name);
+    println!(
+        "Base URL: {}",
+        base_url.as_deref().unwrap_or("未配置（使用官方 API）")
+    );
+    println!(
+        "密钥:     {}",
+        token.as_deref().map(mask_token).unwrap_or("未配置".into())
+    );
+
+    if let Some(url) = base_url.as_deref() {
+        match check_reachable(url) {
+            Ok((code, elapsed)) => {
+                println!("连通性:   ✓ HTTP {} ({} ms)", code, elapsed.as_millis())
+            }
+            Err(e) => println!("连通性:   ✗ {}", e),
+        }
+    }
+
+    if !vendor.is_supported() {
+        println!(
+            "\n当前服务商不受支持，可用 `uucode vendor switch <name>` 切换（支持: {}）",
+            api::get_supported_vendors_str()
+        );
+    }
+
+    let keys = ApiKeys::load().unwrap_or_default();
+    let stored: Vec<_> = api::vendor::registry()
+        .iter()
+        .filter(|v| keys.token_for(v.id()).is_some())
+        .map(|v| v.id())
+        .collect();
+    if !stored.is_empty() {
+        println!("\n已保存凭据: {}", stored.join(", "));
+    }
+
+    Ok(())
+}
+
+fn switch(name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let registry = api::vendor::registry();
+    let target: &dyn Vendor = registry
+        .iter()
+        .find(|v| v.id().eq_ignore_ascii_case(name))
+        .map(|v| v.as_ref())
+        .ok_or_else(|| {
+            UucodeError::Vendor(format!(
+                "未知服务商: {}，支持: {}",
+                name,
+                registry
+                    .iter()
+                    .map(|v| v.id())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+        })?;
+
+    let settings_path = ClaudeSettingsConfigurator::get_settings_path()
+        .ok_or("无法找到 Claude settings.json 路径")?;
+    let mut settings: Value = if settings_path.exists() {
+        crate::utils::jsonc::parse(&fs::read_to_string(&settings_path)?)?
+    } else {
+        json!({})
+    };
+
+    let mut keys = ApiKeys::load()?;
+
+    // 先保存当前服务商的凭据，方便之后切回
+    let current_url = settings
+        .pointer("/env/ANTHROPIC_BASE_URL")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    let current_token = settings
+        .pointer("/env/ANTHROPIC_AUTH_TOKEN")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    if let Some(url) = &current_url {
+        if let Some(current) = api::vendor::for_url(url).filter(|_| current_token.is_some()) {
+            keys.vendors.insert(
+                current.id().to_string(),
+                VendorCredentials {
+                    base_url: current_url.clone(),
+                    auth_token: current_token.clone(),
+                },
+            );
+        }
+    }
+
+    let token = keys.token_for(target.id()).ok_or_else(|| {
+        UucodeError::Config(format!(
+            "没有保存 {} 的凭据，请在 {} 的 [vendors.{}] 中填写 auth_token",
+            target.display_name(),
+            ApiKeys::path()
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|| "api_keys.toml".to_string()),
+            target.id()
+        ))
+    })?;
+    let base_url = keys
+        .vendors
+        .get(target.id())
+        .and_then(|v| v.base_url.clone())
+        .or_else(|| default_base_url(target.vendor_type()).map(|s| s.to_string()))
+        .ok_or("缺少 base_url")?;
+
+    let obj = settings
+        .as_object_mut()
+        .ok_or("settings.json 顶层不是对象")?;
+    let env = obj.entry("env").or_insert_with(|| json!({}));
+    let env = env.as_object_mut().ok_or("settings.json 中 env 不是对象")?;
+    env.insert("ANTHROPIC_BASE_URL".to_string(), json!(base_url));
+    env.insert("ANTHROPIC_AUTH_TOKEN".to_string(), json!(token));
+
+    let written = crate::auto_config::settings::write(
+        &settings_path,
+        &settings,
+        "修改 settings.json",
+        || {
+            format!(
+            "  请在 {} 的 env 中设置:\n    \"ANTHROPIC_BASE_URL\": \"{}\"\n    \"ANTHROPIC_AUTH_TOKEN\": \"<{} 的密钥>\"",
+            settings_path.display(),
+            base_url,
+            target.display_name()
+        )
+        },
+    )?;
+    if !written {
+        return Ok(());
+    }
+    keys.save()?;
+
+    println!("✓ 已切换到 {} ({})", target.display_name(), base_url);
+    println!("  重启 Claude Code 后生效");
+    Ok(())
+}
+
+fn mask_token(token: &str) -> String {
+    let chars: Vec<char> = token.chars().collect();
+    if chars.len() <= 8 {
+        return "已配置".to_string();
+    }
+    let head: String = chars[..4].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}…{}", head, tail)
+}
+
+pub fn check_reachable(url: &str) -> Result<(u16, Duration), String> {
+    let client = api::http::builder_for_url(url, Duration::from_secs(5))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let start = Instant::now();
+    let response = client.get(url).send().map_err(|e| e.to_string())?;
+    Ok((response.status().as_u16(), start.elapsed()))
+}
@@ -0,0 +1,63 @@
+use crate::error::UucodeError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Stored credentials (~/.claude/uucode/api_keys.toml)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApiKeys {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uucode_api_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub glm_api_key: Option<String>,
+    /// Per-vendor Claude Code env, used by `uucode vendor switch`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub vendors: HashMap<String, VendorCredentials>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VendorCredentials {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_token: Option<String>,
+}
+
+impl ApiKeys {
+    pub fn path() -> Option<PathBuf> {
+        crate::utils::paths::uucode_dir().map(|dir| dir.join("api_keys.toml"))
+    }
+
+    /// Load stored keys, returning an empty set when the file does not exist
+    pub fn load() -> Result<Self, UucodeError> {
+        let path = Self::path().ok_or_else(UucodeError::no_home)?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)?;
+        Ok(toml::from_str(&content)?)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        crate::utils::readonly::ensure_writable("saving API keys")?;
+        let path = Self::path().ok_or("Could not find home directory")?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        crate::utils::atomic_write(&path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Token stored for a vendor, falling back to the legacy uucode key
+    pub fn token_for(&self, vendor_id: &str) -> Option<String> {
+        self.vendors
+            .get(vendor_id)
+            .and_then(|v| v.auth_token.clone())
+            .or_else(|| match vendor_id {
+                "uucode" => self.uucode_api_key.clone(),
+                _ => None,
+            })
+            .filter(|t| !t.is_empty())
+    }
+}
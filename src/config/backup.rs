@@ -0,0 +1,84 @@
+//! Timestamped copies of config.toml taken before it is rewritten.
+//!
+//! Backups live in `~/.claude/mycode/backups`, outside the uucode directory,
+//! so they survive `--init` wiping it.
+
+use super::Config;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Backups kept; older ones are deleted after each new backup
+pub const MAX_BACKUPS: usize = 10;
+
+const PREFIX: &str = "config-";
+const SUFFIX: &str = ".toml";
+
+#[derive(Debug, Clone)]
+pub struct Backup {
+    pub path: PathBuf,
+    /// Local time the backup was taken, `YYYYmmdd-HHMMSS.mmm`
+    pub taken_at: String,
+}
+
+pub fn backups_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".claude").join("mycode").join("backups"))
+}
+
+/// Copy `path` into the backups directory unless it is missing or identical
+/// to the newest backup. Returns the new backup, if one was written.
+pub fn snapshot(path: &Path) -> Result<Option<PathBuf>, Box<dyn std::error::Error>> {
+    if crate::utils::readonly::is_enabled() {
+        return Ok(None);
+    }
+    let Ok(content) = fs::read(path) else {
+        return Ok(None);
+    };
+    if content.is_empty() {
+        return Ok(None);
+    }
+    if let Some(newest) = list().first() {
+        if fs::read(&newest.path).is_ok_and(|previous| previous == content) {
+            return Ok(None);
+        }
+    }
+
+    let dir = backups_dir().ok_or("Could not find home directory")?;
+    fs::create_dir_all(&dir)?;
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S%.3f");
+    let backup = dir.join(format!("{}{}{}", PREFIX, stamp, SUFFIX));
+    crate::utils::atomic_write(&backup, content)?;
+
+    for old in list().into_iter().skip(MAX_BACKUPS) {
+        let _ = fs::remove_file(old.path);
+    }
+    Ok(Some(backup))
+}
+
+/// Back up the current config.toml, logging instead of failing so a broken
+/// backups directory never blocks a save
+pub fn snapshot_config() {
+    if let Err(e) = snapshot(&Config::get_config_path()) {
+        eprintln!("Warning: failed to back up config.toml: {}", e);
+    }
+}
+
+/// All backups, newest first
+pub fn list() -> Vec<Backup> {
+    let Some(entries) = backups_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<Backup> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let taken_at = name.strip_prefix(PREFIX)?.strip_suffix(SUFFIX)?.to_string();
+            Some(Backup {
+                path: entry.path(),
+                taken_at,
+            })
+        })
+        .collect();
+    // The timestamp format sorts chronologically as text
+    backups.sort_by(|a, b| b.taken_at.cmp(&a.taken_at));
+    backups
+}
@@ -0,0 +1,168 @@
+//! Line diff between config.toml on disk and a config about to replace it.
+//!
+//! The old side is the raw file, not a re-serialized load, so comments and
+//! hand formatting that a save would drop show up as removed lines.
+
+use super::Config;
+use std::fs;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffTag {
+    Same,
+    Added,
+    Removed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    pub tag: DiffTag,
+    pub text: String,
+}
+
+/// Above this many line pairs the LCS table gets too big; fall back to
+/// replacing the whole file
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// Line-based diff of two texts
+///
+/// ```
+/// use mycode::config::diff::{diff_lines, DiffTag};
+///
+/// let diff = diff_lines("a\nb\nc", "a\nc\nd");
+/// let tags: Vec<_> = diff.iter().map(|l| l.tag).collect();
+/// assert_eq!(
+///     tags,
+///     [DiffTag::Same, DiffTag::Removed, DiffTag::Same, DiffTag::Added]
+/// );
+/// ```
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let line = |tag, text: &str| DiffLine {
+        tag,
+        text: text.to_string(),
+    };
+
+    if old.len().saturating_mul(new.len()) > MAX_LCS_CELLS {
+        return old
+            .iter()
+            .map(|text| line(DiffTag::Removed, text))
+            .chain(new.iter().map(|text| line(DiffTag::Added, text)))
+            .collect();
+    }
+
+    // lcs[i][j] = longest common subsequence of old[i..] and new[j..]
+    let width = new.len() + 1;
+    let mut lcs = vec![0u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i * width + j] = if old[i] == new[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut result = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            result.push(line(DiffTag::Same, old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+            result.push(line(DiffTag::Removed, old[i]));
+            i += 1;
+        } else {
+            result.push(line(DiffTag::Added, new[j]));
+            j += 1;
+        }
+    }
+    result.extend(old[i..].iter().map(|text| line(DiffTag::Removed, text)));
+    result.extend(new[j..].iter().map(|text| line(DiffTag::Added, text)));
+    result
+}
+
+/// What saving `new` would do to config.toml
+pub struct ConfigDiff {
+    /// The config currently on disk, if there is a readable one
+    pub current: Option<Config>,
+    pub lines: Vec<DiffLine>,
+}
+
+impl ConfigDiff {
+    pub fn against_disk(new: &Config) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self::against_disk_text(&toml::to_string_pretty(new)?))
+    }
+
+    /// Diff for replacing config.toml with `new` verbatim
+    pub fn against_disk_text(new: &str) -> Self {
+        let raw = fs::read_to_string(Config::get_config_path()).unwrap_or_default();
+        let current = toml::from_str(&raw).ok();
+        let lines = diff_lines(&raw, new);
+        Self { current, lines }
+    }
+
+    /// Diff of two texts that are not config.toml, such as settings.json;
+    /// `current` stays empty
+    pub fn between(old: &str, new: &str) -> Self {
+        Self {
+            current: None,
+            lines: diff_lines(old, new),
+        }
+    }
+
+    pub fn has_changes(&self) -> bool {
+        self.lines.iter().any(|line| line.tag != DiffTag::Same)
+    }
+
+    /// Changed lines with `context` unchanged lines around them; `None`
+    /// marks skipped stretches
+    pub fn hunks(&self, context: usize) -> Vec<Option<&DiffLine>> {
+        let near_change = |index: usize| {
+            let start = index.saturating_sub(context);
+            let end = (index + context + 1).min(self.lines.len());
+            self.lines[start..end]
+                .iter()
+                .any(|line| line.tag != DiffTag::Same)
+        };
+
+        let mut rows = Vec::new();
+        let mut skipped = false;
+        for (index, line) in self.lines.iter().enumerate() {
+            if near_change(index) {
+                if skipped {
+                    rows.push(None);
+                    skipped = false;
+                }
+                rows.push(Some(line));
+            } else {
+                skipped = true;
+            }
+        }
+        if skipped && !rows.is_empty() {
+            rows.push(None);
+        }
+        rows
+    }
+
+    /// Colored unified-style rendering for the terminal
+    pub fn to_ansi(&self, context: usize) -> String {
+        let mut out = String::new();
+        for row in self.hunks(context) {
+            match row {
+                None => out.push_str("\x1b[2m  …\x1b[0m\n"),
+                Some(line) => {
+                    let (color, sign) = match line.tag {
+                        DiffTag::Same => ("", ' '),
+                        DiffTag::Added => ("\x1b[32m", '+'),
+                        DiffTag::Removed => ("\x1b[31m", '-'),
+                    };
+                    out.push_str(&format!("{}{} {}\x1b[0m\n", color, sign, line.text));
+                }
+            }
+        }
+        out
+    }
+}
@@ -1,4 +1,5 @@
 use super::types::Config;
+use crate::error::UucodeError;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -9,7 +10,7 @@ impl ConfigLoader {
         Config::load().unwrap_or_else(|_| Config::default())
     }
 
-    pub fn load_from_path<P: AsRef<Path>>(path: P) -> Result<Config, Box<dyn std::error::Error>> {
+    pub fn load_from_path<P: AsRef<Path>>(path: P) -> Result<Config, UucodeError> {
         let content = fs::read_to_string(path)?;
         let config: Config = toml::from_str(&content)?;
         Ok(config)
@@ -17,6 +18,9 @@ impl ConfigLoader {
 
     /// Initialize themes directory and create built-in theme files
     pub fn init_themes() -> Result<(), Box<dyn std::error::Error>> {
+        if crate::utils::readonly::is_enabled() {
+            return Ok(());
+        }
         let themes_dir = Self::get_themes_path();
 
         // Create themes directory
@@ -41,7 +45,7 @@ impl ConfigLoader {
             if !theme_path.exists() {
                 let theme_config = crate::ui::themes::ThemePresets::get_theme(theme_name);
                 let content = toml::to_string_pretty(&theme_config)?;
-                fs::write(&theme_path, content)?;
+                crate::utils::atomic_write(&theme_path, content)?;
                 println!("Created theme file: {}", theme_path.display());
                 created_any = true;
             }
@@ -71,6 +75,9 @@ impl ConfigLoader {
 
     /// Initialize themes directory and create built-in theme files (silent mode)
     fn init_themes_silent() -> Result<(), Box<dyn std::error::Error>> {
+        if crate::utils::readonly::is_enabled() {
+            return Ok(());
+        }
         let themes_dir = Self::get_themes_path();
 
         // Create themes directory
@@ -94,7 +101,7 @@ impl ConfigLoader {
             if !theme_path.exists() {
                 let theme_config = crate::ui::themes::ThemePresets::get_theme(theme_name);
                 let content = toml::to_string_pretty(&theme_config)?;
-                fs::write(&theme_path, content)?;
+                crate::utils::atomic_write(&theme_path, content)?;
             }
         }
 
@@ -104,7 +111,7 @@ impl ConfigLoader {
 
 impl Config {
     /// Load configuration from default location
-    pub fn load() -> Result<Config, Box<dyn std::error::Error>> {
+    pub fn load() -> Result<Config, UucodeError> {
         // Ensure themes directory exists and has built-in themes
         ConfigLoader::ensure_themes_exist();
 
@@ -121,6 +128,7 @@ impl Config {
 
     /// Save configuration to default location
     pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        crate::utils::readonly::ensure_writable("writing config.toml")?;
         let config_path = Self::get_config_path();
 
         // Ensure config directory exists
@@ -129,12 +137,13 @@ impl Config {
         }
 
         let content = toml::to_string_pretty(self)?;
-        fs::write(config_path, content)?;
+        super::backup::snapshot_config();
+        crate::utils::atomic_write(config_path, content)?;
         Ok(())
     }
 
     /// Get the default config file path (~/.claude/uucode/config.toml)
-    fn get_config_path() -> PathBuf {
+    pub fn get_config_path() -> PathBuf {
         if let Some(home) = dirs::home_dir() {
             home.join(".claude").join("uucode").join("config.toml")
         } else {
@@ -146,9 +155,20 @@ impl Config {
     pub fn init() -> Result<(), Box<dyn std::error::Error>> {
         let config_path = Self::get_config_path();
 
-        // Delete existing config directory if it exists
+        if crate::utils::readonly::intercept("初始化配置", || {
+            format!(
+                "  --init 会删除并重建 {}，请在可写的环境中运行",
+                config_path.parent().unwrap_or(&config_path).display()
+            )
+        }) {
+            return Ok(());
+        }
+
+        // Delete existing config directory if it exists, keeping a backup of
+        // the old config.toml outside of it
         if let Some(parent) = config_path.parent() {
             if parent.exists() {
+                super::backup::snapshot_config();
                 println!("Removing existing directory: {}", parent.display());
                 fs::remove_dir_all(parent)?;
                 println!("✓ Old configuration cleaned up");
@@ -172,20 +192,91 @@ impl Config {
     }
 
     /// Validate configuration
-    pub fn check(&self) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn check(&self) -> Result<(), UucodeError> {
         // Basic validation
         if self.segments.is_empty() {
-            return Err("No segments configured".into());
+            return Err(UucodeError::Config("No segments configured".to_string()));
         }
 
         // Validate segment IDs are unique
         let mut seen_ids = std::collections::HashSet::new();
         for segment in &self.segments {
             if !seen_ids.insert(segment.id) {
-                return Err(format!("Duplicate segment ID: {:?}", segment.id).into());
+                return Err(UucodeError::Config(format!(
+                    "Duplicate segment ID: {:?}",
+                    segment.id
+                )));
+            }
+        }
+
+        for window in &self.focus.schedule {
+            for time in [&window.start, &window.end] {
+                if chrono::NaiveTime::parse_from_str(time, "%H:%M").is_err() {
+                    return Err(UucodeError::Config(format!(
+                        "Invalid time in focus.schedule: {}",
+                        time
+                    )));
+                }
+            }
+        }
+
+        for key in self.style.icons.keys() {
+            if !crate::core::icons::Glyph::ALL
+                .iter()
+                .any(|glyph| glyph.key() == key)
+            {
+                return Err(UucodeError::Config(format!(
+                    "Unknown icon name in style.icons: {}",
+                    key
+                )));
             }
         }
 
+        for (vendor, overrides) in &self.http.vendors {
+            if crate::api::VendorType::from_id(vendor).is_none() {
+                return Err(UucodeError::Config(format!(
+                    "Unknown vendor in http.vendors: {}",
+                    vendor
+                )));
+            }
+            if let Some(name) = overrides.invalid_header() {
+                return Err(UucodeError::Config(format!(
+                    "Invalid header in http.vendors.{}: {}",
+                    vendor, name
+                )));
+            }
+            if let Some(key) = overrides.invalid_timeout() {
+                return Err(UucodeError::Config(format!(
+                    "http.vendors.{}.{} must be positive",
+                    vendor, key
+                )));
+            }
+            if let Some(host) = overrides.invalid_dns() {
+                return Err(UucodeError::Config(format!(
+                    "Invalid IP address in http.vendors.{}.dns: {}",
+                    vendor, host
+                )));
+            }
+        }
+        if let Some(name) = self.http.global.invalid_header() {
+            return Err(UucodeError::Config(format!(
+                "Invalid header in http: {}",
+                name
+            )));
+        }
+        if let Some(key) = self.http.global.invalid_timeout() {
+            return Err(UucodeError::Config(format!(
+                "http.{} must be positive",
+                key
+            )));
+        }
+        if let Some(host) = self.http.global.invalid_dns() {
+            return Err(UucodeError::Config(format!(
+                "Invalid IP address in http.dns: {}",
+                host
+            )));
+        }
+
         Ok(())
     }
 
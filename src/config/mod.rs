@@ -1,8 +1,13 @@
+pub mod api_keys;
+pub mod backup;
 pub mod defaults;
+pub mod diff;
 pub mod loader;
 pub mod models;
+pub mod project;
 pub mod types;
 
+pub use api_keys::{ApiKeys, VendorCredentials};
 pub use loader::ConfigLoader;
 pub use models::*;
 pub use types::*;
@@ -13,6 +13,64 @@ pub struct ModelEntry {
     pub pattern: String,
     pub display_name: String,
     pub context_limit: u32,
+    /// Overrides the built-in price list for matching models
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pricing: Option<ModelPricing>,
+}
+
+/// API list prices in USD per million tokens
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    pub input: f64,
+    pub output: f64,
+    pub cache_write: f64,
+    pub cache_read: f64,
+}
+
+impl ModelPricing {
+    const fn new(input: f64, output: f64) -> Self {
+        // Anthropic bills 5-minute cache writes at 1.25x and reads at 0.1x input
+        Self {
+            input,
+            output,
+            cache_write: input * 1.25,
+            cache_read: input * 0.1,
+        }
+    }
+
+    /// Built-in list prices by model family, `None` for unknown models
+    pub fn builtin(model_id: &str) -> Option<Self> {
+        let model = model_id.to_lowercase();
+        let legacy_opus = ["opus-4-0", "opus-4-1", "opus-4-2025", "4-opus", "3-opus"];
+        if model.contains("opus") {
+            if legacy_opus.iter().any(|p| model.contains(p)) || model.ends_with("opus-4") {
+                Some(Self::new(15.0, 75.0))
+            } else {
+                Some(Self::new(5.0, 25.0))
+            }
+        } else if model.contains("sonnet") {
+            Some(Self::new(3.0, 15.0))
+        } else if model.contains("haiku") {
+            if model.contains("3-haiku") {
+                Some(Self::new(0.25, 1.25))
+            } else if model.contains("3-5-haiku") {
+                Some(Self::new(0.8, 4.0))
+            } else {
+                Some(Self::new(1.0, 5.0))
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Cost in USD of the given token counts
+    pub fn cost(&self, input: u64, output: u64, cache_write: u64, cache_read: u64) -> f64 {
+        (input as f64 * self.input
+            + output as f64 * self.output
+            + cache_write as f64 * self.cache_write
+            + cache_read as f64 * self.cache_read)
+            / 1_000_000.0
+    }
 }
 
 impl ModelConfig {
@@ -30,7 +88,7 @@ impl ModelConfig {
         // First, try to create default models.toml if it doesn't exist
         if let Some(home_dir) = dirs::home_dir() {
             let user_models_path = home_dir.join(".claude/uucode").join("models.toml");
-            if !user_models_path.exists() {
+            if !user_models_path.exists() && !crate::utils::readonly::is_enabled() {
                 let _ = Self::create_default_file(&user_models_path);
             }
         }
@@ -88,6 +146,18 @@ impl ModelConfig {
         None
     }
 
+    /// Get pricing for a model, preferring entries that set `pricing`
+    /// over the built-in price list
+    pub fn get_pricing(&self, model_id: &str) -> Option<ModelPricing> {
+        let model_lower = model_id.to_lowercase();
+
+        self.model_entries
+            .iter()
+            .filter(|entry| model_lower.contains(&entry.pattern.to_lowercase()))
+            .find_map(|entry| entry.pricing)
+            .or_else(|| ModelPricing::builtin(model_id))
+    }
+
     /// Create default model configuration file with minimal template
     pub fn create_default_file<P: AsRef<Path>>(path: P) -> Result<(), Box<dyn std::error::Error>> {
         // Create a minimal template config (not the full fallback config)
@@ -108,6 +178,8 @@ impl ModelConfig {
              # Model configurations\n\
              # Each [[models]] section defines a model pattern and its properties\n\
              # Order matters: first match wins, so put more specific patterns first\n\
+             # Optional pricing (USD per million tokens) feeds the cost estimate:\n\
+             # pricing = {{ input = 3.0, output = 15.0, cache_write = 3.75, cache_read = 0.3 }}\n\
              \n",
             toml_content.trim()
         );
@@ -117,7 +189,7 @@ impl ModelConfig {
             fs::create_dir_all(parent)?;
         }
 
-        fs::write(path, template_content)?;
+        crate::utils::atomic_write(path, template_content)?;
         Ok(())
     }
 }
@@ -131,6 +203,7 @@ impl Default for ModelConfig {
                     pattern: "[1m]".to_string(),
                     display_name: "Sonnet 4.5 1M".to_string(),
                     context_limit: 1_000_000,
+                    pricing: None,
                 },
                 // ModelEntry {
                 //     pattern: "claude-sonnet-4-5".to_string(),
@@ -0,0 +1,47 @@
+//! Per-project overrides from a `.uucode.toml` in the workspace.
+//!
+//! The file travels with the repository, so anything in it that runs a
+//! command is only honored once the user has trusted that exact file with
+//! `uucode trust` (see [`crate::utils::trust`]).
+
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+pub const FILE_NAME: &str = ".uucode.toml";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ProjectConfig {
+    /// Overrides for the custom command segment
+    #[serde(default)]
+    pub custom: Option<ProjectCustom>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ProjectCustom {
+    /// Shell command whose first output line is shown in the segment
+    pub command: Option<String>,
+}
+
+impl ProjectConfig {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(toml::from_str(&content)?)
+    }
+
+    /// Commands this file would have the statusline run
+    pub fn commands(&self) -> Vec<&str> {
+        self.custom
+            .iter()
+            .filter_map(|custom| custom.command.as_deref())
+            .filter(|command| !command.trim().is_empty())
+            .collect()
+    }
+}
+
+/// Nearest `.uucode.toml` in `start` or one of its parents
+pub fn find(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(FILE_NAME))
+        .find(|path| path.is_file())
+}
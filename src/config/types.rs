@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 // Main config structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,14 +7,982 @@ pub struct Config {
     pub style: StyleConfig,
     pub segments: Vec<SegmentConfig>,
     pub theme: String,
+    #[serde(default)]
+    pub format: FormatConfig,
+    /// Theme to switch to when the detected vendor changes, keyed by vendor id
+    /// (`uucode`, `cubence`)
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "sorted_map"
+    )]
+    pub vendor_themes: HashMap<String, String>,
+    /// Claude config directory to read settings.json from; the
+    /// `CLAUDE_CONFIG_DIR` environment variable takes precedence
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claude_config_dir: Option<String>,
+    /// Finding Claude Code's files across the WSL / Windows boundary
+    #[serde(default, skip_serializing_if = "WslConfig::is_default")]
+    pub wsl: WslConfig,
+    /// Threshold rules and the notification sinks they fire into
+    #[serde(default, skip_serializing_if = "AlertsConfig::is_empty")]
+    pub alerts: AlertsConfig,
+    /// Also write every render to a file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_file: Option<OutputFileConfig>,
+    #[serde(default, skip_serializing_if = "WaybarConfig::is_default")]
+    pub waybar: WaybarConfig,
+    #[serde(default, skip_serializing_if = "PerfConfig::is_default")]
+    pub perf: PerfConfig,
+    #[serde(default, skip_serializing_if = "MarketplaceConfig::is_default")]
+    pub marketplace: MarketplaceConfig,
+    #[serde(default, skip_serializing_if = "AccessibilityConfig::is_default")]
+    pub accessibility: AccessibilityConfig,
+    #[serde(default, skip_serializing_if = "FocusConfig::is_default")]
+    pub focus: FocusConfig,
+    #[serde(default, skip_serializing_if = "PatcherConfig::is_default")]
+    pub patcher: PatcherConfig,
+    #[serde(default, skip_serializing_if = "WrapperConfig::is_default")]
+    pub wrapper: WrapperConfig,
+    #[serde(default, skip_serializing_if = "InstallConfig::is_default")]
+    pub install: InstallConfig,
+    #[serde(default, skip_serializing_if = "HttpConfig::is_default")]
+    pub http: HttpConfig,
+    #[serde(default, skip_serializing_if = "OneApiConfig::is_default")]
+    pub oneapi: OneApiConfig,
+    /// Same as `--no-write` on every invocation
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub read_only: bool,
+    /// Display overrides by workspace directory; the first match applies
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub directory_profiles: Vec<DirectoryProfile>,
 }
 
 // Default implementation moved to ui/themes/presets.rs
 
+/// Write maps in key order so saving the same config always produces the
+/// same file
+fn sorted_map<S, V>(map: &HashMap<String, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    V: Serialize,
+{
+    map.iter()
+        .collect::<std::collections::BTreeMap<_, _>>()
+        .serialize(serializer)
+}
+
+impl Config {
+    /// Build a config from theme parts, leaving global sections at defaults
+    pub fn from_theme(style: StyleConfig, segments: Vec<SegmentConfig>, theme: &str) -> Self {
+        Self {
+            style,
+            segments,
+            theme: theme.to_string(),
+            format: FormatConfig::default(),
+            vendor_themes: HashMap::new(),
+            claude_config_dir: None,
+            wsl: WslConfig::default(),
+            alerts: AlertsConfig::default(),
+            output_file: None,
+            waybar: WaybarConfig::default(),
+            perf: PerfConfig::default(),
+            marketplace: MarketplaceConfig::default(),
+            accessibility: AccessibilityConfig::default(),
+            focus: FocusConfig::default(),
+            patcher: PatcherConfig::default(),
+            wrapper: WrapperConfig::default(),
+            install: InstallConfig::default(),
+            http: HttpConfig::default(),
+            oneapi: OneApiConfig::default(),
+            read_only: false,
+            directory_profiles: Vec::new(),
+        }
+    }
+
+    /// Take the look (style and segments) of another theme while keeping
+    /// the global, non-theme settings of this config
+    pub fn apply_theme(&mut self, theme: Config) {
+        self.style = theme.style;
+        self.segments = theme.segments;
+        self.theme = theme.theme;
+    }
+}
+
+/// `[wsl]` section: Claude Code installed on the other side of WSL.
+///
+/// Inside WSL a Windows path such as `C:\Users\me\.claude` in
+/// `claude_config_dir` or `CLAUDE_CONFIG_DIR` is read through its mount
+/// (`/mnt/c/Users/me/.claude`); on Windows a Linux path is read through
+/// `\\wsl.localhost\<distro>`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WslConfig {
+    pub bridge: WslBridge,
+    /// Windows profile directory, found under the `C:` mount when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub windows_home: Option<String>,
+    /// Where WSL mounts Windows drives (`automount.root` in /etc/wsl.conf)
+    pub mount_root: String,
+    /// Distro holding the Linux side, for paths translated on Windows;
+    /// the default distro's name is not known to Windows programs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distro: Option<String>,
+}
+
+impl Default for WslConfig {
+    fn default() -> Self {
+        Self {
+            bridge: WslBridge::Auto,
+            windows_home: None,
+            mount_root: "/mnt".to_string(),
+            distro: None,
+        }
+    }
+}
+
+impl WslConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WslBridge {
+    /// Only translate configured paths
+    Off,
+    /// Also use the Windows `~/.claude` inside WSL when the Linux one has
+    /// no settings.json
+    #[default]
+    Auto,
+    /// Always use the Windows `~/.claude` inside WSL
+    Windows,
+}
+
+/// `[focus]` section: hide usage and cost during focus hours, on a
+/// schedule or with `uucode focus on|off`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FocusConfig {
+    /// Segments hidden while focus mode is on
+    pub hide: Vec<SegmentId>,
+    /// Hold back alert notifications while focus mode is on
+    pub mute_alerts: bool,
+    /// Times of day focus mode turns on by itself
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub schedule: Vec<FocusWindow>,
+}
+
+impl Default for FocusConfig {
+    fn default() -> Self {
+        Self {
+            hide: vec![
+                SegmentId::Usage,
+                SegmentId::Cost,
+                SegmentId::DailyCost,
+                SegmentId::BlockUsage,
+                SegmentId::QuotaPacing,
+                SegmentId::UucodeUsage,
+                SegmentId::CubenceUsage,
+                SegmentId::CubenceBalance,
+                SegmentId::CubenceFiveHour,
+                SegmentId::CubenceWeekly,
+                SegmentId::OneApiUsage,
+                SegmentId::VendorUsage,
+            ],
+            mute_alerts: false,
+            schedule: Vec::new(),
+        }
+    }
+}
+
+impl FocusConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A daily focus window, e.g. `09:00`-`12:00`, or `22:00`-`07:00` with
+/// only `mute_alerts` for quiet nights
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FocusWindow {
+    /// Local time `HH:MM`
+    pub start: String,
+    /// Local time `HH:MM`, exclusive; before `start` for windows that run
+    /// past midnight
+    pub end: String,
+    /// Segments hidden in this window, `[focus] hide` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hide: Option<Vec<SegmentId>>,
+    /// Whether alerts are held back in this window, `[focus] mute_alerts`
+    /// when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mute_alerts: Option<bool>,
+}
+
+/// `[patcher]` section: optional patches applied by `--patch`
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PatcherConfig {
+    #[serde(skip_serializing_if = "SpinnerPatchConfig::is_default")]
+    pub spinner: SpinnerPatchConfig,
+}
+
+impl PatcherConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// `[wrapper]` section: what `--wrap` prints before Claude Code starts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WrapperConfig {
+    /// Banner lines. `{quota}` is the remaining quota from the last render,
+    /// `{theme}` and `{profile}` what this session uses; lines that end up
+    /// empty are dropped. An empty string turns the banner off.
+    pub motd: String,
+    /// URL that receives each session's ledger record and receipt as JSON
+    /// when Claude Code exits
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_webhook: Option<String>,
+}
+
+impl Default for WrapperConfig {
+    fn default() -> Self {
+        Self {
+            motd: "😊 感谢您使用 uucode！\n{quota}".to_string(),
+            session_webhook: None,
+        }
+    }
+}
+
+impl WrapperConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// `[install]` section: where the binary Claude Code runs lives
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InstallConfig {
+    pub strategy: InstallStrategy,
+}
+
+impl InstallConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallStrategy {
+    /// Reference Homebrew and Scoop installs in place, copy anything else
+    #[default]
+    Auto,
+    /// Copy the binary into ~/.claude/uucode
+    Copy,
+    /// Link ~/.claude/uucode/uucode to the installed binary
+    Symlink,
+    /// Leave the binary where it is and point settings.json at it
+    InPlace,
+}
+
+/// `[http]` section: request settings for every HTTP client. Settings
+/// under `[http.vendors.<id>]` (`uucode`, `cubence`) apply on top of the
+/// global ones for that vendor's endpoints.
+///
+/// ```toml
+/// [http]
+/// ip_family = "ipv4"
+/// connect_timeout = 3
+/// max_response_kb = 512
+///
+/// [http.vendors.cubence]
+/// user_agent = "Mozilla/5.0"
+/// headers = { "X-Relay-Key" = "..." }
+/// dns = { "api.cubence.com" = "203.0.113.7" }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpConfig {
+    #[serde(flatten)]
+    pub global: HttpOverrides,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub vendors: BTreeMap<String, HttpOverrides>,
+}
+
+impl HttpConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpOverrides {
+    /// Replaces the default `uucode/<version>` user agent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    /// Sent with every request; vendor headers replace global ones of the
+    /// same name
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// Address family to connect over, for networks with broken IPv6
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_family: Option<IpFamily>,
+    /// Static host → IP overrides that skip DNS; vendor entries replace
+    /// global ones for the same host
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub dns: BTreeMap<String, String>,
+    /// Seconds to wait for the connection, separately from the response
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connect_timeout: Option<f64>,
+    /// Seconds to wait for the whole response, replacing each request's
+    /// own default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_timeout: Option<f64>,
+    /// Largest response body read, in KB; longer ones fail the request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_response_kb: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IpFamily {
+    /// Try both, racing IPv4 shortly after IPv6 (happy eyeballs)
+    #[default]
+    Auto,
+    /// Connect over IPv4 only
+    Ipv4,
+    /// Connect over IPv6 only
+    Ipv6,
+}
+
+impl HttpOverrides {
+    /// First header whose name or value cannot be sent
+    pub fn invalid_header(&self) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(name, value)| {
+                reqwest::header::HeaderName::from_bytes(name.as_bytes()).is_err()
+                    || reqwest::header::HeaderValue::from_str(value).is_err()
+            })
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// First timeout setting that is not a positive number of seconds
+    pub fn invalid_timeout(&self) -> Option<&'static str> {
+        let invalid = |secs: Option<f64>| secs.is_some_and(|s| !(s.is_finite() && s > 0.0));
+        if invalid(self.connect_timeout) {
+            Some("connect_timeout")
+        } else if invalid(self.read_timeout) {
+            Some("read_timeout")
+        } else if self.max_response_kb == Some(0) {
+            Some("max_response_kb")
+        } else {
+            None
+        }
+    }
+
+    /// First `dns` entry whose value is not an IP address
+    pub fn invalid_dns(&self) -> Option<&str> {
+        self.dns
+            .iter()
+            .find(|(_, ip)| ip.parse::<std::net::IpAddr>().is_err())
+            .map(|(host, _)| host.as_str())
+    }
+}
+
+/// `[patcher.spinner]`: text shown as the spinner tip while Claude Code
+/// works. Project reminders win over global ones, which win over
+/// `message`; with none of them Claude Code's own tip is kept.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpinnerPatchConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Reminders shown in turn
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub reminders: Vec<String>,
+    /// Reminders for projects, keyed by directory (`~` allowed); the
+    /// longest directory containing Claude Code's working directory wins
+    #[serde(
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "sorted_map"
+    )]
+    pub projects: HashMap<String, Vec<String>>,
+    /// Seconds each reminder stays before the next one
+    pub interval_secs: u64,
+}
+
+impl Default for SpinnerPatchConfig {
+    fn default() -> Self {
+        Self {
+            message: None,
+            reminders: Vec::new(),
+            projects: HashMap::new(),
+            interval_secs: 60,
+        }
+    }
+}
+
+impl SpinnerPatchConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether there is anything to show
+    pub fn is_enabled(&self) -> bool {
+        self.message.as_deref().is_some_and(|m| !m.is_empty())
+            || !self.reminders.is_empty()
+            || self.projects.values().any(|r| !r.is_empty())
+    }
+}
+
+/// `[[directory_profiles]]` entry: how the statusline looks for workspaces
+/// under `path`, e.g. a minimal theme without cost for `~/work/**`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryProfile {
+    /// Name for `--profile`, which selects the profile wherever Claude Code
+    /// runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Glob matched against the workspace directory. `~` is the home
+    /// directory, `*` matches within one directory, `**` any depth. Empty
+    /// for profiles only used through `--profile`.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub path: String,
+    /// Theme used instead of the configured one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    /// Segments to turn off
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hide: Vec<SegmentId>,
+    /// Segments of the theme to turn on
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub show: Vec<SegmentId>,
+    /// Turn on every segment of the theme; `hide` still applies
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub show_all: bool,
+}
+
+/// `[output_file]` section: mirror of the statusline for bars and overlays
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputFileConfig {
+    /// Destination, `~` expands to the home directory
+    pub path: String,
+    #[serde(default)]
+    pub format: OutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    /// Statusline text without escape sequences
+    #[default]
+    Plain,
+    /// Text, ANSI text and per-segment data
+    Json,
+    /// Waybar custom module JSON (`text`, `tooltip`, `class`, `percentage`)
+    Waybar,
+    /// Plain text with `%` escaped for Vim/Neovim statuslines
+    Lualine,
+    /// JSON for a VS Code status bar item (`text`, `tooltip`, `severity`)
+    Vscode,
+}
+
+/// `[waybar]` section
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WaybarConfig {
+    /// Segment shown as the module text and used for `class` and `percentage`
+    pub primary_segment: SegmentId,
+}
+
+impl Default for WaybarConfig {
+    fn default() -> Self {
+        Self {
+            primary_segment: SegmentId::ContextWindow,
+        }
+    }
+}
+
+impl WaybarConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// `[oneapi]` section: a self-hosted one-api / new-api gateway. Claude Code
+/// requests through `base_url` are detected as the `oneapi` vendor, and the
+/// `one_api_usage` segment shows the quota from its `/api/user/self`.
+///
+/// ```toml
+/// [oneapi]
+/// base_url = "https://gateway.example.com"
+/// access_token = "..."   # Personal settings → system access token
+/// user_id = 1            # new-api only
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OneApiConfig {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub base_url: String,
+    /// The system access token, not an `sk-` API key; falls back to
+    /// `[vendors.oneapi]` in api_keys.toml and the keyring
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub access_token: String,
+    /// Sent as `New-Api-User`, which new-api requires
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<u64>,
+}
+
+impl OneApiConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// `[perf]` section: time budget for collecting a single segment and for
+/// the whole statusline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PerfConfig {
+    /// Skip segments whose average collection time exceeds the budget.
+    /// Off by default: a slow network would hide usage segments
+    pub auto_skip: bool,
+    pub budget_ms: u64,
+    /// Segments are collected in parallel; those still running after this
+    /// long are left out of the render
+    pub deadline_ms: u64,
+}
+
+impl Default for PerfConfig {
+    fn default() -> Self {
+        Self {
+            auto_skip: false,
+            budget_ms: 1500,
+            deadline_ms: 2000,
+        }
+    }
+}
+
+impl PerfConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// `[accessibility]` section: high-contrast, screen-reader-friendly output.
+/// `UUCODE_A11Y=1` turns it on without editing the config.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessibilityConfig {
+    pub enabled: bool,
+    /// Drop decorative emoji and use text icons
+    pub strip_emoji: bool,
+}
+
+impl Default for AccessibilityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            strip_emoji: true,
+        }
+    }
+}
+
+impl AccessibilityConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.enabled || std::env::var("UUCODE_A11Y").is_ok_and(|v| !v.is_empty() && v != "0")
+    }
+
+    /// Emoji are stripped only while the profile is active
+    pub fn strips_emoji(&self) -> bool {
+        self.strip_emoji && self.is_active()
+    }
+}
+
+/// `[marketplace]` section: where `uucode segment browse` looks for
+/// community segments and themes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarketplaceConfig {
+    pub index_url: String,
+    /// Base64 ed25519 public key the index signature must verify against
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+    /// Minisign public key; when set, the index and every entry must have a
+    /// matching `<url>.minisig` and `public_key` is not used
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minisign_key: Option<String>,
+}
+
+impl Default for MarketplaceConfig {
+    fn default() -> Self {
+        Self {
+            index_url: "https://www.uucode.org/segments/index.json".to_string(),
+            public_key: None,
+            minisign_key: None,
+        }
+    }
+}
+
+impl MarketplaceConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether a key to verify the index against is configured
+    pub fn has_key(&self) -> bool {
+        self.public_key.is_some() || self.minisign_key.is_some()
+    }
+
+    /// The curated index has no published signing key yet, so it cannot
+    /// be verified and `uucode segment` refuses to use it, `--insecure` or not
+    pub fn is_unverifiable_default(&self) -> bool {
+        !self.has_key() && self.index_url == Self::default().index_url
+    }
+}
+
+/// `[alerts]` section: rules evaluated on every render
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertsConfig {
+    pub rules: Vec<AlertRule>,
+    /// Named sinks referenced by `AlertRule::sinks`
+    #[serde(serialize_with = "sorted_map")]
+    pub sinks: HashMap<String, AlertSink>,
+    #[serde(skip_serializing_if = "ContextReminderConfig::is_default")]
+    pub context: ContextReminderConfig,
+}
+
+impl AlertsConfig {
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty() && self.sinks.is_empty() && self.context.is_default()
+    }
+}
+
+/// `[alerts.context]`: reminder the `--wrap` wrapper shows in the terminal
+/// when the context advisor suggests `/compact` or `/clear`. It goes to the
+/// terminal only, never into what Claude Code sends to the model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContextReminderConfig {
+    pub remind: ContextReminder,
+    /// Advisor level that triggers the reminder: `warning` (`/compact`
+    /// suggested) or `critical` (`/clear` suggested)
+    pub level: String,
+}
+
+impl Default for ContextReminderConfig {
+    fn default() -> Self {
+        Self {
+            remind: ContextReminder::Off,
+            level: "critical".to_string(),
+        }
+    }
+}
+
+impl ContextReminderConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextReminder {
+    #[default]
+    Off,
+    /// Print a reminder line
+    Line,
+    /// Ring the terminal bell
+    Bell,
+    /// Both
+    LineAndBell,
+}
+
+/// Fires once when `metric` reaches `threshold`, re-arms when it drops below
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub name: String,
+    pub metric: AlertMetric,
+    pub threshold: f64,
+    /// Sink names to notify, all sinks when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sinks: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertMetric {
+    /// Highest quota usage percentage of the current vendor
+    Quota,
+    /// Context window usage percentage
+    Context,
+    /// Session cost in USD
+    Cost,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AlertSink {
+    /// POSTs a JSON payload describing the alert
+    Webhook { url: String },
+    /// Slack incoming webhook
+    Slack { webhook_url: String },
+    /// Telegram bot API `sendMessage`
+    Telegram { bot_token: String, chat_id: String },
+}
+
+/// Number and currency formatting shared by all segments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FormatConfig {
+    /// Inserted between groups of three digits, empty to disable
+    pub thousands_separator: String,
+    pub decimal_separator: String,
+    /// Currency amounts are displayed in
+    pub currency: Currency,
+    /// Overrides the currency's default symbol
+    pub currency_symbol: Option<String>,
+    /// Static USD -> CNY rate, also the fallback when fetching fails
+    pub cny_rate: f64,
+    /// Endpoint returning the USD -> CNY rate, fetched once a day when set
+    pub exchange_rate_url: Option<String>,
+    /// Forces a fixed precision, otherwise each segment picks its own
+    pub decimal_places: Option<usize>,
+    /// Language of durations such as "剩余17天" / "17 days left"
+    pub locale: Locale,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        Self {
+            thousands_separator: String::new(),
+            decimal_separator: ".".to_string(),
+            currency: Currency::Usd,
+            currency_symbol: None,
+            cny_rate: 7.2,
+            exchange_rate_url: None,
+            decimal_places: None,
+            locale: Locale::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Locale {
+    #[default]
+    Zh,
+    En,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Currency {
+    Usd,
+    Cny,
+}
+
+impl Currency {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Currency::Usd => "$",
+            Currency::Cny => "¥",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StyleConfig {
     pub mode: StyleMode,
     pub separator: String,
+    /// Color of the global separator, white when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub separator_color: Option<AnsiColor>,
+    /// Maximum visible width of the statusline; low-priority segments are
+    /// dropped to fit. `None` disables the guard.
+    #[serde(default)]
+    pub max_width: Option<usize>,
+    /// How many columns emoji occupy when measuring the line
+    #[serde(default)]
+    pub emoji_width: EmojiWidth,
+    /// How segments showing expired cached data are marked
+    #[serde(default)]
+    pub stale_indicator: StaleIndicator,
+    /// Icon set for segment icons and the glyphs inside segment text;
+    /// a segment's `icon_set` option overrides it
+    #[serde(default)]
+    pub icon_set: IconSet,
+    /// Replacements for individual inline glyphs, keyed by glyph name
+    /// (`card`, `package`, `gem`, ...)
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "sorted_map"
+    )]
+    pub icons: HashMap<String, String>,
+    /// Colors for the ok / warning / critical states segments report
+    #[serde(default, skip_serializing_if = "Palette::is_default")]
+    pub palette: Palette,
+    /// The theme's semantic colors (`[style.roles]`)
+    #[serde(default, skip_serializing_if = "RoleColors::is_default")]
+    pub roles: RoleColors,
+    /// Vendor quota segments with less than this percentage left move to
+    /// the front of the line and keep their secondary text when the line is
+    /// trimmed, until the quota recovers. 0 turns this off.
+    #[serde(default = "default_promote_below")]
+    pub promote_below: f64,
+}
+
+fn default_promote_below() -> f64 {
+    10.0
+}
+
+/// Colors segments ask for by meaning rather than by value. Unset roles
+/// fall back to green, yellow, red, gold and white; a color-blind `palette`
+/// takes precedence over `ok`, `warn` and `critical`.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct RoleColors {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ok: Option<AnsiColor>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warn: Option<AnsiColor>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub critical: Option<AnsiColor>,
+    /// Highlights such as multipliers and balances
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accent: Option<AnsiColor>,
+    /// Separators and other secondary text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub muted: Option<AnsiColor>,
+}
+
+impl RoleColors {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// State color palette; the variants keep states apart for the common
+/// color vision deficiencies
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Palette {
+    /// Green, yellow and red
+    #[default]
+    Default,
+    /// Blue, yellow and vermillion for red-green (green-weak) vision
+    Deuteranopia,
+    /// Sky blue, yellow and orange for red-green (red-weak) vision
+    Protanopia,
+    /// Bluish green, pink and red for blue-yellow vision
+    Tritanopia,
+}
+
+impl Palette {
+    pub fn is_default(&self) -> bool {
+        *self == Self::Default
+    }
+}
+
+/// Marker for segments rendered from cache older than its TTL
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StaleIndicator {
+    /// Dimmed text followed by ⟳
+    #[default]
+    Dim,
+    /// Trailing asterisk
+    Asterisk,
+    /// Clock icon with the cache age, e.g. `🕒12m`
+    Clock,
+    Off,
+}
+
+impl Default for StyleConfig {
+    fn default() -> Self {
+        Self {
+            mode: StyleMode::NerdFont,
+            separator: " | ".to_string(),
+            separator_color: None,
+            max_width: None,
+            emoji_width: EmojiWidth::Auto,
+            stale_indicator: StaleIndicator::Dim,
+            icon_set: IconSet::Auto,
+            icons: HashMap::new(),
+            palette: Palette::Default,
+            roles: RoleColors::default(),
+            promote_below: default_promote_below(),
+        }
+    }
+}
+
+/// Emoji rendering width policy; terminals disagree on whether symbols
+/// such as ⏱ or 💳 take one or two columns
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmojiWidth {
+    /// Pick based on `TERM_PROGRAM` / `TERM`
+    #[default]
+    Auto,
+    Wide,
+    Narrow,
+}
+
+impl EmojiWidth {
+    /// Resolve `Auto` against the current terminal
+    pub fn resolve(self) -> EmojiWidth {
+        match self {
+            EmojiWidth::Auto => {
+                let term_program = std::env::var("TERM_PROGRAM").unwrap_or_default();
+                let term = std::env::var("TERM").unwrap_or_default();
+                if term_program == "tmux" || term == "linux" || term.starts_with("vt") {
+                    EmojiWidth::Narrow
+                } else {
+                    EmojiWidth::Wide
+                }
+            }
+            other => other,
+        }
+    }
+}
+
+/// Named icon set
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IconSet {
+    /// Emoji in plain mode, Nerd Font glyphs otherwise
+    #[default]
+    Auto,
+    Emoji,
+    #[serde(alias = "nerd_v3", alias = "nerd")]
+    NerdV3,
+    /// Short text labels for terminals without emoji or Nerd Fonts
+    Ascii,
+    None,
+}
+
+impl IconSet {
+    /// Resolve `Auto` against the style mode
+    pub fn resolve(self, mode: StyleMode) -> IconSet {
+        match (self, mode) {
+            (IconSet::Auto, StyleMode::Plain) => IconSet::Emoji,
+            (IconSet::Auto, _) => IconSet::NerdV3,
+            (other, _) => other,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -32,9 +1000,75 @@ pub struct SegmentConfig {
     pub icon: IconConfig,
     pub colors: ColorConfig,
     pub styles: TextStyleConfig,
+    /// Higher values survive longer when the statusline is too wide;
+    /// `None` uses the segment type's default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u8>,
+    /// Icons picked from the segment's metadata; the first matching rule
+    /// replaces the configured icon
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub icon_rules: Vec<IconRule>,
+    #[serde(serialize_with = "sorted_map")]
     pub options: HashMap<String, serde_json::Value>,
 }
 
+/// Icon used while a metadata value matches, e.g. for the git segment:
+///
+/// ```toml
+/// [[segments.icon_rules]]
+/// key = "status"
+/// equals = "Conflicts"
+/// plain = "💥"
+/// nerd_font = "\u{f071}"
+/// ```
+///
+/// Numeric values can be matched with `min` (inclusive) and `max`
+/// (exclusive), e.g. `key = "percentage"` on the context window segment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IconRule {
+    /// Metadata key to test
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub equals: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[serde(flatten)]
+    pub icon: IconConfig,
+    /// Icon for the ascii icon set; the segment's ascii icon when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ascii: Option<String>,
+}
+
+impl IconRule {
+    pub fn matches(&self, metadata: &HashMap<String, String>) -> bool {
+        let Some(value) = metadata.get(&self.key) else {
+            return false;
+        };
+        if self
+            .equals
+            .as_ref()
+            .is_some_and(|expected| expected != value)
+        {
+            return false;
+        }
+        if self.min.is_none() && self.max.is_none() {
+            return true;
+        }
+        let Ok(number) = value.trim().trim_end_matches('%').parse::<f64>() else {
+            return false;
+        };
+        self.min.is_none_or(|min| number >= min) && self.max.is_none_or(|max| number < max)
+    }
+}
+
+impl SegmentConfig {
+    pub fn effective_priority(&self) -> u8 {
+        self.priority.unwrap_or_else(|| self.id.default_priority())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IconConfig {
     pub plain: String,
@@ -68,10 +1102,17 @@ pub enum SegmentId {
     Directory,
     Git,
     ContextWindow,
+    ContextAdvisor,
+    ApiErrors,
+    Summary,
+    QuotaPacing,
     Usage,
     Cost,
+    DailyCost,
+    BlockUsage,
     Session,
     OutputStyle,
+    Runtime,
     Update,
     UucodeUsage,
     UucodeSubscription,
@@ -85,6 +1126,63 @@ pub enum SegmentId {
     CubenceLatency,      // Cubence API 延迟段
     CubenceSubscription, // Cubence 订阅段
     CubenceMultiplier,   // Cubence 价格倍率段
+    OneApiUsage,         // one-api / new-api 网关用量段
+    VendorUsage,         // vendors.d 中服务商的用量段
+    Custom,              // 自定义命令段
+}
+
+impl SegmentId {
+    /// Vendor whose API backs this segment, `None` for vendor-neutral ones
+    pub fn vendor(&self) -> Option<crate::api::VendorType> {
+        use crate::api::VendorType;
+        match self {
+            SegmentId::UucodeUsage | SegmentId::UucodeSubscription | SegmentId::UucodeStatus => {
+                Some(VendorType::Uucode)
+            }
+            SegmentId::CubenceBalance
+            | SegmentId::CubenceUsage
+            | SegmentId::CubenceStatus
+            | SegmentId::CubenceFiveHour
+            | SegmentId::CubenceWeekly
+            | SegmentId::CubenceLoadStatus
+            | SegmentId::CubenceLatency
+            | SegmentId::CubenceSubscription
+            | SegmentId::CubenceMultiplier => Some(VendorType::Cubence),
+            SegmentId::OneApiUsage => Some(VendorType::OneApi),
+            SegmentId::VendorUsage => Some(VendorType::Custom),
+            _ => None,
+        }
+    }
+
+    /// Default drop priority used by the output length guard
+    pub fn default_priority(&self) -> u8 {
+        match self {
+            SegmentId::Summary => 95,
+            SegmentId::Model => 90,
+            SegmentId::Directory => 80,
+            SegmentId::ContextWindow => 75,
+            SegmentId::Git => 70,
+            SegmentId::UucodeUsage
+            | SegmentId::CubenceFiveHour
+            | SegmentId::BlockUsage
+            | SegmentId::OneApiUsage
+            | SegmentId::VendorUsage => 65,
+            SegmentId::Cost | SegmentId::CubenceWeekly | SegmentId::CubenceUsage => 60,
+            SegmentId::CubenceBalance => 55,
+            SegmentId::Session | SegmentId::DailyCost => 50,
+            SegmentId::ContextAdvisor | SegmentId::QuotaPacing | SegmentId::ApiErrors => 45,
+            SegmentId::Usage => 40,
+            SegmentId::CubenceMultiplier => 35,
+            SegmentId::UucodeSubscription
+            | SegmentId::UucodeStatus
+            | SegmentId::CubenceStatus
+            | SegmentId::CubenceSubscription => 30,
+            SegmentId::CubenceLoadStatus => 25,
+            SegmentId::OutputStyle | SegmentId::Runtime | SegmentId::Custom => 20,
+            SegmentId::CubenceLatency => 15,
+            SegmentId::Update => 10,
+        }
+    }
 }
 
 // Legacy compatibility structure
@@ -97,18 +1195,57 @@ pub struct SegmentsConfig {
 }
 
 // Data structures compatible with existing main.rs
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct Model {
     pub id: String,
     pub display_name: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct Workspace {
     pub current_dir: String,
+    /// Directory Claude Code was started in
+    #[serde(default)]
+    pub project_dir: Option<String>,
+    /// Further roots added to the session with `/add-dir` or `--add-dir`
+    #[serde(default)]
+    pub added_dirs: Vec<String>,
 }
 
-#[derive(Deserialize)]
+impl Workspace {
+    /// Every root of the session, the project directory first
+    pub fn roots(&self) -> Vec<&str> {
+        let first = self.project_dir.as_deref().unwrap_or(&self.current_dir);
+        let mut roots = vec![first];
+        for dir in &self.added_dirs {
+            if !roots.contains(&dir.as_str()) {
+                roots.push(dir);
+            }
+        }
+        roots
+    }
+
+    /// The root `current_dir` lies in, the innermost one when roots nest
+    ///
+    /// ```
+    /// use mycode::config::InputData;
+    ///
+    /// let mut input = InputData::new("claude-sonnet-4-5", "/work/api/src")
+    ///     .with_added_dirs(&["/work/api", "/work/web"]);
+    /// input.workspace.project_dir = Some("/work/app".to_string());
+    /// assert_eq!(input.workspace.roots(), ["/work/app", "/work/api", "/work/web"]);
+    /// assert_eq!(input.workspace.active_root(), Some("/work/api"));
+    /// ```
+    pub fn active_root(&self) -> Option<&str> {
+        let current = std::path::Path::new(&self.current_dir);
+        self.roots()
+            .into_iter()
+            .filter(|root| current.starts_with(root))
+            .max_by_key(|root| root.len())
+    }
+}
+
+#[derive(Deserialize, Clone, Default)]
 pub struct Cost {
     pub total_cost_usd: Option<f64>,
     pub total_duration_ms: Option<u64>,
@@ -117,18 +1254,150 @@ pub struct Cost {
     pub total_lines_removed: Option<u32>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct OutputStyle {
     pub name: String,
 }
 
-#[derive(Deserialize)]
+/// Size of the area Claude Code draws the statusline in
+#[derive(Deserialize, Clone, Default)]
+pub struct TerminalHints {
+    #[serde(default, alias = "width")]
+    pub columns: Option<usize>,
+    /// Columns Claude Code reserves before the statusline
+    #[serde(default)]
+    pub padding: Option<usize>,
+}
+
+#[derive(Deserialize, Clone)]
 pub struct InputData {
+    #[serde(default)]
+    pub session_id: Option<String>,
     pub model: Model,
     pub workspace: Workspace,
     pub transcript_path: String,
     pub cost: Option<Cost>,
     pub output_style: Option<OutputStyle>,
+    #[serde(default)]
+    pub terminal: Option<TerminalHints>,
+}
+
+impl InputData {
+    /// Payload for embedding and tests; Claude Code sends its own as JSON.
+    /// The display name starts out as the model id.
+    ///
+    /// ```
+    /// use mycode::config::{Cost, InputData};
+    ///
+    /// let input = InputData::new("claude-sonnet-4-5", "/home/me/project")
+    ///     .with_display_name("Sonnet 4.5")
+    ///     .with_cost(Cost {
+    ///         total_cost_usd: Some(0.42),
+    ///         ..Default::default()
+    ///     })
+    ///     .with_width(120);
+    /// assert_eq!(input.model.display_name, "Sonnet 4.5");
+    /// assert_eq!(input.available_width(), Some(120));
+    /// ```
+    pub fn new(model_id: &str, current_dir: &str) -> Self {
+        Self {
+            session_id: None,
+            model: Model {
+                id: model_id.to_string(),
+                display_name: model_id.to_string(),
+            },
+            workspace: Workspace {
+                current_dir: current_dir.to_string(),
+                project_dir: None,
+                added_dirs: Vec::new(),
+            },
+            transcript_path: String::new(),
+            cost: None,
+            output_style: None,
+            terminal: None,
+        }
+    }
+
+    pub fn with_display_name(mut self, name: &str) -> Self {
+        self.model.display_name = name.to_string();
+        self
+    }
+
+    /// Additional workspace roots of the session
+    pub fn with_added_dirs(mut self, dirs: &[&str]) -> Self {
+        self.workspace.added_dirs = dirs.iter().map(|dir| dir.to_string()).collect();
+        self
+    }
+
+    pub fn with_session_id(mut self, id: &str) -> Self {
+        self.session_id = Some(id.to_string());
+        self
+    }
+
+    /// Key for state kept per session across renders: the session id, or
+    /// the transcript path for Claude Code versions that do not send one
+    pub fn session_key(&self) -> &str {
+        self.session_id
+            .as_deref()
+            .filter(|id| !id.is_empty())
+            .unwrap_or(&self.transcript_path)
+    }
+
+    /// Session transcript (JSONL) read by the context and cost segments
+    pub fn with_transcript_path(mut self, path: &str) -> Self {
+        self.transcript_path = path.to_string();
+        self
+    }
+
+    pub fn with_cost(mut self, cost: Cost) -> Self {
+        self.cost = Some(cost);
+        self
+    }
+
+    pub fn with_output_style(mut self, name: &str) -> Self {
+        self.output_style = Some(OutputStyle {
+            name: name.to_string(),
+        });
+        self
+    }
+
+    /// Columns available to the statusline, after any padding
+    pub fn with_width(mut self, columns: usize) -> Self {
+        self.terminal = Some(TerminalHints {
+            columns: Some(columns),
+            padding: Some(0),
+        });
+        self
+    }
+
+    /// Columns left for the statusline, when the terminal width is known.
+    ///
+    /// The width comes from the payload or else `$COLUMNS`; the padding from
+    /// the payload or else `statusLine.padding` in Claude's settings.json.
+    pub fn available_width(&self) -> Option<usize> {
+        let hints = self.terminal.as_ref();
+        let columns = hints.and_then(|t| t.columns).or_else(|| {
+            std::env::var("COLUMNS")
+                .ok()
+                .and_then(|c| c.trim().parse().ok())
+        })?;
+        let padding = hints.and_then(|t| t.padding).unwrap_or_else(|| {
+            crate::api::load_claude_settings()
+                .ok()
+                .and_then(|settings| settings.pointer("/statusLine/padding")?.as_u64())
+                .unwrap_or(0) as usize
+        });
+        Some(columns.saturating_sub(padding)).filter(|width| *width > 0)
+    }
+
+    /// Cells for progress bars: narrow terminals get shorter bars
+    pub fn progress_bar_width(&self) -> usize {
+        match self.available_width() {
+            Some(width) if width < 80 => 4,
+            Some(width) if width >= 160 => 12,
+            _ => 8,
+        }
+    }
 }
 
 // OpenAI-style nested token details
@@ -412,7 +1681,16 @@ pub type Usage = RawUsage;
 
 #[derive(Deserialize)]
 pub struct Message {
+    /// API message id, shared by all transcript lines of one response
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Model that produced the response
+    #[serde(default)]
+    pub model: Option<String>,
     pub usage: Option<Usage>,
+    /// Content blocks (text, tool_use, ...) as sent by the API
+    #[serde(default)]
+    pub content: Option<serde_json::Value>,
 }
 
 #[derive(Deserialize)]
@@ -425,4 +1703,10 @@ pub struct TranscriptEntry {
     #[serde(rename = "parentUuid")]
     pub parent_uuid: Option<String>,
     pub summary: Option<String>,
+    /// RFC 3339 time the entry was written
+    #[serde(default)]
+    pub timestamp: Option<String>,
+    /// Set on the assistant entries Claude Code writes for failed requests
+    #[serde(rename = "isApiErrorMessage", default)]
+    pub is_api_error_message: bool,
 }
@@ -0,0 +1,180 @@
+//! Threshold alerts delivered to webhook, Slack and Telegram sinks.
+//!
+//! Rules are evaluated against the render's history sample. Each rule
+//! fires once when its metric reaches the threshold and re-arms after the
+//! metric drops below it again; the armed state lives in `cache/alerts.json`
+//! because every render is a separate process.
+
+use crate::config::{AlertMetric, AlertRule, AlertSink, AlertsConfig};
+use crate::core::history::Sample;
+use crate::debug_log;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Sinks are called inline during a render, keep them from stalling it
+const SINK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// A rule that just crossed its threshold
+#[derive(Debug, Clone)]
+pub struct Alert<'a> {
+    pub rule: &'a AlertRule,
+    pub value: f64,
+    pub timestamp: u64,
+}
+
+impl Alert<'_> {
+    pub fn message(&self) -> String {
+        let (what, value, threshold) = match self.rule.metric {
+            AlertMetric::Quota => (
+                "quota usage",
+                format!("{:.1}%", self.value),
+                format!("{:.0}%", self.rule.threshold),
+            ),
+            AlertMetric::Context => (
+                "context window",
+                format!("{:.1}%", self.value),
+                format!("{:.0}%", self.rule.threshold),
+            ),
+            AlertMetric::Cost => (
+                "session cost",
+                format!("${:.2}", self.value),
+                format!("${:.2}", self.rule.threshold),
+            ),
+        };
+        format!(
+            "uucode [{}]: {} {} reached {}",
+            self.rule.name, what, value, threshold
+        )
+    }
+}
+
+fn state_path() -> Option<PathBuf> {
+    crate::utils::paths::cache_dir().map(|dir| dir.join("alerts.json"))
+}
+
+/// Rule names that have fired and not re-armed yet
+fn load_state() -> HashMap<String, bool> {
+    state_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &HashMap<String, bool>) -> Result<(), Box<dyn std::error::Error>> {
+    let path = state_path().ok_or("Could not find home directory")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    crate::utils::atomic_write(path, serde_json::to_string(state)?)?;
+    Ok(())
+}
+
+fn metric_value(metric: AlertMetric, sample: &Sample) -> Option<f64> {
+    match metric {
+        AlertMetric::Quota => sample.quota_percentage,
+        AlertMetric::Context => sample.context_percentage,
+        AlertMetric::Cost => sample.cost_usd,
+    }
+}
+
+/// Evaluate all rules and notify their sinks for the ones that crossed
+pub fn evaluate(config: &AlertsConfig, sample: &Sample) {
+    if config.rules.is_empty() {
+        return;
+    }
+
+    let mut state = load_state();
+    let mut changed = false;
+
+    for rule in &config.rules {
+        let Some(value) = metric_value(rule.metric, sample) else {
+            continue;
+        };
+        let fired = state.get(&rule.name).copied().unwrap_or(false);
+
+        if value >= rule.threshold && !fired {
+            let alert = Alert {
+                rule,
+                value,
+                timestamp: sample.timestamp,
+            };
+            dispatch(config, &alert);
+            state.insert(rule.name.clone(), true);
+            changed = true;
+        } else if value < rule.threshold && fired {
+            state.insert(rule.name.clone(), false);
+            changed = true;
+        }
+    }
+
+    if changed {
+        if let Err(e) = save_state(&state) {
+            debug_log!("failed to save alert state: {}", e);
+        }
+    }
+}
+
+/// Send an alert to the sinks its rule routes to
+pub fn dispatch(config: &AlertsConfig, alert: &Alert) {
+    let targets: Vec<(&String, &AlertSink)> = if alert.rule.sinks.is_empty() {
+        config.sinks.iter().collect()
+    } else {
+        alert
+            .rule
+            .sinks
+            .iter()
+            .filter_map(|name| match config.sinks.get(name) {
+                Some(sink) => Some((name, sink)),
+                None => {
+                    debug_log!(
+                        "alert rule '{}' routes to unknown sink '{}'",
+                        alert.rule.name,
+                        name
+                    );
+                    None
+                }
+            })
+            .collect()
+    };
+
+    for (name, sink) in targets {
+        match send(sink, alert) {
+            Ok(()) => debug_log!("alert '{}' sent to sink '{}'", alert.rule.name, name),
+            Err(e) => debug_log!("alert sink '{}' failed: {}", name, e),
+        }
+    }
+}
+
+fn send(sink: &AlertSink, alert: &Alert) -> Result<(), Box<dyn std::error::Error>> {
+    let client = crate::api::http::client(None, SINK_TIMEOUT)?;
+    let message = alert.message();
+
+    let request = match sink {
+        AlertSink::Webhook { url } => client.post(url).json(&json!({
+            "rule": alert.rule.name,
+            "metric": alert.rule.metric,
+            "value": alert.value,
+            "threshold": alert.rule.threshold,
+            "message": message,
+            "timestamp": alert.timestamp,
+        })),
+        AlertSink::Slack { webhook_url } => {
+            client.post(webhook_url).json(&json!({ "text": message }))
+        }
+        AlertSink::Telegram { bot_token, chat_id } => client
+            .post(format!(
+                "https://api.telegram.org/bot{}/sendMessage",
+                bot_token
+            ))
+            .json(&json!({ "chat_id": chat_id, "text": message })),
+    };
+
+    let response = request.send()?;
+    if !response.status().is_success() {
+        return Err(format!("HTTP {}", response.status()).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,115 @@
+//! Context size after each message, per session (`cache/context_trend.json`).
+//!
+//! The context window segment records the token count whenever it changes
+//! and extrapolates how many more messages fit before Claude Code compacts,
+//! from the average growth of the last few turns.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Number of recent turns the growth rate is averaged over
+const RATE_WINDOW: usize = 5;
+/// Counts kept per session
+const KEEP_COUNTS: usize = 20;
+/// Sessions kept, the least recently updated are dropped
+const KEEP_SESSIONS: usize = 20;
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SessionTrend {
+    /// Context tokens after each message since the last compaction, oldest first
+    pub tokens: Vec<u32>,
+    /// Unix timestamp of the newest count
+    pub updated_at: i64,
+}
+
+impl SessionTrend {
+    /// Average context growth per message over the recent turns, `None`
+    /// until the context has grown at least once
+    pub fn growth_per_turn(&self) -> Option<f64> {
+        let recent = &self.tokens[self.tokens.len().saturating_sub(RATE_WINDOW + 1)..];
+        let growth: Vec<f64> = recent
+            .windows(2)
+            .map(|w| w[1] as f64 - w[0] as f64)
+            .filter(|delta| *delta > 0.0)
+            .collect();
+        (!growth.is_empty()).then(|| growth.iter().sum::<f64>() / growth.len() as f64)
+    }
+
+    /// Messages that fit before the context reaches `threshold` tokens
+    ///
+    /// ```
+    /// use mycode::core::context_trend::SessionTrend;
+    ///
+    /// let trend = SessionTrend { tokens: vec![10_000, 14_000, 18_000], updated_at: 0 };
+    /// assert_eq!(trend.turns_until(30_000), Some(3));
+    /// assert_eq!(trend.turns_until(15_000), Some(0));
+    /// ```
+    pub fn turns_until(&self, threshold: u32) -> Option<u32> {
+        let current = *self.tokens.last()?;
+        let rate = self.growth_per_turn()?;
+        Some((threshold.saturating_sub(current) as f64 / rate).floor() as u32)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+struct TrendFile {
+    sessions: HashMap<String, SessionTrend>,
+}
+
+fn trend_path() -> Option<PathBuf> {
+    crate::utils::paths::cache_dir().map(|dir| dir.join("context_trend.json"))
+}
+
+fn load() -> TrendFile {
+    trend_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Record `tokens` for `session` and return its trend.
+///
+/// A count equal to the newest one is the same message rendered again and
+/// is not recorded; a smaller one means the session was compacted or
+/// cleared, so the trend starts over. Deterministic renders neither read
+/// nor write the file.
+pub fn record(session: &str, tokens: u32) -> SessionTrend {
+    if crate::utils::clock::is_deterministic() {
+        return SessionTrend::default();
+    }
+    let mut file = load();
+    let trend = file.sessions.entry(session.to_string()).or_default();
+    match trend.tokens.last() {
+        Some(last) if *last == tokens => return trend.clone(),
+        Some(last) if *last > tokens => trend.tokens.clear(),
+        _ => {}
+    }
+    trend.tokens.push(tokens);
+    let excess = trend.tokens.len().saturating_sub(KEEP_COUNTS);
+    trend.tokens.drain(..excess);
+    trend.updated_at = crate::utils::clock::unix_now();
+    let trend = trend.clone();
+
+    if file.sessions.len() > KEEP_SESSIONS {
+        let mut updated: Vec<i64> = file.sessions.values().map(|t| t.updated_at).collect();
+        updated.sort_unstable_by(|a, b| b.cmp(a));
+        let cutoff = updated[KEEP_SESSIONS - 1];
+        file.sessions.retain(|_, t| t.updated_at >= cutoff);
+    }
+    if let Err(e) = save(&file) {
+        crate::debug_log!("failed to save context trend: {}", e);
+    }
+    trend
+}
+
+fn save(file: &TrendFile) -> Result<(), Box<dyn std::error::Error>> {
+    let path = trend_path().ok_or("Could not find home directory")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    crate::utils::atomic_write(path, serde_json::to_string(file)?)?;
+    Ok(())
+}
@@ -0,0 +1,110 @@
+//! Focus mode: usage and cost segments hidden (and optionally alerts held
+//! back) during `[focus] schedule` windows or after `uucode focus on`.
+//!
+//! The manual toggle lives in `cache/focus.json` and wins over the
+//! schedule until `uucode focus auto` removes it.
+
+use crate::config::{Config, FocusConfig, SegmentId};
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Toggle {
+    On,
+    Off,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusState {
+    pub mode: Toggle,
+    pub since: String,
+}
+
+/// Focus mode in effect for one render
+#[derive(Debug, Clone, PartialEq)]
+pub struct Focus {
+    pub hide: Vec<SegmentId>,
+    pub mute_alerts: bool,
+    /// `manual` or the schedule window, e.g. `09:00-12:00`
+    pub source: String,
+}
+
+fn state_path() -> Option<PathBuf> {
+    crate::utils::paths::cache_dir().map(|dir| dir.join("focus.json"))
+}
+
+pub fn load_state() -> Option<FocusState> {
+    let content = fs::read_to_string(state_path()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Persist a manual toggle, or remove it with `None` to follow the schedule
+pub fn set_toggle(mode: Option<Toggle>) -> Result<(), Box<dyn std::error::Error>> {
+    let path = state_path().ok_or("Could not find home directory")?;
+    let Some(mode) = mode else {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        return Ok(());
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let state = FocusState {
+        mode,
+        since: chrono::Local::now().to_rfc3339(),
+    };
+    crate::utils::atomic_write(path, serde_json::to_string(&state)?)?;
+    Ok(())
+}
+
+/// Focus mode at local time `now`, `None` when off
+pub fn at(config: &FocusConfig, toggle: Option<Toggle>, now: NaiveTime) -> Option<Focus> {
+    match toggle {
+        Some(Toggle::On) => {
+            return Some(Focus {
+                hide: config.hide.clone(),
+                mute_alerts: config.mute_alerts,
+                source: "manual".to_string(),
+            })
+        }
+        Some(Toggle::Off) => return None,
+        None => {}
+    }
+
+    let parse = |time: &str| NaiveTime::parse_from_str(time, "%H:%M").ok();
+    config.schedule.iter().find_map(|window| {
+        let (start, end) = (parse(&window.start)?, parse(&window.end)?);
+        let inside = if start <= end {
+            start <= now && now < end
+        } else {
+            now >= start || now < end
+        };
+        inside.then(|| Focus {
+            hide: window.hide.clone().unwrap_or_else(|| config.hide.clone()),
+            mute_alerts: window.mute_alerts.unwrap_or(config.mute_alerts),
+            source: format!("{}-{}", window.start, window.end),
+        })
+    })
+}
+
+/// Focus mode right now
+pub fn current(config: &FocusConfig) -> Option<Focus> {
+    let toggle = load_state().map(|state| state.mode);
+    at(config, toggle, crate::utils::clock::local_now().time())
+}
+
+/// Hide this render's focus segments; returns the focus mode in effect
+pub fn apply(config: &mut Config) -> Option<Focus> {
+    let focus = current(&config.focus)?;
+    for segment in &mut config.segments {
+        if focus.hide.contains(&segment.id) {
+            segment.enabled = false;
+        }
+    }
+    crate::debug_log!("focus mode ({}) active", focus.source);
+    Some(focus)
+}
@@ -0,0 +1,176 @@
+//! Billing cycle of a uucode subscription (`cache/billing_cycle.json`).
+//!
+//! The billing API only reports the seconds left until the quota resets.
+//! The cycle start is derived from that once and kept, so the day count and
+//! the end-of-cycle spend projection stay stable between renders; when a
+//! reset is observed, the previous cycle's end becomes the new start.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+const DAY_SECONDS: i64 = 86_400;
+/// Ends that moved by less than this are the same cycle reported again
+const END_TOLERANCE: i64 = 6 * 3600;
+/// No projection before this share of the cycle has passed, the first
+/// hours of spend say little about the whole cycle
+const MIN_ELAPSED: f64 = 0.02;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cycle {
+    /// Unix timestamps in seconds
+    pub start: i64,
+    pub end: i64,
+}
+
+impl Cycle {
+    /// The cycle ending at `end`, continuing `previous` when that is the
+    /// same cycle and starting where it ended when it was reset
+    ///
+    /// ```
+    /// use mycode::core::forecast::Cycle;
+    ///
+    /// let day = 86_400;
+    /// let first = Cycle::advance(None, 30 * day, 30 * day);
+    /// assert_eq!(first, Cycle { start: 0, end: 30 * day });
+    /// // Same cycle, end reported a few minutes later
+    /// assert_eq!(Cycle::advance(Some(first), 30 * day + 300, 30 * day), first);
+    /// // Reset: the new cycle starts where the old one ended
+    /// let next = Cycle::advance(Some(first), 61 * day, 30 * day);
+    /// assert_eq!(next, Cycle { start: 30 * day, end: 61 * day });
+    /// // Several cycles missed: start over from the default length
+    /// let later = Cycle::advance(Some(first), 95 * day, 30 * day);
+    /// assert_eq!(later, Cycle { start: 65 * day, end: 95 * day });
+    /// // Clock skew: an end slightly earlier than the stored one is the
+    /// // same cycle, one far earlier starts over
+    /// assert_eq!(Cycle::advance(Some(first), 30 * day - 3600, 30 * day), first);
+    /// let skewed = Cycle::advance(Some(first), 20 * day, 30 * day);
+    /// assert_eq!(skewed, Cycle { start: -10 * day, end: 20 * day });
+    /// ```
+    pub fn advance(previous: Option<Cycle>, end: i64, default_length: i64) -> Cycle {
+        match previous {
+            Some(previous) if (end - previous.end).abs() < END_TOLERANCE => previous,
+            Some(previous) if previous.end < end && end - previous.end <= default_length * 2 => {
+                Cycle {
+                    start: previous.end,
+                    end,
+                }
+            }
+            _ => Cycle {
+                start: end - default_length,
+                end,
+            },
+        }
+    }
+
+    /// Cycle length in whole days, at least one
+    pub fn length_days(&self) -> i64 {
+        ((self.end - self.start) as f64 / DAY_SECONDS as f64)
+            .round()
+            .max(1.0) as i64
+    }
+
+    /// Day of the cycle `now` falls on, counting from 1
+    ///
+    /// ```
+    /// use mycode::core::forecast::Cycle;
+    ///
+    /// let cycle = Cycle { start: 0, end: 30 * 86_400 };
+    /// assert_eq!(cycle.day(0), 1);
+    /// assert_eq!(cycle.day(11 * 86_400 + 5), 12);
+    /// assert_eq!(cycle.day(40 * 86_400), 30);
+    /// ```
+    pub fn day(&self, now: i64) -> i64 {
+        ((now - self.start).div_euclid(DAY_SECONDS) + 1).clamp(1, self.length_days())
+    }
+
+    /// Share of the cycle that has passed, between 0 and 1
+    ///
+    /// ```
+    /// use mycode::core::forecast::Cycle;
+    ///
+    /// let cycle = Cycle { start: 0, end: 10 * 86_400 };
+    /// assert_eq!(cycle.elapsed(86_400), 0.1);
+    /// // A clock behind the cycle start
+    /// assert_eq!(cycle.elapsed(-3600), 0.0);
+    /// // An empty cycle is over as soon as it starts
+    /// let empty = Cycle { start: 500, end: 500 };
+    /// assert_eq!(empty.elapsed(400), 0.0);
+    /// assert_eq!(empty.elapsed(500), 0.0);
+    /// assert_eq!(empty.elapsed(501), 1.0);
+    /// assert_eq!(empty.length_days(), 1);
+    /// assert_eq!(empty.day(501), 1);
+    /// ```
+    pub fn elapsed(&self, now: i64) -> f64 {
+        let length = (self.end - self.start).max(1) as f64;
+        ((now - self.start) as f64 / length).clamp(0.0, 1.0)
+    }
+}
+
+/// Spend at the end of the cycle if it continues at the average rate so
+/// far; `None` too early in the cycle to tell
+///
+/// ```
+/// use mycode::core::forecast::project;
+///
+/// assert_eq!(project(40.0, 0.4), Some(100.0));
+/// assert_eq!(project(5.0, 0.01), None);
+/// assert_eq!(project(90.0, 1.0), Some(90.0));
+/// // Nothing spent yet projects nothing
+/// assert_eq!(project(0.0, 0.5), Some(0.0));
+/// // Negative spend (a refund or a bad reading) has no meaningful rate
+/// assert_eq!(project(-5.0, 0.5), None);
+/// ```
+pub fn project(spent: f64, elapsed: f64) -> Option<f64> {
+    (elapsed >= MIN_ELAPSED && spent >= 0.0).then(|| spent / elapsed.min(1.0))
+}
+
+fn cycle_path() -> Option<PathBuf> {
+    crate::utils::paths::cache_dir().map(|dir| dir.join("billing_cycle.json"))
+}
+
+/// The current cycle for `remaining` seconds left at `now`, updating the
+/// stored one. Deterministic renders neither read nor write the file.
+///
+/// ```
+/// use mycode::core::forecast::track;
+///
+/// // Without the stored cycle, as in a deterministic render
+/// std::env::set_var("UUCODE_DETERMINISTIC", "1");
+/// let day = 86_400;
+/// let cycle = track(10 * day, 20 * day, 30);
+/// assert_eq!((cycle.start, cycle.end), (0, 30 * day));
+/// // A reset time already in the past ends the cycle before `now`
+/// let overdue = track(10 * day, -3600, 30);
+/// assert_eq!(overdue.end, 10 * day - 3600);
+/// assert_eq!(overdue.elapsed(10 * day), 1.0);
+/// // A zero-day cycle is treated as one day
+/// let short = track(10 * day, 3600, 0);
+/// assert_eq!(short.end - short.start, day);
+/// ```
+pub fn track(now: i64, remaining: i64, cycle_days: i64) -> Cycle {
+    let end = now + remaining;
+    let default_length = cycle_days.max(1) * DAY_SECONDS;
+    if crate::utils::clock::is_deterministic() {
+        return Cycle::advance(None, end, default_length);
+    }
+    let previous: Option<Cycle> = cycle_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok());
+    let cycle = Cycle::advance(previous, end, default_length);
+    if previous != Some(cycle) {
+        if let Err(e) = save(&cycle) {
+            crate::debug_log!("failed to save billing cycle: {}", e);
+        }
+    }
+    cycle
+}
+
+fn save(cycle: &Cycle) -> Result<(), Box<dyn std::error::Error>> {
+    let path = cycle_path().ok_or("Could not find home directory")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    crate::utils::atomic_write(path, serde_json::to_string(cycle)?)?;
+    Ok(())
+}
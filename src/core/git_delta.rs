@@ -0,0 +1,148 @@
+//! Lines added/removed during a session, measured with git, for Claude
+//! Code versions whose payload has no `cost.total_lines_*`.
+//!
+//! The first render of a session records the workspace's uncommitted
+//! changes (`git diff --numstat HEAD` plus untracked files) as a baseline
+//! in `cache/session_git.json`; later renders count how far each file has
+//! moved from it. Changes committed during the session drop out of the
+//! count, which is the price of not needing Claude Code's numbers.
+
+use crate::utils::subprocess::Subprocess;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Baselines of sessions untouched for this long are dropped
+const BASELINE_TTL_SECS: i64 = 7 * 24 * 3600;
+/// Untracked files larger than this count as changed without their lines
+const MAX_UNTRACKED_BYTES: u64 = 1024 * 1024;
+const GIT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Lines added and removed per file, relative to HEAD
+pub type NumStat = BTreeMap<String, (u32, u32)>;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LineDelta {
+    pub added: u32,
+    pub removed: u32,
+    pub files: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Baseline {
+    /// Unix seconds of the last render that used this baseline
+    seen_at: i64,
+    files: NumStat,
+}
+
+/// Parse `git diff --numstat` output; binary files count as (0, 0)
+pub fn parse_numstat(text: &str) -> NumStat {
+    text.lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, '\t');
+            let added = parts.next()?.parse().unwrap_or(0);
+            let removed = parts.next()?.parse().unwrap_or(0);
+            Some((parts.next()?.to_string(), (added, removed)))
+        })
+        .collect()
+}
+
+/// Movement of `current` away from `baseline`
+///
+/// ```
+/// use mycode::core::git_delta::{delta, parse_numstat, LineDelta};
+///
+/// let baseline = parse_numstat("3\t1\tsrc/lib.rs\n");
+/// let current = parse_numstat("10\t4\tsrc/lib.rs\n2\t0\tREADME.md\n");
+/// assert_eq!(
+///     delta(&baseline, &current),
+///     LineDelta { added: 9, removed: 3, files: 2 }
+/// );
+/// ```
+pub fn delta(baseline: &NumStat, current: &NumStat) -> LineDelta {
+    let mut result = LineDelta::default();
+    for (file, &(added, removed)) in current {
+        let (base_added, base_removed) = baseline.get(file).copied().unwrap_or((0, 0));
+        if (added, removed) == (base_added, base_removed) {
+            continue;
+        }
+        result.files += 1;
+        result.added += added.saturating_sub(base_added);
+        result.removed += removed.saturating_sub(base_removed);
+    }
+    result
+}
+
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Subprocess::new("git")
+        .args(args)
+        .current_dir(dir)
+        .with_timeout(GIT_TIMEOUT)
+        .run()
+        .ok()?;
+    output.success().then_some(output.stdout)
+}
+
+/// Uncommitted changes of the repository at `dir`, `None` outside git
+fn numstat(dir: &Path) -> Option<NumStat> {
+    let mut files = parse_numstat(&git(dir, &["diff", "--numstat", "HEAD"])?);
+
+    let untracked = git(dir, &["ls-files", "--others", "--exclude-standard", "-z"])?;
+    for file in untracked.split('\0').filter(|f| !f.is_empty()) {
+        let path = dir.join(file);
+        let lines = fs::metadata(&path)
+            .ok()
+            .filter(|meta| meta.len() <= MAX_UNTRACKED_BYTES)
+            .and_then(|_| fs::read(&path).ok())
+            .map(|bytes| count_lines(&bytes))
+            .unwrap_or(0);
+        files.insert(file.to_string(), (lines, 0));
+    }
+    Some(files)
+}
+
+fn count_lines(bytes: &[u8]) -> u32 {
+    let newlines = bytes.iter().filter(|&&b| b == b'\n').count() as u32;
+    newlines + u32::from(bytes.last().is_some_and(|&b| b != b'\n'))
+}
+
+fn baselines_path() -> Option<PathBuf> {
+    crate::utils::paths::cache_dir().map(|dir| dir.join("session_git.json"))
+}
+
+/// Lines changed in `dir` since the session writing `transcript_path`
+/// first rendered; zero on that first render
+pub fn session_delta(transcript_path: &str, dir: &str) -> Option<LineDelta> {
+    // A deterministic render must not depend on earlier renders
+    if crate::utils::clock::is_deterministic() || transcript_path.is_empty() {
+        return None;
+    }
+    let current = numstat(Path::new(dir))?;
+
+    let path = baselines_path()?;
+    let mut baselines: HashMap<String, Baseline> = fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+
+    let now = crate::utils::clock::unix_now();
+    let baseline = baselines
+        .entry(transcript_path.to_string())
+        .or_insert_with(|| Baseline {
+            seen_at: now,
+            files: current.clone(),
+        });
+    baseline.seen_at = now;
+    let result = delta(&baseline.files, &current);
+
+    baselines.retain(|_, b| now - b.seen_at < BASELINE_TTL_SECS);
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(json) = serde_json::to_string(&baselines) {
+        let _ = crate::utils::atomic_write(&path, json);
+    }
+    Some(result)
+}
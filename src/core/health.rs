@@ -0,0 +1,197 @@
+//! Health of each enabled segment, gathered from the stores earlier renders
+//! left behind: collection timings (`perf.json`), circuit breakers
+//! (`breakers.json`), mutes and the vendor usage caches. Nothing is
+//! collected or requested here, so the view is cheap to refresh.
+
+use crate::api::breaker::{self, Breaker};
+use crate::api::vendor::{self, Vendor};
+use crate::api::VendorType;
+use crate::config::{Config, SegmentConfig, SegmentId};
+use crate::core::mute::Mutes;
+use crate::core::perf::{PerfStats, SegmentTiming};
+
+const UUCODE_USAGE_URL: &str = "https://api.uucode.org/account/billing";
+const CUBENCE_USAGE_URL: &str = "https://cubence.com/api/v1/user/subscription-info";
+const UUCODE_SUBSCRIPTION_URL: &str = "https://api.cometix.cn/v1/billing/subscription/list";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    /// Not collected since timings were last reset
+    Unmeasured,
+    /// Over the time budget
+    Slow,
+    /// Over the time budget and skipped by `[perf] auto_skip`
+    Skipped,
+    /// The endpoint failed recently but still gets requests
+    Failing,
+    /// The endpoint's circuit breaker stopped requests
+    CircuitOpen,
+    Muted,
+}
+
+impl Status {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::Unmeasured => "not measured",
+            Status::Slow => "slow",
+            Status::Skipped => "skipped",
+            Status::Failing => "failing",
+            Status::CircuitOpen => "circuit open",
+            Status::Muted => "muted",
+        }
+    }
+
+    /// Whether the segment needs attention
+    pub fn is_problem(&self) -> bool {
+        matches!(
+            self,
+            Status::Slow | Status::Skipped | Status::Failing | Status::CircuitOpen
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SegmentHealth {
+    pub id: SegmentId,
+    pub timing: Option<SegmentTiming>,
+    /// Skipped by `[perf] auto_skip` in the next render
+    pub skipped: bool,
+    pub muted: bool,
+    /// Age of the vendor data cache the segment reads, in seconds
+    pub cache_age: Option<u64>,
+    /// Endpoint the segment requests, as keyed in the breaker store
+    pub endpoint: Option<String>,
+    pub breaker: Option<Breaker>,
+}
+
+impl SegmentHealth {
+    /// The most pressing problem, if any
+    ///
+    /// ```
+    /// use mycode::api::breaker::{Breaker, State};
+    /// use mycode::config::SegmentId;
+    /// use mycode::core::health::{SegmentHealth, Status};
+    /// use mycode::core::perf::SegmentTiming;
+    ///
+    /// let mut health = SegmentHealth {
+    ///     id: SegmentId::CubenceUsage,
+    ///     timing: Some(SegmentTiming { average_ms: 1800.0, samples: 4, ..Default::default() }),
+    ///     skipped: false,
+    ///     muted: false,
+    ///     cache_age: Some(30),
+    ///     endpoint: Some("cubence.com/api/v1/user/subscription-info".into()),
+    ///     breaker: None,
+    /// };
+    /// assert_eq!(health.status(500), Status::Slow);
+    /// assert_eq!(health.status(2000), Status::Ok);
+    ///
+    /// health.breaker = Some(Breaker { state: State::Open, failures: 3, ..Default::default() });
+    /// assert_eq!(health.status(2000), Status::CircuitOpen);
+    /// ```
+    pub fn status(&self, budget_ms: u64) -> Status {
+        if self.muted {
+            return Status::Muted;
+        }
+        match &self.breaker {
+            Some(b) if b.state != breaker::State::Closed => return Status::CircuitOpen,
+            Some(b) if b.failures > 0 => return Status::Failing,
+            _ => {}
+        }
+        match &self.timing {
+            None => Status::Unmeasured,
+            Some(_) if self.skipped => Status::Skipped,
+            Some(timing) if timing.is_over(budget_ms) => Status::Slow,
+            Some(_) => Status::Ok,
+        }
+    }
+
+    pub fn last_error(&self) -> Option<&str> {
+        self.breaker.as_ref()?.last_error.as_deref()
+    }
+}
+
+/// The current vendor when it is one described in `vendors.d`
+fn custom_vendor() -> Option<&'static dyn Vendor> {
+    vendor::current().filter(|v| v.vendor_type() == VendorType::Custom)
+}
+
+/// URL a vendor segment requests, honoring its `usage_url` /
+/// `subscription_url` option and `[oneapi] base_url`
+fn request_url(config: &Config, segment: &SegmentConfig) -> Option<String> {
+    let option = |key: &str| {
+        segment
+            .options
+            .get(key)
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+    let url = match segment.id {
+        SegmentId::UucodeUsage => option("usage_url").unwrap_or(UUCODE_USAGE_URL.to_string()),
+        SegmentId::UucodeSubscription => {
+            option("subscription_url").unwrap_or(UUCODE_SUBSCRIPTION_URL.to_string())
+        }
+        SegmentId::CubenceUsage
+        | SegmentId::CubenceFiveHour
+        | SegmentId::CubenceWeekly
+        | SegmentId::CubenceBalance => option("usage_url").unwrap_or(CUBENCE_USAGE_URL.to_string()),
+        #[cfg(feature = "cubence")]
+        SegmentId::CubenceLoadStatus => {
+            crate::core::segments::cubence_load_status::LOAD_STATUS_URL.to_string()
+        }
+        #[cfg(feature = "cubence")]
+        SegmentId::CubenceSubscription => {
+            crate::core::segments::cubence_subscription::SUBSCRIPTION_URL.to_string()
+        }
+        #[cfg(feature = "cubence")]
+        SegmentId::CubenceMultiplier => {
+            crate::core::segments::cubence_multiplier::OVERVIEW_URL.to_string()
+        }
+        SegmentId::OneApiUsage => Some(config.oneapi.base_url.as_str())
+            .filter(|url| !url.is_empty())
+            .map(crate::api::client::oneapi_usage_url)?,
+        SegmentId::VendorUsage => custom_vendor()?.usage_url()?.to_string(),
+        _ => return None,
+    };
+    Some(url)
+}
+
+fn cache_age(id: SegmentId) -> Option<u64> {
+    match id {
+        SegmentId::UucodeUsage => crate::api::cache::usage_cache_age(),
+        SegmentId::CubenceUsage
+        | SegmentId::CubenceFiveHour
+        | SegmentId::CubenceWeekly
+        | SegmentId::CubenceBalance => crate::api::cache::cubence_usage_cache_age(),
+        SegmentId::OneApiUsage => crate::api::cache::oneapi_usage_cache_age(),
+        SegmentId::VendorUsage => crate::api::cache::custom_usage_cache_age(custom_vendor()?.id()),
+        _ => None,
+    }
+}
+
+/// Health of every enabled segment, in config order
+pub fn collect(config: &Config) -> Vec<SegmentHealth> {
+    let perf = PerfStats::load();
+    let breakers = breaker::load();
+    let mutes = Mutes::load();
+
+    config
+        .segments
+        .iter()
+        .filter(|segment| segment.enabled)
+        .map(|segment| {
+            let endpoint = request_url(config, segment).map(|url| breaker::endpoint(&url));
+            SegmentHealth {
+                id: segment.id,
+                timing: perf.segments.get(&segment.id).cloned(),
+                skipped: perf.should_skip(segment.id, &config.perf),
+                muted: mutes.is_muted(segment.id),
+                cache_age: cache_age(segment.id),
+                breaker: endpoint.as_ref().and_then(|e| breakers.get(e).cloned()),
+                endpoint,
+            }
+        })
+        .collect()
+}
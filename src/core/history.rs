@@ -0,0 +1,142 @@
+//! On-disk history of statusline samples (`cache/history.jsonl`).
+//!
+//! Every render appends at most one sample per minute, so dashboards can
+//! show trends without keeping a process alive next to Claude Code.
+
+use crate::config::{InputData, SegmentConfig, SegmentId};
+use crate::core::segments::SegmentData;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Minimum spacing between two recorded samples
+const MIN_INTERVAL_SECS: u64 = 60;
+/// Samples kept after compaction (one day at one sample per minute)
+const MAX_SAMPLES: usize = 1440;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sample {
+    /// Unix timestamp in seconds
+    pub timestamp: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_percentage: Option<f64>,
+    /// Highest quota usage among the vendor's quota segments
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quota_percentage: Option<f64>,
+}
+
+impl Sample {
+    pub fn from_render(input: &InputData, segments: &[(SegmentConfig, SegmentData)]) -> Self {
+        Self {
+            timestamp: now(),
+            cost_usd: input.cost.as_ref().and_then(|c| c.total_cost_usd),
+            context_percentage: context_percentage(segments),
+            quota_percentage: quotas(segments)
+                .into_iter()
+                .map(|(_, percentage)| percentage)
+                .reduce(f64::max),
+        }
+    }
+}
+
+fn history_path() -> Option<PathBuf> {
+    crate::utils::paths::cache_dir().map(|dir| dir.join("history.jsonl"))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// All recorded samples, oldest first
+pub fn load() -> Vec<Sample> {
+    let Some(content) = history_path().and_then(|path| fs::read_to_string(path).ok()) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Append a sample unless the previous one is less than a minute old
+pub fn record(sample: &Sample) -> Result<(), Box<dyn std::error::Error>> {
+    let path = history_path().ok_or("Could not find home directory")?;
+
+    let samples = load();
+    if samples
+        .last()
+        .is_some_and(|last| sample.timestamp.saturating_sub(last.timestamp) < MIN_INTERVAL_SECS)
+    {
+        return Ok(());
+    }
+
+    // Rewrite occasionally instead of on every append to keep renders cheap
+    if samples.len() >= MAX_SAMPLES + MAX_SAMPLES / 4 {
+        let keep = &samples[samples.len() + 1 - MAX_SAMPLES..];
+        let mut content = String::new();
+        for s in keep.iter().chain(std::iter::once(sample)) {
+            content.push_str(&serde_json::to_string(s)?);
+            content.push('\n');
+        }
+        crate::utils::atomic_write(&path, content)?;
+        return Ok(());
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", serde_json::to_string(sample)?)?;
+    Ok(())
+}
+
+/// Usage percentage a segment reports, for the segments that have one
+pub fn segment_percentage(id: SegmentId, data: &SegmentData) -> Option<f64> {
+    let parse = |key: &str| data.metadata.get(key).and_then(|v| v.parse::<f64>().ok());
+    match id {
+        SegmentId::ContextWindow => parse("percentage"),
+        SegmentId::CubenceFiveHour => parse("five_hour_percentage"),
+        SegmentId::CubenceWeekly => parse("weekly_percentage"),
+        SegmentId::UucodeUsage => {
+            let total = parse("total").filter(|t| *t > 0.0)?;
+            Some(parse("used")? / total * 100.0)
+        }
+        _ => None,
+    }
+}
+
+/// Quota usage per vendor quota segment as `(label, percentage)`
+pub fn quotas(segments: &[(SegmentConfig, SegmentData)]) -> Vec<(String, f64)> {
+    segments
+        .iter()
+        .filter_map(|(config, data)| {
+            let label = quota_label(config.id)?;
+            Some((label.to_string(), segment_percentage(config.id, data)?))
+        })
+        .collect()
+}
+
+/// Label of a vendor quota segment, `None` for other segments
+pub fn quota_label(id: SegmentId) -> Option<&'static str> {
+    match id {
+        SegmentId::CubenceFiveHour => Some("Cubence 5h"),
+        SegmentId::CubenceWeekly => Some("Cubence weekly"),
+        SegmentId::UucodeUsage => Some("uucode"),
+        _ => None,
+    }
+}
+
+/// Context window usage reported by the context window segment
+pub fn context_percentage(segments: &[(SegmentConfig, SegmentData)]) -> Option<f64> {
+    segments
+        .iter()
+        .find(|(config, _)| config.id == SegmentId::ContextWindow)
+        .and_then(|(config, data)| segment_percentage(config.id, data))
+}
@@ -0,0 +1,279 @@
+//! Icon sets: segment icons and the glyphs segments embed in their text.
+//!
+//! The set comes from `style.icon_set`, overridden per segment by an
+//! `icon_set` option. Segment icons come from each segment's `icon` table
+//! (`plain` for emoji, `nerd_font` for Nerd Fonts) or an `ascii_icon`
+//! option, unless one of its `icon_rules` matches the collected metadata;
+//! inline glyphs such as 💳 are looked up here so that they follow the same
+//! set, and `style.icons` can replace any of them by name.
+
+use crate::config::{Config, IconSet, SegmentConfig, SegmentId};
+use std::collections::HashMap;
+
+/// Glyphs segments put inside their text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Glyph {
+    /// Quota used / total
+    Card,
+    /// Subscription plan
+    Package,
+    /// Pay-as-you-go balance
+    Money,
+    /// Pool load
+    Bike,
+    /// Pool overloaded
+    Boom,
+    Unknown,
+    /// Subscription time left
+    Gem,
+    /// Load is fine
+    Relaxed,
+    /// Load is high
+    Strained,
+    /// Load is critical
+    Overheated,
+    /// 5-hour window
+    Timer,
+    /// Weekly window
+    Calendar,
+}
+
+impl Glyph {
+    pub const ALL: [Glyph; 12] = [
+        Glyph::Card,
+        Glyph::Package,
+        Glyph::Money,
+        Glyph::Bike,
+        Glyph::Boom,
+        Glyph::Unknown,
+        Glyph::Gem,
+        Glyph::Relaxed,
+        Glyph::Strained,
+        Glyph::Overheated,
+        Glyph::Timer,
+        Glyph::Calendar,
+    ];
+
+    /// Name used in `style.icons`
+    pub fn key(self) -> &'static str {
+        match self {
+            Glyph::Card => "card",
+            Glyph::Package => "package",
+            Glyph::Money => "money",
+            Glyph::Bike => "bike",
+            Glyph::Boom => "boom",
+            Glyph::Unknown => "unknown",
+            Glyph::Gem => "gem",
+            Glyph::Relaxed => "relaxed",
+            Glyph::Strained => "strained",
+            Glyph::Overheated => "overheated",
+            Glyph::Timer => "timer",
+            Glyph::Calendar => "calendar",
+        }
+    }
+
+    fn emoji(self) -> &'static str {
+        match self {
+            Glyph::Card => "💳",
+            Glyph::Package => "📦",
+            Glyph::Money => "💰",
+            Glyph::Bike => "🚴",
+            Glyph::Boom => "💥",
+            Glyph::Unknown => "❓",
+            Glyph::Gem => "💎",
+            Glyph::Relaxed => "😎",
+            Glyph::Strained => "😰",
+            Glyph::Overheated => "🥵",
+            Glyph::Timer => "⏱",
+            Glyph::Calendar => "📅",
+        }
+    }
+
+    fn nerd(self) -> &'static str {
+        match self {
+            Glyph::Card => "\u{f09d}",
+            Glyph::Package => "\u{f487}",
+            Glyph::Money => "\u{f0d6}",
+            Glyph::Bike => "\u{f206}",
+            Glyph::Boom => "\u{f1e2}",
+            Glyph::Unknown => "\u{f128}",
+            Glyph::Gem => "\u{f219}",
+            Glyph::Relaxed => "\u{f118}",
+            Glyph::Strained => "\u{f11a}",
+            Glyph::Overheated => "\u{f119}",
+            Glyph::Timer => "\u{f017}",
+            Glyph::Calendar => "\u{f073}",
+        }
+    }
+
+    /// Decorative glyphs are dropped; the rest keep a short hint
+    fn ascii(self) -> &'static str {
+        match self {
+            Glyph::Boom => "!",
+            Glyph::Unknown => "?",
+            Glyph::Relaxed => ":)",
+            Glyph::Strained => ":|",
+            Glyph::Overheated => ":(",
+            Glyph::Timer => "5h:",
+            Glyph::Calendar => "7d:",
+            Glyph::Card | Glyph::Package | Glyph::Money | Glyph::Bike | Glyph::Gem => "",
+        }
+    }
+
+    fn in_set(self, set: IconSet) -> &'static str {
+        match set {
+            IconSet::Emoji | IconSet::Auto => self.emoji(),
+            IconSet::NerdV3 => self.nerd(),
+            IconSet::Ascii => self.ascii(),
+            IconSet::None => "",
+        }
+    }
+}
+
+/// Fallback icons for the ascii set
+pub fn ascii_icon(id: SegmentId) -> &'static str {
+    match id {
+        SegmentId::Model => "ai",
+        SegmentId::Directory => "dir",
+        SegmentId::Git => "git",
+        SegmentId::ContextWindow => "ctx",
+        SegmentId::ContextAdvisor => "hint",
+        SegmentId::ApiErrors => "err",
+        SegmentId::Summary => "",
+        SegmentId::QuotaPacing => "pace",
+        SegmentId::Usage | SegmentId::CubenceUsage => "use",
+        SegmentId::Cost | SegmentId::DailyCost | SegmentId::CubenceBalance => "$",
+        SegmentId::BlockUsage => "5h",
+        SegmentId::Session => "time",
+        SegmentId::OutputStyle => "style",
+        SegmentId::Runtime => "env",
+        SegmentId::Update => "upd",
+        SegmentId::UucodeUsage | SegmentId::OneApiUsage | SegmentId::VendorUsage => "quota",
+        SegmentId::UucodeSubscription | SegmentId::CubenceSubscription => "sub",
+        SegmentId::UucodeStatus => "uu",
+        SegmentId::CubenceStatus => "cb",
+        SegmentId::CubenceFiveHour => "5h",
+        SegmentId::CubenceWeekly => "7d",
+        SegmentId::CubenceLoadStatus => "load",
+        SegmentId::CubenceLatency => "ms",
+        SegmentId::CubenceMultiplier => "x",
+        SegmentId::Custom => ">",
+    }
+}
+
+/// Set in effect for `segment`, with `Auto` resolved
+pub fn segment_set(config: &Config, segment: &SegmentConfig) -> IconSet {
+    let set = segment
+        .options
+        .get("icon_set")
+        .and_then(|v| serde_json::from_value::<IconSet>(v.clone()).ok())
+        .unwrap_or(config.style.icon_set);
+    effective(config, set)
+}
+
+/// The accessibility profile swaps emoji and Nerd Font glyphs for text
+/// labels; a set of `none` stays empty
+fn effective(config: &Config, set: IconSet) -> IconSet {
+    match set.resolve(config.style.mode) {
+        IconSet::None => IconSet::None,
+        _ if config.accessibility.strips_emoji() => IconSet::Ascii,
+        other => other,
+    }
+}
+
+/// Icon shown in front of `segment`
+pub fn segment_icon(config: &Config, segment: &SegmentConfig) -> String {
+    match segment_set(config, segment) {
+        IconSet::Emoji | IconSet::Auto => segment.icon.plain.clone(),
+        IconSet::NerdV3 => segment.icon.nerd_font.clone(),
+        IconSet::Ascii => segment
+            .options
+            .get("ascii_icon")
+            .and_then(|v| v.as_str())
+            .unwrap_or_else(|| ascii_icon(segment.id))
+            .to_string(),
+        IconSet::None => String::new(),
+    }
+}
+
+/// Icon from the first of the segment's `icon_rules` matching `metadata`
+pub fn rule_icon(
+    config: &Config,
+    segment: &SegmentConfig,
+    metadata: &HashMap<String, String>,
+) -> Option<String> {
+    let rule = segment
+        .icon_rules
+        .iter()
+        .find(|rule| rule.matches(metadata))?;
+    Some(match segment_set(config, segment) {
+        IconSet::Emoji | IconSet::Auto => rule.icon.plain.clone(),
+        IconSet::NerdV3 => rule.icon.nerd_font.clone(),
+        IconSet::Ascii => rule
+            .ascii
+            .clone()
+            .unwrap_or_else(|| segment_icon(config, segment)),
+        IconSet::None => String::new(),
+    })
+}
+
+/// Icon computed by a segment at collection time (`dynamic_icon`
+/// metadata). Nerd Font glyphs are only kept when the Nerd Font set is in
+/// use; otherwise the configured icon is shown instead.
+pub fn dynamic_icon(config: &Config, segment: &SegmentConfig, dynamic: &str) -> String {
+    match segment_set(config, segment) {
+        IconSet::None => String::new(),
+        IconSet::NerdV3 => dynamic.to_string(),
+        _ if dynamic.chars().any(is_private_use) => segment_icon(config, segment),
+        _ => dynamic.to_string(),
+    }
+}
+
+/// Icon rendered in front of `segment`: a matching icon rule, else the
+/// icon the segment computed, else the configured one
+pub fn resolve(
+    config: &Config,
+    segment: &SegmentConfig,
+    metadata: &HashMap<String, String>,
+) -> String {
+    match (
+        rule_icon(config, segment, metadata),
+        metadata.get("dynamic_icon"),
+    ) {
+        (Some(icon), _) => icon,
+        (None, Some(dynamic)) => dynamic_icon(config, segment, dynamic),
+        (None, None) => segment_icon(config, segment),
+    }
+}
+
+/// `glyph` as rendered for the segment `id`
+pub fn glyph(config: &Config, id: SegmentId, glyph: Glyph) -> String {
+    let set = config
+        .segments
+        .iter()
+        .find(|s| s.id == id)
+        .map(|segment| segment_set(config, segment))
+        .unwrap_or_else(|| effective(config, config.style.icon_set));
+    if set == IconSet::None {
+        return String::new();
+    }
+    match config.style.icons.get(glyph.key()) {
+        Some(custom) => custom.clone(),
+        None => glyph.in_set(set).to_string(),
+    }
+}
+
+/// `glyph` followed by a space, or nothing when the set drops it
+pub fn prefix(config: &Config, id: SegmentId, g: Glyph) -> String {
+    let text = glyph(config, id, g);
+    if text.is_empty() {
+        text
+    } else {
+        text + " "
+    }
+}
+
+/// Nerd Font glyphs live in the Unicode private use areas
+fn is_private_use(c: char) -> bool {
+    matches!(c, '\u{e000}'..='\u{f8ff}' | '\u{f0000}'..='\u{ffffd}')
+}
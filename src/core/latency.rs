@@ -0,0 +1,151 @@
+//! Latency samples of vendor endpoints (`cache/latency.jsonl`).
+//!
+//! The latency segment appends one sample per measurement, so
+//! `uucode stats latency` can show how a route behaved over the last days,
+//! hour by hour, when a relay provider asks for evidence.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Samples older than this are dropped on compaction
+const RETENTION_SECS: u64 = 7 * 24 * 3600;
+/// The file is compacted once it grows past this size
+const COMPACT_AT_BYTES: u64 = 2 * 1024 * 1024;
+/// Newest samples kept by a compaction
+const KEEP_SAMPLES: usize = 10_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencySample {
+    /// Unix timestamp in seconds
+    pub timestamp: u64,
+    /// Host of the measured endpoint, e.g. `api-cf.cubence.com`
+    pub endpoint: String,
+    /// `None` when the request failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl LatencySample {
+    pub fn new(endpoint: &str, result: &Result<u64, String>) -> Self {
+        Self {
+            timestamp: now(),
+            endpoint: endpoint.to_string(),
+            latency_ms: result.as_ref().ok().copied(),
+            error: result.as_ref().err().cloned(),
+        }
+    }
+}
+
+/// Summary of a set of samples
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatencyStats {
+    pub samples: usize,
+    pub failures: usize,
+    pub p50_ms: Option<u64>,
+    pub p95_ms: Option<u64>,
+    pub max_ms: Option<u64>,
+}
+
+impl LatencyStats {
+    pub fn from_samples<'a>(samples: impl IntoIterator<Item = &'a LatencySample>) -> Self {
+        let mut count = 0;
+        let mut latencies = Vec::new();
+        for sample in samples {
+            count += 1;
+            latencies.extend(sample.latency_ms);
+        }
+        latencies.sort_unstable();
+        Self {
+            samples: count,
+            failures: count - latencies.len(),
+            p50_ms: percentile(&latencies, 50.0),
+            p95_ms: percentile(&latencies, 95.0),
+            max_ms: latencies.last().copied(),
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted values
+///
+/// ```
+/// use mycode::core::latency::percentile;
+///
+/// let sorted = [100, 120, 130, 150, 900];
+/// assert_eq!(percentile(&sorted, 50.0), Some(130));
+/// assert_eq!(percentile(&sorted, 95.0), Some(900));
+/// assert_eq!(percentile(&[], 50.0), None);
+/// ```
+pub fn percentile(sorted: &[u64], p: f64) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.clamp(1, sorted.len()) - 1).copied()
+}
+
+fn latency_path() -> Option<PathBuf> {
+    crate::utils::paths::cache_dir().map(|dir| dir.join("latency.jsonl"))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// All recorded samples, oldest first
+pub fn load() -> Vec<LatencySample> {
+    let Some(content) = latency_path().and_then(|path| fs::read_to_string(path).ok()) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Samples of `endpoint` from the last `secs` seconds
+pub fn recent(endpoint: &str, secs: u64) -> Vec<LatencySample> {
+    let since = now().saturating_sub(secs);
+    load()
+        .into_iter()
+        .filter(|s| s.endpoint == endpoint && s.timestamp >= since)
+        .collect()
+}
+
+/// Append a sample, dropping expired ones now and then
+pub fn record(sample: &LatencySample) -> Result<(), Box<dyn std::error::Error>> {
+    let path = latency_path().ok_or("Could not find home directory")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    if size >= COMPACT_AT_BYTES {
+        let since = sample.timestamp.saturating_sub(RETENTION_SECS);
+        let kept: Vec<_> = load()
+            .into_iter()
+            .filter(|s| s.timestamp >= since)
+            .collect();
+        let mut content = String::new();
+        for s in kept[kept.len().saturating_sub(KEEP_SAMPLES)..]
+            .iter()
+            .chain(std::iter::once(sample))
+        {
+            content.push_str(&serde_json::to_string(s)?);
+            content.push('\n');
+        }
+        crate::utils::atomic_write(&path, content)?;
+        return Ok(());
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}", serde_json::to_string(sample)?)?;
+    Ok(())
+}
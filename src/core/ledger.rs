@@ -0,0 +1,292 @@
+//! Per-session receipts kept in `~/.claude/uucode/ledger.jsonl`.
+//!
+//! The wrapper appends one record when Claude Code exits. Figures come from
+//! the last statusline payload of the session and from its transcript.
+
+use crate::api;
+use crate::config::{Config, InputData, ModelConfig};
+use crate::core::segments::session::SessionSegment;
+use crate::core::{collect_all_segments, history, transcript};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Columns of [`to_csv`]
+const CSV_HEADER: &str = "started_at,ended_at,vendor,model,directory,duration_ms,cost_usd,\
+estimated_cost_usd,input_tokens,output_tokens,cache_creation_tokens,cache_read_tokens,\
+lines_added,lines_removed,files_touched";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaLeft {
+    pub label: String,
+    /// Percentage of the quota still available
+    pub remaining_percentage: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRecord {
+    /// Unix timestamps in seconds
+    pub started_at: u64,
+    pub ended_at: u64,
+    pub vendor: String,
+    pub model: String,
+    pub directory: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+    /// Cost of the transcript's tokens at list prices
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_cost_usd: Option<f64>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lines_added: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lines_removed: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files_touched: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub quota_left: Vec<QuotaLeft>,
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+pub fn ledger_path() -> Option<PathBuf> {
+    crate::utils::paths::uucode_dir().map(|dir| dir.join("ledger.jsonl"))
+}
+
+impl SessionRecord {
+    /// Build the record of a session that started at `started`.
+    ///
+    /// Returns `None` when the statusline did not render since then, as the
+    /// last payload would belong to an earlier session.
+    pub fn collect(started: SystemTime) -> Option<Self> {
+        let modified = api::cache::last_input_modified()?;
+        if modified < started {
+            return None;
+        }
+        let input: InputData = serde_json::from_str(&api::cache::load_last_input()?).ok()?;
+        Some(Self::from_input(&input, started))
+    }
+
+    /// Build the record of the session writing `transcript_path`, for the
+    /// `SessionEnd` hook. Returns `None` when the last payload belongs to
+    /// another session.
+    pub fn collect_for_transcript(transcript_path: &str) -> Option<Self> {
+        let input: InputData = serde_json::from_str(&api::cache::load_last_input()?).ok()?;
+        if input.transcript_path != transcript_path {
+            return None;
+        }
+        let started = transcript::first_timestamp(Path::new(transcript_path))?;
+        Some(Self::from_input(&input, started.into()))
+    }
+
+    fn from_input(input: &InputData, started: SystemTime) -> Self {
+        let totals = transcript::totals(Path::new(&input.transcript_path)).unwrap_or_default();
+
+        let mut config = Config::load().unwrap_or_else(|_| Config::default());
+        api::exchange::resolve_rate(&mut config.format);
+        let segments = collect_all_segments(&config, input);
+        let quota_left = history::quotas(&segments)
+            .into_iter()
+            .map(|(label, used)| QuotaLeft {
+                label,
+                remaining_percentage: (100.0 - used).max(0.0),
+            })
+            .collect();
+
+        let cost = input.cost.as_ref();
+        Self {
+            started_at: unix_secs(started),
+            ended_at: unix_secs(SystemTime::now()),
+            vendor: api::detect_vendor_from_claude_settings().id().to_string(),
+            model: input.model.display_name.clone(),
+            directory: input.workspace.current_dir.clone(),
+            duration_ms: cost.and_then(|c| c.total_duration_ms),
+            cost_usd: cost.and_then(|c| c.total_cost_usd),
+            estimated_cost_usd: totals.estimated_cost(&ModelConfig::load()),
+            input_tokens: totals.input_tokens,
+            output_tokens: totals.output_tokens,
+            cache_creation_tokens: totals.cache_creation_tokens,
+            cache_read_tokens: totals.cache_read_tokens,
+            lines_added: cost.and_then(|c| c.total_lines_added),
+            lines_removed: cost.and_then(|c| c.total_lines_removed),
+            files_touched: totals.files_touched.into_iter().collect(),
+            quota_left,
+        }
+    }
+
+    /// Append this record to the ledger
+    pub fn append(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if crate::utils::readonly::is_enabled() {
+            return Ok(());
+        }
+        let path = ledger_path().ok_or("Could not find home directory")?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// `YYYY-MM` of the local start time
+    pub fn month(&self) -> String {
+        local_time(self.started_at)
+            .map(|t| t.format("%Y-%m").to_string())
+            .unwrap_or_default()
+    }
+
+    /// POST the record and its receipt to `url` as JSON
+    pub fn post(&self, url: &str, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+        let client = crate::api::http::client(None, WEBHOOK_TIMEOUT)?;
+        let response = client
+            .post(url)
+            .json(&serde_json::json!({
+                "session": self,
+                "summary": self.summary(config),
+            }))
+            .send()?;
+        if !response.status().is_success() {
+            return Err(format!("HTTP {}", response.status()).into());
+        }
+        Ok(())
+    }
+
+    fn csv_row(&self) -> String {
+        let time = |secs: u64| {
+            local_time(secs)
+                .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Secs, false))
+                .unwrap_or_default()
+        };
+        let opt = |value: Option<String>| value.unwrap_or_default();
+        [
+            time(self.started_at),
+            time(self.ended_at),
+            csv_field(&self.vendor),
+            csv_field(&self.model),
+            csv_field(&self.directory),
+            opt(self.duration_ms.map(|v| v.to_string())),
+            opt(self.cost_usd.map(|v| format!("{:.4}", v))),
+            opt(self.estimated_cost_usd.map(|v| format!("{:.4}", v))),
+            self.input_tokens.to_string(),
+            self.output_tokens.to_string(),
+            self.cache_creation_tokens.to_string(),
+            self.cache_read_tokens.to_string(),
+            opt(self.lines_added.map(|v| v.to_string())),
+            opt(self.lines_removed.map(|v| v.to_string())),
+            self.files_touched.len().to_string(),
+        ]
+        .join(",")
+    }
+
+    /// Human readable receipt printed when the wrapper exits
+    pub fn summary(&self, config: &Config) -> String {
+        let fmt = &config.format;
+        let tokens = |n: u64| crate::utils::format::number(n as f64, 0, fmt);
+
+        let mut lines = vec![
+            "━━━━━━━━━━━━━━━━ 本次会话 ━━━━━━━━━━━━━━━━".to_string(),
+            format!("模型      {} · {}", self.model, self.directory),
+        ];
+        if let Some(duration) = self.duration_ms {
+            lines.push(format!(
+                "时长      {}",
+                SessionSegment::format_duration(duration, fmt.locale)
+            ));
+        }
+        lines.push(format!(
+            "Token     输入 {} · 输出 {} · 缓存写 {} · 缓存读 {}",
+            tokens(self.input_tokens),
+            tokens(self.output_tokens),
+            tokens(self.cache_creation_tokens),
+            tokens(self.cache_read_tokens)
+        ));
+        let money = |cost: f64| crate::utils::format::money(cost, 2, fmt);
+        match (self.cost_usd, self.estimated_cost_usd) {
+            (Some(cost), Some(estimated)) => lines.push(format!(
+                "费用      {} (估算 {})",
+                money(cost),
+                money(estimated)
+            )),
+            (Some(cost), None) => lines.push(format!("费用      {}", money(cost))),
+            (None, Some(estimated)) => lines.push(format!("费用      估算 {}", money(estimated))),
+            (None, None) => {}
+        }
+        if self.lines_added.is_some() || self.lines_removed.is_some() {
+            lines.push(format!(
+                "代码行    +{} -{}",
+                self.lines_added.unwrap_or(0),
+                self.lines_removed.unwrap_or(0)
+            ));
+        }
+        if !self.files_touched.is_empty() {
+            lines.push(format!("修改文件  {} 个", self.files_touched.len()));
+        }
+        if !self.quota_left.is_empty() {
+            let quotas: Vec<String> = self
+                .quota_left
+                .iter()
+                .map(|q| format!("{} 剩余 {:.0}%", q.label, q.remaining_percentage))
+                .collect();
+            lines.push(format!("额度      {}", quotas.join(" · ")));
+        }
+        lines.push("━".repeat(42));
+        lines.join("\n")
+    }
+}
+
+fn local_time(secs: u64) -> Option<chrono::DateTime<chrono::Local>> {
+    use chrono::TimeZone;
+    chrono::Local.timestamp_opt(secs as i64, 0).single()
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break
+///
+/// ```
+/// use mycode::core::ledger::csv_field;
+///
+/// assert_eq!(csv_field("Opus 4"), "Opus 4");
+/// assert_eq!(csv_field("/work/a,b"), "\"/work/a,b\"");
+/// assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+/// ```
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Records as CSV with a header line, times in local RFC 3339
+pub fn to_csv(records: &[SessionRecord]) -> String {
+    let mut out = String::from(CSV_HEADER);
+    out.push('\n');
+    for record in records {
+        out.push_str(&record.csv_row());
+        out.push('\n');
+    }
+    out
+}
+
+/// All recorded sessions, oldest first
+pub fn load() -> Vec<SessionRecord> {
+    let Some(content) = ledger_path().and_then(|path| fs::read_to_string(path).ok()) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
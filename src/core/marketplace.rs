@@ -0,0 +1,202 @@
+//! Community segment and theme index used by `uucode segment`.
+//!
+//! The index is a JSON document served next to a detached ed25519 signature
+//! (`<index_url>.sig`, base64). Every entry points at a single file and
+//! carries its SHA-256; nothing is written to disk unless both check out.
+//! With `minisign_key` configured, the index and every entry are checked
+//! against minisign signatures (`<url>.minisig`) instead; see
+//! [`crate::utils::download`].
+
+use crate::config::{Config, ConfigLoader, MarketplaceConfig};
+use crate::utils::download::{self, Integrity};
+use base64::Engine;
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Largest index or entry file we are willing to download
+const MAX_DOWNLOAD_BYTES: u64 = 4 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketIndex {
+    #[serde(default)]
+    pub version: u32,
+    pub entries: Vec<MarketEntry>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    /// Script rendering a segment, installed into the plugins directory
+    Segment,
+    /// Theme TOML, installed into the themes directory
+    Theme,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketEntry {
+    pub name: String,
+    pub kind: EntryKind,
+    pub description: String,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    pub url: String,
+    /// Hex SHA-256 of the file at `url`
+    pub sha256: String,
+}
+
+impl MarketEntry {
+    /// Where the entry lives once installed
+    pub fn install_path(&self) -> Option<PathBuf> {
+        if !is_safe_name(&self.name) {
+            return None;
+        }
+        match self.kind {
+            EntryKind::Theme => {
+                Some(ConfigLoader::get_themes_path().join(format!("{}.toml", self.name)))
+            }
+            EntryKind::Segment => {
+                let extension = self
+                    .url
+                    .rsplit('/')
+                    .next()
+                    .and_then(|file| file.rsplit_once('.'))
+                    .map(|(_, ext)| ext)
+                    .filter(|ext| is_safe_name(ext));
+                let file = match extension {
+                    Some(ext) => format!("{}.{}", self.name, ext),
+                    None => self.name.clone(),
+                };
+                crate::utils::paths::plugins_dir().map(|dir| dir.join(file))
+            }
+        }
+    }
+
+    pub fn is_installed(&self) -> bool {
+        self.install_path().is_some_and(|path| path.exists())
+    }
+}
+
+/// Names become file names, so keep them to a conservative alphabet
+fn is_safe_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Check a base64 ed25519 signature over `body` against a base64 public key
+///
+/// ```
+/// use base64::Engine;
+/// use ed25519_dalek::{Signer, SigningKey};
+/// use mycode::core::marketplace::verify_signature;
+///
+/// let b64 = base64::engine::general_purpose::STANDARD;
+/// let key = SigningKey::from_bytes(&[7; 32]);
+/// let public = b64.encode(key.verifying_key().as_bytes());
+/// let signature = b64.encode(key.sign(b"{\"entries\":[]}").to_bytes());
+///
+/// assert!(verify_signature(b"{\"entries\":[]}", &signature, &public).is_ok());
+/// assert!(verify_signature(b"{\"entries\":[1]}", &signature, &public).is_err());
+/// ```
+pub fn verify_signature(
+    body: &[u8],
+    signature: &str,
+    public_key: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let engine = base64::engine::general_purpose::STANDARD;
+    let key: [u8; 32] = engine
+        .decode(public_key.trim())?
+        .try_into()
+        .map_err(|_| "public key must be 32 bytes")?;
+    let key = VerifyingKey::from_bytes(&key)?;
+    let signature = Signature::from_slice(&engine.decode(signature.trim())?)?;
+    key.verify_strict(body, &signature)
+        .map_err(|_| "index signature does not match the configured public key".into())
+}
+
+/// Download the index and verify its signature: the minisign signature
+/// (`<index_url>.minisig`) when `minisign_key` is configured, the ed25519
+/// one otherwise. With `insecure` neither is checked, which is only meant
+/// for testing private indexes.
+pub fn fetch_index(
+    config: &MarketplaceConfig,
+    insecure: bool,
+) -> Result<MarketIndex, Box<dyn std::error::Error>> {
+    let timeout = download::DEFAULT_TIMEOUT;
+    if insecure {
+        let body = download::fetch(&config.index_url, MAX_DOWNLOAD_BYTES, timeout)?;
+        return Ok(serde_json::from_slice(&body)?);
+    }
+
+    let body = match config.minisign_key.as_deref() {
+        Some(minisign_key) => download::fetch_verified(
+            &config.index_url,
+            MAX_DOWNLOAD_BYTES,
+            timeout,
+            &Integrity {
+                sha256: None,
+                minisign_key: Some(minisign_key),
+            },
+        )?,
+        None => {
+            let public_key = config.public_key.as_deref().ok_or(
+                "no marketplace.public_key or marketplace.minisign_key configured; set one in config.toml",
+            )?;
+            let body = download::fetch(&config.index_url, MAX_DOWNLOAD_BYTES, timeout)?;
+            let signature = download::fetch(
+                &format!("{}.sig", config.index_url),
+                MAX_DOWNLOAD_BYTES,
+                timeout,
+            )?;
+            verify_signature(&body, &String::from_utf8_lossy(&signature), public_key)?;
+            body
+        }
+    };
+
+    Ok(serde_json::from_slice(&body)?)
+}
+
+/// Download an entry, check its SHA-256 (and its minisign signature when
+/// `minisign_key` is configured) and write it to its install path
+pub fn install(
+    entry: &MarketEntry,
+    config: &MarketplaceConfig,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    crate::utils::readonly::ensure_writable("installing")?;
+    let path = entry
+        .install_path()
+        .ok_or_else(|| format!("invalid entry name: {}", entry.name))?;
+    let body = download::fetch_verified(
+        &entry.url,
+        MAX_DOWNLOAD_BYTES,
+        download::DEFAULT_TIMEOUT,
+        &Integrity {
+            sha256: Some(&entry.sha256),
+            minisign_key: config.minisign_key.as_deref(),
+        },
+    )
+    .map_err(|e| format!("{}: {}", entry.name, e))?;
+
+    if entry.kind == EntryKind::Theme {
+        let text = std::str::from_utf8(&body)?;
+        toml::from_str::<Config>(text).map_err(|e| format!("not a valid theme: {}", e))?;
+    }
+
+    let dir = path.parent().ok_or("invalid install path")?;
+    fs::create_dir_all(dir)?;
+    crate::utils::atomic_write(&path, &body)?;
+
+    #[cfg(unix)]
+    if entry.kind == EntryKind::Segment {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+    }
+
+    Ok(path)
+}
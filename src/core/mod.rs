@@ -1,4 +1,28 @@
+pub mod alerts;
+pub mod context_trend;
+pub mod focus;
+pub mod forecast;
+pub mod git_delta;
+pub mod health;
+pub mod history;
+pub mod icons;
+pub mod latency;
+pub mod ledger;
+pub mod marketplace;
+pub mod mute;
+pub mod output;
+pub mod palette;
+pub mod peak;
+pub mod perf;
+pub mod profiles;
+pub mod render_log;
+pub mod runtime;
+pub mod safe_mode;
 pub mod segments;
 pub mod statusline;
+pub mod transcript;
+pub mod turn_delta;
+pub mod vendor;
+pub mod watch;
 
 pub use statusline::{collect_all_segments, StatusLineGenerator};
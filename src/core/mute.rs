@@ -0,0 +1,130 @@
+//! Muted segments (`cache/mutes.json`).
+//!
+//! `uucode mute <segment>` keeps a segment out of collection, either until
+//! `uucode unmute` or for a limited time with `--for 2h`. Expired mutes are
+//! ignored on read and dropped the next time the file is written.
+
+use crate::config::SegmentId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Mute {
+    /// Unix timestamp the mute ends at, `None` until unmuted
+    pub until: Option<i64>,
+}
+
+impl Mute {
+    pub fn is_active(&self, now: i64) -> bool {
+        self.until.is_none_or(|until| now < until)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Mutes {
+    pub segments: HashMap<SegmentId, Mute>,
+}
+
+fn mutes_path() -> Option<PathBuf> {
+    crate::utils::paths::cache_dir().map(|dir| dir.join("mutes.json"))
+}
+
+impl Mutes {
+    pub fn load() -> Self {
+        mutes_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let now = crate::utils::clock::unix_now();
+        self.segments.retain(|_, mute| mute.is_active(now));
+        let path = mutes_path().ok_or("Could not find home directory")?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        crate::utils::atomic_write(path, serde_json::to_string_pretty(&self.segments)?)?;
+        Ok(())
+    }
+
+    /// Whether `id` is muted right now
+    pub fn is_muted(&self, id: SegmentId) -> bool {
+        // A deterministic render must not depend on earlier commands
+        if crate::utils::clock::is_deterministic() {
+            return false;
+        }
+        let now = crate::utils::clock::unix_now();
+        self.segments
+            .get(&id)
+            .is_some_and(|mute| mute.is_active(now))
+    }
+
+    /// Mutes in effect right now, soonest to expire first
+    pub fn active(&self) -> Vec<(SegmentId, &Mute)> {
+        let now = crate::utils::clock::unix_now();
+        let mut active: Vec<_> = self
+            .segments
+            .iter()
+            .filter(|(_, mute)| mute.is_active(now))
+            .map(|(id, mute)| (*id, mute))
+            .collect();
+        active.sort_by_key(|(_, mute)| mute.until.unwrap_or(i64::MAX));
+        active
+    }
+}
+
+/// Mute `id` for `secs` seconds, or until unmuted with `None`
+pub fn mute(id: SegmentId, secs: Option<i64>) -> Result<Mute, Box<dyn std::error::Error>> {
+    let mut mutes = Mutes::load();
+    let mute = Mute {
+        until: secs.map(|secs| crate::utils::clock::unix_now() + secs),
+    };
+    mutes.segments.insert(id, mute.clone());
+    mutes.save()?;
+    Ok(mute)
+}
+
+/// Lift the mute of `id`; returns whether it was muted
+pub fn unmute(id: SegmentId) -> Result<bool, Box<dyn std::error::Error>> {
+    let mut mutes = Mutes::load();
+    let removed = mutes.segments.remove(&id).is_some();
+    if removed {
+        mutes.save()?;
+    }
+    Ok(removed)
+}
+
+/// Parse a duration such as `90s`, `30m`, `2h`, `1d` or `1h30m` into seconds
+///
+/// ```
+/// use mycode::core::mute::parse_duration;
+///
+/// assert_eq!(parse_duration("2h"), Some(7200));
+/// assert_eq!(parse_duration("1h30m"), Some(5400));
+/// assert_eq!(parse_duration("90"), None);
+/// assert_eq!(parse_duration("2 hours"), None);
+/// ```
+pub fn parse_duration(text: &str) -> Option<i64> {
+    let mut total = 0i64;
+    let mut digits = String::new();
+    for ch in text.trim().chars() {
+        if ch.is_ascii_digit() {
+            digits.push(ch);
+            continue;
+        }
+        let unit = match ch {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86400,
+            _ => return None,
+        };
+        total += digits.parse::<i64>().ok()? * unit;
+        digits.clear();
+    }
+    (digits.is_empty() && total > 0).then_some(total)
+}
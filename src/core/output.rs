@@ -0,0 +1,164 @@
+//! Alternative renderings of a statusline for consumers other than the
+//! terminal (`--output <format>` and `[output_file]`).
+//!
+//! Every format is a single line. Text never contains escape sequences.
+//! The JSON formats are a stable contract: fields are only ever added.
+//!
+//! - `plain`: the statusline text.
+//! - `lualine`: the statusline text with `%` doubled, ready for a Vim
+//!   `statusline` or a lualine component.
+//! - `json`: `{"text", "ansi", "segments": [{"id", "primary", "secondary",
+//!   "metadata"}]}`, where `ansi` is the colored terminal rendering.
+//! - `waybar`: `{"text", "tooltip", "class": ["uucode", <level>],
+//!   "percentage"?}` built around `[waybar] primary_segment`.
+//! - `vscode`: `{"text", "tooltip", "severity": <level>, "percentage"?}`
+//!   with the same primary segment as `waybar`.
+//!
+//! `<level>` is `normal`, `warning` (70%+) or `critical` (90%+) and
+//! `percentage` is an integer 0-100, omitted when no usage is known.
+
+use crate::config::{OutputFileConfig, OutputFormat, SegmentConfig, WaybarConfig};
+use crate::core::history;
+use crate::core::segments::SegmentData;
+use crate::utils::ansi::strip;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::PathBuf;
+
+/// Usage level above which bars should highlight the module
+const WARNING_PERCENTAGE: f64 = 70.0;
+const CRITICAL_PERCENTAGE: f64 = 90.0;
+
+/// Enabled segments as JSON objects with escape sequences removed
+pub fn segments_json(segments: &[(SegmentConfig, SegmentData)]) -> Vec<Value> {
+    segments
+        .iter()
+        .filter(|(config, _)| config.enabled)
+        .map(|(config, data)| {
+            json!({
+                "id": config.id,
+                "primary": strip(&data.primary),
+                "secondary": strip(&data.secondary),
+                "metadata": data.metadata,
+            })
+        })
+        .collect()
+}
+
+/// Highest context or quota usage among the segments
+pub fn highest_usage(segments: &[(SegmentConfig, SegmentData)]) -> Option<f64> {
+    history::quotas(segments)
+        .into_iter()
+        .map(|(_, percentage)| percentage)
+        .chain(history::context_percentage(segments))
+        .reduce(f64::max)
+}
+
+/// `normal`, `warning` or `critical` for a usage percentage
+pub fn usage_level(percentage: f64) -> &'static str {
+    if percentage >= CRITICAL_PERCENTAGE {
+        "critical"
+    } else if percentage >= WARNING_PERCENTAGE {
+        "warning"
+    } else {
+        "normal"
+    }
+}
+
+/// Render `statusline` and the segments it was built from in `format`
+pub fn render(
+    format: OutputFormat,
+    waybar: &WaybarConfig,
+    statusline: &str,
+    segments: &[(SegmentConfig, SegmentData)],
+) -> String {
+    match format {
+        OutputFormat::Plain => strip(statusline),
+        OutputFormat::Json => json!({
+            "text": strip(statusline),
+            "ansi": statusline,
+            "segments": segments_json(segments),
+        })
+        .to_string(),
+        OutputFormat::Waybar => waybar_json(waybar, statusline, segments).to_string(),
+        OutputFormat::Lualine => strip(statusline).replace('%', "%%"),
+        OutputFormat::Vscode => {
+            let mut output = waybar_json(waybar, statusline, segments);
+            output["severity"] = output["class"][1].take();
+            if let Some(map) = output.as_object_mut() {
+                map.remove("class");
+            }
+            output.to_string()
+        }
+    }
+}
+
+/// Waybar custom module output built around the configured primary segment.
+/// Falls back to the whole line and the highest usage when that segment is
+/// not shown.
+fn waybar_json(
+    waybar: &WaybarConfig,
+    statusline: &str,
+    segments: &[(SegmentConfig, SegmentData)],
+) -> Value {
+    let primary = segments
+        .iter()
+        .find(|(config, _)| config.enabled && config.id == waybar.primary_segment);
+
+    let (text, percentage) = match primary {
+        Some((config, data)) => (
+            strip(&data.primary),
+            history::segment_percentage(config.id, data),
+        ),
+        None => (strip(statusline), highest_usage(segments)),
+    };
+
+    let mut tooltip = vec![strip(statusline)];
+    tooltip.extend(
+        segments
+            .iter()
+            .filter(|(config, _)| config.enabled)
+            .map(|(_, data)| {
+                strip(&format!("{} {}", data.primary, data.secondary))
+                    .trim()
+                    .to_string()
+            }),
+    );
+
+    let mut output = json!({
+        "text": text,
+        "tooltip": tooltip.join("\n"),
+        "class": ["uucode", usage_level(percentage.unwrap_or(0.0))],
+    });
+    if let Some(percentage) = percentage {
+        output["percentage"] = json!(percentage.round().clamp(0.0, 100.0) as u8);
+    }
+    output
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .map(|home| home.join(rest))
+            .unwrap_or_else(|| PathBuf::from(path)),
+        None => PathBuf::from(path),
+    }
+}
+
+/// Write the render to the configured output file
+pub fn write_file(
+    config: &OutputFileConfig,
+    waybar: &WaybarConfig,
+    statusline: &str,
+    segments: &[(SegmentConfig, SegmentData)],
+) -> Result<(), Box<dyn std::error::Error>> {
+    crate::utils::readonly::ensure_writable("writing the output file")?;
+    let path = expand_home(&config.path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut content = render(config.format, waybar, statusline, segments);
+    content.push('\n');
+    crate::utils::atomic_write(path, content)?;
+    Ok(())
+}
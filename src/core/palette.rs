@@ -0,0 +1,118 @@
+//! Semantic colors.
+//!
+//! Segments ask for a [`Role`] instead of hardcoding escapes, so switching
+//! themes (`[style.roles]`) or a color-blind `style.palette` changes the
+//! colors inside their text too. Segments also put `level` (`ok`, `warning`
+//! or `critical`) in their metadata; the statusline colors warning and
+//! critical text with the matching role.
+
+use crate::config::{AnsiColor, Palette, StyleConfig};
+use crate::core::output;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Ok,
+    Warn,
+    Critical,
+    Accent,
+    Muted,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Ok,
+    Warning,
+    Critical,
+}
+
+impl Level {
+    /// Level of a usage percentage, with the thresholds of `--output`
+    pub fn from_percentage(percentage: f64) -> Self {
+        match output::usage_level(percentage) {
+            "critical" => Level::Critical,
+            "warning" => Level::Warning,
+            _ => Level::Ok,
+        }
+    }
+
+    /// Level a segment reported in its `level` metadata
+    pub fn from_metadata(metadata: &HashMap<String, String>) -> Option<Self> {
+        Self::from_key(metadata.get("level")?)
+    }
+
+    /// Inverse of [`Level::key`]
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "ok" => Some(Level::Ok),
+            "warning" => Some(Level::Warning),
+            "critical" => Some(Level::Critical),
+            _ => None,
+        }
+    }
+
+    /// Value stored in the `level` metadata
+    pub fn key(self) -> &'static str {
+        match self {
+            Level::Ok => "ok",
+            Level::Warning => "warning",
+            Level::Critical => "critical",
+        }
+    }
+
+    pub fn role(self) -> Role {
+        match self {
+            Level::Ok => Role::Ok,
+            Level::Warning => Role::Warn,
+            Level::Critical => Role::Critical,
+        }
+    }
+}
+
+pub fn color(style: &StyleConfig, role: Role) -> AnsiColor {
+    if let Some(color) = palette_color(style.palette, role) {
+        return color;
+    }
+    let roles = &style.roles;
+    let (theme, fallback) = match role {
+        Role::Ok => (&roles.ok, AnsiColor::Color16 { c16: 2 }),
+        Role::Warn => (&roles.warn, AnsiColor::Color16 { c16: 3 }),
+        Role::Critical => (&roles.critical, AnsiColor::Color16 { c16: 1 }),
+        Role::Accent => (&roles.accent, AnsiColor::Color256 { c256: 220 }),
+        Role::Muted => (&roles.muted, AnsiColor::Color16 { c16: 7 }),
+    };
+    theme.clone().unwrap_or(fallback)
+}
+
+/// State colors of the color-blind palettes; `None` leaves the role to
+/// the theme
+fn palette_color(palette: Palette, role: Role) -> Option<AnsiColor> {
+    let (r, g, b) = match (palette, role) {
+        (Palette::Default, _) | (_, Role::Accent | Role::Muted) => return None,
+        (Palette::Deuteranopia, Role::Ok) => (0, 114, 178),
+        (Palette::Deuteranopia, Role::Warn) => (240, 228, 66),
+        (Palette::Deuteranopia, Role::Critical) => (213, 94, 0),
+        (Palette::Protanopia, Role::Ok) => (86, 180, 233),
+        (Palette::Protanopia, Role::Warn) => (240, 228, 66),
+        (Palette::Protanopia, Role::Critical) => (230, 159, 0),
+        (Palette::Tritanopia, Role::Ok) => (0, 158, 115),
+        (Palette::Tritanopia, Role::Warn) => (204, 121, 167),
+        (Palette::Tritanopia, Role::Critical) => (213, 0, 0),
+    };
+    Some(AnsiColor::Rgb { r, g, b })
+}
+
+/// Foreground escape for `role`, to be closed with `\x1b[0m`
+pub fn escape(style: &StyleConfig, role: Role) -> String {
+    match color(style, role) {
+        AnsiColor::Color16 { c16 } if c16 < 8 => format!("\x1b[{}m", 30 + c16),
+        AnsiColor::Color16 { c16 } => format!("\x1b[{}m", 90 + (c16 - 8)),
+        AnsiColor::Color256 { c256 } => format!("\x1b[38;5;{}m", c256),
+        AnsiColor::Rgb { r, g, b } => format!("\x1b[38;2;{};{};{}m", r, g, b),
+    }
+}
+
+/// `text` in the color of `role`
+pub fn paint(style: &StyleConfig, role: Role, text: &str) -> String {
+    format!("{}{}\x1b[0m", escape(style, role), text)
+}
@@ -0,0 +1,161 @@
+//! Peak hold (`cache/peaks.json`).
+//!
+//! A segment with `peak_hold = <minutes>` in its options remembers the
+//! highest value it showed within that window and, while the current value
+//! is lower, appends the peak to its secondary text (`↑87%`), so a spike
+//! between two glances at the bar is not lost. The value comes from the
+//! segment's metadata: `percentage` for the context window, `turn_cost`
+//! (the most expensive recent turn) for the cost segment, or any numeric
+//! key named by the `peak_key` option.
+
+use crate::config::{FormatConfig, SegmentConfig, SegmentId};
+use crate::core::segments::SegmentData;
+use crate::utils::format;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Sample {
+    /// Unix seconds
+    pub at: i64,
+    pub value: f64,
+}
+
+/// Samples that can still become the window's maximum: values strictly
+/// decrease from front to back, so the front is the peak
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Window {
+    pub samples: Vec<Sample>,
+}
+
+impl Window {
+    /// Add a sample and return the highest value since `now - window_secs`
+    ///
+    /// ```
+    /// use mycode::core::peak::Window;
+    ///
+    /// let mut window = Window::default();
+    /// assert_eq!(window.push(0, 40.0, 600), 40.0);
+    /// assert_eq!(window.push(60, 87.0, 600), 87.0);
+    /// assert_eq!(window.push(120, 30.0, 600), 87.0);
+    /// // The spike leaves the window ten minutes later
+    /// assert_eq!(window.push(700, 35.0, 600), 35.0);
+    /// ```
+    pub fn push(&mut self, now: i64, value: f64, window_secs: i64) -> f64 {
+        self.samples.retain(|s| now - s.at < window_secs);
+        while self.samples.last().is_some_and(|s| s.value <= value) {
+            self.samples.pop();
+        }
+        self.samples.push(Sample { at: now, value });
+        self.samples[0].value
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Peaks {
+    windows: HashMap<SegmentId, Window>,
+    dirty: bool,
+}
+
+fn peaks_path() -> Option<PathBuf> {
+    crate::utils::paths::cache_dir().map(|dir| dir.join("peaks.json"))
+}
+
+/// Metadata key holding the value a segment tracks by default
+fn default_key(id: SegmentId) -> Option<&'static str> {
+    match id {
+        SegmentId::ContextWindow => Some("percentage"),
+        SegmentId::Cost => Some("turn_cost"),
+        _ => None,
+    }
+}
+
+fn format_peak(key: &str, value: f64, money: &FormatConfig) -> String {
+    if key == "percentage" {
+        format!("{:.0}%", value)
+    } else if key.contains("cost") {
+        format::money(value, 2, money)
+    } else {
+        let text = format!("{:.2}", value);
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
+
+impl Peaks {
+    pub fn load() -> Self {
+        let windows = peaks_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            windows,
+            dirty: false,
+        }
+    }
+
+    /// Record the segment's value and show the held peak when it is higher
+    pub fn apply(
+        &mut self,
+        segment_config: &SegmentConfig,
+        mut data: SegmentData,
+        money: &FormatConfig,
+    ) -> SegmentData {
+        let option = |key: &str| segment_config.options.get(key);
+        let Some(minutes) = option("peak_hold").and_then(|v| v.as_f64()) else {
+            return data;
+        };
+        // A deterministic render must not depend on earlier renders
+        if minutes <= 0.0 || crate::utils::clock::is_deterministic() {
+            return data;
+        }
+        let Some(key) = option("peak_key")
+            .and_then(|v| v.as_str())
+            .or_else(|| default_key(segment_config.id))
+        else {
+            return data;
+        };
+        let Some(value) = data
+            .metadata
+            .get(key)
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|v| v.is_finite())
+        else {
+            return data;
+        };
+
+        let now = crate::utils::clock::unix_now();
+        let peak = self.windows.entry(segment_config.id).or_default().push(
+            now,
+            value,
+            (minutes * 60.0) as i64,
+        );
+        self.dirty = true;
+
+        data.metadata.insert("peak".to_string(), peak.to_string());
+        if peak > value {
+            let text = format_peak(key, peak, money);
+            let secondary = data.secondary.to_mut();
+            if !secondary.is_empty() {
+                secondary.push(' ');
+            }
+            secondary.push('↑');
+            secondary.push_str(&text);
+        }
+        data
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.dirty {
+            return Ok(());
+        }
+        let path = peaks_path().ok_or("Could not find home directory")?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        crate::utils::atomic_write(path, serde_json::to_string(&self.windows)?)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+//! Per-segment collection timings (`cache/perf.json`).
+//!
+//! Each render updates a rolling average per segment. With `[perf]
+//! auto_skip` on, segments whose average over at least [`MIN_SAMPLES`]
+//! renders exceeds `budget_ms` are skipped and show a marker instead, then
+//! get one retry every few minutes so a recovered API is picked up again.
+
+use crate::config::{PerfConfig, SegmentId};
+use crate::core::segments::SegmentData;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Weight of the newest sample in the rolling average
+const SMOOTHING: f64 = 0.3;
+/// Renders measured before a segment can be skipped, so one cold start
+/// (a first TLS handshake, an empty cache) does not decide it
+pub const MIN_SAMPLES: u64 = 5;
+/// How long a skipped segment waits before it is measured again
+const RETRY_AFTER_SECS: u64 = 600;
+
+/// Primary text shown in place of a skipped segment
+pub const SKIPPED_MARKER: &str = "⏱ skipped";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SegmentTiming {
+    /// Rolling average of collection time in milliseconds
+    pub average_ms: f64,
+    /// Most recent collection time in milliseconds
+    pub last_ms: f64,
+    pub samples: u64,
+    /// Unix timestamp of the most recent measurement
+    pub measured_at: u64,
+}
+
+impl SegmentTiming {
+    pub fn is_over(&self, budget_ms: u64) -> bool {
+        self.average_ms > budget_ms as f64
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PerfStats {
+    pub segments: HashMap<SegmentId, SegmentTiming>,
+    #[serde(skip)]
+    dirty: bool,
+}
+
+fn perf_path() -> Option<PathBuf> {
+    crate::utils::paths::cache_dir().map(|dir| dir.join("perf.json"))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl PerfStats {
+    pub fn load() -> Self {
+        perf_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Write the stats back if any segment was measured
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.dirty {
+            return Ok(());
+        }
+        let path = perf_path().ok_or("Could not find home directory")?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        crate::utils::atomic_write(path, serde_json::to_string_pretty(&self.segments)?)?;
+        Ok(())
+    }
+
+    /// Whether `id` should be skipped in this render
+    pub fn should_skip(&self, id: SegmentId, config: &PerfConfig) -> bool {
+        // Skipping depends on earlier renders, which a deterministic one must not
+        if !config.auto_skip || crate::utils::clock::is_deterministic() {
+            return false;
+        }
+        self.segments.get(&id).is_some_and(|timing| {
+            timing.samples >= MIN_SAMPLES
+                && timing.is_over(config.budget_ms)
+                && now().saturating_sub(timing.measured_at) < RETRY_AFTER_SECS
+        })
+    }
+
+    pub fn record(&mut self, id: SegmentId, elapsed: Duration, config: &PerfConfig) {
+        let ms = elapsed.as_secs_f64() * 1000.0;
+        let timing = self.segments.entry(id).or_default();
+        timing.average_ms = if timing.samples == 0
+            || (timing.is_over(config.budget_ms) && ms <= config.budget_ms as f64)
+        {
+            // First sample, or a skipped segment that is fast again
+            ms
+        } else {
+            timing.average_ms * (1.0 - SMOOTHING) + ms * SMOOTHING
+        };
+        timing.last_ms = ms;
+        timing.samples += 1;
+        timing.measured_at = now();
+        self.dirty = true;
+    }
+
+    /// Forget all timings so every segment is collected again
+    pub fn reset() -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(path) = perf_path().filter(|p| p.exists()) {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Placeholder data for a skipped segment
+pub fn skipped_data(timing: Option<&SegmentTiming>) -> SegmentData {
+    let mut metadata = HashMap::new();
+    metadata.insert("skipped".to_string(), "slow".to_string());
+    if let Some(timing) = timing {
+        metadata.insert(
+            "average_ms".to_string(),
+            format!("{:.0}", timing.average_ms),
+        );
+    }
+    SegmentData {
+        primary: SKIPPED_MARKER.into(),
+        secondary: "".into(),
+        metadata,
+    }
+}
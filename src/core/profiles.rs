@@ -0,0 +1,75 @@
+//! Per-directory display profiles (`[[directory_profiles]]`).
+//!
+//! They live in the user's own config.toml and are resolved from the
+//! workspace Claude Code reports on every render, so one setup can show
+//! everything for personal projects and stay minimal at work. A project's
+//! `.uucode.toml` is separate: it ships with the repository and needs
+//! `uucode trust`.
+//!
+//! A profile with a `name` can also be picked for a whole session with
+//! `uucode --wrap --profile <name>`, whatever the workspace.
+
+use crate::config::{Config, DirectoryProfile};
+use crate::debug_log;
+use crate::utils::{glob, paths};
+use crate::wrapper;
+use std::path::Path;
+
+/// First profile whose `path` matches `dir`
+pub fn matching<'a>(config: &'a Config, dir: &str) -> Option<&'a DirectoryProfile> {
+    config
+        .directory_profiles
+        .iter()
+        .filter(|profile| !profile.path.is_empty())
+        .find(|profile| {
+            let pattern = paths::expand_home(Path::new(&profile.path));
+            glob::matches(&pattern.to_string_lossy(), dir)
+        })
+}
+
+/// Profile called `name`
+pub fn named<'a>(config: &'a Config, name: &str) -> Option<&'a DirectoryProfile> {
+    config
+        .directory_profiles
+        .iter()
+        .find(|profile| profile.name.as_deref() == Some(name))
+}
+
+/// Profile for this render: the one the wrapper selected with `--profile`,
+/// otherwise the first matching `dir`
+pub fn selected<'a>(config: &'a Config, dir: &str) -> Option<&'a DirectoryProfile> {
+    if let Some(name) = wrapper::passed_through(wrapper::PROFILE_ENV) {
+        match named(config, &name) {
+            Some(profile) => return Some(profile),
+            None => debug_log!("no directory profile named '{}'", name),
+        }
+    }
+    matching(config, dir)
+}
+
+/// Apply the selected profile to this render's config. Nothing is saved;
+/// returns false when no profile applies.
+pub fn apply(config: &mut Config, dir: &str) -> bool {
+    let Some(profile) = selected(config, dir).cloned() else {
+        return false;
+    };
+
+    if let Some(theme) = profile.theme.as_deref().filter(|t| *t != config.theme) {
+        config.apply_theme(crate::ui::themes::ThemePresets::get_theme(theme));
+    }
+    for segment in &mut config.segments {
+        if profile.show_all || profile.show.contains(&segment.id) {
+            segment.enabled = true;
+        }
+        if profile.hide.contains(&segment.id) {
+            segment.enabled = false;
+        }
+    }
+
+    debug_log!(
+        "directory profile '{}' applied for {}",
+        profile.name.as_deref().unwrap_or(&profile.path),
+        dir
+    );
+    true
+}
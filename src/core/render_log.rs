@@ -0,0 +1,96 @@
+//! The last rendered statusline (`cache/last_render.json`).
+//!
+//! Every render overwrites it, so `uucode explain` can describe the bar the
+//! user is actually looking at without collecting the segments again.
+
+use crate::config::{Config, SegmentConfig, SegmentId};
+use crate::core::segments::SegmentData;
+use crate::core::{history, icons};
+use crate::utils::ansi::strip;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderLog {
+    pub rendered_at: String,
+    /// Statusline text without escape sequences
+    pub text: String,
+    pub segments: Vec<RenderedSegment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderedSegment {
+    pub id: SegmentId,
+    #[serde(default)]
+    pub icon: String,
+    pub primary: String,
+    #[serde(default)]
+    pub secondary: String,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+impl RenderLog {
+    pub fn new(config: &Config, segments: &[(SegmentConfig, SegmentData)]) -> Self {
+        Self {
+            rendered_at: chrono::Local::now().to_rfc3339(),
+            text: String::new(),
+            segments: segments
+                .iter()
+                .filter(|(segment, _)| segment.enabled)
+                .map(|(segment, data)| RenderedSegment {
+                    id: segment.id,
+                    icon: icons::resolve(config, segment, &data.metadata),
+                    primary: strip(&data.primary),
+                    secondary: strip(&data.secondary),
+                    metadata: data.metadata.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn with_text(mut self, statusline: &str) -> Self {
+        self.text = strip(statusline);
+        self
+    }
+
+    /// Vendor quota usage as `(label, percentage)`, like
+    /// [`history::quotas`] for a fresh render
+    pub fn quotas(&self) -> Vec<(String, f64)> {
+        self.segments
+            .iter()
+            .filter_map(|segment| {
+                let label = history::quota_label(segment.id)?;
+                let data = SegmentData {
+                    primary: segment.primary.clone().into(),
+                    secondary: segment.secondary.clone().into(),
+                    metadata: segment.metadata.clone(),
+                };
+                Some((
+                    label.to_string(),
+                    history::segment_percentage(segment.id, &data)?,
+                ))
+            })
+            .collect()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = log_path().ok_or("Could not find home directory")?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        crate::utils::atomic_write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn load() -> Option<Self> {
+        let content = fs::read_to_string(log_path()?).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+}
+
+fn log_path() -> Option<PathBuf> {
+    crate::utils::paths::cache_dir().map(|dir| dir.join("last_render.json"))
+}
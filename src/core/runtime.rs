@@ -0,0 +1,130 @@
+//! Where uucode runs: locally, in WSL, a devcontainer, a Codespace or
+//! over SSH.
+//!
+//! Each of these has its own home directory, so its own `~/.claude`,
+//! config.toml and cookies, which is easy to lose track of when the same
+//! project is opened from several of them.
+
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuntimeKind {
+    Local,
+    Wsl,
+    Devcontainer,
+    Codespaces,
+    Ssh,
+}
+
+impl RuntimeKind {
+    pub fn key(&self) -> &'static str {
+        match self {
+            RuntimeKind::Local => "local",
+            RuntimeKind::Wsl => "wsl",
+            RuntimeKind::Devcontainer => "devcontainer",
+            RuntimeKind::Codespaces => "codespaces",
+            RuntimeKind::Ssh => "ssh",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RuntimeKind::Local => "local",
+            RuntimeKind::Wsl => "WSL",
+            RuntimeKind::Devcontainer => "devcontainer",
+            RuntimeKind::Codespaces => "Codespaces",
+            RuntimeKind::Ssh => "SSH",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Runtime {
+    pub kind: RuntimeKind,
+    /// WSL distro, devcontainer or Codespace name, SSH host
+    pub name: Option<String>,
+}
+
+impl Runtime {
+    /// `WSL: Ubuntu`, or just the kind when there is no name
+    pub fn describe(&self) -> String {
+        match &self.name {
+            Some(name) => format!("{}: {}", self.kind.label(), name),
+            None => self.kind.label().to_string(),
+        }
+    }
+}
+
+fn env(key: &str) -> Option<String> {
+    std::env::var(key).ok().filter(|value| !value.is_empty())
+}
+
+/// Detect the runtime; `workspace` is searched for a `.devcontainer`
+/// definition when the container does not carry one at `/.devcontainer`
+pub fn detect(workspace: &str) -> Runtime {
+    if env("CODESPACES").is_some_and(|v| v == "true") {
+        return Runtime {
+            kind: RuntimeKind::Codespaces,
+            name: env("CODESPACE_NAME"),
+        };
+    }
+
+    if in_container() {
+        let name = devcontainer_name(Path::new("/.devcontainer"))
+            .or_else(|| devcontainer_name(&Path::new(workspace).join(".devcontainer")));
+        return Runtime {
+            kind: RuntimeKind::Devcontainer,
+            name,
+        };
+    }
+
+    if is_wsl() {
+        return Runtime {
+            kind: RuntimeKind::Wsl,
+            name: env("WSL_DISTRO_NAME"),
+        };
+    }
+
+    if env("SSH_CONNECTION").is_some() {
+        let host = env("HOSTNAME").or_else(|| {
+            std::fs::read_to_string("/etc/hostname")
+                .ok()
+                .map(|h| h.trim().to_string())
+                .filter(|h| !h.is_empty())
+        });
+        return Runtime {
+            kind: RuntimeKind::Ssh,
+            name: host,
+        };
+    }
+
+    Runtime {
+        kind: RuntimeKind::Local,
+        name: None,
+    }
+}
+
+/// Inside WSL, from its environment or the kernel release
+pub fn is_wsl() -> bool {
+    env("WSL_DISTRO_NAME").is_some()
+        || std::fs::read_to_string("/proc/sys/kernel/osrelease")
+            .is_ok_and(|release| release.to_ascii_lowercase().contains("microsoft"))
+}
+
+fn in_container() -> bool {
+    env("REMOTE_CONTAINERS").is_some()
+        || env("DEVCONTAINER").is_some()
+        || env("VSCODE_REMOTE_CONTAINERS_SESSION").is_some()
+        || Path::new("/.devcontainer").is_dir()
+}
+
+/// `name` from `devcontainer.json` in `dir`
+fn devcontainer_name(dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(dir.join("devcontainer.json")).ok()?;
+    let value = crate::utils::jsonc::parse(&content).ok()?;
+    value
+        .get("name")
+        .and_then(|name| name.as_str())
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+}
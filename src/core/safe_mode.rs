@@ -0,0 +1,242 @@
+//! Safe mode after repeated failed renders (`cache/safe_mode.json`).
+//!
+//! Every statusline render leaves a marker (`cache/renders/<pid>-<ms>`)
+//! and removes it once the line is printed. A render that crashes (release
+//! builds abort on panic) or that Claude Code kills for hanging leaves its
+//! marker behind. Markers older than [`STALE_AFTER_SECS`] belong to renders
+//! that were cut short; a finished render also clears those that started
+//! before it, since Claude Code cancels a render when a newer one
+//! supersedes it. When [`MAX_UNFINISHED`] such markers pile up within
+//! [`WINDOW_SECS`] the statusline falls back to [`render`]: model and
+//! directory from the built-in theme, without the user's config, network
+//! requests, git or plugins, followed by a "安全模式" marker. It stays that
+//! way for [`DURATION_SECS`] or until `uucode doctor --reset`.
+
+use crate::config::{InputData, OutputFormat, SegmentId};
+use crate::core::segments::{directory::DirectorySegment, Segment, SegmentData};
+use crate::core::StatusLineGenerator;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Unfinished renders within [`WINDOW_SECS`] that switch safe mode on
+pub const MAX_UNFINISHED: u32 = 3;
+/// A render still running after this long was cut short
+pub const STALE_AFTER_SECS: i64 = 30;
+/// Only renders cut short this recently count
+pub const WINDOW_SECS: i64 = 15 * 60;
+/// Safe mode switches itself off after this long
+pub const DURATION_SECS: i64 = 60 * 60;
+
+/// Marker of the render in this process
+static MARKER: OnceLock<PathBuf> = OnceLock::new();
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SafeMode {
+    /// Renders cut short when safe mode was switched on
+    pub unfinished: u32,
+    /// When safe mode was switched on (RFC 3339), `None` while off
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+}
+
+fn state_path() -> Option<PathBuf> {
+    crate::utils::paths::cache_dir().map(|dir| dir.join("safe_mode.json"))
+}
+
+fn markers_dir() -> Option<PathBuf> {
+    crate::utils::paths::cache_dir().map(|dir| dir.join("renders"))
+}
+
+fn now_millis() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+impl SafeMode {
+    pub fn load() -> Self {
+        state_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.since.is_some()
+    }
+
+    /// Whether safe mode has been on for [`DURATION_SECS`]
+    fn is_expired(&self) -> bool {
+        self.since
+            .as_deref()
+            .and_then(|since| chrono::DateTime::parse_from_rfc3339(since).ok())
+            .is_some_and(|since| now_millis() - since.timestamp_millis() >= DURATION_SECS * 1000)
+    }
+
+    fn save(&self) {
+        let Some(path) = state_path() else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(json) = serde_json::to_string(self) {
+            if let Err(e) = crate::utils::atomic_write(&path, json) {
+                crate::debug_log!("failed to save safe mode state: {}", e);
+            }
+        }
+    }
+}
+
+/// Render markers with their start time in Unix milliseconds
+fn markers() -> Vec<(PathBuf, i64)> {
+    let Some(entries) = markers_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let started = name.to_str()?.split_once('-')?.1.parse().ok()?;
+            Some((entry.path(), started))
+        })
+        .collect()
+}
+
+/// Renders cut short within [`WINDOW_SECS`]; older markers are removed
+pub fn unfinished() -> u32 {
+    let now = now_millis();
+    let mut count = 0;
+    for (path, started) in markers() {
+        let age = now - started;
+        if age >= WINDOW_SECS * 1000 {
+            let _ = fs::remove_file(path);
+        } else if age >= STALE_AFTER_SECS * 1000 {
+            count += 1;
+        }
+    }
+    count
+}
+
+/// Mark a render as started. Returns true when it must use [`render`].
+pub fn begin() -> bool {
+    // A deterministic render must not depend on earlier ones
+    if crate::utils::clock::is_deterministic() {
+        return false;
+    }
+    let state = SafeMode::load();
+    if state.is_active() {
+        if !state.is_expired() {
+            return true;
+        }
+        crate::debug_log!("safe mode expired, trying a normal render");
+        clear();
+    }
+    if crate::utils::readonly::is_enabled() {
+        return false;
+    }
+
+    let unfinished = unfinished();
+    if unfinished >= MAX_UNFINISHED {
+        SafeMode {
+            unfinished,
+            since: Some(crate::utils::clock::local_now().to_rfc3339()),
+        }
+        .save();
+        crate::debug_log!(
+            "{} renders did not finish, switching to safe mode",
+            unfinished
+        );
+        return true;
+    }
+
+    let Some(dir) = markers_dir() else {
+        return false;
+    };
+    let marker = dir.join(format!("{}-{}", std::process::id(), now_millis()));
+    if fs::create_dir_all(&dir)
+        .and_then(|()| crate::utils::atomic_write(&marker, ""))
+        .is_ok()
+    {
+        let _ = MARKER.set(marker);
+    }
+    false
+}
+
+/// Mark the render started by [`begin`] as finished, together with renders
+/// cut short before it was started
+pub fn finish() {
+    let Some(marker) = MARKER.get() else {
+        return;
+    };
+    let _ = fs::remove_file(marker);
+    let started = marker
+        .file_name()
+        .and_then(|name| name.to_str()?.split_once('-')?.1.parse::<i64>().ok())
+        .unwrap_or_default();
+    let now = now_millis();
+    for (path, other) in markers() {
+        if other < started && now - other >= STALE_AFTER_SECS * 1000 {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Forget safe mode and every render marker
+fn clear() {
+    if let Some(path) = state_path() {
+        let _ = fs::remove_file(path);
+    }
+    if let Some(dir) = markers_dir() {
+        let _ = fs::remove_dir_all(dir);
+    }
+}
+
+/// Leave safe mode and forget unfinished renders. Returns whether safe mode
+/// was on.
+pub fn reset() -> Result<bool, Box<dyn std::error::Error>> {
+    crate::utils::readonly::ensure_writable("leaving safe mode")?;
+    let was_active = SafeMode::load().is_active();
+    clear();
+    Ok(was_active)
+}
+
+/// The minimal statusline shown in safe mode
+pub fn render(input: &InputData, output: Option<OutputFormat>) -> String {
+    let mut config = crate::ui::themes::ThemePresets::get_default();
+    config
+        .segments
+        .retain(|segment| matches!(segment.id, SegmentId::Model | SegmentId::Directory));
+
+    let mut segments = Vec::new();
+    for segment in &config.segments {
+        let data = match segment.id {
+            // The model segment may look up the Cubence multiplier
+            SegmentId::Model => Some(SegmentData {
+                primary: input.model.display_name.clone().into(),
+                secondary: "".into(),
+                metadata: HashMap::from([("model_id".to_string(), input.model.id.clone())]),
+            }),
+            _ => DirectorySegment::new().collect(input),
+        };
+        if let Some(data) = data {
+            segments.push((segment.clone(), data));
+        }
+    }
+
+    let separator = config.style.separator.clone();
+    let waybar = config.waybar.clone();
+    let statusline = StatusLineGenerator::new(config)
+        .with_available_width(input.available_width())
+        .generate(segments.clone());
+    let statusline = format!(
+        "{}{}\x1b[33m⚠ 安全模式 · uucode doctor --reset\x1b[0m",
+        statusline, separator
+    );
+
+    match output {
+        Some(format) => crate::core::output::render(format, &waybar, &statusline, &segments),
+        None => statusline,
+    }
+}
@@ -0,0 +1,102 @@
+use super::{Segment, SegmentData};
+use crate::config::{InputData, SegmentId};
+use crate::core::transcript::{self, ApiErrorKind};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Counts the API errors (overloaded, rate limited) of the last minutes.
+///
+/// Hidden while requests go through, so it only takes space when the
+/// endpoint is struggling and switching vendors may be worth it.
+pub struct ApiErrorsSegment {
+    window_minutes: i64,
+    critical_at: usize,
+}
+
+impl Default for ApiErrorsSegment {
+    fn default() -> Self {
+        Self {
+            window_minutes: 10,
+            critical_at: 3,
+        }
+    }
+}
+
+impl ApiErrorsSegment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only errors from the last `minutes` count
+    pub fn with_window(mut self, minutes: i64) -> Self {
+        self.window_minutes = minutes.max(1);
+        self
+    }
+
+    /// Error count from which the streak is shown as critical
+    pub fn with_critical_at(mut self, count: usize) -> Self {
+        self.critical_at = count.max(1);
+        self
+    }
+}
+
+impl Segment for ApiErrorsSegment {
+    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+        let errors = transcript::api_errors(Path::new(&input.transcript_path))?;
+        let since = crate::utils::clock::now() - chrono::Duration::minutes(self.window_minutes);
+        let recent: Vec<_> = errors.iter().filter(|e| e.at >= since).collect();
+        let last = recent.last()?;
+
+        let count = |kind: ApiErrorKind| recent.iter().filter(|e| e.kind == kind).count();
+        let breakdown: Vec<String> = [
+            (ApiErrorKind::Overloaded, "529"),
+            (ApiErrorKind::RateLimit, "429"),
+            (ApiErrorKind::Other, "other"),
+        ]
+        .iter()
+        .filter_map(|(kind, label)| match count(*kind) {
+            0 => None,
+            n => Some(format!("{}×{}", label, n)),
+        })
+        .collect();
+
+        let mut metadata = HashMap::new();
+        metadata.insert("count".to_string(), recent.len().to_string());
+        metadata.insert(
+            "window_minutes".to_string(),
+            self.window_minutes.to_string(),
+        );
+        for kind in [
+            ApiErrorKind::Overloaded,
+            ApiErrorKind::RateLimit,
+            ApiErrorKind::Other,
+        ] {
+            metadata.insert(kind.key().to_string(), count(kind).to_string());
+        }
+        metadata.insert("last_kind".to_string(), last.kind.key().to_string());
+        metadata.insert(
+            "last_error_secs".to_string(),
+            (crate::utils::clock::now() - last.at.to_utc())
+                .num_seconds()
+                .max(0)
+                .to_string(),
+        );
+        let level = if recent.len() >= self.critical_at {
+            "critical"
+        } else {
+            "warning"
+        };
+        metadata.insert("level".to_string(), level.to_string());
+
+        let noun = if recent.len() == 1 { "error" } else { "errors" };
+        Some(SegmentData {
+            primary: format!("{} {}/{}min", recent.len(), noun, self.window_minutes).into(),
+            secondary: breakdown.join(" ").into(),
+            metadata,
+        })
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::ApiErrors
+    }
+}
@@ -0,0 +1,136 @@
+//! 5 小时计费窗口段
+//! 按本机对话记录把用量划分为 Claude 订阅的 5 小时计费窗口，显示当前窗口的 token 数、
+//! 估算花费和距重置的时间，例如 "5h ████░░░░ 1.2M $3.40 (2h10m)"。
+//!
+//! 进度条以 `token_limit` 为上限；未设置时取记录中最大的一个已结束窗口，
+//! 没有历史窗口时显示窗口已过去的时间。记录来自 [`crate::core::transcript::daily`]。
+
+use crate::config::{ModelConfig, SegmentId};
+use crate::core::palette::{self, Level, Role};
+use crate::core::segments::{CollectionContext, SegmentData};
+use crate::core::transcript::{blocks, daily};
+use crate::utils::i18n::{self, Phrase};
+use crate::utils::{clock, format};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// 每次渲染用于解析新增记录的默认时间，与 daily_cost 段相同
+const DEFAULT_SCAN_BUDGET_MS: u64 = 300;
+
+/// ANSI 重置代码
+const RESET: &str = "\x1b[0m";
+
+/// 收集当前 5 小时窗口的用量
+pub fn collect(ctx: &CollectionContext) -> Option<SegmentData> {
+    let config = ctx.config;
+    let segment = config
+        .segments
+        .iter()
+        .find(|s| s.id == SegmentId::BlockUsage)?;
+    if !segment.enabled {
+        return None;
+    }
+    let option = |key: &str| segment.options.get(key);
+
+    let projects_dir = crate::utils::paths::claude_dir()?.join("projects");
+    let budget = option("scan_budget_ms")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_SCAN_BUDGET_MS);
+    let now = clock::unix_now();
+    let scan = daily::scan(&projects_dir, now, Duration::from_millis(budget));
+    let blocks = blocks::group(&scan.records, &ModelConfig::load());
+    let pending = if scan.complete { "" } else { "…" };
+
+    let mut metadata = HashMap::new();
+    if !scan.complete {
+        metadata.insert("scanning".to_string(), "true".to_string());
+    }
+    let Some((active, finished)) = blocks
+        .split_last()
+        .filter(|(block, _)| block.is_active(now))
+    else {
+        metadata.insert("active".to_string(), "false".to_string());
+        return Some(SegmentData {
+            primary: format!("5h 空闲{}", pending).into(),
+            secondary: "".into(),
+            metadata,
+        });
+    };
+
+    let tokens = active.totals.tokens;
+    let resets_in = active.end - now;
+    metadata.insert("active".to_string(), "true".to_string());
+    metadata.insert("block_start".to_string(), active.start.to_string());
+    metadata.insert("resets_at".to_string(), active.end.to_string());
+    metadata.insert("resets_in_secs".to_string(), resets_in.to_string());
+    metadata.insert("block_tokens".to_string(), tokens.to_string());
+    metadata.insert(
+        "block_cost".to_string(),
+        format!("{:.2}", active.totals.cost),
+    );
+    metadata.insert(
+        "block_responses".to_string(),
+        active.totals.responses.to_string(),
+    );
+
+    // 有上限时按用量比例，否则按窗口已过去的时间
+    let limit = option("token_limit")
+        .and_then(|v| v.as_u64())
+        .filter(|limit| *limit > 0)
+        .map(|limit| (limit, "option"))
+        .or_else(|| {
+            finished
+                .iter()
+                .map(|block| block.totals.tokens)
+                .max()
+                .filter(|max| *max > 0)
+                .map(|max| (max, "max_block"))
+        });
+    let (percentage, level) = match limit {
+        Some((limit, source)) => {
+            let percentage = tokens as f64 / limit as f64 * 100.0;
+            metadata.insert("token_limit".to_string(), limit.to_string());
+            metadata.insert("limit_source".to_string(), source.to_string());
+            metadata.insert("used_percentage".to_string(), format!("{:.1}", percentage));
+            let level = Level::from_percentage(percentage);
+            metadata.insert("level".to_string(), level.key().to_string());
+            (percentage, level)
+        }
+        None => {
+            let elapsed = (now - active.start) as f64 / blocks::BLOCK_SECONDS as f64 * 100.0;
+            (elapsed, Level::Ok)
+        }
+    };
+
+    // 无障碍模式下用百分比代替进度条字符
+    let progress_bar = if config.accessibility.is_active() {
+        format!("{:.0}%", percentage)
+    } else {
+        make_progress_bar(percentage.min(100.0), ctx.input.progress_bar_width())
+    };
+    let primary = format!(
+        "5h {}{}{} {}{} {}{}{} ({})",
+        palette::escape(&config.style, level.role()),
+        progress_bar,
+        RESET,
+        palette::escape(&config.style, Role::Accent),
+        format::token_count(tokens),
+        format::money(active.totals.cost, 2, &config.format),
+        pending,
+        RESET,
+        i18n::countdown(resets_in, Phrase::ResettingSoon, config.format.locale)
+    );
+
+    Some(SegmentData {
+        primary: primary.into(),
+        secondary: "".into(),
+        metadata,
+    })
+}
+
+/// 生成进度条
+fn make_progress_bar(percentage: f64, width: usize) -> String {
+    let filled = ((percentage / 100.0) * width as f64).round() as usize;
+    let empty = width.saturating_sub(filled);
+    format!("{}{}", "█".repeat(filled), "░".repeat(empty))
+}
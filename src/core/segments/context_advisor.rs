@@ -0,0 +1,178 @@
+use super::{Segment, SegmentData};
+use crate::config::{InputData, ModelConfig, SegmentId, TranscriptEntry};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader};
+
+/// Number of recent assistant turns used to estimate the burn rate
+const BURN_RATE_WINDOW: usize = 5;
+
+/// Suggests `/compact` or `/clear` before the context window runs out.
+///
+/// Stays hidden while the session is healthy, so it can replace the
+/// auto-compact warning the patcher removes without taking up space.
+pub struct ContextAdvisorSegment {
+    compact_threshold: f64,
+    clear_threshold: f64,
+    max_messages: usize,
+    min_turns_left: f64,
+}
+
+impl Default for ContextAdvisorSegment {
+    fn default() -> Self {
+        Self {
+            compact_threshold: 70.0,
+            clear_threshold: 90.0,
+            max_messages: 200,
+            min_turns_left: 5.0,
+        }
+    }
+}
+
+/// What the transcript tells us about the current session
+#[derive(Debug, Default)]
+struct TranscriptStats {
+    context_tokens: u32,
+    messages: usize,
+    /// Average context growth per assistant turn
+    burn_rate: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Advice {
+    Compact,
+    Clear,
+}
+
+impl ContextAdvisorSegment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Context percentages at which `/compact` and `/clear` are suggested
+    pub fn with_thresholds(mut self, compact: f64, clear: f64) -> Self {
+        self.compact_threshold = compact;
+        self.clear_threshold = clear;
+        self
+    }
+
+    /// Message count after which `/clear` is suggested regardless of context
+    pub fn with_max_messages(mut self, max_messages: usize) -> Self {
+        self.max_messages = max_messages;
+        self
+    }
+
+    /// Suggest `/compact` once fewer turns than this fit at the current burn rate
+    pub fn with_min_turns_left(mut self, turns: f64) -> Self {
+        self.min_turns_left = turns;
+        self
+    }
+
+    fn read_stats(transcript_path: &str) -> Option<TranscriptStats> {
+        let file = fs::File::open(transcript_path).ok()?;
+        let reader = BufReader::new(file);
+
+        let mut stats = TranscriptStats::default();
+        let mut history: Vec<u32> = Vec::new();
+
+        for line in reader.lines().map_while(Result::ok) {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Ok(entry) = serde_json::from_str::<TranscriptEntry>(line) else {
+                continue;
+            };
+
+            match entry.r#type.as_deref() {
+                Some("user") => stats.messages += 1,
+                Some("assistant") => {
+                    stats.messages += 1;
+                    if let Some(usage) = entry.message.and_then(|m| m.usage) {
+                        history.push(usage.normalize().display_tokens());
+                    }
+                }
+                // A summary marks a compaction, earlier growth no longer applies
+                Some("summary") => {
+                    history.clear();
+                    stats.messages = 0;
+                }
+                _ => {}
+            }
+        }
+
+        stats.context_tokens = *history.last()?;
+
+        let recent = &history[history.len().saturating_sub(BURN_RATE_WINDOW + 1)..];
+        let growth: Vec<f64> = recent
+            .windows(2)
+            .map(|w| w[1] as f64 - w[0] as f64)
+            .filter(|delta| *delta > 0.0)
+            .collect();
+        if !growth.is_empty() {
+            stats.burn_rate = growth.iter().sum::<f64>() / growth.len() as f64;
+        }
+
+        Some(stats)
+    }
+
+    fn advise(&self, stats: &TranscriptStats, limit: u32) -> Option<(Advice, String)> {
+        let percentage = stats.context_tokens as f64 / limit as f64 * 100.0;
+        let remaining = limit.saturating_sub(stats.context_tokens) as f64;
+        let turns_left = (stats.burn_rate > 0.0).then(|| remaining / stats.burn_rate);
+
+        if percentage >= self.clear_threshold {
+            return Some((Advice::Clear, format!("context {:.0}%", percentage)));
+        }
+        if stats.messages >= self.max_messages {
+            return Some((Advice::Clear, format!("{} messages", stats.messages)));
+        }
+        if percentage >= self.compact_threshold {
+            return Some((Advice::Compact, format!("context {:.0}%", percentage)));
+        }
+        if let Some(turns) = turns_left.filter(|t| *t < self.min_turns_left) {
+            return Some((Advice::Compact, format!("~{:.0} turns left", turns.floor())));
+        }
+        None
+    }
+}
+
+impl Segment for ContextAdvisorSegment {
+    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+        let stats = Self::read_stats(&input.transcript_path)?;
+        let limit = ModelConfig::load().get_context_limit(&input.model.id);
+        if limit == 0 {
+            return None;
+        }
+
+        let (advice, reason) = self.advise(&stats, limit)?;
+        let primary = match advice {
+            Advice::Compact => "/compact soon",
+            Advice::Clear => "consider /clear",
+        };
+
+        let mut metadata = HashMap::new();
+        metadata.insert(
+            "advice".to_string(),
+            match advice {
+                Advice::Compact => "compact",
+                Advice::Clear => "clear",
+            }
+            .to_string(),
+        );
+        metadata.insert("tokens".to_string(), stats.context_tokens.to_string());
+        metadata.insert("limit".to_string(), limit.to_string());
+        metadata.insert("messages".to_string(), stats.messages.to_string());
+        metadata.insert("burn_rate".to_string(), format!("{:.0}", stats.burn_rate));
+
+        Some(SegmentData {
+            primary: primary.into(),
+            secondary: reason.into(),
+            metadata,
+        })
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::ContextAdvisor
+    }
+}
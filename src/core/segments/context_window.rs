@@ -1,16 +1,29 @@
 use super::{Segment, SegmentData};
 use crate::config::{InputData, ModelConfig, SegmentId, TranscriptEntry};
+use crate::core::context_trend;
+use crate::core::palette::Level;
 use std::collections::HashMap;
 use std::fs;
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 
 #[derive(Default)]
-pub struct ContextWindowSegment;
+pub struct ContextWindowSegment {
+    /// Percentage of the window at which Claude Code compacts; set to
+    /// predict the messages left until then
+    compact_at: Option<f64>,
+}
 
 impl ContextWindowSegment {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Show "≈N msgs until compaction" in the secondary slot, extrapolated
+    /// from the recent growth per message up to `compact_at` percent
+    pub fn with_prediction(mut self, compact_at: f64) -> Self {
+        self.compact_at = Some(compact_at);
+        self
     }
 
     /// Get context limit for the specified model
@@ -62,6 +75,10 @@ impl Segment for ContextWindowSegment {
                 let context_used_rate = (context_used_token as f64 / context_limit as f64) * 100.0;
                 metadata.insert("tokens".to_string(), context_used_token.to_string());
                 metadata.insert("percentage".to_string(), context_used_rate.to_string());
+                metadata.insert(
+                    "level".to_string(),
+                    Level::from_percentage(context_used_rate).key().to_string(),
+                );
             }
             None => {
                 metadata.insert("tokens".to_string(), "-".to_string());
@@ -71,9 +88,19 @@ impl Segment for ContextWindowSegment {
         metadata.insert("limit".to_string(), context_limit.to_string());
         metadata.insert("model".to_string(), input.model.id.clone());
 
+        let mut secondary = String::new();
+        if let (Some(compact_at), Some(tokens)) = (self.compact_at, context_used_token_opt) {
+            let threshold = (context_limit as f64 * compact_at / 100.0) as u32;
+            let trend = context_trend::record(&input.transcript_path, tokens);
+            if let Some(turns) = trend.turns_until(threshold) {
+                metadata.insert("turns_until_compaction".to_string(), turns.to_string());
+                secondary = format!("≈{} msgs until compaction", turns);
+            }
+        }
+
         Some(SegmentData {
-            primary: format!("{} · {} tokens", percentage_display, tokens_display),
-            secondary: String::new(),
+            primary: format!("{} · {} tokens", percentage_display, tokens_display).into(),
+            secondary: secondary.into(),
             metadata,
         })
     }
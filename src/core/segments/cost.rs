@@ -1,42 +1,111 @@
 use super::{Segment, SegmentData};
-use crate::config::{InputData, SegmentId};
+use crate::config::{FormatConfig, InputData, ModelConfig, SegmentId};
+use crate::core::{transcript, turn_delta};
+use crate::utils::format;
 use std::collections::HashMap;
+use std::path::Path;
+
+/// Billed amounts this close to the estimate count as caught up
+const RECONCILE_TOLERANCE_USD: f64 = 0.01;
 
 #[derive(Default)]
-pub struct CostSegment;
+pub struct CostSegment {
+    format: FormatConfig,
+    estimate: bool,
+    turn_delta: bool,
+}
 
 impl CostSegment {
     pub fn new() -> Self {
-        Self
+        Self::default()
     }
-}
 
-impl Segment for CostSegment {
-    fn collect(&self, input: &InputData) -> Option<SegmentData> {
-        let cost_data = input.cost.as_ref()?;
-
-        // Primary display: total cost
-        let primary = if let Some(cost) = cost_data.total_cost_usd {
-            if cost == 0.0 || cost < 0.01 {
-                "$0".to_string()
-            } else {
-                format!("${:.2}", cost)
-            }
+    pub fn with_format(mut self, format: FormatConfig) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Estimate spend from transcript tokens and model prices, shown while
+    /// the billed cost lags behind it
+    pub fn with_estimate(mut self, estimate: bool) -> Self {
+        self.estimate = estimate;
+        self
+    }
+
+    /// Append the cost of the most recent turn: `$1.24 (+$0.18)`
+    pub fn with_turn_delta(mut self, turn_delta: bool) -> Self {
+        self.turn_delta = turn_delta;
+        self
+    }
+
+    fn format_cost(&self, cost: f64) -> String {
+        if cost < 0.01 {
+            format::money(0.0, 0, &self.format)
         } else {
+            format::money(cost, 2, &self.format)
+        }
+    }
+
+    fn transcript_totals(&self, input: &InputData) -> Option<transcript::TranscriptTotals> {
+        if !self.estimate {
             return None;
-        };
+        }
+        transcript::totals(Path::new(&input.transcript_path))
+    }
+}
 
-        // Secondary display: empty for cost segment
-        let secondary = String::new();
+impl Segment for CostSegment {
+    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+        let billed = input.cost.as_ref().and_then(|c| c.total_cost_usd);
+        let totals = self.transcript_totals(input);
+        let estimated = totals
+            .as_ref()
+            .and_then(|t| t.estimated_cost(&ModelConfig::load()));
 
         let mut metadata = HashMap::new();
-        if let Some(cost) = cost_data.total_cost_usd {
+        if let Some(cost) = billed {
             metadata.insert("cost".to_string(), cost.to_string());
         }
+        if let Some(cost) = estimated {
+            metadata.insert("estimated_cost".to_string(), format!("{:.4}", cost));
+        }
+
+        // Show the estimate until the billed cost catches up with it
+        let (primary, mut secondary) = match (estimated, billed) {
+            (Some(est), Some(billed)) if billed + RECONCILE_TOLERANCE_USD < est => (
+                format!("est. {}", self.format_cost(est)),
+                format!("(billed {})", self.format_cost(billed)),
+            ),
+            (Some(est), None) => (format!("est. {}", self.format_cost(est)), String::new()),
+            (_, Some(billed)) => (self.format_cost(billed), String::new()),
+            (None, None) => return None,
+        };
+
+        if self.turn_delta {
+            // The estimate runs ahead of the billed cost, so the larger one
+            // is the best running total
+            let total = billed.unwrap_or(0.0).max(estimated.unwrap_or(0.0));
+            let tokens = totals.as_ref().map_or(0, |t| t.total_tokens());
+            let snapshot = turn_delta::record(input.session_key(), total, tokens);
+            metadata.insert(
+                "turn_cost".to_string(),
+                format!("{:.4}", snapshot.turn_cost),
+            );
+            metadata.insert("turn_tokens".to_string(), snapshot.turn_tokens.to_string());
+            if snapshot.turn_cost >= 0.01 {
+                if !secondary.is_empty() {
+                    secondary.push(' ');
+                }
+                secondary.push_str(&format!(
+                    "(+{})",
+                    format::money(snapshot.turn_cost, 2, &self.format)
+                ));
+            }
+        }
 
         Some(SegmentData {
-            primary,
-            secondary,
+            primary: primary.into(),
+            secondary: secondary.into(),
             metadata,
         })
     }
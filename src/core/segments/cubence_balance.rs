@@ -1,14 +1,16 @@
 //! Cubence 账户余额段
 //! 显示 Cubence 账户的美元余额
 
-use crate::api::{cache, client::ApiClient, ApiConfig, CubenceData, VendorType};
-use crate::config::Config;
-use crate::config::InputData;
-use crate::core::segments::SegmentData;
+use crate::api::{fetch, VendorType};
+use crate::core::palette::{self, Role};
+use crate::core::segments::{CollectionContext, SegmentData};
+use crate::utils::format;
 use std::collections::HashMap;
 
 /// 收集 Cubence 余额数据
-pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
+pub fn collect(ctx: &CollectionContext) -> Option<SegmentData> {
+    let config = ctx.config;
+
     let segment = config
         .segments
         .iter()
@@ -19,7 +21,7 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
     }
 
     // 检查是否是 Cubence 服务商，不是则静默跳过
-    let vendor = crate::api::detect_vendor_from_claude_settings();
+    let vendor = ctx.vendor;
     if vendor != VendorType::Cubence {
         return None;
     }
@@ -31,14 +33,14 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
         .and_then(|v| v.as_str())
         .filter(|s| !s.is_empty())
         .map(|s| s.to_string())
-        .or_else(crate::api::get_cubence_api_key_from_claude_settings);
+        .or_else(|| crate::api::credentials::resolve_api_key(VendorType::Cubence));
 
     let api_key = match api_key {
         Some(key) if !key.is_empty() => key,
         _ => {
             return Some(SegmentData {
-                primary: "未配置密钥".to_string(),
-                secondary: String::new(),
+                primary: "未配置密钥".into(),
+                secondary: "".into(),
                 metadata: HashMap::new(),
             });
         }
@@ -54,7 +56,8 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
         .unwrap_or_else(|| "https://cubence.com/api/v1/user/subscription-info".to_string());
 
     // 获取数据
-    let cubence_data = fetch_or_cache(&api_key, &usage_url)?;
+    let fetched = fetch::cubence_usage(&api_key, &usage_url)?;
+    let cubence_data = &fetched.data;
 
     // 构建显示
     let mut metadata = HashMap::new();
@@ -63,44 +66,16 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
         format!("{:.2}", cubence_data.balance_usd),
     );
     metadata.insert("service".to_string(), "cubence".to_string());
-
-    // 金色/黄色 ANSI 代码
-    const GOLD: &str = "\x1b[38;5;220m";
-    const RESET: &str = "\x1b[0m";
+    fetched.annotate(&mut metadata);
 
     Some(SegmentData {
-        primary: format!("{}${:.2}{}", GOLD, cubence_data.balance_usd, RESET),
-        secondary: String::new(),
+        primary: palette::paint(
+            &config.style,
+            Role::Accent,
+            &format::money(cubence_data.balance_usd, 2, &config.format),
+        )
+        .into(),
+        secondary: "".into(),
         metadata,
     })
 }
-
-fn fetch_or_cache(api_key: &str, usage_url: &str) -> Option<CubenceData> {
-    let (cached, _) = cache::get_cached_cubence_usage();
-
-    if let Some(mut fresh) = fetch_cubence_sync(api_key, usage_url) {
-        fresh.calculate();
-        let _ = cache::save_cached_cubence_usage(&fresh);
-        Some(fresh)
-    } else if let Some(mut cached_data) = cached {
-        cached_data.calculate();
-        Some(cached_data)
-    } else {
-        None
-    }
-}
-
-fn fetch_cubence_sync(api_key: &str, usage_url: &str) -> Option<CubenceData> {
-    let api_config = ApiConfig {
-        enabled: true,
-        api_key: api_key.to_string(),
-        usage_url: usage_url.to_string(),
-        subscription_url: String::new(),
-        auto_cookie: true, // Cubence 需要 Cookie
-        cookie: None,
-    };
-
-    let client = ApiClient::new(api_config).ok()?;
-    let usage = client.get_usage().ok()?;
-    usage.as_cubence().cloned()
-}
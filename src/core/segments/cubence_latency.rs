@@ -2,10 +2,8 @@
 //! 显示 API 延迟信息，根据 base_url 自动选择对应的 health 端点
 
 use crate::api::VendorType;
-use crate::config::Config;
-use crate::config::InputData;
-use crate::core::segments::SegmentData;
-use reqwest::blocking::Client;
+use crate::core::latency::{self, LatencySample, LatencyStats};
+use crate::core::segments::{CollectionContext, SegmentData};
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
@@ -20,26 +18,27 @@ const CUBENCE_ENDPOINTS: &[(&str, &str)] = &[
     ("api.cubence.com", "https://api.cubence.com/health"),
 ];
 
-/// 根据 base_url 获取对应的 health 端点
-fn get_health_url_from_base(base_url: &str) -> Option<&'static str> {
-    for (pattern, health_url) in CUBENCE_ENDPOINTS {
-        if base_url.contains(pattern) {
-            return Some(health_url);
-        }
-    }
-    None
+/// 根据 base_url 获取对应的线路和 health 端点
+fn get_endpoint_from_base(base_url: &str) -> Option<(&'static str, &'static str)> {
+    CUBENCE_ENDPOINTS
+        .iter()
+        .find(|(pattern, _)| base_url.contains(pattern))
+        .copied()
 }
 
+/// 分位数统计的时间范围
+const PERCENTILE_WINDOW_SECS: u64 = 24 * 3600;
+
 /// 测量 API 延迟
-fn measure_latency(health_url: &str) -> Result<u128, String> {
-    let client = Client::builder()
-        .timeout(Duration::from_secs(10))
+fn measure_latency(health_url: &str) -> Result<u64, String> {
+    // 每次新建连接，延迟包含握手，与历史记录保持可比
+    let client = crate::api::http::builder(Some(VendorType::Cubence), Duration::from_secs(10))
         .build()
         .map_err(|e| e.to_string())?;
 
     let start = Instant::now();
     let response = client.get(health_url).send().map_err(|e| e.to_string())?;
-    let latency_ms = start.elapsed().as_millis();
+    let latency_ms = start.elapsed().as_millis() as u64;
 
     if response.status().is_success() {
         Ok(latency_ms)
@@ -49,7 +48,7 @@ fn measure_latency(health_url: &str) -> Result<u128, String> {
 }
 
 /// 根据延迟返回对应的 emoji
-fn get_latency_emoji(latency_ms: u128) -> &'static str {
+fn get_latency_emoji(latency_ms: u64) -> &'static str {
     if latency_ms <= 300 {
         "🟢" // 绿色：300ms 以内
     } else if latency_ms <= 1000 {
@@ -62,7 +61,9 @@ fn get_latency_emoji(latency_ms: u128) -> &'static str {
 }
 
 /// 收集 Cubence 延迟数据
-pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
+pub fn collect(ctx: &CollectionContext) -> Option<SegmentData> {
+    let config = ctx.config;
+
     let segment = config
         .segments
         .iter()
@@ -73,40 +74,78 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
     }
 
     // 检查是否是 Cubence 服务商
-    let vendor = crate::api::detect_vendor_from_claude_settings();
+    let vendor = ctx.vendor;
     if vendor != VendorType::Cubence {
         return None;
     }
 
+    // 确定性模式下无法复现一次网络往返，不测量也不显示
+    if crate::utils::clock::is_deterministic() {
+        return None;
+    }
+
     // 获取当前 base_url
     let base_url = crate::api::get_current_base_url()?;
 
     // 根据 base_url 获取对应的 health 端点
-    let health_url = get_health_url_from_base(&base_url)?;
+    let (endpoint, health_url) = get_endpoint_from_base(&base_url)?;
 
     let mut metadata = HashMap::new();
     metadata.insert("health_url".to_string(), health_url.to_string());
 
-    // 测量延迟
-    match measure_latency(health_url) {
+    // 测量延迟，并记入历史供 uucode stats latency 使用
+    let result = measure_latency(health_url);
+    if let Err(e) = latency::record(&LatencySample::new(endpoint, &result)) {
+        crate::debug_log!("failed to record latency: {}", e);
+    }
+
+    // 可选：在段中显示近 24 小时的 p50/p95
+    let show_percentiles = segment
+        .options
+        .get("show_percentiles")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let percentiles = if show_percentiles {
+        let stats = LatencyStats::from_samples(&latency::recent(endpoint, PERCENTILE_WINDOW_SECS));
+        match (stats.p50_ms, stats.p95_ms) {
+            (Some(p50), Some(p95)) => {
+                metadata.insert("p50_ms".to_string(), p50.to_string());
+                metadata.insert("p95_ms".to_string(), p95.to_string());
+                format!(" p50 {} / p95 {}", p50, p95)
+            }
+            _ => String::new(),
+        }
+    } else {
+        String::new()
+    };
+
+    match result {
         Ok(latency_ms) => {
             let emoji = get_latency_emoji(latency_ms);
             metadata.insert("latency_ms".to_string(), latency_ms.to_string());
             metadata.insert("status".to_string(), "ok".to_string());
+            // 颜色之外的状态提示（无障碍模式显示为文字）
+            let level = match latency_ms {
+                0..=1000 => "ok",
+                1001..=2000 => "warning",
+                _ => "critical",
+            };
+            metadata.insert("level".to_string(), level.to_string());
 
             Some(SegmentData {
-                primary: format!("{}延迟[{}ms]", emoji, latency_ms),
-                secondary: String::new(),
+                primary: format!("{}延迟[{}ms{}]", emoji, latency_ms, percentiles).into(),
+                secondary: "".into(),
                 metadata,
             })
         }
         Err(e) => {
             metadata.insert("status".to_string(), "error".to_string());
+            metadata.insert("level".to_string(), "critical".to_string());
             metadata.insert("error".to_string(), e.clone());
 
             Some(SegmentData {
-                primary: "🔴延迟[超时]".to_string(),
-                secondary: e,
+                primary: format!("🔴延迟[超时{}]", percentiles).into(),
+                secondary: e.into(),
                 metadata,
             })
         }
@@ -1,47 +1,18 @@
 //! Cubence 负载状态段
 //! 显示 Claude Pool 负载状态
-//! Cookie 通过 ~/.claude/mycode/cache/cubence/cookie.json 手动配置
-
-use crate::api::VendorType;
-use crate::config::Config;
-use crate::config::InputData;
-use crate::core::segments::SegmentData;
-use reqwest::blocking::Client;
-use serde::{Deserialize, Serialize};
+//! Cookie 通过 ~/.claude/mycode/cache/cubence/cookie.json 或 `uucode cubence set-cookie` 配置
+
+use crate::api::{cookie, VendorType};
+use crate::config::{Config, SegmentId};
+use crate::core::icons::{self, Glyph};
+use crate::core::palette::{self, Level};
+use crate::core::segments::{CollectionContext, SegmentData};
+use serde::Deserialize;
 use std::collections::HashMap;
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicU8, Ordering};
 use std::time::Duration;
 
-/// 失败计数器（最多重试3次）
-static FAILURE_COUNT: AtomicU8 = AtomicU8::new(0);
-const MAX_FAILURES: u8 = 3;
-
 /// 负载 API 端点
-const LOAD_STATUS_URL: &str = "https://cubence.com/api/v1/claudepool/load-status";
-
-/// Cookie 配置文件结构
-#[derive(Debug, Deserialize, Serialize)]
-struct CookieConfig {
-    /// Cookie 值，为空表示未配置
-    cookie: String,
-    /// 配置说明
-    #[serde(default = "default_description")]
-    description: String,
-}
-
-fn default_description() -> String {
-    "请将 Cubence 网站的 Cookie 粘贴到 cookie 字段中".to_string()
-}
-
-impl Default for CookieConfig {
-    fn default() -> Self {
-        Self {
-            cookie: String::new(),
-            description: default_description(),
-        }
-    }
-}
+pub(crate) const LOAD_STATUS_URL: &str = "https://cubence.com/api/v1/claudepool/load-status";
 
 /// 负载状态 API 响应
 #[derive(Debug, Deserialize)]
@@ -56,63 +27,15 @@ struct CurrentLoadStatus {
     load_level: String,
 }
 
-/// 获取 cookie 配置文件路径
-fn get_cookie_config_path() -> Option<PathBuf> {
-    dirs::home_dir().map(|home| {
-        home.join(".claude")
-            .join("mycode")
-            .join("cache")
-            .join("cubence")
-            .join("cookie.json")
-    })
-}
-
-/// 确保配置文件存在，如果不存在则创建模板
-fn ensure_cookie_config_exists() -> Option<PathBuf> {
-    let path = get_cookie_config_path()?;
-
-    // 如果文件已存在，直接返回
-    if path.exists() {
-        return Some(path);
-    }
-
-    // 创建目录
-    if let Some(parent) = path.parent() {
-        if std::fs::create_dir_all(parent).is_err() {
-            return None;
-        }
-    }
-
-    // 创建默认配置文件
-    let default_config = CookieConfig::default();
-    let content = serde_json::to_string_pretty(&default_config).ok()?;
-    std::fs::write(&path, content).ok()?;
-
-    Some(path)
-}
-
-/// 读取 cookie 配置
-/// 返回: Ok(Some(cookie)) - cookie 已配置
-///       Ok(None) - cookie 为空（未配置）
-///       Err - 文件读取失败
+/// 读取 cookie，文件不存在时写入模板方便手动填写
 fn read_cookie() -> Result<Option<String>, String> {
-    let path = ensure_cookie_config_exists().ok_or("无法创建配置文件")?;
-
-    let content = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
-    let config: CookieConfig = serde_json::from_str(&content).map_err(|e| e.to_string())?;
-
-    if config.cookie.trim().is_empty() {
-        Ok(None) // Cookie 为空，未配置
-    } else {
-        Ok(Some(config.cookie))
-    }
+    cookie::ensure_exists().ok_or("无法创建配置文件")?;
+    cookie::load()
 }
 
-/// 请求负载状态
-fn fetch_load_status(cookie: &str) -> Result<(f64, String), String> {
-    let client = Client::builder()
-        .timeout(Duration::from_secs(10))
-        .build()
+/// 请求负载状态，`uucode cubence set-cookie` 也用它校验 Cookie
+pub fn fetch_load_status(cookie: &str) -> Result<(f64, String), String> {
+    let client = crate::api::http::client(Some(VendorType::Cubence), Duration::from_secs(10))
         .map_err(|e| e.to_string())?;
 
     let response = client
@@ -125,7 +48,7 @@ fn fetch_load_status(cookie: &str) -> Result<(f64, String), String> {
         return Err(format!("HTTP {}", response.status()));
     }
 
-    let text = response.text().map_err(|e| e.to_string())?;
+    let text = crate::api::http::read_text(response).map_err(|e| e.to_string())?;
 
     // 检查是否是错误响应
     if text.contains("No token provided") || text.contains("error") {
@@ -137,58 +60,67 @@ fn fetch_load_status(cookie: &str) -> Result<(f64, String), String> {
     Ok((resp.current.load_percentage, resp.current.load_level))
 }
 
-/// ANSI 颜色代码
-const GREEN: &str = "\x1b[32m";
-const YELLOW: &str = "\x1b[33m";
-const RED: &str = "\x1b[31m";
 const RESET: &str = "\x1b[0m";
 
 /// 根据状态返回对应的 emoji 和描述
-fn get_status_display(load_level: &str, load_percentage: f64) -> (String, String) {
+fn get_status_display(config: &Config, load_level: &str, load_percentage: f64) -> (String, String) {
+    let glyph = |g| icons::glyph(config, SegmentId::CubenceLoadStatus, g);
+    let prefix = |g| icons::prefix(config, SegmentId::CubenceLoadStatus, g);
+    // 数字颜色取自 style.palette
+    let color = |level: Level| palette::escape(&config.style, level.role());
     // 转换为百分比整数显示
     let percent = (load_percentage * 100.0).round() as i64;
 
     match load_level {
         "normal" => {
-            let emoji = "🚴";
-            let status_emoji = "😎";
-            // 绿色数字
+            let emoji = prefix(Glyph::Bike);
+            let status_emoji = glyph(Glyph::Relaxed);
             (
                 format!(
-                    "{} 负载[{}{}%{}-使劲蹬{}]",
-                    emoji, GREEN, percent, RESET, status_emoji
+                    "{}负载[{}{}%{}-使劲蹬{}]",
+                    emoji,
+                    color(Level::Ok),
+                    percent,
+                    RESET,
+                    status_emoji
                 ),
                 "normal".to_string(),
             )
         }
         "warning" => {
-            let emoji = "🚴";
-            let status_emoji = "😰";
-            // 黄色数字
+            let emoji = prefix(Glyph::Bike);
+            let status_emoji = glyph(Glyph::Strained);
             (
                 format!(
-                    "{} 负载[{}{}%{}-轻点蹬{}]",
-                    emoji, YELLOW, percent, RESET, status_emoji
+                    "{}负载[{}{}%{}-轻点蹬{}]",
+                    emoji,
+                    color(Level::Warning),
+                    percent,
+                    RESET,
+                    status_emoji
                 ),
                 "warning".to_string(),
             )
         }
         "emergency" => {
-            let emoji = "💥";
-            let status_emoji = "🥵";
-            // 红色数字
+            let emoji = prefix(Glyph::Boom);
+            let status_emoji = glyph(Glyph::Overheated);
             (
                 format!(
-                    "{} 负载[{}{}%{}-蹬炸了{}]",
-                    emoji, RED, percent, RESET, status_emoji
+                    "{}负载[{}{}%{}-蹬炸了{}]",
+                    emoji,
+                    color(Level::Critical),
+                    percent,
+                    RESET,
+                    status_emoji
                 ),
                 "emergency".to_string(),
             )
         }
         _ => {
-            let emoji = "❓";
+            let emoji = prefix(Glyph::Unknown);
             (
-                format!("{} 负载[{}%-未知]", emoji, percent),
+                format!("{}负载[{}%-未知]", emoji, percent),
                 "unknown".to_string(),
             )
         }
@@ -196,7 +128,9 @@ fn get_status_display(load_level: &str, load_percentage: f64) -> (String, String
 }
 
 /// 收集 Cubence 负载状态数据
-pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
+pub fn collect(ctx: &CollectionContext) -> Option<SegmentData> {
+    let config = ctx.config;
+
     let segment = config
         .segments
         .iter()
@@ -207,24 +141,13 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
     }
 
     // 检查是否是 Cubence 服务商
-    let vendor = crate::api::detect_vendor_from_claude_settings();
+    let vendor = ctx.vendor;
     if vendor != VendorType::Cubence {
         return None;
     }
 
     let mut metadata = HashMap::new();
 
-    // 检查失败次数，超过3次则不再请求
-    let failures = FAILURE_COUNT.load(Ordering::Relaxed);
-    if failures >= MAX_FAILURES {
-        metadata.insert("status".to_string(), "disabled".to_string());
-        return Some(SegmentData {
-            primary: "🔒 负载: Cookie已失效".to_string(),
-            secondary: String::new(),
-            metadata,
-        });
-    }
-
     // 读取 cookie
     let cookie = match read_cookie() {
         Ok(Some(c)) => c,
@@ -232,8 +155,8 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
             // Cookie 为空，未配置
             metadata.insert("status".to_string(), "not_configured".to_string());
             return Some(SegmentData {
-                primary: "🔧 负载: 请配置Cookie".to_string(),
-                secondary: String::new(),
+                primary: "🔧 负载: 请配置Cookie".into(),
+                secondary: "".into(),
                 metadata,
             });
         }
@@ -241,38 +164,57 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
             // 文件读取失败
             metadata.insert("status".to_string(), "config_error".to_string());
             return Some(SegmentData {
-                primary: "⚠️ 负载: 配置文件错误".to_string(),
-                secondary: String::new(),
+                primary: "⚠️ 负载: 配置文件错误".into(),
+                secondary: "".into(),
                 metadata,
             });
         }
     };
 
-    // 请求负载状态
-    match fetch_load_status(&cookie) {
-        Ok((load_percentage, load_level)) => {
-            // 成功，重置失败计数
-            FAILURE_COUNT.store(0, Ordering::Relaxed);
+    // JWT 自带过期时间：过期后不再请求，临近过期时提醒续期
+    let mut secondary = String::new();
+    if let Some(expires_at) = cookie::expires_at(&cookie) {
+        let remaining = expires_at - crate::utils::clock::unix_now();
+        metadata.insert("cookie_expires_at".to_string(), expires_at.to_string());
+        metadata.insert("cookie_expires_in".to_string(), remaining.to_string());
+        if remaining <= 0 {
+            metadata.insert("status".to_string(), "expired".to_string());
+            return Some(SegmentData {
+                primary: "🔒 负载: Cookie已过期".into(),
+                secondary: "uucode cubence set-cookie".into(),
+                metadata,
+            });
+        }
+        if remaining <= cookie::RENEW_WARNING_SECS {
+            secondary = format!("Cookie {} 后过期", cookie::format_remaining(remaining));
+        }
+    }
 
-            let (display, status) = get_status_display(&load_level, load_percentage);
+    // 请求负载状态，接口熔断期间不请求
+    match crate::api::breaker::call(LOAD_STATUS_URL, || fetch_load_status(&cookie)) {
+        Ok((load_percentage, load_level)) => {
+            let (display, status) = get_status_display(config, &load_level, load_percentage);
             let percent = (load_percentage * 100.0).round() as i64;
+            let level = match status.as_str() {
+                "warning" => Level::Warning,
+                "emergency" => Level::Critical,
+                _ => Level::Ok,
+            };
+            metadata.insert("level".to_string(), level.key().to_string());
             metadata.insert("status".to_string(), status);
             metadata.insert("load_percentage".to_string(), percent.to_string());
 
             Some(SegmentData {
-                primary: display,
-                secondary: String::new(),
+                primary: display.into(),
+                secondary: secondary.into(),
                 metadata,
             })
         }
         Err(_) => {
-            // 失败，增加计数
-            FAILURE_COUNT.fetch_add(1, Ordering::Relaxed);
-
             metadata.insert("status".to_string(), "invalid".to_string());
             Some(SegmentData {
-                primary: "🔒 负载: Cookie已失效".to_string(),
-                secondary: String::new(),
+                primary: "🔒 负载: Cookie已失效".into(),
+                secondary: "".into(),
                 metadata,
             })
         }
@@ -2,13 +2,13 @@
 //! 显示 Cubence 服务商标识 (🦢 Cubence)
 
 use crate::api::VendorType;
-use crate::config::Config;
-use crate::config::InputData;
-use crate::core::segments::SegmentData;
+use crate::core::segments::{CollectionContext, SegmentData};
 use std::collections::HashMap;
 
 /// 收集 Cubence 标识数据
-pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
+pub fn collect(ctx: &CollectionContext) -> Option<SegmentData> {
+    let config = ctx.config;
+
     let segment = config
         .segments
         .iter()
@@ -19,7 +19,7 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
     }
 
     // 检查是否是 Cubence 服务商，不是则静默跳过
-    let vendor = crate::api::detect_vendor_from_claude_settings();
+    let vendor = ctx.vendor;
     if vendor != VendorType::Cubence {
         return None;
     }
@@ -29,8 +29,8 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
 
     // 简单显示 Cubence 标识
     Some(SegmentData {
-        primary: "Cubence".to_string(),
-        secondary: String::new(),
+        primary: "Cubence".into(),
+        secondary: "".into(),
         metadata,
     })
 }
@@ -1,14 +1,19 @@
 //! Cubence 用量段
 //! 显示 5小时窗口 + 周窗口的使用情况
 
-use crate::api::{cache, client::ApiClient, ApiConfig, CubenceData, VendorType};
-use crate::config::Config;
-use crate::config::InputData;
-use crate::core::segments::SegmentData;
+use crate::api::{fetch, CubenceData, VendorType};
+use crate::config::{Config, SegmentId};
+use crate::core::icons::{self, Glyph};
+use crate::core::palette::Level;
+use crate::core::segments::{CollectionContext, SegmentData};
+use crate::utils::format;
+use crate::utils::i18n::{self, Phrase};
 use std::collections::HashMap;
 
 /// 收集 Cubence 用量数据（5小时窗口 + 周窗口）
-pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
+pub fn collect(ctx: &CollectionContext) -> Option<SegmentData> {
+    let config = ctx.config;
+
     let segment = config
         .segments
         .iter()
@@ -19,7 +24,7 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
     }
 
     // 检查是否是 Cubence 服务商，不是则静默跳过
-    let vendor = crate::api::detect_vendor_from_claude_settings();
+    let vendor = ctx.vendor;
     if vendor != VendorType::Cubence {
         return None;
     }
@@ -31,14 +36,14 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
         .and_then(|v| v.as_str())
         .filter(|s| !s.is_empty())
         .map(|s| s.to_string())
-        .or_else(crate::api::get_cubence_api_key_from_claude_settings);
+        .or_else(|| crate::api::credentials::resolve_api_key(VendorType::Cubence));
 
     let api_key = match api_key {
         Some(key) if !key.is_empty() => key,
         _ => {
             return Some(SegmentData {
-                primary: "未配置密钥".to_string(),
-                secondary: String::new(),
+                primary: "未配置密钥".into(),
+                secondary: "".into(),
                 metadata: HashMap::new(),
             });
         }
@@ -54,43 +59,16 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
         .unwrap_or_else(|| "https://cubence.com/api/v1/user/subscription-info".to_string());
 
     // 获取数据
-    let cubence_data = fetch_or_cache(&api_key, &usage_url)?;
+    let fetched = fetch::cubence_usage(&api_key, &usage_url)?;
 
     // 构建显示数据
-    build_segment_data(&cubence_data)
-}
-
-fn fetch_or_cache(api_key: &str, usage_url: &str) -> Option<CubenceData> {
-    let (cached, _) = cache::get_cached_cubence_usage();
-
-    if let Some(mut fresh) = fetch_cubence_sync(api_key, usage_url) {
-        fresh.calculate();
-        let _ = cache::save_cached_cubence_usage(&fresh);
-        Some(fresh)
-    } else if let Some(mut cached_data) = cached {
-        cached_data.calculate();
-        Some(cached_data)
-    } else {
-        None
-    }
+    let mut data = build_segment_data(config, &fetched.data)?;
+    fetched.annotate(&mut data.metadata);
+    Some(data)
 }
 
-fn fetch_cubence_sync(api_key: &str, usage_url: &str) -> Option<CubenceData> {
-    let api_config = ApiConfig {
-        enabled: true,
-        api_key: api_key.to_string(),
-        usage_url: usage_url.to_string(),
-        subscription_url: String::new(),
-        auto_cookie: true, // Cubence 需要 Cookie
-        cookie: None,
-    };
-
-    let client = ApiClient::new(api_config).ok()?;
-    let usage = client.get_usage().ok()?;
-    usage.as_cubence().cloned()
-}
-
-fn build_segment_data(data: &CubenceData) -> Option<SegmentData> {
+fn build_segment_data(config: &Config, data: &CubenceData) -> Option<SegmentData> {
+    let fmt = &config.format;
     let mut metadata = HashMap::new();
 
     // 存储原始数据
@@ -121,26 +99,34 @@ fn build_segment_data(data: &CubenceData) -> Option<SegmentData> {
         format!("{:.1}", data.weekly_percentage),
     );
     metadata.insert("service".to_string(), "cubence".to_string());
+    let level = Level::from_percentage(data.five_hour_percentage.max(data.weekly_percentage));
+    metadata.insert("level".to_string(), level.key().to_string());
 
     // 格式化显示
-    let five_hour_used_fmt = CubenceData::format_tokens(data.five_hour_used);
-    let five_hour_limit_fmt = CubenceData::format_tokens(data.five_hour_limit);
-    let weekly_used_fmt = CubenceData::format_tokens(data.weekly_used);
-    let weekly_limit_fmt = CubenceData::format_tokens(data.weekly_limit);
+    let five_hour_used_fmt = format::tokens_as_money(data.five_hour_used, fmt);
+    let five_hour_limit_fmt = format::tokens_as_money(data.five_hour_limit, fmt);
+    let weekly_used_fmt = format::tokens_as_money(data.weekly_used, fmt);
+    let weekly_limit_fmt = format::tokens_as_money(data.weekly_limit, fmt);
 
     // 计算重置时间
-    let five_hour_reset_str = format_duration(data.get_five_hour_reset_seconds());
-    let weekly_reset_str = format_duration(data.get_weekly_reset_seconds());
+    let five_hour_reset_str =
+        i18n::countdown(data.get_five_hour_reset_seconds(), Phrase::Due, fmt.locale);
+    let weekly_reset_str =
+        i18n::countdown(data.get_weekly_reset_seconds(), Phrase::Due, fmt.locale);
 
     // 主显示：5小时窗口
     let primary = format!(
-        "⏱ {}/{} ({:.0}%)",
-        five_hour_used_fmt, five_hour_limit_fmt, data.five_hour_percentage
+        "{}{}/{} ({:.0}%)",
+        icons::prefix(config, SegmentId::CubenceUsage, Glyph::Timer),
+        five_hour_used_fmt,
+        five_hour_limit_fmt,
+        data.five_hour_percentage
     );
 
     // 次要显示：周窗口 + 重置时间
     let secondary = format!(
-        "📅 {}/{} ({:.0}%) | 5h重置: {} | 周重置: {}",
+        "{}{}/{} ({:.0}%) | 5h重置: {} | 周重置: {}",
+        icons::prefix(config, SegmentId::CubenceUsage, Glyph::Calendar),
         weekly_used_fmt,
         weekly_limit_fmt,
         data.weekly_percentage,
@@ -149,28 +135,8 @@ fn build_segment_data(data: &CubenceData) -> Option<SegmentData> {
     );
 
     Some(SegmentData {
-        primary,
-        secondary,
+        primary: primary.into(),
+        secondary: secondary.into(),
         metadata,
     })
 }
-
-/// 格式化持续时间
-fn format_duration(seconds: i64) -> String {
-    if seconds <= 0 {
-        return "已到期".to_string();
-    }
-
-    let hours = seconds / 3600;
-    let minutes = (seconds % 3600) / 60;
-
-    if hours > 24 {
-        let days = hours / 24;
-        let remaining_hours = hours % 24;
-        format!("{}天{}h", days, remaining_hours)
-    } else if hours > 0 {
-        format!("{}h{}m", hours, minutes)
-    } else {
-        format!("{}m", minutes)
-    }
-}
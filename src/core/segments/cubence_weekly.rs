@@ -1,14 +1,18 @@
 //! Cubence 周窗口段
 //! 显示周滚动窗口的用量和重置时间（带进度条）
 
-use crate::api::{cache, client::ApiClient, ApiConfig, CubenceData, VendorType};
+use crate::api::{fetch, CubenceData, VendorType};
 use crate::config::Config;
-use crate::config::InputData;
-use crate::core::segments::SegmentData;
+use crate::core::palette::{self, Level, Role};
+use crate::core::segments::{CollectionContext, SegmentData};
+use crate::utils::format;
+use crate::utils::i18n::{self, Phrase};
 use std::collections::HashMap;
 
 /// 收集 Cubence 周窗口数据
-pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
+pub fn collect(ctx: &CollectionContext) -> Option<SegmentData> {
+    let (config, input) = (ctx.config, ctx.input);
+
     let segment = config
         .segments
         .iter()
@@ -19,7 +23,7 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
     }
 
     // 检查是否是 Cubence 服务商，不是则静默跳过
-    let vendor = crate::api::detect_vendor_from_claude_settings();
+    let vendor = ctx.vendor;
     if vendor != VendorType::Cubence {
         return None;
     }
@@ -31,14 +35,14 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
         .and_then(|v| v.as_str())
         .filter(|s| !s.is_empty())
         .map(|s| s.to_string())
-        .or_else(crate::api::get_cubence_api_key_from_claude_settings);
+        .or_else(|| crate::api::credentials::resolve_api_key(VendorType::Cubence));
 
     let api_key = match api_key {
         Some(key) if !key.is_empty() => key,
         _ => {
             return Some(SegmentData {
-                primary: "未配置".to_string(),
-                secondary: String::new(),
+                primary: "未配置".into(),
+                secondary: "".into(),
                 metadata: HashMap::new(),
             });
         }
@@ -54,40 +58,12 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
         .unwrap_or_else(|| "https://cubence.com/api/v1/user/subscription-info".to_string());
 
     // 获取数据
-    let cubence_data = fetch_or_cache(&api_key, &usage_url)?;
+    let fetched = fetch::cubence_usage(&api_key, &usage_url)?;
 
     // 构建显示数据
-    build_segment_data(&cubence_data)
-}
-
-fn fetch_or_cache(api_key: &str, usage_url: &str) -> Option<CubenceData> {
-    let (cached, _) = cache::get_cached_cubence_usage();
-
-    if let Some(mut fresh) = fetch_cubence_sync(api_key, usage_url) {
-        fresh.calculate();
-        let _ = cache::save_cached_cubence_usage(&fresh);
-        Some(fresh)
-    } else if let Some(mut cached_data) = cached {
-        cached_data.calculate();
-        Some(cached_data)
-    } else {
-        None
-    }
-}
-
-fn fetch_cubence_sync(api_key: &str, usage_url: &str) -> Option<CubenceData> {
-    let api_config = ApiConfig {
-        enabled: true,
-        api_key: api_key.to_string(),
-        usage_url: usage_url.to_string(),
-        subscription_url: String::new(),
-        auto_cookie: true, // Cubence 需要 Cookie
-        cookie: None,
-    };
-
-    let client = ApiClient::new(api_config).ok()?;
-    let usage = client.get_usage().ok()?;
-    usage.as_cubence().cloned()
+    let mut data = build_segment_data(config, &fetched.data, input.progress_bar_width())?;
+    fetched.annotate(&mut data.metadata);
+    Some(data)
 }
 
 /// 生成进度条
@@ -106,32 +82,15 @@ fn make_progress_bar(percentage: f64, width: usize) -> String {
     )
 }
 
-/// 格式化持续时间
-fn format_duration(seconds: i64) -> String {
-    if seconds <= 0 {
-        return "即将重置".to_string();
-    }
-
-    let hours = seconds / 3600;
-    let minutes = (seconds % 3600) / 60;
-
-    if hours > 24 {
-        let days = hours / 24;
-        let remaining_hours = hours % 24;
-        format!("{}d{}h", days, remaining_hours)
-    } else if hours > 0 {
-        format!("{}h{}m", hours, minutes)
-    } else {
-        format!("{}m", minutes)
-    }
-}
-
-/// ANSI 颜色代码
-const GREEN: &str = "\x1b[32m";
-const YELLOW: &str = "\x1b[33m";
+/// ANSI 重置代码
 const RESET: &str = "\x1b[0m";
 
-fn build_segment_data(data: &CubenceData) -> Option<SegmentData> {
+fn build_segment_data(
+    config: &Config,
+    data: &CubenceData,
+    bar_width: usize,
+) -> Option<SegmentData> {
+    let fmt = &config.format;
     let mut metadata = HashMap::new();
 
     // 存储原始数据
@@ -148,21 +107,38 @@ fn build_segment_data(data: &CubenceData) -> Option<SegmentData> {
     metadata.insert("service".to_string(), "cubence".to_string());
 
     // 格式化显示
-    let used_fmt = CubenceData::format_tokens(data.weekly_used);
-    let limit_fmt = CubenceData::format_tokens(data.weekly_limit);
-    let reset_str = format_duration(data.get_weekly_reset_seconds());
-    let progress_bar = make_progress_bar(data.weekly_percentage, 8);
+    let used_fmt = format::tokens_as_money(data.weekly_used, fmt);
+    let limit_fmt = format::tokens_as_money(data.weekly_limit, fmt);
+    let reset_str = i18n::countdown(
+        data.get_weekly_reset_seconds(),
+        Phrase::ResettingSoon,
+        fmt.locale,
+    );
+    // 无障碍模式下用百分比代替进度条字符
+    let progress_bar = if config.accessibility.is_active() {
+        format!("{:.0}%", data.weekly_percentage)
+    } else {
+        make_progress_bar(data.weekly_percentage, bar_width)
+    };
 
-    // 主显示：周 [进度条(绿色)] 数字(黄色) (重置时间)
+    // 主显示：周 [进度条(状态色)] 数字(accent 色) (重置时间)
     // 格式: 周 █████░░░ $121.0/$200.0 (3d5h)
+    let level = Level::from_percentage(data.weekly_percentage);
     let primary = format!(
         "周 {}{}{} {}{}/{}{} ({})",
-        GREEN, progress_bar, RESET, YELLOW, used_fmt, limit_fmt, RESET, reset_str
+        palette::escape(&config.style, level.role()),
+        progress_bar,
+        RESET,
+        palette::escape(&config.style, Role::Accent),
+        used_fmt,
+        limit_fmt,
+        RESET,
+        reset_str
     );
 
     Some(SegmentData {
-        primary,
-        secondary: String::new(),
+        primary: primary.into(),
+        secondary: "".into(),
         metadata,
     })
 }
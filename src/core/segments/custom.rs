@@ -0,0 +1,135 @@
+//! 自定义命令段
+//! 运行一条 shell 命令（或插件目录中的脚本），显示输出的第一行。
+//!
+//! 命令来源按优先级：
+//! 1. 项目 `.uucode.toml` 的 `[custom] command`，仅在 `uucode trust` 信任过
+//!    该文件当前内容后才会执行，否则段内提示未信任
+//! 2. config.toml 中本段的 `command` 选项
+//! 3. config.toml 中本段的 `script` 选项，指向 `uucode segment install`
+//!    安装到插件目录的脚本
+//!
+//! `timeout_ms` 限制执行时间（默认 1000）；`sandbox = true` 时在 Unix 上以
+//! 清空的环境变量运行，Linux 上还会尽量断开网络。
+
+use crate::config::project::{self, ProjectConfig};
+use crate::config::SegmentId;
+use crate::core::segments::{CollectionContext, SegmentData};
+use crate::utils::subprocess::Subprocess;
+use crate::utils::trust::{self, Trust};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const DEFAULT_TIMEOUT_MS: u64 = 1000;
+/// 输出超过该字符数时截断
+const MAX_OUTPUT_CHARS: usize = 80;
+
+/// 要执行的内容
+enum Source {
+    Shell(String),
+    Script(PathBuf),
+}
+
+pub fn collect(ctx: &CollectionContext) -> Option<SegmentData> {
+    let (config, input) = (ctx.config, ctx.input);
+
+    let segment = config.segments.iter().find(|s| s.id == SegmentId::Custom)?;
+    if !segment.enabled {
+        return None;
+    }
+
+    let workspace = Path::new(&input.workspace.current_dir);
+    let mut metadata = HashMap::new();
+
+    let mut source = None;
+    if let Some(file) = project::find(workspace) {
+        let project_command = ProjectConfig::load(&file)
+            .ok()
+            .and_then(|p| p.commands().first().map(|c| c.to_string()));
+        if let Some(command) = project_command {
+            match trust::check(&file) {
+                Trust::Trusted => {
+                    metadata.insert("source".to_string(), "project".to_string());
+                    source = Some(Source::Shell(command));
+                }
+                state => return Some(untrusted(state)),
+            }
+        }
+    }
+
+    let source = source
+        .or_else(|| {
+            segment
+                .options
+                .get("command")
+                .and_then(|v| v.as_str())
+                .filter(|c| !c.trim().is_empty())
+                .map(|c| Source::Shell(c.to_string()))
+        })
+        .or_else(|| {
+            let name = segment.options.get("script").and_then(|v| v.as_str())?;
+            // 只接受插件目录下的文件名，不接受路径
+            if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+                return None;
+            }
+            let path = crate::utils::paths::plugins_dir()?.join(name);
+            path.is_file().then_some(Source::Script(path))
+        })?;
+
+    let timeout = segment
+        .options
+        .get("timeout_ms")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_TIMEOUT_MS);
+    let sandbox = segment
+        .options
+        .get("sandbox")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let output = run(&source, workspace, Duration::from_millis(timeout), sandbox)?;
+    let line = output.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let primary = if line.chars().count() > MAX_OUTPUT_CHARS {
+        let mut cut: String = line.chars().take(MAX_OUTPUT_CHARS - 1).collect();
+        cut.push('…');
+        cut
+    } else {
+        line.to_string()
+    };
+
+    Some(SegmentData {
+        primary: primary.into(),
+        secondary: "".into(),
+        metadata,
+    })
+}
+
+/// 项目文件要求执行命令但未被信任时显示的提示
+fn untrusted(state: Trust) -> SegmentData {
+    let (primary, reason) = match state {
+        Trust::Changed => (".uucode.toml 已变更，需重新信任", "changed"),
+        _ => ("未信任的 .uucode.toml", "untrusted"),
+    };
+    let mut metadata = HashMap::new();
+    metadata.insert("trust".to_string(), reason.to_string());
+    SegmentData {
+        primary: format!("🔒 {}", primary).into(),
+        secondary: "· uucode trust".into(),
+        metadata,
+    }
+}
+
+/// 执行命令并返回标准输出，超时或失败返回 None
+fn run(source: &Source, dir: &Path, timeout: Duration, sandbox: bool) -> Option<String> {
+    let command = match source {
+        Source::Shell(line) => Subprocess::shell(line),
+        Source::Script(path) => Subprocess::new(path),
+    };
+    let output = command
+        .current_dir(dir)
+        .with_timeout(timeout)
+        .with_sandbox(sandbox)
+        .run()
+        .ok()?;
+    output.success().then_some(output.stdout)
+}
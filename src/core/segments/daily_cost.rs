@@ -0,0 +1,91 @@
+//! 本地花费段
+//! 汇总 `~/.claude/projects/` 下所有 Claude Code 对话记录中的 token 用量，按模型单价
+//! 估算今日和本周（周一起）的花费，例如 "今日 $3.21 | 本周 $18.40"，不请求任何服务商接口。
+//!
+//! 解析结果按文件增量缓存，见 [`crate::core::transcript::daily`]。
+
+use crate::config::{ModelConfig, SegmentId};
+use crate::core::palette::Level;
+use crate::core::segments::{CollectionContext, SegmentData};
+use crate::core::transcript::daily;
+use crate::utils::{clock, format};
+use chrono::Datelike;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// 每次渲染用于解析新增记录的默认时间，首次扫描未完成时下次接着解析
+const DEFAULT_SCAN_BUDGET_MS: u64 = 300;
+
+/// 收集今日和本周的本地花费
+pub fn collect(ctx: &CollectionContext) -> Option<SegmentData> {
+    let config = ctx.config;
+    let segment = config
+        .segments
+        .iter()
+        .find(|s| s.id == SegmentId::DailyCost)?;
+    if !segment.enabled {
+        return None;
+    }
+    let option = |key: &str| segment.options.get(key);
+
+    let projects_dir = crate::utils::paths::claude_dir()?.join("projects");
+    let budget = option("scan_budget_ms")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_SCAN_BUDGET_MS);
+    let now = clock::local_now();
+    let scan = daily::scan(
+        &projects_dir,
+        now.timestamp(),
+        Duration::from_millis(budget),
+    );
+
+    // 当地零点和本周一零点
+    let today_start = now
+        .date_naive()
+        .and_hms_opt(0, 0, 0)?
+        .and_local_timezone(*now.offset())
+        .single()?
+        .timestamp();
+    let week_start = today_start - i64::from(now.weekday().num_days_from_monday()) * 86_400;
+    let end = now.timestamp() + 1;
+
+    let models = ModelConfig::load();
+    let today = scan.period(today_start, end, &models);
+    let week = scan.period(week_start, end, &models);
+    let fmt = &config.format;
+
+    let mut metadata = HashMap::new();
+    metadata.insert("today_cost".to_string(), format!("{:.2}", today.cost));
+    metadata.insert("week_cost".to_string(), format!("{:.2}", week.cost));
+    metadata.insert("today_tokens".to_string(), today.tokens.to_string());
+    metadata.insert("week_tokens".to_string(), week.tokens.to_string());
+    metadata.insert("today_responses".to_string(), today.responses.to_string());
+    if week.unpriced_tokens > 0 {
+        metadata.insert(
+            "unpriced_tokens".to_string(),
+            week.unpriced_tokens.to_string(),
+        );
+    }
+    if !scan.complete {
+        metadata.insert("scanning".to_string(), "true".to_string());
+    }
+    if let Some(limit) = option("daily_budget")
+        .and_then(|v| v.as_f64())
+        .filter(|limit| *limit > 0.0)
+    {
+        let percentage = today.cost / limit * 100.0;
+        metadata.insert("used_percentage".to_string(), format!("{:.1}", percentage));
+        metadata.insert(
+            "level".to_string(),
+            Level::from_percentage(percentage).key().to_string(),
+        );
+    }
+
+    // 首次扫描还没读完全部记录时，数字只会偏小
+    let pending = if scan.complete { "" } else { "…" };
+    Some(SegmentData {
+        primary: format!("今日 {}{}", format::money(today.cost, 2, fmt), pending).into(),
+        secondary: format!("本周 {}{}", format::money(week.cost, 2, fmt), pending).into(),
+        metadata,
+    })
+}
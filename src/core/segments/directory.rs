@@ -47,9 +47,20 @@ impl Segment for DirectorySegment {
         let mut metadata = HashMap::new();
         metadata.insert("full_path".to_string(), current_dir.clone());
 
+        // Sessions with added directories show how many other roots there are
+        let roots = input.workspace.roots();
+        metadata.insert("roots".to_string(), roots.len().to_string());
+        if let Some(root) = input.workspace.active_root() {
+            metadata.insert("active_root".to_string(), root.to_string());
+        }
+        let secondary = match roots.len() {
+            0 | 1 => String::new(),
+            n => format!("+{}", n - 1),
+        };
+
         Some(SegmentData {
-            primary: dir_name,
-            secondary: String::new(),
+            primary: dir_name.into(),
+            secondary: secondary.into(),
             metadata,
         })
     }
@@ -1,7 +1,9 @@
 use super::{Segment, SegmentData};
 use crate::config::{InputData, SegmentId};
+use crate::utils::subprocess::{Output, Subprocess};
 use std::collections::HashMap;
-use std::process::Command;
+use std::path::Path;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub struct GitInfo {
@@ -21,6 +23,8 @@ pub enum GitStatus {
 
 pub struct GitSegment {
     show_sha: bool,
+    /// Limit for each git invocation
+    timeout: Duration,
 }
 
 impl Default for GitSegment {
@@ -31,7 +35,10 @@ impl Default for GitSegment {
 
 impl GitSegment {
     pub fn new() -> Self {
-        Self { show_sha: false }
+        Self {
+            show_sha: false,
+            timeout: Duration::from_millis(1000),
+        }
     }
 
     pub fn with_sha(mut self, show_sha: bool) -> Self {
@@ -39,6 +46,26 @@ impl GitSegment {
         self
     }
 
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Directory git runs in: in sessions with added roots, the one holding
+    /// the file Claude Code touched last, since that is the repo being
+    /// worked on; otherwise the current directory
+    fn working_dir(input: &InputData) -> String {
+        let current_dir = &input.workspace.current_dir;
+        if input.workspace.added_dirs.is_empty() {
+            return current_dir.clone();
+        }
+        crate::core::transcript::last_touched_file(Path::new(&input.transcript_path))
+            .and_then(|file| Some(Path::new(&file).parent()?.to_path_buf()))
+            .filter(|dir| dir.is_dir())
+            .map(|dir| dir.to_string_lossy().into_owned())
+            .unwrap_or_else(|| current_dir.clone())
+    }
+
     fn get_git_info(&self, working_dir: &str) -> Option<GitInfo> {
         if !self.is_git_repository(working_dir) {
             return None;
@@ -64,54 +91,37 @@ impl GitSegment {
         })
     }
 
-    fn is_git_repository(&self, working_dir: &str) -> bool {
-        Command::new("git")
-            .args(["rev-parse", "--git-dir"])
+    fn git(&self, working_dir: &str, args: &[&str]) -> Option<Output> {
+        Subprocess::new("git")
+            .args(args)
             .current_dir(working_dir)
-            .output()
-            .map(|output| output.status.success())
-            .unwrap_or(false)
+            .with_timeout(self.timeout)
+            .run()
+            .ok()
     }
 
-    fn get_branch(&self, working_dir: &str) -> Option<String> {
-        if let Ok(output) = Command::new("git")
-            .args(["branch", "--show-current"])
-            .current_dir(working_dir)
-            .output()
-        {
-            if output.status.success() {
-                let branch = String::from_utf8(output.stdout).ok()?.trim().to_string();
-                if !branch.is_empty() {
-                    return Some(branch);
-                }
-            }
-        }
-
-        if let Ok(output) = Command::new("git")
-            .args(["symbolic-ref", "--short", "HEAD"])
-            .current_dir(working_dir)
-            .output()
-        {
-            if output.status.success() {
-                let branch = String::from_utf8(output.stdout).ok()?.trim().to_string();
-                if !branch.is_empty() {
-                    return Some(branch);
-                }
-            }
-        }
+    fn is_git_repository(&self, working_dir: &str) -> bool {
+        self.git(working_dir, &["rev-parse", "--git-dir"])
+            .is_some_and(|output| output.success())
+    }
 
-        None
+    fn get_branch(&self, working_dir: &str) -> Option<String> {
+        [
+            &["branch", "--show-current"][..],
+            &["symbolic-ref", "--short", "HEAD"][..],
+        ]
+        .iter()
+        .find_map(|args| {
+            self.git(working_dir, args)?
+                .stdout_trimmed()
+                .map(str::to_string)
+        })
     }
 
     fn get_status(&self, working_dir: &str) -> GitStatus {
-        let output = Command::new("git")
-            .args(["status", "--porcelain"])
-            .current_dir(working_dir)
-            .output();
-
-        match output {
-            Ok(output) if output.status.success() => {
-                let status_text = String::from_utf8(output.stdout).unwrap_or_default();
+        match self.git(working_dir, &["status", "--porcelain"]) {
+            Some(output) if output.success() => {
+                let status_text = output.stdout;
 
                 if status_text.trim().is_empty() {
                     return GitStatus::Clean;
@@ -137,45 +147,25 @@ impl GitSegment {
     }
 
     fn get_commit_count(&self, working_dir: &str, range: &str) -> u32 {
-        let output = Command::new("git")
-            .args(["rev-list", "--count", range])
-            .current_dir(working_dir)
-            .output();
-
-        match output {
-            Ok(output) if output.status.success() => String::from_utf8(output.stdout)
-                .ok()
-                .and_then(|s| s.trim().parse().ok())
-                .unwrap_or(0),
-            _ => 0,
-        }
+        self.git(working_dir, &["rev-list", "--count", range])
+            .and_then(|output| output.stdout_trimmed()?.parse().ok())
+            .unwrap_or(0)
     }
 
     fn get_sha(&self, working_dir: &str) -> Option<String> {
-        let output = Command::new("git")
-            .args(["rev-parse", "--short=7", "HEAD"])
-            .current_dir(working_dir)
-            .output()
-            .ok()?;
-
-        if output.status.success() {
-            let sha = String::from_utf8(output.stdout).ok()?.trim().to_string();
-            if sha.is_empty() {
-                None
-            } else {
-                Some(sha)
-            }
-        } else {
-            None
-        }
+        self.git(working_dir, &["rev-parse", "--short=7", "HEAD"])?
+            .stdout_trimmed()
+            .map(str::to_string)
     }
 }
 
 impl Segment for GitSegment {
     fn collect(&self, input: &InputData) -> Option<SegmentData> {
-        let git_info = self.get_git_info(&input.workspace.current_dir)?;
+        let working_dir = Self::working_dir(input);
+        let git_info = self.get_git_info(&working_dir)?;
 
         let mut metadata = HashMap::new();
+        metadata.insert("repo_dir".to_string(), working_dir);
         metadata.insert("branch".to_string(), git_info.branch.clone());
         metadata.insert("status".to_string(), format!("{:?}", git_info.status));
         metadata.insert("ahead".to_string(), git_info.ahead.to_string());
@@ -206,8 +196,8 @@ impl Segment for GitSegment {
         }
 
         Some(SegmentData {
-            primary,
-            secondary: status_parts.join(" "),
+            primary: primary.into(),
+            secondary: status_parts.join(" ").into(),
             metadata,
         })
     }
@@ -1,26 +1,51 @@
+pub mod api_errors;
+pub mod block_usage;
+pub mod context_advisor;
 pub mod context_window;
 pub mod cost;
+#[cfg(feature = "cubence")]
 pub mod cubence_balance;
+#[cfg(feature = "cubence")]
 pub mod cubence_five_hour;
+#[cfg(feature = "cubence")]
 pub mod cubence_latency;
+#[cfg(feature = "cubence")]
 pub mod cubence_load_status;
+#[cfg(feature = "cubence")]
 pub mod cubence_multiplier;
+#[cfg(feature = "cubence")]
 pub mod cubence_status;
+#[cfg(feature = "cubence")]
 pub mod cubence_subscription;
+#[cfg(feature = "cubence")]
 pub mod cubence_usage;
+#[cfg(feature = "cubence")]
 pub mod cubence_weekly;
+pub mod custom;
+pub mod daily_cost;
 pub mod directory;
 pub mod git;
 pub mod model;
+pub mod one_api_usage;
 pub mod output_style;
+pub mod quota_pacing;
+pub mod runtime;
 pub mod session;
+pub mod summary;
+#[cfg(feature = "self-update")]
 pub mod update;
 pub mod usage;
+#[cfg(feature = "uucode")]
 pub mod uucode_status;
+#[cfg(feature = "uucode")]
 pub mod uucode_subscription;
+#[cfg(feature = "uucode")]
 pub mod uucode_usage;
+pub mod vendor_usage;
 
-use crate::config::{InputData, SegmentId};
+use crate::api::VendorType;
+use crate::config::{Config, InputData, SegmentId};
+use std::borrow::Cow;
 use std::collections::HashMap;
 
 // New Segment trait for data collection only
@@ -29,20 +54,84 @@ pub trait Segment {
     fn id(&self) -> SegmentId;
 }
 
+/// Texts are `Cow` so fixed labels such as "未配置" are not allocated on
+/// every render
 #[derive(Debug, Clone)]
 pub struct SegmentData {
-    pub primary: String,
-    pub secondary: String,
+    pub primary: Cow<'static, str>,
+    pub secondary: Cow<'static, str>,
     pub metadata: HashMap<String, String>,
 }
 
+/// What collectors get for one render: the config, Claude Code's payload
+/// and the data of the segments collected before them.
+///
+/// Segments are collected in parallel. One that builds on others declares
+/// them in [`dependencies`] and is started once they are done, with their
+/// data published here; the summary, assembled last, sees every segment.
+pub struct CollectionContext<'a> {
+    pub config: &'a Config,
+    pub input: &'a InputData,
+    /// Vendor detected once for the whole render
+    pub vendor: VendorType,
+    published: HashMap<SegmentId, SegmentData>,
+}
+
+impl<'a> CollectionContext<'a> {
+    pub fn new(config: &'a Config, input: &'a InputData, vendor: VendorType) -> Self {
+        Self {
+            config,
+            input,
+            vendor,
+            published: HashMap::new(),
+        }
+    }
+
+    /// Make a segment's data available to the segments collected after it
+    pub fn publish(&mut self, id: SegmentId, data: &SegmentData) {
+        self.published.insert(id, data.clone());
+    }
+
+    /// Data of an earlier segment, `None` if it was not collected or
+    /// produced nothing
+    pub fn segment(&self, id: SegmentId) -> Option<&SegmentData> {
+        self.published.get(&id)
+    }
+
+    /// One metadata value of an earlier segment
+    pub fn metadata(&self, id: SegmentId, key: &str) -> Option<&str> {
+        self.segment(id)?.metadata.get(key).map(String::as_str)
+    }
+}
+
+/// Segments `id` builds on; it is collected after them. The summary's
+/// sources come from its rules instead.
+pub fn dependencies(id: SegmentId) -> &'static [SegmentId] {
+    match id {
+        // Reads the usage caches these segments refresh
+        SegmentId::QuotaPacing => &[
+            SegmentId::UucodeUsage,
+            SegmentId::CubenceUsage,
+            SegmentId::CubenceFiveHour,
+            SegmentId::CubenceWeekly,
+            SegmentId::CubenceBalance,
+        ],
+        _ => &[],
+    }
+}
+
 // Re-export all segment types
+pub use api_errors::ApiErrorsSegment;
+pub use context_advisor::ContextAdvisorSegment;
 pub use context_window::ContextWindowSegment;
 pub use cost::CostSegment;
 pub use directory::DirectorySegment;
 pub use git::GitSegment;
 pub use model::ModelSegment;
 pub use output_style::OutputStyleSegment;
+pub use runtime::RuntimeSegment;
 pub use session::SessionSegment;
+pub use summary::SummarySegment;
+#[cfg(feature = "self-update")]
 pub use update::UpdateSegment;
 pub use usage::UsageSegment;
@@ -2,16 +2,23 @@ use super::{Segment, SegmentData};
 use crate::config::{InputData, ModelConfig, SegmentId};
 use std::collections::HashMap;
 
-/// ANSI 颜色代码
-const GOLD: &str = "\x1b[38;5;220m";
+/// ANSI 重置代码
 const RESET: &str = "\x1b[0m";
 
 #[derive(Default)]
-pub struct ModelSegment;
+pub struct ModelSegment {
+    /// 倍率的颜色（主题的 accent 色）
+    accent: String,
+}
 
 impl ModelSegment {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    pub fn with_accent(mut self, escape: String) -> Self {
+        self.accent = escape;
+        self
     }
 }
 
@@ -28,14 +35,14 @@ impl Segment for ModelSegment {
 
         let primary = if let Some(mult) = multiplier_suffix {
             metadata.insert("has_ansi_colors".to_string(), "true".to_string());
-            format!("{}{}(x{}){}", model_name, GOLD, mult, RESET)
+            format!("{}{}(x{}){}", model_name, self.accent, mult, RESET)
         } else {
             model_name
         };
 
         Some(SegmentData {
-            primary,
-            secondary: String::new(),
+            primary: primary.into(),
+            secondary: "".into(),
             metadata,
         })
     }
@@ -59,6 +66,7 @@ impl ModelSegment {
     }
 
     /// 获取 Cubence 倍率（如果是 Cubence 服务商）
+    #[cfg(feature = "cubence")]
     fn get_cubence_multiplier(&self) -> Option<f64> {
         use crate::api::VendorType;
 
@@ -71,4 +79,9 @@ impl ModelSegment {
         // 尝试从 cubence_multiplier 模块获取倍率
         super::cubence_multiplier::get_multiplier()
     }
+
+    #[cfg(not(feature = "cubence"))]
+    fn get_cubence_multiplier(&self) -> Option<f64> {
+        None
+    }
 }
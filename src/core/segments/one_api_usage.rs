@@ -0,0 +1,95 @@
+//! one-api / new-api 用量段
+//! 显示自建网关 `/api/user/self` 中的额度，例如 "$2.50 / $10 (25%)"，
+//! 附带剩余额度、用户分组和请求次数。
+//!
+//! 网关地址和访问令牌来自 config.toml 的 `[oneapi]`；当前服务商不是该网关时静默跳过。
+
+use crate::api::credentials::{self, CredentialSource};
+use crate::api::{fetch, VendorType};
+use crate::config::SegmentId;
+use crate::core::icons::{self, Glyph};
+use crate::core::palette::Level;
+use crate::core::segments::{CollectionContext, SegmentData};
+use crate::utils::format;
+use std::collections::HashMap;
+
+/// 收集 one-api 用量
+pub fn collect(ctx: &CollectionContext) -> Option<SegmentData> {
+    let config = ctx.config;
+
+    let segment = config
+        .segments
+        .iter()
+        .find(|s| s.id == SegmentId::OneApiUsage)?;
+
+    if !segment.enabled || ctx.vendor != VendorType::OneApi {
+        return None;
+    }
+    let oneapi = &config.oneapi;
+
+    // /api/user/self 只认系统访问令牌；环境变量中的 sk- 密钥是转发用的，这里不使用
+    let access_token = Some(oneapi.access_token.clone())
+        .filter(|s| !s.is_empty())
+        .or_else(|| credentials::lookup(CredentialSource::ApiKeysFile, VendorType::OneApi))
+        .or_else(|| credentials::lookup(CredentialSource::Keyring, VendorType::OneApi));
+    let Some(access_token) = access_token else {
+        return Some(SegmentData {
+            primary: "未配置访问令牌".into(),
+            secondary: "".into(),
+            metadata: HashMap::new(),
+        });
+    };
+
+    let fetched = fetch::oneapi_usage(&oneapi.base_url, &access_token, oneapi.user_id)?;
+    let usage = &fetched.data;
+    let fmt = &config.format;
+    let card = icons::prefix(config, SegmentId::OneApiUsage, Glyph::Card);
+    let money = icons::prefix(config, SegmentId::OneApiUsage, Glyph::Money);
+
+    let percentage = usage.percentage_used();
+    let mut metadata = HashMap::new();
+    metadata.insert("used".to_string(), format!("{:.2}", usage.used_usd()));
+    metadata.insert("total".to_string(), format!("{:.2}", usage.total_usd()));
+    metadata.insert(
+        "remaining".to_string(),
+        format!("{:.2}", usage.remaining_usd()),
+    );
+    metadata.insert("used_percentage".to_string(), format!("{:.1}", percentage));
+    metadata.insert("request_count".to_string(), usage.request_count.to_string());
+    if !usage.group.is_empty() {
+        metadata.insert("group".to_string(), usage.group.clone());
+    }
+    let level = if usage.is_exhausted() {
+        Level::Critical
+    } else {
+        Level::from_percentage(percentage)
+    };
+    metadata.insert("level".to_string(), level.key().to_string());
+    fetched.annotate(&mut metadata);
+
+    let primary = format!(
+        "{}{} / {} ({:.0}%)",
+        card,
+        format::money(usage.used_usd(), 2, fmt),
+        format::money(usage.total_usd(), 0, fmt),
+        percentage
+    );
+
+    let mut details = vec![format!(
+        "{}剩余 {}",
+        money,
+        format::money(usage.remaining_usd(), 2, fmt)
+    )];
+    if !usage.group.is_empty() {
+        details.push(usage.group.clone());
+    }
+    if usage.request_count > 0 {
+        details.push(format!("{} 次请求", usage.request_count));
+    }
+
+    Some(SegmentData {
+        primary: primary.into(),
+        secondary: details.join(" | ").into(),
+        metadata,
+    })
+}
@@ -22,8 +22,8 @@ impl Segment for OutputStyleSegment {
         metadata.insert("style_name".to_string(), output_style.name.clone());
 
         Some(SegmentData {
-            primary,
-            secondary: String::new(),
+            primary: primary.into(),
+            secondary: "".into(),
             metadata,
         })
     }
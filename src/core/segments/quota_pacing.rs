@@ -0,0 +1,152 @@
+//! 额度节奏段
+//! 比较订阅窗口已过去的时间和已消耗的额度，例如 "62% used @ 40% time ⚠"，
+//! 帮助用户在大型重构前判断额度是否够用。
+//!
+//! 只读取用量段刚写入的本地缓存，不会额外请求接口。
+
+use crate::api::{cache, UsageData, VendorType};
+use crate::config::SegmentId;
+use crate::core::segments::{CollectionContext, SegmentData};
+use std::collections::HashMap;
+
+const FIVE_HOUR_SECONDS: f64 = 5.0 * 3600.0;
+const WEEK_SECONDS: f64 = 7.0 * 86400.0;
+const DAY_SECONDS: f64 = 86400.0;
+
+/// 一个订阅窗口的消耗情况
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    label: &'static str,
+    /// 已用额度百分比
+    used: f64,
+    /// 窗口已过去的时间百分比
+    elapsed: f64,
+}
+
+impl Window {
+    fn new(label: &'static str, used: f64, remaining_secs: f64, length_secs: f64) -> Option<Self> {
+        if length_secs <= 0.0 || remaining_secs <= 0.0 {
+            return None;
+        }
+        let elapsed = (1.0 - remaining_secs / length_secs).clamp(0.0, 1.0) * 100.0;
+        Some(Self {
+            label,
+            used: used.clamp(0.0, 100.0),
+            elapsed,
+        })
+    }
+
+    /// 超出理想消耗的百分点，负数表示比节奏慢
+    fn ahead(&self) -> f64 {
+        self.used - self.elapsed
+    }
+}
+
+/// 收集额度节奏数据
+pub fn collect(ctx: &CollectionContext) -> Option<SegmentData> {
+    let config = ctx.config;
+
+    let segment = config
+        .segments
+        .iter()
+        .find(|s| s.id == SegmentId::QuotaPacing)?;
+
+    if !segment.enabled {
+        return None;
+    }
+
+    let option_f64 = |key: &str| segment.options.get(key).and_then(|v| v.as_f64());
+    let warning_margin = option_f64("warning_margin").unwrap_or(10.0);
+    let critical_margin = option_f64("critical_margin").unwrap_or(25.0);
+    let uucode_window_days = option_f64("uucode_window_days").unwrap_or(30.0);
+
+    let (windows, cache_age) = match ctx.vendor {
+        VendorType::Cubence => cubence_windows()?,
+        VendorType::Uucode => uucode_windows(uucode_window_days * DAY_SECONDS)?,
+        _ => return None,
+    };
+
+    // 显示节奏最紧张的窗口
+    let window = windows
+        .into_iter()
+        .max_by(|a, b| a.ahead().total_cmp(&b.ahead()))?;
+
+    let (pace, mark) = if window.ahead() >= critical_margin {
+        ("critical", " 🛑")
+    } else if window.ahead() >= warning_margin {
+        ("warning", " ⚠")
+    } else {
+        ("on_track", "")
+    };
+
+    let mut metadata = HashMap::new();
+    metadata.insert("window".to_string(), window.label.to_string());
+    metadata.insert("used_percentage".to_string(), format!("{:.1}", window.used));
+    metadata.insert(
+        "time_percentage".to_string(),
+        format!("{:.1}", window.elapsed),
+    );
+    metadata.insert("pace".to_string(), pace.to_string());
+    metadata.insert(
+        "level".to_string(),
+        if pace == "on_track" { "ok" } else { pace }.to_string(),
+    );
+    if let Some(age) = cache_age {
+        metadata.insert("cache_age_secs".to_string(), age.to_string());
+        if age >= cache::CACHE_FRESH_SECONDS {
+            metadata.insert("stale".to_string(), "true".to_string());
+        }
+    }
+
+    Some(SegmentData {
+        primary: format!(
+            "{:.0}% used @ {:.0}% time{}",
+            window.used, window.elapsed, mark
+        )
+        .into(),
+        secondary: window.label.into(),
+        metadata,
+    })
+}
+
+/// Cubence 的 5 小时窗口和周窗口
+fn cubence_windows() -> Option<(Vec<Window>, Option<u64>)> {
+    let (data, _) = cache::get_cached_cubence_usage();
+    let mut data = data?;
+    data.calculate();
+
+    let windows = [
+        Window::new(
+            "5h",
+            data.five_hour_percentage,
+            data.get_five_hour_reset_seconds() as f64,
+            FIVE_HOUR_SECONDS,
+        ),
+        Window::new(
+            "weekly",
+            data.weekly_percentage,
+            data.get_weekly_reset_seconds() as f64,
+            WEEK_SECONDS,
+        ),
+    ];
+    let windows: Vec<Window> = windows.into_iter().flatten().collect();
+    if windows.is_empty() {
+        return None;
+    }
+    Some((windows, cache::cubence_usage_cache_age()))
+}
+
+/// uucode 的月度订阅窗口
+fn uucode_windows(length_secs: f64) -> Option<(Vec<Window>, Option<u64>)> {
+    let (data, _) = cache::get_cached_usage();
+    let UsageData::NewVendor(mut data) = data? else {
+        return None;
+    };
+    data.calculate();
+
+    // 剩余秒数是请求时的值，需要减去缓存年龄
+    let cache_age = cache::usage_cache_age();
+    let remaining = data.remaining_seconds as f64 - cache_age.unwrap_or(0) as f64;
+    let window = Window::new("monthly", data.percentage_used, remaining, length_secs)?;
+    Some((vec![window], cache_age))
+}
@@ -0,0 +1,58 @@
+use super::{Segment, SegmentData};
+use crate::config::{InputData, SegmentId};
+use crate::core::runtime::{self, RuntimeKind};
+use std::collections::HashMap;
+
+/// Names the environment uucode runs in (WSL distro, devcontainer,
+/// Codespace, SSH host) and the config directory that applies there.
+///
+/// Hidden on a local machine unless `show_local` is set.
+#[derive(Default)]
+pub struct RuntimeSegment {
+    show_local: bool,
+}
+
+impl RuntimeSegment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_show_local(mut self, show_local: bool) -> Self {
+        self.show_local = show_local;
+        self
+    }
+}
+
+impl Segment for RuntimeSegment {
+    fn collect(&self, input: &InputData) -> Option<SegmentData> {
+        let runtime = runtime::detect(&input.workspace.current_dir);
+        if runtime.kind == RuntimeKind::Local && !self.show_local {
+            return None;
+        }
+
+        let mut metadata = HashMap::new();
+        metadata.insert("kind".to_string(), runtime.kind.key().to_string());
+        if let Some(name) = &runtime.name {
+            metadata.insert("name".to_string(), name.clone());
+        }
+        if let Some(dir) = crate::utils::paths::claude_dir() {
+            metadata.insert("claude_dir".to_string(), dir.display().to_string());
+        }
+        metadata.insert(
+            "config_path".to_string(),
+            crate::config::Config::get_config_path()
+                .display()
+                .to_string(),
+        );
+
+        Some(SegmentData {
+            primary: runtime.describe().into(),
+            secondary: "".into(),
+            metadata,
+        })
+    }
+
+    fn id(&self) -> SegmentId {
+        SegmentId::Runtime
+    }
+}
@@ -1,54 +1,68 @@
 use super::{Segment, SegmentData};
-use crate::config::{InputData, SegmentId};
+use crate::config::{InputData, Locale, SegmentId};
+use crate::core::{git_delta, transcript};
+use crate::utils::i18n;
 use std::collections::HashMap;
+use std::path::Path;
 
 #[derive(Default)]
-pub struct SessionSegment;
+pub struct SessionSegment {
+    locale: Locale,
+}
 
 impl SessionSegment {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    pub fn with_locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
     }
 
-    fn format_duration(ms: u64) -> String {
+    pub fn format_duration(ms: u64, locale: Locale) -> String {
         if ms < 1000 {
             format!("{}ms", ms)
-        } else if ms < 60_000 {
-            let seconds = ms / 1000;
-            format!("{}s", seconds)
-        } else if ms < 3_600_000 {
-            let minutes = ms / 60_000;
-            let seconds = (ms % 60_000) / 1000;
-            if seconds == 0 {
-                format!("{}m", minutes)
-            } else {
-                format!("{}m{}s", minutes, seconds)
-            }
         } else {
-            let hours = ms / 3_600_000;
-            let minutes = (ms % 3_600_000) / 60_000;
-            if minutes == 0 {
-                format!("{}h", hours)
-            } else {
-                format!("{}h{}m", hours, minutes)
-            }
+            i18n::span(ms / 1000, locale)
         }
     }
 }
 
 impl Segment for SessionSegment {
     fn collect(&self, input: &InputData) -> Option<SegmentData> {
-        let cost_data = input.cost.as_ref()?;
+        let cost = input.cost.as_ref();
+        let mut metadata = HashMap::new();
 
-        // Primary display: total duration
-        let primary = if let Some(duration) = cost_data.total_duration_ms {
-            Self::format_duration(duration)
-        } else {
-            return None;
+        // Older Claude Code versions send no cost block; time the session
+        // from its transcript instead
+        let duration = match cost.and_then(|c| c.total_duration_ms) {
+            Some(duration) => duration,
+            None => {
+                let started = transcript::first_timestamp(Path::new(&input.transcript_path))?;
+                let elapsed = crate::utils::clock::unix_now() - started.timestamp();
+                metadata.insert("duration_source".to_string(), "transcript".to_string());
+                elapsed.max(0) as u64 * 1000
+            }
         };
+        let primary = Self::format_duration(duration, self.locale);
+
+        // Line changes, measured with git when the payload has none
+        let mut lines_added = cost.and_then(|c| c.total_lines_added);
+        let mut lines_removed = cost.and_then(|c| c.total_lines_removed);
+        if lines_added.is_none() && lines_removed.is_none() {
+            if let Some(delta) =
+                git_delta::session_delta(&input.transcript_path, &input.workspace.current_dir)
+            {
+                lines_added = Some(delta.added);
+                lines_removed = Some(delta.removed);
+                metadata.insert("lines_source".to_string(), "git".to_string());
+                metadata.insert("files_changed".to_string(), delta.files.to_string());
+            }
+        }
 
         // Secondary display: line changes if available
-        let secondary = match (cost_data.total_lines_added, cost_data.total_lines_removed) {
+        let secondary = match (lines_added, lines_removed) {
             (Some(added), Some(removed)) if added > 0 || removed > 0 => {
                 format!("+{} -{}", added, removed)
             }
@@ -61,23 +75,20 @@ impl Segment for SessionSegment {
             _ => String::new(),
         };
 
-        let mut metadata = HashMap::new();
-        if let Some(duration) = cost_data.total_duration_ms {
-            metadata.insert("duration_ms".to_string(), duration.to_string());
-        }
-        if let Some(api_duration) = cost_data.total_api_duration_ms {
+        metadata.insert("duration_ms".to_string(), duration.to_string());
+        if let Some(api_duration) = cost.and_then(|c| c.total_api_duration_ms) {
             metadata.insert("api_duration_ms".to_string(), api_duration.to_string());
         }
-        if let Some(added) = cost_data.total_lines_added {
+        if let Some(added) = lines_added {
             metadata.insert("lines_added".to_string(), added.to_string());
         }
-        if let Some(removed) = cost_data.total_lines_removed {
+        if let Some(removed) = lines_removed {
             metadata.insert("lines_removed".to_string(), removed.to_string());
         }
 
         Some(SegmentData {
-            primary,
-            secondary,
+            primary: primary.into(),
+            secondary: secondary.into(),
             metadata,
         })
     }
@@ -0,0 +1,192 @@
+use super::{CollectionContext, SegmentData};
+use crate::config::{Config, SegmentId};
+use crate::core::icons;
+use crate::core::palette::Level;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// One slot showing the most pressing thing the other segments found.
+///
+/// Rules are tried in order against the data the other segments published
+/// to the [`CollectionContext`] of this render, and the first match is
+/// shown. Source segments do not
+/// need to be enabled: they are collected for the summary either way, so a
+/// bar can consist of little more than this segment.
+pub struct SummarySegment {
+    rules: Vec<SummaryRule>,
+}
+
+/// `rules` entry in the summary segment options
+#[derive(Debug, Clone, Deserialize)]
+pub struct SummaryRule {
+    pub segment: SegmentId,
+    /// Minimum `level` the segment must report
+    #[serde(default)]
+    pub level: Option<String>,
+    /// Metadata key compared with `equals` or `above`
+    #[serde(default)]
+    pub key: Option<String>,
+    #[serde(default)]
+    pub equals: Option<String>,
+    #[serde(default)]
+    pub above: Option<f64>,
+    /// Text shown on a match; `{icon}`, `{primary}`, `{secondary}` and any
+    /// metadata key are filled in. Defaults to `{icon} {primary} {secondary}`.
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+impl SummaryRule {
+    fn new(segment: SegmentId) -> Self {
+        Self {
+            segment,
+            level: None,
+            key: None,
+            equals: None,
+            above: None,
+            text: None,
+        }
+    }
+
+    fn with_level(mut self, level: Level) -> Self {
+        self.level = Some(level.key().to_string());
+        self
+    }
+
+    fn matches(&self, data: &SegmentData) -> bool {
+        if let Some(minimum) = self.level.as_deref().and_then(Level::from_key) {
+            if Level::from_metadata(&data.metadata).is_none_or(|level| level < minimum) {
+                return false;
+            }
+        }
+        let Some(key) = &self.key else {
+            return true;
+        };
+        let Some(value) = data.metadata.get(key) else {
+            return false;
+        };
+        if let Some(expected) = &self.equals {
+            if value != expected {
+                return false;
+            }
+        }
+        if let Some(threshold) = self.above {
+            if !value.parse::<f64>().is_ok_and(|v| v > threshold) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Critical quota, then a filling context window, then failing requests,
+/// then uncommitted changes
+pub fn default_rules() -> Vec<SummaryRule> {
+    let mut rules: Vec<SummaryRule> = [
+        SegmentId::UucodeUsage,
+        SegmentId::CubenceFiveHour,
+        SegmentId::CubenceWeekly,
+        SegmentId::OneApiUsage,
+        SegmentId::VendorUsage,
+        SegmentId::Usage,
+        SegmentId::QuotaPacing,
+    ]
+    .into_iter()
+    .map(|id| SummaryRule::new(id).with_level(Level::Critical))
+    .collect();
+    rules.push(SummaryRule::new(SegmentId::ContextWindow).with_level(Level::Warning));
+    rules.push(SummaryRule::new(SegmentId::ApiErrors).with_level(Level::Critical));
+    rules.push(SummaryRule {
+        key: Some("status".to_string()),
+        equals: Some("Dirty".to_string()),
+        ..SummaryRule::new(SegmentId::Git)
+    });
+    rules
+}
+
+impl SummarySegment {
+    /// The summary segment of `config`, `None` when it is not enabled
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let segment = config
+            .segments
+            .iter()
+            .find(|s| s.id == SegmentId::Summary && s.enabled)?;
+        let rules = match segment.options.get("rules") {
+            Some(rules) => match serde_json::from_value(rules.clone()) {
+                Ok(rules) => rules,
+                Err(e) => {
+                    crate::debug_log!("invalid summary rules: {}", e);
+                    default_rules()
+                }
+            },
+            None => default_rules(),
+        };
+        Some(Self { rules })
+    }
+
+    /// Segments the rules read
+    pub fn sources(&self) -> Vec<SegmentId> {
+        let mut sources = Vec::new();
+        for rule in &self.rules {
+            if !sources.contains(&rule.segment) {
+                sources.push(rule.segment);
+            }
+        }
+        sources
+    }
+
+    /// Copy of `config` with every source segment enabled, so collectors
+    /// that skip disabled segments still run for the summary
+    pub fn with_sources_enabled(&self, config: &Config) -> Config {
+        let sources = self.sources();
+        let mut config = config.clone();
+        for segment in &mut config.segments {
+            if sources.contains(&segment.id) {
+                segment.enabled = true;
+            }
+        }
+        config
+    }
+
+    /// Pick the first rule that matches the segments published so far
+    pub fn summarize(&self, ctx: &CollectionContext) -> Option<SegmentData> {
+        let config = ctx.config;
+        self.rules.iter().enumerate().find_map(|(index, rule)| {
+            let data = ctx
+                .segment(rule.segment)
+                .filter(|data| rule.matches(data))?;
+            let segment = config.segments.iter().find(|s| s.id == rule.segment)?;
+
+            let template = rule
+                .text
+                .as_deref()
+                .unwrap_or("{icon} {primary} {secondary}");
+            let mut text = template
+                .replace("{icon}", &icons::resolve(config, segment, &data.metadata))
+                .replace("{primary}", &data.primary)
+                .replace("{secondary}", &data.secondary);
+            for (key, value) in &data.metadata {
+                text = text.replace(&format!("{{{}}}", key), value);
+            }
+
+            let mut metadata = HashMap::new();
+            metadata.insert("source".to_string(), segment_key(rule.segment));
+            metadata.insert("rule".to_string(), index.to_string());
+            if let Some(level) = data.metadata.get("level") {
+                metadata.insert("level".to_string(), level.clone());
+            }
+            Some(SegmentData {
+                primary: text.trim().to_string().into(),
+                secondary: "".into(),
+                metadata,
+            })
+        })
+    }
+}
+
+fn segment_key(id: SegmentId) -> String {
+    serde_json::to_value(id)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
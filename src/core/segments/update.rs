@@ -17,8 +17,8 @@ impl Segment for UpdateSegment {
         let update_state = UpdateState::load();
 
         update_state.status_text().map(|status_text| SegmentData {
-            primary: status_text,
-            secondary: String::new(),
+            primary: status_text.into(),
+            secondary: "".into(),
             metadata: std::collections::HashMap::new(),
         })
     }
@@ -1,9 +1,17 @@
 use super::{Segment, SegmentData};
 use crate::config::{InputData, SegmentId};
+use crate::core::palette::Level;
 use crate::utils::credentials;
-use chrono::{DateTime, Datelike, Duration, Local, Timelike, Utc};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Timelike, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+/// Admin API version header; the usage and cost reports need an admin key
+/// (`sk-ant-admin...`), not a regular API key
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// A month of daily buckets fits in one page; this only guards the loop
+const MAX_REPORT_PAGES: usize = 4;
 
 #[derive(Debug, Deserialize)]
 struct ApiUsageResponse {
@@ -25,6 +33,48 @@ struct ApiUsageCache {
     cached_at: String,
 }
 
+/// One UTC day of organization usage from the Admin API reports
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct DayTotals {
+    cost_usd: f64,
+    input_tokens: u64,
+    output_tokens: u64,
+    cache_read_tokens: u64,
+    cache_creation_tokens: u64,
+    /// Fetched after the day ended, so it will not change any more
+    #[serde(default)]
+    complete: bool,
+}
+
+impl DayTotals {
+    fn tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens + self.cache_read_tokens + self.cache_creation_tokens
+    }
+}
+
+/// Daily totals of the current month, keyed by UTC date (`YYYY-MM-DD`).
+/// Finished days are fetched once; only today is refreshed.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AdminUsageCache {
+    days: BTreeMap<String, DayTotals>,
+    today_fetched_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReportPage {
+    data: Vec<ReportBucket>,
+    #[serde(default)]
+    has_more: bool,
+    next_page: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReportBucket {
+    starting_at: String,
+    #[serde(default)]
+    results: Vec<serde_json::Value>,
+}
+
 #[derive(Default)]
 pub struct UsageSegment;
 
@@ -50,7 +100,7 @@ impl UsageSegment {
     fn format_reset_time(reset_time_str: Option<&str>) -> String {
         if let Some(time_str) = reset_time_str {
             if let Ok(dt) = DateTime::parse_from_rfc3339(time_str) {
-                let mut local_dt = dt.with_timezone(&Local);
+                let mut local_dt = crate::utils::clock::to_local(dt);
                 if local_dt.minute() > 45 {
                     local_dt += Duration::hours(1);
                 }
@@ -85,12 +135,15 @@ impl UsageSegment {
     }
 
     fn save_cache(&self, cache: &ApiUsageCache) {
+        if crate::utils::readonly::is_enabled() {
+            return;
+        }
         if let Some(cache_path) = Self::get_cache_path() {
             if let Some(parent) = cache_path.parent() {
                 let _ = std::fs::create_dir_all(parent);
             }
             if let Ok(json) = serde_json::to_string_pretty(cache) {
-                let _ = std::fs::write(&cache_path, json);
+                let _ = crate::utils::atomic_write(&cache_path, json);
             }
         }
     }
@@ -105,24 +158,281 @@ impl UsageSegment {
         }
     }
 
-    fn get_claude_code_version() -> String {
-        use std::process::Command;
+    fn get_admin_cache_path() -> Option<std::path::PathBuf> {
+        Some(crate::utils::paths::uucode_dir()?.join(".admin_usage_cache.json"))
+    }
 
-        let output = Command::new("npm")
-            .args(["view", "@anthropic-ai/claude-code", "version"])
-            .output();
+    fn load_admin_cache(&self) -> Option<AdminUsageCache> {
+        let content = std::fs::read_to_string(Self::get_admin_cache_path()?).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn save_admin_cache(&self, cache: &AdminUsageCache) {
+        if crate::utils::readonly::is_enabled() {
+            return;
+        }
+        if let Some(cache_path) = Self::get_admin_cache_path() {
+            if let Some(parent) = cache_path.parent() {
+                let _ = std::fs::create_dir_all(parent);
+            }
+            if let Ok(json) = serde_json::to_string_pretty(cache) {
+                let _ = crate::utils::atomic_write(&cache_path, json);
+            }
+        }
+    }
+
+    fn day_key(day: NaiveDate) -> String {
+        day.format("%Y-%m-%d").to_string()
+    }
+
+    /// Numbers in the reports are sometimes strings (cost amounts are
+    /// decimal strings in cents)
+    fn report_number(value: Option<&serde_json::Value>) -> f64 {
+        match value {
+            Some(serde_json::Value::Number(n)) => n.as_f64().unwrap_or(0.0),
+            Some(serde_json::Value::String(s)) => s.parse().unwrap_or(0.0),
+            _ => 0.0,
+        }
+    }
+
+    /// All daily buckets of one Admin API report from `starting_at` on
+    fn fetch_report(
+        client: &reqwest::blocking::Client,
+        url: &str,
+        admin_key: &str,
+        starting_at: &str,
+    ) -> Option<Vec<ReportBucket>> {
+        let mut buckets = Vec::new();
+        let mut page: Option<String> = None;
+        for _ in 0..MAX_REPORT_PAGES {
+            let mut request = client.get(url).query(&[
+                ("starting_at", starting_at),
+                ("bucket_width", "1d"),
+                ("limit", "31"),
+            ]);
+            if let Some(page) = &page {
+                request = request.query(&[("page", page)]);
+            }
+            let response = request
+                .header("x-api-key", admin_key)
+                .header("anthropic-version", ANTHROPIC_VERSION)
+                .send()
+                .ok()?;
+            if !response.status().is_success() {
+                return None;
+            }
+            let text = crate::api::http::read_text(response).ok()?;
+            let report: ReportPage = serde_json::from_str(&text).ok()?;
+            buckets.extend(report.data);
+            match report.next_page.filter(|_| report.has_more) {
+                Some(next) => page = Some(next),
+                None => break,
+            }
+        }
+        Some(buckets)
+    }
+
+    /// Daily totals from `start` through today, from the messages usage
+    /// report and the cost report
+    fn fetch_admin_days(
+        api_base_url: &str,
+        admin_key: &str,
+        start: NaiveDate,
+        today: NaiveDate,
+        timeout_secs: u64,
+    ) -> Option<BTreeMap<String, DayTotals>> {
+        let client =
+            crate::api::http::client(None, std::time::Duration::from_secs(timeout_secs)).ok()?;
+        let base = api_base_url.trim_end_matches('/');
+        let starting_at = format!("{}T00:00:00Z", Self::day_key(start));
+
+        // Days without buckets had no usage at all
+        let mut days: BTreeMap<String, DayTotals> = start
+            .iter_days()
+            .take_while(|day| *day <= today)
+            .map(|day| {
+                let totals = DayTotals {
+                    complete: day < today,
+                    ..Default::default()
+                };
+                (Self::day_key(day), totals)
+            })
+            .collect();
+        let day_of = |bucket: &ReportBucket| bucket.starting_at.get(..10).map(str::to_string);
+
+        let usage = Self::fetch_report(
+            &client,
+            &format!("{}/v1/organizations/usage_report/messages", base),
+            admin_key,
+            &starting_at,
+        )?;
+        for bucket in &usage {
+            let Some(totals) = day_of(bucket).and_then(|day| days.get_mut(&day)) else {
+                continue;
+            };
+            for result in &bucket.results {
+                let tokens = |key: &str| Self::report_number(result.get(key)) as u64;
+                totals.input_tokens += tokens("uncached_input_tokens");
+                totals.output_tokens += tokens("output_tokens");
+                totals.cache_read_tokens += tokens("cache_read_input_tokens");
+                if let Some(creation) = result.get("cache_creation").and_then(|v| v.as_object()) {
+                    totals.cache_creation_tokens += creation
+                        .values()
+                        .map(|v| Self::report_number(Some(v)) as u64)
+                        .sum::<u64>();
+                }
+            }
+        }
+
+        let cost = Self::fetch_report(
+            &client,
+            &format!("{}/v1/organizations/cost_report", base),
+            admin_key,
+            &starting_at,
+        )?;
+        for bucket in &cost {
+            let Some(totals) = day_of(bucket).and_then(|day| days.get_mut(&day)) else {
+                continue;
+            };
+            for result in &bucket.results {
+                let currency = result.get("currency").and_then(|v| v.as_str());
+                if currency.is_some_and(|c| !c.eq_ignore_ascii_case("USD")) {
+                    continue;
+                }
+                totals.cost_usd += Self::report_number(result.get("amount")) / 100.0;
+            }
+        }
+
+        Some(days)
+    }
 
-        match output {
-            Ok(output) if output.status.success() => {
-                let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                if !version.is_empty() {
-                    return format!("claude-code/{}", version);
+    /// Organization spend and tokens for today and this month from the
+    /// Admin API, used instead of the OAuth utilization when `admin_key` is
+    /// set
+    fn collect_admin(
+        &self,
+        admin_key: &str,
+        api_base_url: &str,
+        cache_duration: u64,
+        timeout_secs: u64,
+        segment_config: Option<&crate::config::SegmentConfig>,
+        config: &crate::config::Config,
+    ) -> Option<SegmentData> {
+        let now = Utc::now();
+        let today = now.date_naive();
+        let month_start = today.with_day(1)?;
+        let month_key = Self::day_key(month_start);
+        let today_key = Self::day_key(today);
+
+        let mut cache = self.load_admin_cache().unwrap_or_default();
+        cache.days.retain(|day, _| *day >= month_key);
+
+        let first_missing = month_start
+            .iter_days()
+            .take_while(|day| *day < today)
+            .find(|day| {
+                !cache
+                    .days
+                    .get(&Self::day_key(*day))
+                    .is_some_and(|totals| totals.complete)
+            });
+        let today_fresh = cache.days.contains_key(&today_key)
+            && cache
+                .today_fetched_at
+                .as_deref()
+                .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+                .is_some_and(|at| {
+                    now.signed_duration_since(at.with_timezone(&Utc))
+                        .num_seconds()
+                        < cache_duration as i64
+                });
+
+        if first_missing.is_some() || !today_fresh {
+            let start = first_missing.unwrap_or(today);
+            match Self::fetch_admin_days(api_base_url, admin_key, start, today, timeout_secs) {
+                Some(days) => {
+                    cache.days.extend(days);
+                    cache.today_fetched_at = Some(now.to_rfc3339());
+                    self.save_admin_cache(&cache);
                 }
+                None if cache.days.is_empty() => return None,
+                None => {}
             }
-            _ => {}
         }
 
-        "claude-code".to_string()
+        let today_totals = cache.days.get(&today_key).cloned().unwrap_or_default();
+        let month_cost: f64 = cache.days.values().map(|d| d.cost_usd).sum();
+        let month_tokens: u64 = cache.days.values().map(DayTotals::tokens).sum();
+        let fmt = &config.format;
+
+        let primary = format!(
+            "{} · {} tok",
+            crate::utils::format::money(today_totals.cost_usd, 2, fmt),
+            crate::utils::format::token_count(today_totals.tokens())
+        );
+        let secondary = format!("· 本月 {}", crate::utils::format::money(month_cost, 2, fmt));
+
+        let mut metadata = HashMap::new();
+        metadata.insert("mode".to_string(), "admin".to_string());
+        metadata.insert(
+            "today_cost".to_string(),
+            format!("{:.2}", today_totals.cost_usd),
+        );
+        metadata.insert("month_cost".to_string(), format!("{:.2}", month_cost));
+        metadata.insert(
+            "today_tokens".to_string(),
+            today_totals.tokens().to_string(),
+        );
+        metadata.insert(
+            "input_tokens".to_string(),
+            today_totals.input_tokens.to_string(),
+        );
+        metadata.insert(
+            "output_tokens".to_string(),
+            today_totals.output_tokens.to_string(),
+        );
+        metadata.insert(
+            "cache_read_tokens".to_string(),
+            today_totals.cache_read_tokens.to_string(),
+        );
+        metadata.insert(
+            "cache_creation_tokens".to_string(),
+            today_totals.cache_creation_tokens.to_string(),
+        );
+        metadata.insert("month_tokens".to_string(), month_tokens.to_string());
+
+        let budget = segment_config
+            .and_then(|sc| sc.options.get("monthly_budget"))
+            .and_then(|v| v.as_f64())
+            .filter(|budget| *budget > 0.0);
+        if let Some(budget) = budget {
+            let percentage = month_cost / budget * 100.0;
+            metadata.insert("used_percentage".to_string(), format!("{:.1}", percentage));
+            metadata.insert(
+                "level".to_string(),
+                Level::from_percentage(percentage).key().to_string(),
+            );
+        }
+
+        Some(SegmentData {
+            primary: primary.into(),
+            secondary: secondary.into(),
+            metadata,
+        })
+    }
+
+    fn get_claude_code_version() -> String {
+        use crate::utils::subprocess::Subprocess;
+
+        let output = Subprocess::new("npm")
+            .args(["view", "@anthropic-ai/claude-code", "version"])
+            .with_timeout(std::time::Duration::from_secs(3))
+            .run();
+
+        match output.as_ref().ok().and_then(|o| o.stdout_trimmed()) {
+            Some(version) => format!("claude-code/{}", version),
+            None => "claude-code".to_string(),
+        }
     }
 
     fn get_proxy_from_settings() -> Option<String> {
@@ -152,28 +462,29 @@ impl UsageSegment {
         let url = format!("{}/api/oauth/usage", api_base_url);
         let user_agent = Self::get_claude_code_version();
 
-        let mut agent_builder = ureq::AgentBuilder::new();
+        let mut client_builder =
+            crate::api::http::builder(None, std::time::Duration::from_secs(timeout_secs));
 
         // Configure proxy from Claude settings if available
         if let Some(proxy_url) = Self::get_proxy_from_settings() {
-            if let Ok(proxy) = ureq::Proxy::new(&proxy_url) {
-                agent_builder = agent_builder.proxy(proxy);
+            if let Ok(proxy) = reqwest::Proxy::all(&proxy_url) {
+                client_builder = client_builder.proxy(proxy);
             }
         }
 
-        let agent = agent_builder.build();
+        let client = client_builder.build().ok()?;
 
-        let response = agent
+        let response = client
             .get(&url)
-            .set("Authorization", &format!("Bearer {}", token))
-            .set("anthropic-beta", "oauth-2025-04-20")
-            .set("User-Agent", &user_agent)
-            .timeout(std::time::Duration::from_secs(timeout_secs))
-            .call()
+            .header("Authorization", format!("Bearer {}", token))
+            .header("anthropic-beta", "oauth-2025-04-20")
+            .header("User-Agent", &user_agent)
+            .send()
             .ok()?;
 
         if response.status() == 200 {
-            response.into_json().ok()
+            let text = crate::api::http::read_text(response).ok()?;
+            serde_json::from_str(&text).ok()
         } else {
             None
         }
@@ -182,8 +493,6 @@ impl UsageSegment {
 
 impl Segment for UsageSegment {
     fn collect(&self, _input: &InputData) -> Option<SegmentData> {
-        let token = credentials::get_oauth_token()?;
-
         // Load config from file to get segment options
         let config = crate::config::Config::load().ok()?;
         let segment_config = config.segments.iter().find(|s| s.id == SegmentId::Usage);
@@ -203,6 +512,22 @@ impl Segment for UsageSegment {
             .and_then(|v| v.as_u64())
             .unwrap_or(2);
 
+        let admin_key = segment_config
+            .and_then(|sc| sc.options.get("admin_key"))
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty());
+        if let Some(admin_key) = admin_key {
+            return self.collect_admin(
+                admin_key,
+                api_base_url,
+                cache_duration,
+                timeout,
+                segment_config,
+                &config,
+            );
+        }
+
+        let token = credentials::get_oauth_token()?;
         let cached_data = self.load_cache();
         let use_cached = cached_data
             .as_ref()
@@ -261,10 +586,16 @@ impl Segment for UsageSegment {
             "seven_day_utilization".to_string(),
             seven_day_util.to_string(),
         );
+        metadata.insert(
+            "level".to_string(),
+            Level::from_percentage(five_hour_util.max(seven_day_util))
+                .key()
+                .to_string(),
+        );
 
         Some(SegmentData {
-            primary,
-            secondary,
+            primary: primary.into(),
+            secondary: secondary.into(),
             metadata,
         })
     }
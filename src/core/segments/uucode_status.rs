@@ -1,8 +1,6 @@
-use crate::config::Config;
-use crate::config::InputData;
-use crate::core::segments::SegmentData;
+use crate::core::segments::{CollectionContext, SegmentData};
 
-pub fn collect(_config: &Config, _input: &InputData) -> Option<SegmentData> {
+pub fn collect(_ctx: &CollectionContext) -> Option<SegmentData> {
     // 不再显示任何内容
     None
 }
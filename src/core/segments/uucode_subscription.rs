@@ -1,39 +1,129 @@
 use crate::api::{cache, client::ApiClient, ApiConfig, VendorType};
-use crate::config::Config;
-use crate::config::InputData;
-use crate::core::segments::SegmentData;
+use crate::core::segments::{CollectionContext, SegmentData};
+use crate::utils::i18n::{self, Phrase};
 use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::fs;
 use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
 
-/// 生成柔和的随机颜色（基于字符串哈希）
-fn get_soft_color(text: &str) -> String {
-    let mut hasher = DefaultHasher::new();
-    text.hash(&mut hasher);
-    let hash = hasher.finish();
-
-    // 定义一组柔和的颜色（RGB格式）
-    let soft_colors = [
-        (150, 180, 220), // 柔和蓝
-        (180, 150, 200), // 柔和紫
-        (200, 170, 150), // 柔和橙
-        (150, 200, 180), // 柔和青
-        (220, 180, 150), // 柔和棕
-        (180, 200, 150), // 柔和绿
-        (200, 150, 180), // 柔和粉
-        (170, 190, 200), // 柔和灰蓝
-    ];
-
-    let idx = (hash % soft_colors.len() as u64) as usize;
-    let (r, g, b) = soft_colors[idx];
+/// 柔和的颜色（RGB格式）
+const SOFT_COLORS: [(u8, u8, u8); 8] = [
+    (150, 180, 220), // 柔和蓝
+    (180, 150, 200), // 柔和紫
+    (200, 170, 150), // 柔和橙
+    (150, 200, 180), // 柔和青
+    (220, 180, 150), // 柔和棕
+    (180, 200, 150), // 柔和绿
+    (200, 150, 180), // 柔和粉
+    (170, 190, 200), // 柔和灰蓝
+];
 
+/// 计划名对应的初始颜色序号（基于字符串哈希）
+///
+/// 确定性模式下改用 FNV-1a，结果不随 Rust 版本的哈希实现变化
+fn seed_index(plan: &str) -> usize {
+    let hash = if crate::utils::clock::is_deterministic() {
+        fnv1a(plan)
+    } else {
+        let mut hasher = DefaultHasher::new();
+        plan.hash(&mut hasher);
+        hasher.finish()
+    };
+    (hash % SOFT_COLORS.len() as u64) as usize
+}
+
+fn soft_color(index: usize) -> String {
+    let (r, g, b) = SOFT_COLORS[index % SOFT_COLORS.len()];
     format!("\x1b[38;2;{};{};{}m", r, g, b)
 }
 
+/// 计划与颜色的对应关系（cache/plan_colors.json）
+///
+/// 同一次渲染中并列的计划不会撞色；选定的颜色会保存下来，之后的会话里
+/// 同一计划保持同一颜色。确定性模式下不读也不写保存的记录。
+#[derive(Default)]
+struct PlanColors {
+    saved: HashMap<String, usize>,
+    /// 本次渲染已使用的颜色序号
+    taken: Vec<usize>,
+    dirty: bool,
+}
+
+impl PlanColors {
+    fn path() -> Option<PathBuf> {
+        crate::utils::paths::cache_dir().map(|dir| dir.join("plan_colors.json"))
+    }
+
+    fn load() -> Self {
+        if crate::utils::clock::is_deterministic() {
+            return Self::default();
+        }
+        let saved = Self::path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            saved,
+            ..Self::default()
+        }
+    }
+
+    /// 为 `plan` 选择颜色：优先沿用保存的颜色，其次从哈希位置起找第一个
+    /// 本次未用、也不属于其他计划的颜色，最后退而只求本次不重复
+    fn assign(&mut self, plan: &str) -> String {
+        let len = SOFT_COLORS.len();
+        let saved = self.saved.get(plan).map(|index| index % len);
+        let index = saved
+            .filter(|index| !self.taken.contains(index))
+            .or_else(|| {
+                let seed = seed_index(plan);
+                let candidates = || (0..len).map(|offset| (seed + offset) % len);
+                let owned_by_other = |index: &usize| {
+                    self.saved
+                        .iter()
+                        .any(|(other, saved)| other != plan && saved % len == *index)
+                };
+                candidates()
+                    .find(|index| !self.taken.contains(index) && !owned_by_other(index))
+                    .or_else(|| candidates().find(|index| !self.taken.contains(index)))
+            })
+            .unwrap_or_else(|| seed_index(plan));
+
+        self.taken.push(index);
+        if saved != Some(index) {
+            self.saved.insert(plan.to_string(), index);
+            self.dirty = true;
+        }
+        soft_color(index)
+    }
+
+    fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.dirty || crate::utils::clock::is_deterministic() {
+            return Ok(());
+        }
+        let path = Self::path().ok_or("Could not find home directory")?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        crate::utils::atomic_write(path, serde_json::to_string_pretty(&self.saved)?)?;
+        Ok(())
+    }
+}
+
+/// 64 位 FNV-1a 哈希
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
 /// ANSI 重置代码
 const RESET: &str = "\x1b[0m";
 
-pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
+pub fn collect(ctx: &CollectionContext) -> Option<SegmentData> {
+    let config = ctx.config;
+
     // Get API config from segment options
     let segment = config
         .segments
@@ -45,7 +135,7 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
     }
 
     // 检查是否是 uucode 服务商，不是则静默跳过
-    let vendor = crate::api::detect_vendor_from_claude_settings();
+    let vendor = ctx.vendor;
     if vendor != VendorType::Uucode {
         return None;
     }
@@ -57,14 +147,14 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
         .and_then(|v| v.as_str())
         .filter(|s| !s.is_empty())
         .map(|s| s.to_string())
-        .or_else(crate::api::get_api_key_from_claude_settings);
+        .or_else(|| crate::api::credentials::resolve_api_key(VendorType::Uucode));
 
     let api_key = match api_key {
         Some(key) if !key.is_empty() => key,
         _ => {
             return Some(SegmentData {
-                primary: "未订阅".to_string(),
-                secondary: String::new(),
+                primary: "未订阅".into(),
+                secondary: "".into(),
                 metadata: HashMap::new(),
             });
         }
@@ -85,12 +175,10 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
     let subscriptions = if let Some(fresh) = fetch_subscriptions_sync(&api_key, &subscription_url) {
         let _ = cache::save_cached_subscriptions(&fresh);
         fresh
-    } else if let Some(subs) = cached {
-        // 请求失败：如果有缓存（无论是否过期）就继续显示缓存
-        subs
     } else {
+        // 请求失败：如果有缓存（无论是否过期）就继续显示缓存；
         // 没有缓存且请求失败：整个段不显示
-        return None;
+        cached?
     };
 
     fn fetch_subscriptions_sync(
@@ -104,10 +192,13 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
             subscription_url: subscription_url.to_string(),
             auto_cookie: false,
             cookie: None,
+            base_url: String::new(),
+            user_id: None,
         };
 
         let client = ApiClient::new(api_config).ok()?;
-        let subs = client.get_subscriptions().ok()?;
+        let subs =
+            crate::api::breaker::call(subscription_url, || client.get_subscriptions()).ok()?;
         Some(subs)
     }
 
@@ -116,8 +207,8 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
 
     if active_subscriptions.is_empty() {
         return Some(SegmentData {
-            primary: "未订阅".to_string(),
-            secondary: String::new(),
+            primary: "未订阅".into(),
+            secondary: "".into(),
             metadata: HashMap::new(),
         });
     }
@@ -125,17 +216,19 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
     // 组合所有订阅信息
     let mut subscription_texts = Vec::new();
     let mut metadata = HashMap::new();
+    let mut colors = PlanColors::load();
+    let locale = config.format.locale;
 
     for (idx, sub) in active_subscriptions.iter().enumerate() {
         // 构建每个订阅的完整信息
         let expiry_info = if sub.remaining_days >= 0 {
-            format!("剩余{}天", sub.remaining_days)
+            i18n::days_left(sub.remaining_days as u64, locale)
         } else {
-            "已过期".to_string()
+            Phrase::Expired.text(locale).to_string()
         };
 
-        // 为每个订阅生成基于其计划名的柔和颜色
-        let color = get_soft_color(&sub.plan_name);
+        // 为每个订阅分配柔和颜色，并列的计划互不重复
+        let color = colors.assign(&sub.plan_name);
 
         // PAYGO 不显示重置次数，其他订阅显示
         let subscription_text = if sub.plan_name == "PAYGO" {
@@ -165,13 +258,17 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
         }
     }
 
+    if let Err(e) = colors.save() {
+        crate::debug_log!("failed to save plan colors: {}", e);
+    }
+
     // 用分隔符连接多个订阅
     let primary = subscription_texts.join(" | ");
     let secondary = String::new();
 
     Some(SegmentData {
-        primary,
-        secondary,
+        primary: primary.into(),
+        secondary: secondary.into(),
         metadata,
     })
 }
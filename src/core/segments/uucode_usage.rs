@@ -1,10 +1,15 @@
-use crate::api::{cache, client::ApiClient, ApiConfig, VendorType};
-use crate::config::Config;
-use crate::config::InputData;
-use crate::core::segments::SegmentData;
+use crate::api::{client::ApiClient, fetch, ApiConfig, VendorType};
+use crate::config::SegmentId;
+use crate::core::forecast;
+use crate::core::icons::{self, Glyph};
+use crate::core::palette::Level;
+use crate::core::segments::{CollectionContext, SegmentData};
+use crate::utils::{format, i18n};
 use std::collections::HashMap;
 
-pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
+pub fn collect(ctx: &CollectionContext) -> Option<SegmentData> {
+    let config = ctx.config;
+
     // Get API config from segment options
     let segment = config
         .segments
@@ -15,8 +20,12 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
         return None;
     }
 
+    let card = icons::prefix(config, SegmentId::UucodeUsage, Glyph::Card);
+    let package = icons::prefix(config, SegmentId::UucodeUsage, Glyph::Package);
+    let money = icons::prefix(config, SegmentId::UucodeUsage, Glyph::Money);
+
     // 检查是否是 uucode 服务商，不是则不显示此段（静默跳过）
-    let vendor = crate::api::detect_vendor_from_claude_settings();
+    let vendor = ctx.vendor;
     if vendor != VendorType::Uucode {
         // 不是 uucode 服务商，静默跳过此段
         return None;
@@ -29,14 +38,14 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
         .and_then(|v| v.as_str())
         .filter(|s| !s.is_empty())
         .map(|s| s.to_string())
-        .or_else(crate::api::get_api_key_from_claude_settings);
+        .or_else(|| crate::api::credentials::resolve_api_key(VendorType::Uucode));
 
     let api_key = match api_key {
         Some(key) if !key.is_empty() => key,
         _ => {
             return Some(SegmentData {
-                primary: "未配置密钥".to_string(),
-                secondary: String::new(),
+                primary: "未配置密钥".into(),
+                secondary: "".into(),
                 metadata: HashMap::new(),
             });
         }
@@ -60,45 +69,19 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
         .map(|s| s.to_string())
         .unwrap_or_else(|| "".to_string());
 
-    // 获取使用数据：每次先尝试请求 API，失败时回退到本地缓存
-    let usage = if is_uucode {
-        // 先拿到当前缓存（可能为空，用于失败回退）
-        let (cached, _needs_refresh) = cache::get_cached_usage();
-
-        // 1. 每次先尝试同步请求最新用量
-        if let Some(mut fresh) = fetch_usage_sync(&api_key, &usage_url) {
-            fresh.calculate();
-            let _ = cache::save_cached_usage(&fresh);
-            fresh
-        } else if let Some(mut cached_usage) = cached {
-            // 2. 请求失败：如果有缓存（无论是否过期）就继续显示缓存
-            cached_usage.calculate();
-            cached_usage
-        } else {
-            // 3. 既没有网络也没有缓存：整个段不显示
-            return None;
-        }
+    // 获取使用数据：每次先尝试请求 API，失败时回退到本地缓存（无论是否过期）；
+    // 既没有网络也没有缓存时整个段不显示
+    let fetched = if is_uucode {
+        fetch::uucode_usage(&api_key, &usage_url)?
     } else {
         // 理论上不会走到这里（前面已经限制仅支持 uucode），保留兜底逻辑
-        let mut fresh = fetch_usage_sync(&api_key, &usage_url)?;
-        fresh.calculate();
-        fresh
+        let fetched = fetch::uucode_usage(&api_key, &usage_url)?;
+        if fetched.stale {
+            return None;
+        }
+        fetched
     };
-
-    fn fetch_usage_sync(api_key: &str, usage_url: &str) -> Option<crate::api::UsageData> {
-        let api_config = ApiConfig {
-            enabled: true,
-            api_key: api_key.to_string(),
-            usage_url: usage_url.to_string(),
-            subscription_url: String::new(),
-            auto_cookie: false,
-            cookie: None,
-        };
-
-        let client = ApiClient::new(api_config).ok()?;
-        let usage = client.get_usage().ok()?;
-        Some(usage)
-    }
+    let usage = &fetched.data;
 
     // 处理使用数据
     let used_dollars = usage.get_used_tokens() as f64 / 100.0;
@@ -109,6 +92,15 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
     metadata.insert("used".to_string(), format!("{:.2}", used_dollars));
     metadata.insert("total".to_string(), format!("{:.2}", total_dollars));
     metadata.insert("remaining".to_string(), format!("{:.2}", remaining_dollars));
+    if total_dollars > 0.0 {
+        let level = Level::from_percentage(used_dollars / total_dollars * 100.0);
+        metadata.insert("level".to_string(), level.key().to_string());
+    }
+    fetched.annotate(&mut metadata);
+
+    let fmt = &config.format;
+    let used_fmt = format::money(used_dollars, 2, fmt);
+    let total_fmt = format::money(total_dollars, 0, fmt);
 
     // 对 uucode，将订阅和 PAYG 信息也写入 metadata 方便主题使用
     if is_uucode {
@@ -143,7 +135,7 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
         if is_uucode {
             let payg_display = usage
                 .get_payg_balance_usd()
-                .and_then(|s| s.parse::<f64>().ok().map(|v| format!("{:.2}", v)))
+                .and_then(|s| s.parse::<f64>().ok().map(|v| format::money(v, 2, fmt)))
                 .unwrap_or_else(|| "-".to_string());
 
             if let Some(name) = usage.get_subscription_name() {
@@ -157,19 +149,23 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
 
                     let secondary = if days > 0 {
                         format!(
-                            "📦 {} - 剩余{}天 | 💰 payg余额 ${} | 欢迎使用uucode（额度已用完）",
-                            name, days, payg_display
+                            "{}{} - {} | {}payg余额 {} | 欢迎使用uucode（额度已用完）",
+                            package,
+                            name,
+                            i18n::days_left(days as u64, fmt.locale),
+                            money,
+                            payg_display
                         )
                     } else {
                         format!(
-                            "📦 {} | 💰 payg余额 ${} | 欢迎使用uucode（额度已用完）",
-                            name, payg_display
+                            "{}{} | {}payg余额 {} | 欢迎使用uucode（额度已用完）",
+                            package, name, money, payg_display
                         )
                     };
 
                     return Some(SegmentData {
-                        primary: format!("💳 ${:.2} / ${:.0}", used_dollars, total_dollars),
-                        secondary,
+                        primary: format!("{}{} / {}", card, used_fmt, total_fmt).into(),
+                        secondary: secondary.into(),
                         metadata,
                     });
                 }
@@ -183,11 +179,14 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
                 {
                     if payg > 0.0 {
                         return Some(SegmentData {
-                            primary: format!("💳 ${:.2} / ${:.0}", used_dollars, total_dollars),
+                            primary: format!("{}{} / {}", card, used_fmt, total_fmt).into(),
                             secondary: format!(
-                                "📦 无订阅 - 使用PayGo额度中 | 💰 payg余额 ${:.2} | 欢迎使用uucode",
-                                payg
-                            ),
+                                "{}无订阅 - 使用PayGo额度中 | {}payg余额 {} | 欢迎使用uucode",
+                                package,
+                                money,
+                                format::money(payg, 2, fmt)
+                            )
+                            .into(),
                             metadata,
                         });
                     }
@@ -196,8 +195,8 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
 
             // 没有订阅信息且无 PAYG 余额时的兜底提示
             return Some(SegmentData {
-                primary: format!("💳 ${:.2} / ${:.0}", used_dollars, total_dollars),
-                secondary: "📦 额度已用完 | 欢迎使用uucode".to_string(),
+                primary: format!("{}{} / {}", card, used_fmt, total_fmt).into(),
+                secondary: format!("{}额度已用完 | 欢迎使用uucode", package).into(),
                 metadata,
             });
         }
@@ -211,8 +210,8 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
             if active_subs.len() > 1 {
                 // 有多个订阅，提示切换到其他套餐
                 return Some(SegmentData {
-                    primary: format!("${:.2}/${:.0} 已用完", used_dollars, total_dollars),
-                    secondary: "提示：你有其他套餐可用".to_string(),
+                    primary: format!("{}/{} 已用完", used_fmt, total_fmt).into(),
+                    secondary: "提示：你有其他套餐可用".into(),
                     metadata,
                 });
             } else if active_subs.len() == 1 {
@@ -220,14 +219,14 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
                 let reset_times = active_subs[0].reset_times;
                 if reset_times > 0 {
                     return Some(SegmentData {
-                        primary: format!("${:.2}/${:.0} 已用完", used_dollars, total_dollars),
-                        secondary: format!("可重置{}次，请手动重置", reset_times),
+                        primary: format!("{}/{} 已用完", used_fmt, total_fmt).into(),
+                        secondary: format!("可重置{}次，请手动重置", reset_times).into(),
                         metadata,
                     });
                 } else {
                     return Some(SegmentData {
-                        primary: format!("${:.2}/${:.0} 已用完", used_dollars, total_dollars),
-                        secondary: "无可用重置次数".to_string(),
+                        primary: format!("{}/{} 已用完", used_fmt, total_fmt).into(),
+                        secondary: "无可用重置次数".into(),
                         metadata,
                     });
                 }
@@ -236,19 +235,19 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
 
         // 没有订阅信息或无活跃订阅，显示基本提示
         return Some(SegmentData {
-            primary: format!("${:.2}/${:.0} 已用完", used_dollars, total_dollars),
-            secondary: "请充值或重置额度".to_string(),
+            primary: format!("{}/{} 已用完", used_fmt, total_fmt).into(),
+            secondary: "请充值或重置额度".into(),
             metadata,
         });
     }
 
     // 正常显示
     if is_uucode {
-        let primary = format!("💳 ${:.2} / ${:.0}", used_dollars, total_dollars);
+        let primary = format!("{}{} / {}", card, used_fmt, total_fmt);
 
         let payg_display = usage
             .get_payg_balance_usd()
-            .and_then(|s| s.parse::<f64>().ok().map(|v| format!("{:.2}", v)))
+            .and_then(|s| s.parse::<f64>().ok().map(|v| format::money(v, 2, fmt)))
             .unwrap_or_else(|| "-".to_string());
 
         let secondary = if let Some(name) = usage.get_subscription_name() {
@@ -259,40 +258,82 @@ pub fn collect(config: &Config, _input: &InputData) -> Option<SegmentData> {
                     0
                 };
 
-                if days > 0 {
+                let show_cycle = segment
+                    .options
+                    .get("show_cycle")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(true);
+                if show_cycle && days > 0 {
+                    let cycle_days = segment
+                        .options
+                        .get("cycle_days")
+                        .and_then(|v| v.as_i64())
+                        .unwrap_or(30);
+                    // 剩余秒数是请求时的值，需要减去缓存年龄
+                    let now = crate::utils::clock::unix_now();
+                    let remaining = seconds - fetched.cache_age.unwrap_or(0) as i64;
+                    let cycle = forecast::track(now, remaining, cycle_days);
+                    let projected = forecast::project(used_dollars, cycle.elapsed(now));
+
+                    metadata.insert("cycle_day".to_string(), cycle.day(now).to_string());
+                    metadata.insert("cycle_days".to_string(), cycle.length_days().to_string());
+                    metadata.insert("cycle_start".to_string(), cycle.start.to_string());
+                    let projection = match projected {
+                        Some(projected) => {
+                            metadata
+                                .insert("projected_usd".to_string(), format!("{:.2}", projected));
+                            format!(" · 预计月末 {}", format::money(projected, 0, fmt))
+                        }
+                        None => String::new(),
+                    };
+                    format!(
+                        "{}{} - 周期第{}/{}天{} | {}payg余额 {} | 欢迎使用uucode",
+                        package,
+                        name,
+                        cycle.day(now),
+                        cycle.length_days(),
+                        projection,
+                        money,
+                        payg_display
+                    )
+                } else if days > 0 {
                     format!(
-                        "📦 {} - 剩余{}天 | 💰 payg余额 ${} | 欢迎使用uucode",
-                        name, days, payg_display
+                        "{}{} - {} | {}payg余额 {} | 欢迎使用uucode",
+                        package,
+                        name,
+                        i18n::days_left(days as u64, fmt.locale),
+                        money,
+                        payg_display
                     )
                 } else {
                     format!(
-                        "📦 {} | 💰 payg余额 ${} | 欢迎使用uucode",
-                        name, payg_display
+                        "{}{} | {}payg余额 {} | 欢迎使用uucode",
+                        package, name, money, payg_display
                     )
                 }
             } else {
                 format!(
-                    "📦 {} | 💰 payg余额 ${} | 欢迎使用uucode",
-                    name, payg_display
+                    "{}{} | {}payg余额 {} | 欢迎使用uucode",
+                    package, name, money, payg_display
                 )
             }
         } else {
             format!(
-                "📦 无订阅 - 使用PayGo额度中 | 💰 payg余额 ${} | 欢迎使用uucode",
-                payg_display
+                "{}无订阅 - 使用PayGo额度中 | {}payg余额 {} | 欢迎使用uucode",
+                package, money, payg_display
             )
         };
 
         Some(SegmentData {
-            primary,
-            secondary,
+            primary: primary.into(),
+            secondary: secondary.into(),
             metadata,
         })
     } else {
         // 默认显示（非 uucode 场景）
         Some(SegmentData {
-            primary: format!("${:.2}/${:.0}", used_dollars, total_dollars),
-            secondary: format!("剩${:.2}", remaining_dollars),
+            primary: format!("{}/{}", used_fmt, total_fmt).into(),
+            secondary: format!("剩{}", format::money(remaining_dollars, 2, fmt)).into(),
             metadata,
         })
     }
@@ -309,9 +350,11 @@ fn fetch_subscriptions_sync(
         subscription_url: subscription_url.to_string(),
         auto_cookie: false,
         cookie: None,
+        base_url: String::new(),
+        user_id: None,
     };
 
     let client = ApiClient::new(api_config).ok()?;
-    let subs = client.get_subscriptions().ok()?;
+    let subs = crate::api::breaker::call(subscription_url, || client.get_subscriptions()).ok()?;
     Some(subs)
 }
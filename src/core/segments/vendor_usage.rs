@@ -0,0 +1,111 @@
+//! 自定义服务商用量段
+//! 显示 `~/.claude/uucode/vendors.d` 中描述的服务商的用量，例如
+//! "$12.50 / $50 (25%)"，附带套餐、余额和重置倒计时。
+//!
+//! 内置服务商有各自的段，当前服务商不是 vendors.d 中的服务商时静默跳过。
+
+use crate::api::{credentials, fetch, vendor, VendorType};
+use crate::config::SegmentId;
+use crate::core::icons::{self, Glyph};
+use crate::core::palette::Level;
+use crate::core::segments::{CollectionContext, SegmentData};
+use crate::utils::{clock, format, i18n};
+use std::collections::HashMap;
+
+/// 收集自定义服务商用量
+pub fn collect(ctx: &CollectionContext) -> Option<SegmentData> {
+    let config = ctx.config;
+
+    let segment = config
+        .segments
+        .iter()
+        .find(|s| s.id == SegmentId::VendorUsage)?;
+
+    if !segment.enabled || ctx.vendor != VendorType::Custom {
+        return None;
+    }
+    let vendor = vendor::current()?;
+    vendor.usage_url()?;
+
+    let api_key = segment
+        .options
+        .get("api_key")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .or_else(|| credentials::resolve_api_key(VendorType::Custom));
+    let Some(api_key) = api_key else {
+        return Some(SegmentData {
+            primary: "未配置密钥".into(),
+            secondary: "".into(),
+            metadata: HashMap::new(),
+        });
+    };
+
+    let fetched = fetch::custom_usage(vendor, &api_key)?;
+    let usage = &fetched.data;
+    let fmt = &config.format;
+    let card = icons::prefix(config, SegmentId::VendorUsage, Glyph::Card);
+    let money = icons::prefix(config, SegmentId::VendorUsage, Glyph::Money);
+
+    let mut metadata = HashMap::new();
+    metadata.insert("vendor".to_string(), vendor.id().to_string());
+    metadata.insert("used".to_string(), format!("{:.2}", usage.used));
+    if let Some(limit) = usage.limit {
+        metadata.insert("total".to_string(), format!("{:.2}", limit));
+    }
+    if let Some(balance) = usage.balance {
+        metadata.insert("balance".to_string(), format!("{:.2}", balance));
+    }
+    if let Some(percentage) = usage.percentage() {
+        metadata.insert("used_percentage".to_string(), format!("{:.1}", percentage));
+        metadata.insert(
+            "level".to_string(),
+            Level::from_percentage(percentage).key().to_string(),
+        );
+    }
+    fetched.annotate(&mut metadata);
+
+    let primary = match (usage.limit, usage.percentage()) {
+        (Some(limit), Some(percentage)) => format!(
+            "{}{} / {} ({:.0}%)",
+            card,
+            format::money(usage.used, 2, fmt),
+            format::money(limit, 0, fmt),
+            percentage
+        ),
+        _ => match usage.balance {
+            // 只有余额的按量计费账户
+            Some(balance) if usage.used == 0.0 => {
+                format!("{}{}", money, format::money(balance, 2, fmt))
+            }
+            _ => format!("{}{}", card, format::money(usage.used, 2, fmt)),
+        },
+    };
+
+    let mut details = Vec::new();
+    details.push(
+        usage
+            .plan
+            .clone()
+            .unwrap_or_else(|| vendor.display_name().to_string()),
+    );
+    if let (Some(balance), Some(_)) = (usage.balance, usage.limit) {
+        details.push(format!("{}{}", money, format::money(balance, 2, fmt)));
+    }
+    if let Some(resets_at) = usage.resets_at {
+        let secs = resets_at - clock::unix_now();
+        metadata.insert("reset_seconds".to_string(), secs.max(0).to_string());
+        details.push(i18n::countdown(
+            secs,
+            i18n::Phrase::ResettingSoon,
+            fmt.locale,
+        ));
+    }
+
+    Some(SegmentData {
+        primary: primary.into(),
+        secondary: details.join(" | ").into(),
+        metadata,
+    })
+}
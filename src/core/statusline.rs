@@ -1,68 +1,212 @@
-use crate::config::{AnsiColor, Config, SegmentConfig, SegmentId, StyleMode};
+use crate::config::{AnsiColor, Config, SegmentConfig, SegmentId, StaleIndicator};
+use crate::core::palette::{self, Level, Role};
 use crate::core::segments::SegmentData;
-
-/// Strip ANSI escape sequences and return visible text length
-fn visible_width(text: &str) -> usize {
-    let mut visible = String::new();
-    let mut in_escape = false;
-    let mut chars = text.chars().peekable();
-
-    while let Some(ch) = chars.next() {
-        if ch == '\x1b' {
-            // Start of ANSI escape sequence
-            in_escape = true;
-            // Skip the [ character
-            if chars.peek() == Some(&'[') {
-                chars.next();
-            }
-        } else if in_escape {
-            // Skip until we find the end of the escape sequence (letter)
-            if ch.is_alphabetic() {
-                in_escape = false;
-            }
-        } else {
-            // Regular character
-            visible.push(ch);
-        }
-    }
-
-    visible.chars().count()
-}
+use crate::core::{history, icons};
+use crate::utils::ansi::{sanitize, strip, strip_emoji, truncate_visible, visible_width};
 
 pub struct StatusLineGenerator {
     config: Config,
+    available_width: Option<usize>,
 }
 
 impl StatusLineGenerator {
-    pub fn new(config: Config) -> Self {
-        Self { config }
+    pub fn new(mut config: Config) -> Self {
+        if config.accessibility.is_active() {
+            Self::apply_high_contrast(&mut config);
+        }
+        Self {
+            config,
+            available_width: None,
+        }
+    }
+
+    /// Accessibility profile colors: bold default-colored text, or bright
+    /// white on black where a segment has a background
+    fn apply_high_contrast(config: &mut Config) {
+        config.style.separator_color = None;
+        for segment in &mut config.segments {
+            Self::high_contrast(segment);
+        }
+    }
+
+    /// Bright white on black where the segment has a background, the
+    /// terminal's own colors otherwise; bold either way
+    fn high_contrast(segment: &mut SegmentConfig) {
+        if segment.colors.background.is_some() {
+            segment.colors.background = Some(AnsiColor::Color16 { c16: 0 });
+            segment.colors.icon = Some(AnsiColor::Color16 { c16: 15 });
+            segment.colors.text = Some(AnsiColor::Color16 { c16: 15 });
+        } else {
+            segment.colors.icon = None;
+            segment.colors.text = None;
+        }
+        segment.styles.text_bold = true;
+    }
+
+    /// Columns Claude Code has for the statusline; the line is kept within
+    /// them on top of `style.max_width`
+    pub fn with_available_width(mut self, width: Option<usize>) -> Self {
+        self.available_width = width;
+        self
     }
 
     pub fn generate(&self, segments: Vec<(SegmentConfig, SegmentData)>) -> String {
-        let mut output = Vec::new();
-        let enabled_segments: Vec<_> = segments
+        let mut enabled_segments: Vec<_> = segments
             .into_iter()
             .filter(|(config, _)| config.enabled)
             .collect();
+        self.promote_low_quota(&mut enabled_segments);
 
-        for (config, data) in enabled_segments.iter() {
-            let rendered = self.render_segment(config, data);
-            if !rendered.is_empty() {
-                output.push(rendered);
+        let configured = self.config.style.max_width.filter(|w| *w > 0);
+        let max_width = match (configured, self.available_width) {
+            (Some(configured), Some(available)) => Some(configured.min(available)),
+            (configured, available) => configured.or(available),
+        };
+        match max_width {
+            Some(max_width) => self.fit_to_width(enabled_segments, max_width),
+            None => self.render_line(&enabled_segments),
+        }
+    }
+
+    /// Move vendor quota segments running low (`style.promote_below`) to
+    /// the front, keeping their order, and mark them `promoted` so that
+    /// [`Self::fit_to_width`] trims them last
+    fn promote_low_quota(&self, segments: &mut [(SegmentConfig, SegmentData)]) {
+        let threshold = self.config.style.promote_below;
+        if threshold <= 0.0 {
+            return;
+        }
+        for (config, data) in segments.iter_mut() {
+            if history::quota_label(config.id).is_none() {
+                continue;
+            }
+            let remaining = history::segment_percentage(config.id, data).map(|used| 100.0 - used);
+            if remaining.is_some_and(|remaining| remaining < threshold) {
+                data.metadata
+                    .insert("promoted".to_string(), "true".to_string());
             }
         }
+        segments.sort_by_key(|(_, data)| !Self::is_promoted(data));
+    }
+
+    fn is_promoted(data: &SegmentData) -> bool {
+        data.metadata.get("promoted").is_some_and(|v| v == "true")
+    }
+
+    /// Render and join already-filtered segments into a single line
+    fn render_line(&self, enabled_segments: &[(SegmentConfig, SegmentData)]) -> String {
+        let rendered: Vec<(&SegmentConfig, String)> = enabled_segments
+            .iter()
+            .map(|(config, data)| (config, self.render_segment(config, data)))
+            .filter(|(_, text)| !text.is_empty())
+            .collect();
 
-        if output.is_empty() {
+        if rendered.is_empty() {
             return String::new();
         }
 
-        // Handle Powerline arrow separators with color transition
-        if self.config.style.separator == "\u{e0b0}" {
-            self.join_with_powerline_arrows(&output, &enabled_segments)
-        } else {
-            // For all other separators, use white color and simple join
-            self.join_with_white_separators(&output)
+        // Room for the texts plus a colored separator between each pair
+        let capacity = rendered.iter().map(|(_, text)| text.len() + 16).sum();
+        let mut result = String::with_capacity(capacity);
+        for (i, (config, text)) in rendered.iter().enumerate() {
+            if i > 0 {
+                result.push_str(&self.separator_between(rendered[i - 1].0, config));
+            }
+            result.push_str(text);
+        }
+
+        if self.is_powerline() {
+            // Reset colors at the end
+            result.push_str("\x1b[0m");
+        }
+        result
+    }
+
+    fn is_powerline(&self) -> bool {
+        self.config.style.separator == "\u{e0b0}"
+    }
+
+    /// Separator drawn between two adjacent segments.
+    ///
+    /// The left segment's `separator_after` option wins over the right
+    /// segment's `separator_before`; both fall back to the global separator.
+    /// An empty override glues the two segments together.
+    fn separator_between(&self, prev: &SegmentConfig, next: &SegmentConfig) -> String {
+        let custom = prev
+            .options
+            .get("separator_after")
+            .or_else(|| next.options.get("separator_before"))
+            .and_then(|v| v.as_str());
+
+        match custom {
+            Some("") => String::new(),
+            Some(separator) => self.color_separator(separator),
+            None if self.is_powerline() => self.create_powerline_arrow(
+                prev.colors.background.as_ref(),
+                next.colors.background.as_ref(),
+            ),
+            None => self.color_separator(&self.config.style.separator),
+        }
+    }
+
+    fn color_separator(&self, separator: &str) -> String {
+        match &self.config.style.separator_color {
+            Some(color) => self.apply_color(separator, Some(color)),
+            // Plain white can vanish on light terminals
+            None if self.config.accessibility.is_active() => separator.to_string(),
+            None => palette::paint(&self.config.style, Role::Muted, separator),
+        }
+    }
+
+    /// Keep the line within `max_width` visible columns so Claude Code never
+    /// has to cut it (which can leave an escape sequence half-written).
+    ///
+    /// Lowest-priority segments are dropped first (rightmost on ties), then
+    /// secondary texts are removed, and as a last resort the line is cut at
+    /// a sequence boundary. Promoted segments are dropped only after all
+    /// others and keep their secondary text.
+    fn fit_to_width(
+        &self,
+        mut segments: Vec<(SegmentConfig, SegmentData)>,
+        max_width: usize,
+    ) -> String {
+        let emoji_width = self.config.style.emoji_width.resolve();
+        let mut output = self.render_line(&segments);
+
+        while visible_width(&output, emoji_width) > max_width && segments.len() > 1 {
+            let drop_idx = segments
+                .iter()
+                .enumerate()
+                .min_by_key(|(idx, (config, data))| {
+                    (
+                        Self::is_promoted(data),
+                        config.effective_priority(),
+                        std::cmp::Reverse(*idx),
+                    )
+                })
+                .map(|(idx, _)| idx)
+                .unwrap_or(segments.len() - 1);
+            segments.remove(drop_idx);
+            output = self.render_line(&segments);
         }
+
+        if visible_width(&output, emoji_width) > max_width {
+            let mut order: Vec<usize> = (0..segments.len()).collect();
+            order.sort_by_key(|&idx| segments[idx].0.effective_priority());
+
+            for idx in order {
+                if segments[idx].1.secondary.is_empty() || Self::is_promoted(&segments[idx].1) {
+                    continue;
+                }
+                segments[idx].1.secondary = "".into();
+                output = self.render_line(&segments);
+                if visible_width(&output, emoji_width) <= max_width {
+                    break;
+                }
+            }
+        }
+
+        truncate_visible(&output, max_width, emoji_width)
     }
 
     /// Generate statusline for TUI preview with proper width calculation
@@ -89,6 +233,7 @@ impl StatusLineGenerator {
     }
 
     /// Generate TUI-optimized text with intelligent wrapping by segment for preview
+    #[cfg(feature = "tui")]
     pub fn generate_for_tui_preview(
         &self,
         segments: Vec<(SegmentConfig, SegmentData)>,
@@ -123,33 +268,21 @@ impl StatusLineGenerator {
         }
 
         // Pre-calculate separators between segments
-        let mut separators = Vec::new();
-        for i in 0..rendered_segments.len().saturating_sub(1) {
-            let separator = if self.config.style.separator == "\u{e0b0}" {
-                // Powerline arrows with color transition
-                let prev_bg = segment_configs
-                    .get(i)
-                    .and_then(|config| config.colors.background.as_ref());
-                let curr_bg = segment_configs
-                    .get(i + 1)
-                    .and_then(|config| config.colors.background.as_ref());
-                self.create_powerline_arrow(prev_bg, curr_bg)
-            } else {
-                // Regular separators with white color
-                format!("\x1b[37m{}\x1b[0m", self.config.style.separator)
-            };
-            separators.push(separator);
-        }
+        let separators: Vec<String> = segment_configs
+            .windows(2)
+            .map(|pair| self.separator_between(&pair[0], &pair[1]))
+            .collect();
 
         // Intelligent line wrapping by segment
         let mut lines: Vec<String> = Vec::new();
         let mut current_line = String::new();
         let mut current_width = 0usize;
         let max_w = max_width as usize;
+        let emoji_width = self.config.style.emoji_width.resolve();
 
         for i in 0..rendered_segments.len() {
             let segment = &rendered_segments[i];
-            let segment_width = visible_width(segment);
+            let segment_width = visible_width(segment, emoji_width);
 
             // Check if adding this segment would exceed max_width
             if current_width > 0 && current_width + segment_width > max_w {
@@ -166,12 +299,12 @@ impl StatusLineGenerator {
             // Handle separator if not the last segment
             if i < separators.len() {
                 let separator = &separators[i];
-                let separator_width = visible_width(separator);
+                let separator_width = visible_width(separator, emoji_width);
 
                 // Check if next segment exists
                 if i + 1 < rendered_segments.len() {
                     let next_segment = &rendered_segments[i + 1];
-                    let next_width = visible_width(next_segment);
+                    let next_width = visible_width(next_segment, emoji_width);
 
                     // Check if separator AND next segment both fit
                     if current_width + separator_width + next_width <= max_w {
@@ -214,13 +347,110 @@ impl StatusLineGenerator {
         Text::from(tui_lines)
     }
 
+    /// Strip terminal control sequences from collected text; segments may
+    /// carry untrusted content such as branch or subscription names
+    fn sanitize_data(data: &SegmentData) -> SegmentData {
+        SegmentData {
+            primary: sanitize(&data.primary).into(),
+            secondary: sanitize(&data.secondary).into(),
+            metadata: data
+                .metadata
+                .iter()
+                .map(|(k, v)| (k.clone(), sanitize(v)))
+                .collect(),
+        }
+    }
+
+    /// Accessibility profile text: no embedded colors, optionally no
+    /// emoji, and states spelled out instead of shown only by color
+    fn accessible_data(&self, data: &mut SegmentData) {
+        data.primary = strip(&data.primary).into();
+        data.secondary = strip(&data.secondary).into();
+        data.metadata.remove("has_ansi_colors");
+        if self.config.accessibility.strips_emoji() {
+            data.primary = strip_emoji(&data.primary).into();
+            data.secondary = strip_emoji(&data.secondary).into();
+        }
+        match data.metadata.get("level").map(String::as_str) {
+            Some("warning") => data.primary.to_mut().push_str(" WARN"),
+            Some("critical") => data.primary.to_mut().push_str(" CRIT"),
+            _ => {}
+        }
+        if data.metadata.get("stale").is_some_and(|v| v == "true") {
+            data.primary.to_mut().push_str(" STALE");
+        }
+    }
+
     fn render_segment(&self, config: &SegmentConfig, data: &SegmentData) -> String {
-        let icon = if let Some(dynamic_icon) = data.metadata.get("dynamic_icon") {
-            dynamic_icon.clone()
+        let mut data = Self::sanitize_data(data);
+        let accessible = self.config.accessibility.is_active();
+        if accessible {
+            self.accessible_data(&mut data);
+        }
+        let stale = data.metadata.get("stale").is_some_and(|v| v == "true");
+        // The accessible text already says STALE
+        let indicator = if stale && !accessible {
+            self.config.style.stale_indicator
         } else {
-            self.get_icon(config)
+            StaleIndicator::Off
         };
 
+        match indicator {
+            StaleIndicator::Dim => data.primary.to_mut().push_str(" ⟳"),
+            StaleIndicator::Asterisk => data.primary.to_mut().push('*'),
+            StaleIndicator::Clock => {
+                let age = data
+                    .metadata
+                    .get("cache_age_secs")
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .map(Self::format_age)
+                    .unwrap_or_default();
+                data.primary.to_mut().push_str(&format!(" 🕒{}", age));
+            }
+            StaleIndicator::Off => {}
+        }
+
+        // Warning and critical states take the palette's color; the
+        // accessible profile says them in words instead
+        let level = Level::from_metadata(&data.metadata).filter(|level| *level != Level::Ok);
+        let rendered = if accessible {
+            let mut config = config.clone();
+            Self::high_contrast(&mut config);
+            self.render_segment_body(&config, &data)
+        } else if let Some(level) = level {
+            let mut config = config.clone();
+            config.colors.text = Some(palette::color(&self.config.style, level.role()));
+            self.render_segment_body(&config, &data)
+        } else {
+            self.render_segment_body(config, &data)
+        };
+        if indicator == StaleIndicator::Dim && !rendered.is_empty() {
+            Self::dim(&rendered)
+        } else {
+            rendered
+        }
+    }
+
+    /// Compact cache age such as `45s`, `12m` or `3h`
+    fn format_age(secs: u64) -> String {
+        match secs {
+            0..=59 => format!("{}s", secs),
+            60..=3599 => format!("{}m", secs / 60),
+            _ => format!("{}h", secs / 3600),
+        }
+    }
+
+    /// Render text dimmed, re-applying dim after every reset inside it
+    fn dim(text: &str) -> String {
+        format!(
+            "\x1b[2m{}\x1b[22m",
+            text.replace("\x1b[0m", "\x1b[0m\x1b[2m")
+        )
+    }
+
+    fn render_segment_body(&self, config: &SegmentConfig, data: &SegmentData) -> String {
+        let icon = icons::resolve(&self.config, config, &data.metadata);
+
         // Apply background color to the entire segment if set
         if let Some(bg_color) = &config.colors.background {
             let bg_code = self.apply_background_color(bg_color);
@@ -241,7 +471,11 @@ impl StatusLineGenerator {
                 )
                 .replace("\x1b[0m", "");
 
-            let mut segment_content = format!(" {} {} ", icon_colored, text_styled);
+            let mut segment_content = if icon.is_empty() {
+                format!(" {} ", text_styled)
+            } else {
+                format!(" {} {} ", icon_colored, text_styled)
+            };
 
             if !data.secondary.is_empty() {
                 let secondary_styled = self
@@ -251,7 +485,8 @@ impl StatusLineGenerator {
                         config.styles.text_bold,
                     )
                     .replace("\x1b[0m", "");
-                segment_content.push_str(&format!("{} ", secondary_styled));
+                segment_content.push_str(&secondary_styled);
+                segment_content.push(' ');
             }
 
             // Apply background to the entire content and reset at the end
@@ -270,7 +505,7 @@ impl StatusLineGenerator {
 
             let text_styled = if has_ansi_colors {
                 // 已包含 ANSI 颜色，不再包裹
-                data.primary.clone()
+                data.primary.to_string()
             } else {
                 self.apply_style(
                     &data.primary,
@@ -279,7 +514,12 @@ impl StatusLineGenerator {
                 )
             };
 
-            let mut segment = format!("{} {}", icon_colored, text_styled);
+            // The icon set may have no icon for this segment
+            let mut segment = if icon.is_empty() {
+                text_styled
+            } else {
+                format!("{} {}", icon_colored, text_styled)
+            };
 
             if !data.secondary.is_empty() {
                 // 对 uucode 用量段，单独给“payg余额 ...”和“欢迎使用uucode”使用不同颜色
@@ -292,7 +532,7 @@ impl StatusLineGenerator {
                     let welcome_color: Option<&AnsiColor> = Some(&welcome_color_ansi);
 
                     let mut styled = String::new();
-                    let mut remaining = secondary.as_str();
+                    let mut remaining: &str = secondary;
 
                     // 先处理 payg余额 段
                     if let Some(payg_pos) = remaining.find("payg余额 ") {
@@ -371,14 +611,6 @@ impl StatusLineGenerator {
         }
     }
 
-    fn get_icon(&self, config: &SegmentConfig) -> String {
-        match self.config.style.mode {
-            StyleMode::Plain => config.icon.plain.clone(),
-            StyleMode::NerdFont => config.icon.nerd_font.clone(),
-            StyleMode::Powerline => config.icon.nerd_font.clone(), // Future: use Powerline icons
-        }
-    }
-
     fn apply_color(&self, text: &str, color: Option<&AnsiColor>) -> String {
         match color {
             Some(AnsiColor::Color16 { c16 }) => {
@@ -446,53 +678,6 @@ impl StatusLineGenerator {
         }
     }
 
-    /// Join segments with white separators (non-Powerline)
-    fn join_with_white_separators(&self, rendered_segments: &[String]) -> String {
-        if rendered_segments.is_empty() {
-            return String::new();
-        }
-
-        // Use white color for separator
-        let white_separator = format!("\x1b[37m{}\x1b[0m", self.config.style.separator);
-        rendered_segments.join(&white_separator)
-    }
-
-    /// Join segments with Powerline arrow separators with proper color transitions
-    fn join_with_powerline_arrows(
-        &self,
-        rendered_segments: &[String],
-        segment_configs: &[(SegmentConfig, SegmentData)],
-    ) -> String {
-        if rendered_segments.is_empty() {
-            return String::new();
-        }
-
-        if rendered_segments.len() == 1 {
-            return rendered_segments[0].clone();
-        }
-
-        let mut result = rendered_segments[0].clone();
-
-        for (i, _) in rendered_segments.iter().enumerate().skip(1) {
-            let prev_bg = segment_configs
-                .get(i - 1)
-                .and_then(|(config, _)| config.colors.background.as_ref());
-            let curr_bg = segment_configs
-                .get(i)
-                .and_then(|(config, _)| config.colors.background.as_ref());
-
-            // Create Powerline arrow with color transition
-            let arrow = self.create_powerline_arrow(prev_bg, curr_bg);
-
-            result.push_str(&arrow);
-            result.push_str(&rendered_segments[i]);
-        }
-
-        // Reset colors at the end
-        result.push_str("\x1b[0m");
-        result
-    }
-
     /// Create a Powerline arrow with proper color transition
     fn create_powerline_arrow(
         &self,
@@ -543,83 +728,421 @@ impl StatusLineGenerator {
     }
 }
 
+/// Segments whose workers are still running, by position in the config.
+/// A segment that missed an earlier render's deadline is not started again
+/// until that worker returns, so slow segments never pile up threads in
+/// long-running modes (`daemon`, `--watch`, `serve`, `top`).
+static IN_FLIGHT: std::sync::OnceLock<
+    std::sync::Mutex<std::collections::HashSet<(SegmentId, usize)>>,
+> = std::sync::OnceLock::new();
+
+/// Removes a worker from [`IN_FLIGHT`] when it returns, panics included
+struct InFlight((SegmentId, usize));
+
+impl InFlight {
+    /// `None` when a worker for the same segment is still running
+    fn claim(key: (SegmentId, usize)) -> Option<Self> {
+        let mut running = IN_FLIGHT.get_or_init(Default::default).lock().ok()?;
+        running.insert(key).then_some(Self(key))
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if let Some(running) = IN_FLIGHT.get() {
+            if let Ok(mut running) = running.lock() {
+                running.remove(&self.0);
+            }
+        }
+    }
+}
+
+/// Whether renders in this process save segment timings and peaks
+static RECORDING: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(true);
+
+/// Stop saving segment timings and peaks for the rest of the process, for
+/// renders that are not the user's own (`--self-test`). Vendor caches are
+/// still refreshed.
+pub fn disable_recording() {
+    RECORDING.store(false, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Collect every configured segment within `perf.deadline_ms`.
+///
+/// Segments run in parallel, each on a worker thread. One with
+/// [`dependencies`](crate::core::segments::dependencies) starts once those
+/// have finished and sees their data in its [`CollectionContext`](crate::core::segments::CollectionContext);
+/// the summary is assembled last from everything collected.
 pub fn collect_all_segments(
     config: &Config,
     input: &crate::config::InputData,
 ) -> Vec<(SegmentConfig, SegmentData)> {
     use crate::core::segments::*;
+    use std::collections::HashMap;
+    use std::sync::{mpsc, Arc};
+    use std::time::{Duration, Instant};
+
+    crate::utils::clock::freeze(input);
+    let started = Instant::now();
+    let vendor = crate::api::detect_vendor_from_claude_settings();
+    let mut perf = crate::core::perf::PerfStats::load();
+    let mutes = crate::core::mute::Mutes::load();
+    let mut peaks = crate::core::peak::Peaks::load();
+
+    // The summary reads other segments, including ones hidden from the bar
+    let summary = SummarySegment::from_config(config);
+    let with_sources = summary.as_ref().map(|s| s.with_sources_enabled(config));
+    let collect_config = with_sources.as_ref().unwrap_or(config);
+
+    let mut slots: Vec<(&SegmentConfig, Option<SegmentData>)> = Vec::new();
+    let mut positions = Vec::new();
+    let mut waiting = Vec::new();
+    let mut summary_slot = None;
+
+    for (position, segment_config) in config.segments.iter().enumerate() {
+        // Segments of other vendors never show data for the current one
+        if segment_config.id.vendor().is_some_and(|v| v != vendor) {
+            continue;
+        }
+        if mutes.is_muted(segment_config.id) {
+            continue;
+        }
+        let index = slots.len();
+        positions.push(position);
+        if segment_config.id == SegmentId::Summary {
+            // Filled in once every other segment is collected
+            summary_slot = Some(index);
+            slots.push((segment_config, None));
+            continue;
+        }
 
-    let mut results = Vec::new();
+        // Only enabled segments are timed, disabled ones are never shown
+        if segment_config.enabled && perf.should_skip(segment_config.id, &config.perf) {
+            let timing = perf.segments.get(&segment_config.id);
+            slots.push((
+                segment_config,
+                Some(crate::core::perf::skipped_data(timing)),
+            ));
+            continue;
+        }
+        slots.push((segment_config, None));
+        waiting.push(index);
+    }
 
-    for segment_config in &config.segments {
-        let segment_data = match segment_config.id {
-            crate::config::SegmentId::Model => {
-                let segment = ModelSegment::new();
-                segment.collect(input)
-            }
-            crate::config::SegmentId::Directory => {
-                let segment = DirectorySegment::new();
-                segment.collect(input)
-            }
-            crate::config::SegmentId::Git => {
-                let show_sha = segment_config
-                    .options
-                    .get("show_sha")
-                    .and_then(|v| v.as_bool())
-                    .unwrap_or(false);
-                let segment = GitSegment::new().with_sha(show_sha);
-                segment.collect(input)
-            }
-            crate::config::SegmentId::ContextWindow => {
-                let segment = ContextWindowSegment::new();
-                segment.collect(input)
-            }
-            crate::config::SegmentId::Usage => {
-                let segment = UsageSegment::new();
-                segment.collect(input)
-            }
-            crate::config::SegmentId::Cost => {
-                let segment = CostSegment::new();
-                segment.collect(input)
-            }
-            crate::config::SegmentId::Session => {
-                let segment = SessionSegment::new();
-                segment.collect(input)
+    // Workers own their copy: one that misses the deadline keeps running
+    // after this function has returned
+    let job = Arc::new((config.clone(), collect_config.clone(), input.clone()));
+    let (tx, rx) = mpsc::channel();
+    let mut running: Vec<usize> = Vec::new();
+    let mut stalled: Vec<usize> = Vec::new();
+
+    // A deterministic render waits for every segment
+    let deadline = (!crate::utils::clock::is_deterministic())
+        .then(|| started + Duration::from_millis(config.perf.deadline_ms));
+    loop {
+        // Start every segment whose dependencies are done
+        let pending: Vec<SegmentId> = waiting
+            .iter()
+            .chain(&running)
+            .map(|&i| slots[i].0.id)
+            .collect();
+        let (ready, blocked): (Vec<usize>, Vec<usize>) = waiting.iter().partition(|&&i| {
+            !dependencies(slots[i].0.id)
+                .iter()
+                .any(|dependency| pending.contains(dependency))
+        });
+        waiting = blocked;
+
+        for index in ready {
+            let segment_config = slots[index].0;
+            let Some(claim) = InFlight::claim((segment_config.id, positions[index])) else {
+                crate::debug_log!(
+                    "{:?} is still running from an earlier render",
+                    segment_config.id
+                );
+                stalled.push(index);
+                continue;
+            };
+            // Dependents see what was collected before them
+            let published: HashMap<SegmentId, SegmentData> =
+                if dependencies(segment_config.id).is_empty() {
+                    HashMap::new()
+                } else {
+                    slots
+                        .iter()
+                        .filter_map(|(slot_config, data)| Some((slot_config.id, data.clone()?)))
+                        .collect()
+                };
+            let worker_job = Arc::clone(&job);
+            let worker_config = segment_config.clone();
+            let worker_tx = tx.clone();
+            let spawned = std::thread::Builder::new()
+                .name(format!("segment-{}", index))
+                .spawn(move || {
+                    let _claim = claim;
+                    let (config, collect_config, input) = &*worker_job;
+                    let mut ctx = CollectionContext::new(collect_config, input, vendor);
+                    for (id, data) in &published {
+                        ctx.publish(*id, data);
+                    }
+                    let collect_started = Instant::now();
+                    let data = collect_segment(&worker_config, config, &ctx);
+                    let _ = worker_tx.send((index, data, collect_started.elapsed()));
+                });
+            match spawned {
+                Ok(_) => running.push(index),
+                Err(e) => {
+                    crate::debug_log!("collecting {:?} inline: {}", segment_config.id, e);
+                    let mut ctx = CollectionContext::new(collect_config, input, vendor);
+                    for (slot_config, data) in &slots {
+                        if let Some(data) = data {
+                            ctx.publish(slot_config.id, data);
+                        }
+                    }
+                    let collect_started = Instant::now();
+                    let data = collect_segment(segment_config, config, &ctx);
+                    if segment_config.enabled {
+                        perf.record(segment_config.id, collect_started.elapsed(), &config.perf);
+                    }
+                    slots[index].1 = data;
+                }
             }
-            crate::config::SegmentId::OutputStyle => {
-                let segment = OutputStyleSegment::new();
-                segment.collect(input)
+        }
+        if running.is_empty() {
+            break;
+        }
+
+        let received = match deadline {
+            Some(deadline) => rx.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+            None => rx.recv().map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+        };
+        // Timed out, or the remaining workers panicked
+        let Ok((index, data, elapsed)) = received else {
+            break;
+        };
+        running.retain(|&i| i != index);
+        let (segment_config, slot) = &mut slots[index];
+        if segment_config.enabled {
+            perf.record(segment_config.id, elapsed, &config.perf);
+        }
+        *slot = data;
+    }
+
+    // Count the deadline against the segments that missed it, so auto_skip
+    // takes them out of later renders
+    for index in running {
+        let (segment_config, slot) = &mut slots[index];
+        crate::debug_log!("{:?} missed the render deadline", segment_config.id);
+        if segment_config.enabled {
+            perf.record(segment_config.id, started.elapsed(), &config.perf);
+            *slot = Some(crate::core::perf::skipped_data(
+                perf.segments.get(&segment_config.id),
+            ));
+        }
+    }
+    // Not started: still running from an earlier render, or waiting for a
+    // dependency that missed the deadline. Their timings stay as they were.
+    for index in waiting.into_iter().chain(stalled) {
+        let (segment_config, slot) = &mut slots[index];
+        crate::debug_log!("{:?} was not collected in this render", segment_config.id);
+        if segment_config.enabled {
+            *slot = Some(crate::core::perf::skipped_data(
+                perf.segments.get(&segment_config.id),
+            ));
+        }
+    }
+
+    // Publish in config order for the summary
+    let mut ctx = CollectionContext::new(collect_config, input, vendor);
+    let mut results = Vec::new();
+    let mut summary_at = None;
+    for (index, (segment_config, data)) in slots.into_iter().enumerate() {
+        if Some(index) == summary_slot {
+            summary_at = Some((results.len(), segment_config));
+            continue;
+        }
+        if let Some(data) = data {
+            let data = peaks.apply(segment_config, data, &config.format);
+            ctx.publish(segment_config.id, &data);
+            results.push((segment_config.clone(), data));
+        }
+    }
+
+    if let (Some(summary), Some((slot, segment_config))) = (summary, summary_at) {
+        if let Some(data) = summary.summarize(&ctx) {
+            results.insert(slot, (segment_config.clone(), data));
+        }
+    }
+
+    if RECORDING.load(std::sync::atomic::Ordering::Relaxed) {
+        if let Err(e) = perf.save() {
+            crate::debug_log!("failed to save segment timings: {}", e);
+        }
+        if let Err(e) = peaks.save() {
+            crate::debug_log!("failed to save peaks: {}", e);
+        }
+    }
+
+    results
+}
+
+/// Collect one segment; the summary is assembled separately from the others
+fn collect_segment(
+    segment_config: &SegmentConfig,
+    config: &Config,
+    ctx: &crate::core::segments::CollectionContext,
+) -> Option<SegmentData> {
+    use crate::core::segments::*;
+
+    let input = ctx.input;
+    match segment_config.id {
+        crate::config::SegmentId::Model => {
+            let segment =
+                ModelSegment::new().with_accent(palette::escape(&config.style, Role::Accent));
+            segment.collect(input)
+        }
+        crate::config::SegmentId::Directory => {
+            let segment = DirectorySegment::new();
+            segment.collect(input)
+        }
+        crate::config::SegmentId::Git => {
+            let show_sha = segment_config
+                .options
+                .get("show_sha")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let mut segment = GitSegment::new().with_sha(show_sha);
+            if let Some(ms) = segment_config
+                .options
+                .get("timeout_ms")
+                .and_then(|v| v.as_u64())
+            {
+                segment = segment.with_timeout(std::time::Duration::from_millis(ms));
             }
-            crate::config::SegmentId::Update => {
-                let segment = UpdateSegment::new();
-                segment.collect(input)
+            segment.collect(input)
+        }
+        crate::config::SegmentId::ContextWindow => {
+            let mut segment = ContextWindowSegment::new();
+            let option = |key: &str| segment_config.options.get(key);
+            if option("predict_compaction").and_then(|v| v.as_bool()) == Some(true) {
+                let compact_at = option("compact_at").and_then(|v| v.as_f64());
+                segment = segment.with_prediction(compact_at.unwrap_or(92.0));
             }
-            crate::config::SegmentId::UucodeUsage => uucode_usage::collect(config, input),
-            crate::config::SegmentId::UucodeSubscription => {
-                uucode_subscription::collect(config, input)
+            segment.collect(input)
+        }
+        crate::config::SegmentId::ContextAdvisor => {
+            let option_f64 = |key: &str| segment_config.options.get(key).and_then(|v| v.as_f64());
+            let mut segment = ContextAdvisorSegment::new().with_thresholds(
+                option_f64("compact_threshold").unwrap_or(70.0),
+                option_f64("clear_threshold").unwrap_or(90.0),
+            );
+            if let Some(max) = segment_config
+                .options
+                .get("max_messages")
+                .and_then(|v| v.as_u64())
+            {
+                segment = segment.with_max_messages(max as usize);
             }
-            crate::config::SegmentId::UucodeStatus => uucode_status::collect(config, input),
-            crate::config::SegmentId::CubenceBalance => cubence_balance::collect(config, input),
-            crate::config::SegmentId::CubenceUsage => cubence_usage::collect(config, input),
-            crate::config::SegmentId::CubenceStatus => cubence_status::collect(config, input),
-            crate::config::SegmentId::CubenceFiveHour => cubence_five_hour::collect(config, input),
-            crate::config::SegmentId::CubenceWeekly => cubence_weekly::collect(config, input),
-            crate::config::SegmentId::CubenceLoadStatus => {
-                cubence_load_status::collect(config, input)
+            if let Some(turns) = option_f64("min_turns_left") {
+                segment = segment.with_min_turns_left(turns);
             }
-            crate::config::SegmentId::CubenceLatency => cubence_latency::collect(config, input),
-            crate::config::SegmentId::CubenceSubscription => {
-                cubence_subscription::collect(config, input)
+            segment.collect(input)
+        }
+        crate::config::SegmentId::ApiErrors => {
+            let mut segment = ApiErrorsSegment::new();
+            if let Some(minutes) = segment_config
+                .options
+                .get("window_minutes")
+                .and_then(|v| v.as_i64())
+            {
+                segment = segment.with_window(minutes);
             }
-            crate::config::SegmentId::CubenceMultiplier => {
-                cubence_multiplier::collect(config, input)
+            if let Some(count) = segment_config
+                .options
+                .get("critical_at")
+                .and_then(|v| v.as_u64())
+            {
+                segment = segment.with_critical_at(count as usize);
             }
-        };
-
-        if let Some(data) = segment_data {
-            results.push((segment_config.clone(), data));
+            segment.collect(input)
+        }
+        // Filled in once every other segment is collected
+        crate::config::SegmentId::Summary => None,
+        crate::config::SegmentId::QuotaPacing => quota_pacing::collect(ctx),
+        crate::config::SegmentId::Usage => {
+            let segment = UsageSegment::new();
+            segment.collect(input)
+        }
+        crate::config::SegmentId::Cost => {
+            let estimate = segment_config
+                .options
+                .get("estimate")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let turn_delta = segment_config
+                .options
+                .get("turn_delta")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+            let segment = CostSegment::new()
+                .with_format(config.format.clone())
+                .with_estimate(estimate)
+                .with_turn_delta(turn_delta);
+            segment.collect(input)
         }
+        crate::config::SegmentId::DailyCost => daily_cost::collect(ctx),
+        crate::config::SegmentId::BlockUsage => block_usage::collect(ctx),
+        crate::config::SegmentId::Session => {
+            let segment = SessionSegment::new().with_locale(config.format.locale);
+            segment.collect(input)
+        }
+        crate::config::SegmentId::OutputStyle => {
+            let segment = OutputStyleSegment::new();
+            segment.collect(input)
+        }
+        crate::config::SegmentId::Runtime => {
+            let show_local = segment_config
+                .options
+                .get("show_local")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let segment = RuntimeSegment::new().with_show_local(show_local);
+            segment.collect(input)
+        }
+        #[cfg(feature = "self-update")]
+        crate::config::SegmentId::Update => {
+            let segment = UpdateSegment::new();
+            segment.collect(input)
+        }
+        #[cfg(feature = "uucode")]
+        crate::config::SegmentId::UucodeUsage => uucode_usage::collect(ctx),
+        #[cfg(feature = "uucode")]
+        crate::config::SegmentId::UucodeSubscription => uucode_subscription::collect(ctx),
+        #[cfg(feature = "uucode")]
+        crate::config::SegmentId::UucodeStatus => uucode_status::collect(ctx),
+        #[cfg(feature = "cubence")]
+        crate::config::SegmentId::CubenceBalance => cubence_balance::collect(ctx),
+        #[cfg(feature = "cubence")]
+        crate::config::SegmentId::CubenceUsage => cubence_usage::collect(ctx),
+        #[cfg(feature = "cubence")]
+        crate::config::SegmentId::CubenceStatus => cubence_status::collect(ctx),
+        #[cfg(feature = "cubence")]
+        crate::config::SegmentId::CubenceFiveHour => cubence_five_hour::collect(ctx),
+        #[cfg(feature = "cubence")]
+        crate::config::SegmentId::CubenceWeekly => cubence_weekly::collect(ctx),
+        #[cfg(feature = "cubence")]
+        crate::config::SegmentId::CubenceLoadStatus => cubence_load_status::collect(ctx),
+        #[cfg(feature = "cubence")]
+        crate::config::SegmentId::CubenceLatency => cubence_latency::collect(ctx),
+        #[cfg(feature = "cubence")]
+        crate::config::SegmentId::CubenceSubscription => cubence_subscription::collect(ctx),
+        #[cfg(feature = "cubence")]
+        crate::config::SegmentId::CubenceMultiplier => cubence_multiplier::collect(ctx),
+        crate::config::SegmentId::OneApiUsage => one_api_usage::collect(ctx),
+        crate::config::SegmentId::VendorUsage => vendor_usage::collect(ctx),
+        crate::config::SegmentId::Custom => custom::collect(ctx),
+        // Segments left out of this build by its Cargo features
+        #[cfg(not(all(feature = "cubence", feature = "uucode", feature = "self-update")))]
+        _ => None,
     }
-
-    results
 }
@@ -0,0 +1,78 @@
+//! 5-hour billing blocks, the window Claude subscriptions are metered in.
+//!
+//! A block starts at the top of the hour of the first response after the
+//! previous block ended and runs for [`BLOCK_SECONDS`]; responses in that
+//! span count towards it. Built from the records of [`super::daily`], so it
+//! covers the same retention window.
+
+use super::daily::{PeriodTotals, UsageRecord};
+use crate::config::ModelConfig;
+
+pub const BLOCK_SECONDS: i64 = 5 * 3600;
+
+/// One billing block
+#[derive(Debug, Clone)]
+pub struct Block {
+    /// Unix seconds, on the hour
+    pub start: i64,
+    /// When the block resets, `start` + [`BLOCK_SECONDS`]
+    pub end: i64,
+    /// Time of the latest response in the block
+    pub last_activity: i64,
+    pub totals: PeriodTotals,
+}
+
+impl Block {
+    pub fn is_active(&self, now: i64) -> bool {
+        self.start <= now && now < self.end
+    }
+}
+
+/// Group `records` into blocks, oldest first
+///
+/// ```
+/// use mycode::config::ModelConfig;
+/// use mycode::core::transcript::blocks::{group, BLOCK_SECONDS};
+/// use mycode::core::transcript::daily::UsageRecord;
+/// use mycode::core::transcript::TokenCounts;
+///
+/// let record = |at| UsageRecord {
+///     id: format!("msg_{}", at),
+///     at,
+///     model: "claude-sonnet-4-5".to_string(),
+///     tokens: TokenCounts { output: 1_000, ..Default::default() },
+/// };
+/// // 10:20, 14:59 and 15:30 on the same day
+/// let day = 1_700_000_000 / 86_400 * 86_400;
+/// let records = [record(day + 37_200), record(day + 53_940), record(day + 55_800)];
+///
+/// let blocks = group(&records, &ModelConfig::default());
+/// assert_eq!(blocks.len(), 2);
+/// assert_eq!(blocks[0].start, day + 36_000);
+/// assert_eq!(blocks[0].end - blocks[0].start, BLOCK_SECONDS);
+/// assert_eq!(blocks[0].totals.tokens, 2_000);
+/// assert_eq!(blocks[1].start, day + 54_000);
+/// assert!(blocks[1].is_active(day + 60_000));
+/// ```
+pub fn group(records: &[UsageRecord], models: &ModelConfig) -> Vec<Block> {
+    let mut sorted: Vec<&UsageRecord> = records.iter().collect();
+    sorted.sort_by_key(|record| record.at);
+
+    let mut blocks: Vec<Block> = Vec::new();
+    for record in sorted {
+        if blocks.last().is_none_or(|block| record.at >= block.end) {
+            let start = record.at - record.at.rem_euclid(3600);
+            blocks.push(Block {
+                start,
+                end: start + BLOCK_SECONDS,
+                last_activity: record.at,
+                totals: PeriodTotals::default(),
+            });
+        }
+        if let Some(current) = blocks.last_mut() {
+            current.last_activity = record.at;
+            current.totals.add(record, models);
+        }
+    }
+    blocks
+}
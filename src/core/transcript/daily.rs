@@ -0,0 +1,299 @@
+//! Token usage of the last days across every Claude Code transcript under
+//! `<claude dir>/projects/`, priced locally for the `daily_cost` segment.
+//!
+//! Transcripts only ever grow, so each file is parsed from where the last
+//! scan stopped and its usage records are kept in
+//! `~/.claude/mycode/cache/transcript_usage.json`. Records older than
+//! [`RETENTION_DAYS`] are dropped, and files not modified since then are not
+//! opened at all. A scan stops after its time budget and carries on from
+//! the saved offsets on the next render, so a first scan over a large
+//! history never blocks the statusline.
+//!
+//! Claude Code copies earlier messages into the new transcript when a
+//! session is resumed, so a message is counted once by id across all files.
+
+use super::TokenCounts;
+use crate::config::{ModelConfig, TranscriptEntry};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+/// Covers a full week starting on any weekday
+pub const RETENTION_DAYS: i64 = 8;
+
+/// Bump when the record format changes so old caches are rebuilt
+const CACHE_VERSION: u32 = 1;
+
+/// Subagent transcripts sit in `projects/<project>/<session>/subagents/`
+const MAX_DEPTH: usize = 4;
+
+/// Usage of one API response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageRecord {
+    /// API message id
+    pub id: String,
+    /// Unix seconds
+    pub at: i64,
+    pub model: String,
+    pub tokens: TokenCounts,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FileState {
+    /// Bytes parsed so far, always at a line boundary
+    offset: u64,
+    /// Modification time (Unix seconds) when `offset` was reached
+    modified: i64,
+    records: Vec<UsageRecord>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct UsageCache {
+    version: u32,
+    files: BTreeMap<PathBuf, FileState>,
+}
+
+/// Records of the retention window, each message once
+#[derive(Debug, Default)]
+pub struct Scan {
+    pub records: Vec<UsageRecord>,
+    /// False when the time budget ran out before every file was parsed
+    pub complete: bool,
+}
+
+/// Tokens and cost of a time range
+#[derive(Debug, Default, Clone)]
+pub struct PeriodTotals {
+    pub cost: f64,
+    pub tokens: u64,
+    pub responses: usize,
+    /// Tokens of models without a known price, left out of `cost`
+    pub unpriced_tokens: u64,
+}
+
+impl Scan {
+    /// Sum the records with `from <= at < to` (Unix seconds)
+    ///
+    /// ```
+    /// use mycode::config::ModelConfig;
+    /// use mycode::core::transcript::daily::{Scan, UsageRecord};
+    /// use mycode::core::transcript::TokenCounts;
+    ///
+    /// let record = |at, model: &str| UsageRecord {
+    ///     id: format!("msg_{}", at),
+    ///     at,
+    ///     model: model.to_string(),
+    ///     tokens: TokenCounts { input: 1_000_000, output: 100_000, ..Default::default() },
+    /// };
+    /// let scan = Scan {
+    ///     records: vec![record(100, "claude-sonnet-4-5"), record(200, "local-model"), record(300, "claude-sonnet-4-5")],
+    ///     complete: true,
+    /// };
+    ///
+    /// let totals = scan.period(100, 300, &ModelConfig::default());
+    /// // $3 input + $1.50 output at Sonnet prices; the unknown model is not priced
+    /// assert!((totals.cost - 4.5).abs() < 1e-9);
+    /// assert_eq!(totals.responses, 2);
+    /// assert_eq!(totals.unpriced_tokens, 1_100_000);
+    /// ```
+    pub fn period(&self, from: i64, to: i64, models: &ModelConfig) -> PeriodTotals {
+        let mut totals = PeriodTotals::default();
+        for record in self.records.iter().filter(|r| r.at >= from && r.at < to) {
+            totals.add(record, models);
+        }
+        totals
+    }
+}
+
+impl PeriodTotals {
+    /// Count one response
+    pub fn add(&mut self, record: &UsageRecord, models: &ModelConfig) {
+        self.tokens += record.tokens.total();
+        self.responses += 1;
+        match record.tokens.cost(&record.model, models) {
+            Some(cost) => self.cost += cost,
+            None => self.unpriced_tokens += record.tokens.total(),
+        }
+    }
+}
+
+fn cache_path() -> Option<PathBuf> {
+    crate::utils::paths::cache_dir().map(|dir| dir.join("transcript_usage.json"))
+}
+
+fn load_cache() -> UsageCache {
+    cache_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<UsageCache>(&content).ok())
+        .filter(|cache| cache.version == CACHE_VERSION)
+        .unwrap_or_default()
+}
+
+fn save_cache(cache: &UsageCache) {
+    if crate::utils::readonly::is_enabled() {
+        return;
+    }
+    let Some(path) = cache_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(json) = serde_json::to_string(cache) {
+        let _ = crate::utils::atomic_write(&path, json);
+    }
+}
+
+/// Every `.jsonl` file below `dir`
+fn transcripts(dir: &Path, depth: usize, found: &mut Vec<(PathBuf, fs::Metadata)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            if depth < MAX_DEPTH {
+                transcripts(&path, depth + 1, found);
+            }
+        } else if path.extension().is_some_and(|ext| ext == "jsonl") {
+            found.push((path, metadata));
+        }
+    }
+}
+
+fn modified_secs(metadata: &fs::Metadata) -> i64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+/// Usage of one transcript line, when it is an assistant response
+fn parse_record(line: &str) -> Option<UsageRecord> {
+    // Most lines are tool results and user turns; skip them before parsing
+    if !line.contains("\"usage\"") {
+        return None;
+    }
+    let entry = serde_json::from_str::<TranscriptEntry>(line.trim()).ok()?;
+    if entry.r#type.as_deref() != Some("assistant") || entry.is_api_error_message {
+        return None;
+    }
+    let at = chrono::DateTime::parse_from_rfc3339(entry.timestamp.as_deref()?)
+        .ok()?
+        .timestamp();
+    let message = entry.message?;
+    let usage = message.usage?.normalize();
+    Some(UsageRecord {
+        id: message.id.or(entry.uuid)?,
+        at,
+        model: message.model.unwrap_or_default(),
+        tokens: TokenCounts {
+            input: u64::from(usage.input_tokens),
+            output: u64::from(usage.output_tokens),
+            cache_creation: u64::from(usage.cache_creation_input_tokens),
+            cache_read: u64::from(usage.cache_read_input_tokens),
+        },
+    })
+}
+
+/// Parse `path` from `state.offset`, stopping at the deadline. Returns
+/// false when the file was not read to the end.
+fn parse_from(path: &Path, state: &mut FileState, since: i64, deadline: Instant) -> bool {
+    let Ok(mut file) = fs::File::open(path) else {
+        return true;
+    };
+    if file.seek(SeekFrom::Start(state.offset)).is_err() {
+        return true;
+    }
+    let mut reader = BufReader::new(file);
+    let mut line = Vec::new();
+    loop {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) | Err(_) => return true,
+            // A line still being written; picked up once it is complete
+            Ok(_) if line.last() != Some(&b'\n') => return true,
+            Ok(read) => {
+                state.offset += read as u64;
+                if let Some(record) = parse_record(&String::from_utf8_lossy(&line)) {
+                    if record.at >= since {
+                        state.records.push(record);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Bring the cache up to date with the transcripts under `projects_dir`,
+/// spending at most `budget` on parsing
+pub fn scan(projects_dir: &Path, now: i64, budget: Duration) -> Scan {
+    let deadline = Instant::now() + budget;
+    let since = now - RETENTION_DAYS * 86_400;
+    let mut cache = load_cache();
+    cache.version = CACHE_VERSION;
+    let mut changed = false;
+
+    let mut found = Vec::new();
+    transcripts(projects_dir, 0, &mut found);
+    // Newest first, so a scan cut short has covered the recent days
+    found.sort_by_key(|(_, metadata)| std::cmp::Reverse(modified_secs(metadata)));
+
+    let live: HashSet<&PathBuf> = found.iter().map(|(path, _)| path).collect();
+    let before = cache.files.len();
+    cache.files.retain(|path, _| live.contains(path));
+    changed |= cache.files.len() != before;
+
+    let mut complete = true;
+    for (path, metadata) in &found {
+        let modified = modified_secs(metadata);
+        if modified < since {
+            changed |= cache.files.remove(path).is_some();
+            continue;
+        }
+        let state = cache.files.entry(path.clone()).or_default();
+        if state.offset == metadata.len() && state.modified == modified {
+            continue;
+        }
+        if metadata.len() < state.offset {
+            // Rewritten rather than appended to; start over
+            *state = FileState::default();
+        }
+        if !complete {
+            continue;
+        }
+        complete = parse_from(path, state, since, deadline);
+        state.modified = modified;
+        changed = true;
+    }
+
+    for state in cache.files.values_mut() {
+        let before = state.records.len();
+        state.records.retain(|record| record.at >= since);
+        changed |= state.records.len() != before;
+    }
+
+    let mut seen = HashSet::new();
+    let records = cache
+        .files
+        .values()
+        .flat_map(|state| &state.records)
+        .filter(|record| seen.insert(record.id.as_str()))
+        .cloned()
+        .collect();
+
+    if changed {
+        save_cache(&cache);
+    }
+    Scan { records, complete }
+}
@@ -0,0 +1,273 @@
+//! Whole-session aggregates read from a Claude Code transcript.
+//!
+//! [`daily`] aggregates across every transcript instead, by day, and
+//! [`blocks`] groups the same records into 5-hour billing blocks.
+
+pub mod blocks;
+pub mod daily;
+
+use crate::config::{ModelConfig, TranscriptEntry};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// Tools whose `file_path` input means a file was modified
+const EDIT_TOOLS: &[&str] = &["Edit", "MultiEdit", "Write", "NotebookEdit"];
+
+#[derive(Debug, Default, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub struct TokenCounts {
+    pub input: u64,
+    pub output: u64,
+    pub cache_creation: u64,
+    pub cache_read: u64,
+}
+
+impl TokenCounts {
+    pub fn total(&self) -> u64 {
+        self.input + self.output + self.cache_creation + self.cache_read
+    }
+
+    /// Cost at the model's price, `None` when the model has no known price
+    pub fn cost(&self, model: &str, models: &ModelConfig) -> Option<f64> {
+        let pricing = models.get_pricing(model)?;
+        Some(pricing.cost(
+            self.input,
+            self.output,
+            self.cache_creation,
+            self.cache_read,
+        ))
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct TranscriptTotals {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+    /// Assistant responses, counted once per API message
+    pub responses: usize,
+    pub files_touched: BTreeSet<String>,
+    /// Token usage split by the model that produced each response
+    pub by_model: BTreeMap<String, TokenCounts>,
+}
+
+impl TranscriptTotals {
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens + self.cache_creation_tokens + self.cache_read_tokens
+    }
+
+    /// Session cost at list prices, or `None` when no response came from a
+    /// model with known pricing
+    pub fn estimated_cost(&self, models: &ModelConfig) -> Option<f64> {
+        self.by_model
+            .iter()
+            .filter_map(|(model, tokens)| tokens.cost(model, models))
+            .reduce(|a, b| a + b)
+    }
+}
+
+/// Sum token usage and collect edited files over the whole transcript.
+///
+/// Claude Code writes one line per content block, all carrying the usage
+/// of the same API message, so usage is counted once per message id.
+pub fn totals(path: &Path) -> Option<TranscriptTotals> {
+    let file = fs::File::open(path).ok()?;
+    let mut totals = TranscriptTotals::default();
+    let mut seen_messages = HashSet::new();
+
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        let Ok(entry) = serde_json::from_str::<TranscriptEntry>(line.trim()) else {
+            continue;
+        };
+        if entry.r#type.as_deref() != Some("assistant") {
+            continue;
+        }
+        let Some(message) = entry.message else {
+            continue;
+        };
+
+        if let Some(blocks) = message.content.as_ref().and_then(|c| c.as_array()) {
+            for block in blocks {
+                let is_edit = block
+                    .get("name")
+                    .and_then(|n| n.as_str())
+                    .is_some_and(|name| EDIT_TOOLS.contains(&name));
+                if let (true, Some(file_path)) = (is_edit, tool_file_path(block)) {
+                    totals.files_touched.insert(file_path.to_string());
+                }
+            }
+        }
+
+        let first_time = match &message.id {
+            Some(id) => seen_messages.insert(id.clone()),
+            None => true,
+        };
+        if !first_time {
+            continue;
+        }
+        if let Some(usage) = message.usage {
+            let usage = usage.normalize();
+            totals.input_tokens += u64::from(usage.input_tokens);
+            totals.output_tokens += u64::from(usage.output_tokens);
+            totals.cache_creation_tokens += u64::from(usage.cache_creation_input_tokens);
+            totals.cache_read_tokens += u64::from(usage.cache_read_input_tokens);
+            totals.responses += 1;
+
+            let model = message.model.unwrap_or_default();
+            let tokens = totals.by_model.entry(model).or_default();
+            tokens.input += u64::from(usage.input_tokens);
+            tokens.output += u64::from(usage.output_tokens);
+            tokens.cache_creation += u64::from(usage.cache_creation_input_tokens);
+            tokens.cache_read += u64::from(usage.cache_read_input_tokens);
+        }
+    }
+
+    Some(totals)
+}
+
+/// File a `tool_use` content block reads or writes
+fn tool_file_path(block: &serde_json::Value) -> Option<&str> {
+    if block.get("type").and_then(|t| t.as_str()) != Some("tool_use") {
+        return None;
+    }
+    let input = block.get("input")?;
+    input
+        .get("file_path")
+        .or_else(|| input.get("notebook_path"))
+        .and_then(|p| p.as_str())
+}
+
+/// The file the latest tool call in the transcript read or wrote
+pub fn last_touched_file(path: &Path) -> Option<String> {
+    let file = fs::File::open(path).ok()?;
+    let mut last = None;
+
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        // Skip lines without a tool call before parsing
+        if !line.contains("\"tool_use\"") {
+            continue;
+        }
+        let Ok(entry) = serde_json::from_str::<TranscriptEntry>(line.trim()) else {
+            continue;
+        };
+        let blocks = entry.message.and_then(|m| m.content);
+        for block in blocks
+            .as_ref()
+            .and_then(|c| c.as_array())
+            .into_iter()
+            .flatten()
+        {
+            if let Some(file_path) = tool_file_path(block) {
+                last = Some(file_path.to_string());
+            }
+        }
+    }
+
+    last
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiErrorKind {
+    /// 529, the API is overloaded
+    Overloaded,
+    /// 429 or a usage limit
+    RateLimit,
+    Other,
+}
+
+impl ApiErrorKind {
+    fn classify(text: &str) -> Self {
+        let text = text.to_ascii_lowercase();
+        if text.contains("529") || text.contains("overloaded") {
+            ApiErrorKind::Overloaded
+        } else if text.contains("429")
+            || text.contains("rate_limit")
+            || text.contains("limit reached")
+        {
+            ApiErrorKind::RateLimit
+        } else {
+            ApiErrorKind::Other
+        }
+    }
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            ApiErrorKind::Overloaded => "overloaded",
+            ApiErrorKind::RateLimit => "rate_limit",
+            ApiErrorKind::Other => "other",
+        }
+    }
+}
+
+/// A failed API request recorded in the transcript
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    pub at: chrono::DateTime<chrono::FixedOffset>,
+    pub kind: ApiErrorKind,
+}
+
+/// API errors Claude Code recorded in the transcript, oldest first
+pub fn api_errors(path: &Path) -> Option<Vec<ApiError>> {
+    let file = fs::File::open(path).ok()?;
+    let mut errors = Vec::new();
+
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        // Most lines are not errors; skip them before parsing
+        if !line.contains("isApiErrorMessage") {
+            continue;
+        }
+        let Ok(entry) = serde_json::from_str::<TranscriptEntry>(line.trim()) else {
+            continue;
+        };
+        if !entry.is_api_error_message {
+            continue;
+        }
+        let Some(at) = entry
+            .timestamp
+            .as_deref()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        else {
+            continue;
+        };
+        let text = entry
+            .message
+            .and_then(|m| m.content)
+            .map(|content| content.to_string())
+            .unwrap_or_default();
+        errors.push(ApiError {
+            at,
+            kind: ApiErrorKind::classify(&text),
+        });
+    }
+
+    Some(errors)
+}
+
+/// Time of the newest entry in the transcript
+pub fn last_timestamp(path: &Path) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+    timestamps(path)?.max()
+}
+
+/// Time of the oldest entry in the transcript, when the session started
+pub fn first_timestamp(path: &Path) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+    timestamps(path)?.min()
+}
+
+fn timestamps(path: &Path) -> Option<impl Iterator<Item = chrono::DateTime<chrono::FixedOffset>>> {
+    #[derive(serde::Deserialize)]
+    struct Stamped {
+        timestamp: Option<String>,
+    }
+
+    let file = fs::File::open(path).ok()?;
+    Some(
+        BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter(|line| line.contains("\"timestamp\""))
+            .filter_map(|line| serde_json::from_str::<Stamped>(line.trim()).ok()?.timestamp)
+            .filter_map(|t| chrono::DateTime::parse_from_rfc3339(&t).ok()),
+    )
+}
@@ -0,0 +1,126 @@
+//! Cost of the most recent turn, per session (`cache/turn_delta.json`).
+//!
+//! Each render compares the session's running totals with the ones the
+//! previous render stored. When they grew, the difference is what the turn
+//! that just finished cost, and it is kept until the totals move again, so
+//! every render between two turns shows the same delta.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Sessions kept, the least recently updated are dropped
+const KEEP_SESSIONS: usize = 20;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Running totals at the previous render
+    pub cost: f64,
+    pub tokens: u64,
+    /// Growth of the totals over the most recent turn
+    pub turn_cost: f64,
+    pub turn_tokens: u64,
+    /// Unix timestamp of the last change
+    pub updated_at: i64,
+}
+
+impl Snapshot {
+    /// Totals seen for the first time: the session may have been resumed,
+    /// so none of it is attributed to a turn
+    pub fn new(cost: f64, tokens: u64) -> Self {
+        Self {
+            cost,
+            tokens,
+            ..Self::default()
+        }
+    }
+
+    /// Move to the current totals; returns whether they changed
+    ///
+    /// ```
+    /// use mycode::core::turn_delta::Snapshot;
+    ///
+    /// let mut snapshot = Snapshot::new(1.06, 40_000);
+    /// assert!(snapshot.advance(1.24, 52_000));
+    /// assert!((snapshot.turn_cost - 0.18).abs() < 1e-9);
+    /// assert_eq!(snapshot.turn_tokens, 12_000);
+    ///
+    /// // Rendering again without a new turn keeps the delta
+    /// assert!(!snapshot.advance(1.24, 52_000));
+    /// assert_eq!(snapshot.turn_tokens, 12_000);
+    /// ```
+    pub fn advance(&mut self, cost: f64, tokens: u64) -> bool {
+        if cost == self.cost && tokens == self.tokens {
+            return false;
+        }
+        // Totals only shrink when the session starts over
+        if cost < self.cost || tokens < self.tokens {
+            *self = Self::new(cost, tokens);
+        } else {
+            self.turn_cost = cost - self.cost;
+            self.turn_tokens = tokens - self.tokens;
+            self.cost = cost;
+            self.tokens = tokens;
+        }
+        true
+    }
+}
+
+fn snapshots_path() -> Option<PathBuf> {
+    crate::utils::paths::cache_dir().map(|dir| dir.join("turn_delta.json"))
+}
+
+fn load() -> HashMap<String, Snapshot> {
+    snapshots_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Record the totals of `session` and return its snapshot. Deterministic
+/// renders neither read nor write the file and never show a delta.
+pub fn record(session: &str, cost: f64, tokens: u64) -> Snapshot {
+    if crate::utils::clock::is_deterministic() || session.is_empty() {
+        return Snapshot::new(cost, tokens);
+    }
+    let mut snapshots = load();
+    let now = crate::utils::clock::unix_now();
+    let snapshot = match snapshots.get_mut(session) {
+        Some(snapshot) => {
+            if !snapshot.advance(cost, tokens) {
+                return *snapshot;
+            }
+            snapshot.updated_at = now;
+            *snapshot
+        }
+        None => {
+            let snapshot = Snapshot {
+                updated_at: now,
+                ..Snapshot::new(cost, tokens)
+            };
+            snapshots.insert(session.to_string(), snapshot);
+            snapshot
+        }
+    };
+
+    if snapshots.len() > KEEP_SESSIONS {
+        let mut updated: Vec<i64> = snapshots.values().map(|s| s.updated_at).collect();
+        updated.sort_unstable_by(|a, b| b.cmp(a));
+        let cutoff = updated[KEEP_SESSIONS - 1];
+        snapshots.retain(|_, s| s.updated_at >= cutoff);
+    }
+    if let Err(e) = save(&snapshots) {
+        crate::debug_log!("failed to save turn delta: {}", e);
+    }
+    snapshot
+}
+
+fn save(snapshots: &HashMap<String, Snapshot>) -> Result<(), Box<dyn std::error::Error>> {
+    let path = snapshots_path().ok_or("Could not find home directory")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    crate::utils::atomic_write(path, serde_json::to_string(snapshots)?)?;
+    Ok(())
+}
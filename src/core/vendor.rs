@@ -0,0 +1,73 @@
+//! Reacting to the detected vendor. When ANTHROPIC_BASE_URL switched to
+//! another provider since the last run in the same session (Claude config
+//! dir and base URL override), stale caches of the old vendor are
+//! dropped and the theme configured for the new one in `vendor_themes` is
+//! switched to. The switch is kept in the cache dir rather than config.toml
+//! and lasts until the next vendor change or until the configured theme is
+//! changed; an explicit `--theme` always wins.
+
+use crate::api::{cache, VendorType};
+use crate::config::Config;
+use crate::debug_log;
+
+/// Record the detected vendor and, if it differs from the previous run,
+/// invalidate other vendors' caches and switch to the theme configured for
+/// it. `theme_override` is set when `--theme` (or the wrapper's theme)
+/// chose the theme for this render; vendor themes are left out then.
+///
+/// Returns `true` when the vendor changed since the last run.
+pub fn on_vendor_detected(config: &mut Config, vendor: VendorType, theme_override: bool) -> bool {
+    let changed = update(vendor);
+    if theme_override {
+        return changed;
+    }
+
+    if changed {
+        let switch = config
+            .vendor_themes
+            .get(vendor.id())
+            .filter(|theme| **theme != config.theme)
+            .map(|theme| cache::VendorTheme {
+                theme: theme.clone(),
+                replaces: config.theme.clone(),
+            });
+        if let Some(switch) = &switch {
+            debug_log!(
+                "switched to theme '{}' for vendor {}",
+                switch.theme,
+                vendor.id()
+            );
+        }
+        if let Err(e) = cache::save_vendor_theme(switch.as_ref()) {
+            debug_log!("failed to save the vendor theme: {}", e);
+        }
+    }
+
+    // A theme picked in the config since the switch takes precedence
+    if let Some(switch) = cache::get_vendor_theme().filter(|s| s.replaces == config.theme) {
+        config.apply_theme(crate::ui::themes::ThemePresets::get_theme(&switch.theme));
+    }
+    changed
+}
+
+/// Record `vendor`, returning whether it differs from the previous run
+fn update(vendor: VendorType) -> bool {
+    let previous = cache::get_last_vendor();
+    if previous == Some(vendor) {
+        return false;
+    }
+
+    debug_log!(
+        "vendor changed: {} -> {}",
+        previous.map(|v| v.id()).unwrap_or("none"),
+        vendor.id()
+    );
+
+    if previous.is_some() {
+        cache::invalidate_other_vendors(vendor);
+        debug_log!("invalidated usage caches of other vendors");
+    }
+
+    let _ = cache::save_last_vendor(vendor);
+    true
+}
@@ -0,0 +1,169 @@
+//! Change detection for the long-running modes (`--follow`, `--watch`).
+//!
+//! Watches the cache directory, config.toml and the current transcript.
+//! With the `watch` feature this uses filesystem notifications; without it
+//! the same files are polled for modification times.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Events arriving this close together are treated as one change
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+pub struct ChangeWatcher {
+    #[cfg(feature = "watch")]
+    cache_dir: Option<PathBuf>,
+    config_path: PathBuf,
+    transcript: Option<PathBuf>,
+    #[cfg(feature = "watch")]
+    watcher: Option<notify::RecommendedWatcher>,
+    #[cfg(feature = "watch")]
+    events: std::sync::mpsc::Receiver<notify::Result<notify::Event>>,
+    #[cfg(not(feature = "watch"))]
+    seen: Option<std::time::SystemTime>,
+}
+
+impl ChangeWatcher {
+    pub fn new() -> Self {
+        let cache_dir = crate::utils::paths::cache_dir();
+        if let Some(dir) = &cache_dir {
+            let _ = std::fs::create_dir_all(dir);
+        }
+
+        #[cfg(feature = "watch")]
+        {
+            use notify::Watcher;
+
+            let (sender, events) = std::sync::mpsc::channel();
+            let mut watcher = notify::recommended_watcher(sender)
+                .map_err(|e| crate::debug_log!("file watcher unavailable: {}", e))
+                .ok();
+
+            let config_path = crate::config::Config::get_config_path();
+            if let Some(watcher) = watcher.as_mut() {
+                if let Some(dir) = &cache_dir {
+                    let _ = watcher.watch(dir, notify::RecursiveMode::Recursive);
+                }
+                // Editors replace files on save, watch the directory instead
+                if let Some(dir) = config_path.parent() {
+                    let _ = watcher.watch(dir, notify::RecursiveMode::NonRecursive);
+                }
+            }
+
+            Self {
+                cache_dir,
+                config_path,
+                transcript: None,
+                watcher,
+                events,
+            }
+        }
+
+        #[cfg(not(feature = "watch"))]
+        {
+            let mut watcher = Self {
+                config_path: crate::config::Config::get_config_path(),
+                transcript: None,
+                seen: None,
+            };
+            watcher.seen = watcher.latest_modification();
+            watcher
+        }
+    }
+
+    /// Follow another transcript file, e.g. after a new session started
+    pub fn watch_transcript(&mut self, path: &Path) {
+        if self.transcript.as_deref() == Some(path) {
+            return;
+        }
+
+        #[cfg(feature = "watch")]
+        if let Some(watcher) = self.watcher.as_mut() {
+            use notify::Watcher;
+
+            if let Some(dir) = self.transcript.as_ref().and_then(|p| p.parent()) {
+                let _ = watcher.unwatch(dir);
+            }
+            if let Some(dir) = path.parent() {
+                let _ = watcher.watch(dir, notify::RecursiveMode::NonRecursive);
+            }
+        }
+
+        self.transcript = Some(path.to_path_buf());
+    }
+
+    /// Block until a watched file changes or `timeout` passes.
+    /// Returns `true` when something changed.
+    pub fn wait(&mut self, timeout: Duration) -> bool {
+        #[cfg(feature = "watch")]
+        {
+            if self.watcher.is_none() {
+                std::thread::sleep(timeout);
+                return false;
+            }
+
+            let deadline = std::time::Instant::now() + timeout;
+            loop {
+                let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+                match self.events.recv_timeout(remaining) {
+                    Ok(Ok(event)) if event.paths.iter().any(|p| self.is_relevant(p)) => {
+                        std::thread::sleep(DEBOUNCE);
+                        self.clear();
+                        return true;
+                    }
+                    Ok(_) => continue,
+                    Err(_) => return false,
+                }
+            }
+        }
+
+        #[cfg(not(feature = "watch"))]
+        {
+            let deadline = std::time::Instant::now() + timeout;
+            while std::time::Instant::now() < deadline {
+                std::thread::sleep(DEBOUNCE);
+                let latest = self.latest_modification();
+                if latest > self.seen {
+                    self.seen = latest;
+                    return true;
+                }
+            }
+            false
+        }
+    }
+
+    /// Forget changes seen so far, used after our own render wrote to the cache
+    pub fn clear(&mut self) {
+        #[cfg(feature = "watch")]
+        while self.events.try_recv().is_ok() {}
+
+        #[cfg(not(feature = "watch"))]
+        {
+            self.seen = self.latest_modification();
+        }
+    }
+
+    #[cfg(feature = "watch")]
+    fn is_relevant(&self, path: &Path) -> bool {
+        path == self.config_path
+            || self.transcript.as_deref() == Some(path)
+            || self
+                .cache_dir
+                .as_ref()
+                .is_some_and(|dir| path.starts_with(dir))
+    }
+
+    #[cfg(not(feature = "watch"))]
+    fn latest_modification(&self) -> Option<std::time::SystemTime> {
+        let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        crate::api::cache::latest_modification()
+            .max(modified(&self.config_path))
+            .max(self.transcript.as_deref().and_then(modified))
+    }
+}
+
+impl Default for ChangeWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
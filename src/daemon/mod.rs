@@ -0,0 +1,182 @@
+//! Background refresh of vendor usage data (`uucode daemon`).
+//!
+//! Without the daemon every statusline render asks the usage endpoints
+//! itself and waits for them. While a daemon is running it refreshes the
+//! usage caches on its own schedule and renders read them without touching
+//! the network, so a slow or hanging endpoint never delays Claude Code.
+//!
+//! The daemon announces itself in `cache/daemon.json` and rewrites its
+//! heartbeat there after each refresh. Renders only trust the cache while
+//! the heartbeat is recent, so a killed daemon falls back to direct
+//! requests after a couple of intervals. Removing the file (`uucode daemon
+//! stop`) or another daemon taking it over makes the running one exit.
+//!
+//! Each round runs in a child process (`uucode daemon refresh`): settings,
+//! credentials and the detected vendor are read once per process, so a
+//! fresh process picks up a vendor switch, and a panic or a hung request
+//! only costs that round.
+
+pub mod refresh;
+
+use crate::utils::subprocess::Subprocess;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Shortest and longest refresh interval. Longer intervals would let the
+/// usage caches go stale (`api::cache::CACHE_FRESH_SECONDS`) between
+/// refreshes.
+pub const MIN_INTERVAL_SECS: u64 = 10;
+pub const MAX_INTERVAL_SECS: u64 = 300;
+
+/// Heartbeats older than this many intervals mean the daemon is gone
+const MISSED_HEARTBEATS: u64 = 2;
+
+/// How often the running daemon checks whether it was stopped
+const POLL: Duration = Duration::from_secs(1);
+
+/// A refresh round taking longer than this is killed
+const REFRESH_TIMEOUT: Duration = Duration::from_secs(60);
+
+static IN_DAEMON: AtomicBool = AtomicBool::new(false);
+static CACHE_ONLY: OnceLock<bool> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct State {
+    pub pid: u32,
+    pub interval_secs: u64,
+    /// Unix timestamps
+    pub started_at: i64,
+    pub heartbeat: i64,
+    /// Refreshes that failed since the last successful one
+    #[serde(default)]
+    pub failures: u32,
+}
+
+impl State {
+    /// Whether the daemon wrote a heartbeat recently enough at `now`
+    ///
+    /// ```
+    /// use mycode::daemon::State;
+    ///
+    /// let state = State {
+    ///     pid: 4242,
+    ///     interval_secs: 60,
+    ///     started_at: 1_000,
+    ///     heartbeat: 1_000,
+    ///     failures: 0,
+    /// };
+    /// assert!(state.is_alive(1_090));
+    /// assert!(!state.is_alive(1_000 + 2 * 60 + 11));
+    /// ```
+    pub fn is_alive(&self, now: i64) -> bool {
+        // Allow for a refresh that takes a while on top of the interval
+        let limit = self.interval_secs * MISSED_HEARTBEATS + MIN_INTERVAL_SECS;
+        now - self.heartbeat <= limit as i64
+    }
+}
+
+fn state_path() -> Option<PathBuf> {
+    crate::utils::paths::cache_dir().map(|dir| dir.join("daemon.json"))
+}
+
+/// The daemon's state file, whether or not it is still alive
+pub fn load() -> Option<State> {
+    let content = fs::read_to_string(state_path()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save(state: &State) -> Result<(), Box<dyn std::error::Error>> {
+    let path = state_path().ok_or("Could not find home directory")?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    crate::utils::atomic_write(path, serde_json::to_string_pretty(state)?)?;
+    Ok(())
+}
+
+/// Wall-clock seconds; heartbeats must not follow the frozen render clock
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// State of a daemon that is currently alive
+pub fn running() -> Option<State> {
+    load().filter(|state| state.is_alive(unix_now()))
+}
+
+/// Whether vendor usage should come from the cache only: a daemon is
+/// alive and this process is not the daemon itself. Checked once per
+/// process, as several segments ask during one render.
+pub fn cache_only() -> bool {
+    if IN_DAEMON.load(Ordering::Relaxed) {
+        return false;
+    }
+    *CACHE_ONLY.get_or_init(|| running().is_some())
+}
+
+/// Ask the running daemon to exit; returns its state if there was one
+pub fn stop() -> Result<Option<State>, Box<dyn std::error::Error>> {
+    let Some(state) = load() else {
+        return Ok(None);
+    };
+    if let Some(path) = state_path() {
+        fs::remove_file(path)?;
+    }
+    Ok(Some(state))
+}
+
+/// Refresh every `interval` until stopped. Runs in the foreground; `uucode
+/// daemon start` launches it in the background.
+pub fn run(interval: Duration) -> Result<(), Box<dyn std::error::Error>> {
+    let now = unix_now();
+    let mut state = State {
+        pid: std::process::id(),
+        interval_secs: interval.as_secs(),
+        started_at: now,
+        heartbeat: now,
+        failures: 0,
+    };
+    save(&state)?;
+
+    let exe = std::env::current_exe()?;
+    loop {
+        let round = Subprocess::new(&exe)
+            .args(["daemon", "refresh"])
+            .with_timeout(REFRESH_TIMEOUT)
+            .run();
+        match round {
+            Ok(output) if output.success() => state.failures = 0,
+            Ok(output) => {
+                state.failures += 1;
+                crate::debug_log!("daemon refresh failed: {}", output.stderr.trim());
+            }
+            Err(e) => {
+                state.failures += 1;
+                crate::debug_log!("daemon refresh failed: {}", e);
+            }
+        }
+
+        // Stopped, or replaced by a newer daemon, while refreshing
+        if load().is_none_or(|current| current.pid != state.pid) {
+            return Ok(());
+        }
+        state.heartbeat = unix_now();
+        save(&state)?;
+
+        let mut waited = Duration::ZERO;
+        while waited < interval {
+            std::thread::sleep(POLL);
+            waited += POLL;
+            if load().is_none_or(|current| current.pid != state.pid) {
+                return Ok(());
+            }
+        }
+    }
+}
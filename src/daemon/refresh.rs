@@ -0,0 +1,83 @@
+//! One refresh round of the daemon, run as `uucode daemon refresh`.
+
+use crate::config::{Config, InputData, SegmentId};
+use crate::core::segments::{CollectionContext, SegmentData};
+use crate::error::UucodeError;
+use std::sync::atomic::Ordering;
+
+/// Segments that fill a vendor usage cache, grouped by cache: collecting
+/// one enabled segment of a group refreshes the data of all of them
+const GROUPS: &[&[SegmentId]] = &[
+    &[SegmentId::UucodeUsage],
+    &[
+        SegmentId::CubenceUsage,
+        SegmentId::CubenceFiveHour,
+        SegmentId::CubenceWeekly,
+        SegmentId::CubenceBalance,
+    ],
+    &[SegmentId::OneApiUsage],
+    &[SegmentId::VendorUsage],
+];
+
+fn collect(id: SegmentId, ctx: &CollectionContext) -> Option<SegmentData> {
+    #[cfg(any(feature = "uucode", feature = "cubence"))]
+    use crate::core::segments::*;
+
+    match id {
+        #[cfg(feature = "uucode")]
+        SegmentId::UucodeUsage => uucode_usage::collect(ctx),
+        #[cfg(feature = "cubence")]
+        SegmentId::CubenceUsage => cubence_usage::collect(ctx),
+        #[cfg(feature = "cubence")]
+        SegmentId::CubenceFiveHour => cubence_five_hour::collect(ctx),
+        #[cfg(feature = "cubence")]
+        SegmentId::CubenceWeekly => cubence_weekly::collect(ctx),
+        #[cfg(feature = "cubence")]
+        SegmentId::CubenceBalance => cubence_balance::collect(ctx),
+        SegmentId::OneApiUsage => crate::core::segments::one_api_usage::collect(ctx),
+        SegmentId::VendorUsage => crate::core::segments::vendor_usage::collect(ctx),
+        _ => None,
+    }
+}
+
+/// Request the usage of the current vendor through the segments that show
+/// it, so the options, credentials and caches are exactly the ones a
+/// render uses. Fails when any request fell back to the cache.
+pub fn refresh() -> Result<(), UucodeError> {
+    // Renders read the cache while the daemon is alive; this process is
+    // the one that has to fill it
+    super::IN_DAEMON.store(true, Ordering::Relaxed);
+
+    let config = Config::load()?;
+    let vendor = crate::api::detect_vendor_from_claude_settings();
+    let input = crate::api::cache::load_last_input()
+        .and_then(|raw| serde_json::from_str::<InputData>(&raw).ok())
+        .unwrap_or_else(|| InputData::new("", "."));
+    let ctx = CollectionContext::new(&config, &input, vendor);
+    let mutes = crate::core::mute::Mutes::load();
+
+    let mut failed = Vec::new();
+    for group in GROUPS {
+        let Some(id) = group.iter().copied().find(|id| {
+            id.vendor() == Some(vendor)
+                && !mutes.is_muted(*id)
+                && config.segments.iter().any(|s| s.id == *id && s.enabled)
+        }) else {
+            continue;
+        };
+        match collect(id, &ctx) {
+            // Data from a successful request carries no cache age
+            Some(data) if !data.metadata.contains_key("cache_age_secs") => {}
+            _ => failed.push(format!("{:?}", id)),
+        }
+    }
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(UucodeError::Vendor(format!(
+            "刷新失败: {}",
+            failed.join(", ")
+        )))
+    }
+}
@@ -0,0 +1,95 @@
+//! Error type shared by the api client, caches, config loading and the
+//! wrapper.
+//!
+//! The variants are categories callers can act on: network failures are
+//! worth retrying or falling back to cache for, config problems need the
+//! user, and the binary turns each category into its own exit code:
+//!
+//! | code | meaning |
+//! |------|---------|
+//! | 0 | success |
+//! | 1 | any other error, including I/O |
+//! | 2 | config invalid |
+//! | 3 | vendor unsupported or answering with unusable data |
+//! | 4 | patch failed |
+//! | 5 | Claude Code not found |
+//! | 6 | network unreachable, or the endpoint's circuit breaker is open |
+
+use crate::api::breaker::CircuitOpen;
+use crate::api::schema::SchemaMismatch;
+use std::io;
+
+#[derive(Debug, thiserror::Error)]
+pub enum UucodeError {
+    /// A config file is invalid
+    #[error("配置无效: {0}")]
+    Config(String),
+    /// The request never got an answer (DNS, TLS, timeout, ...)
+    #[error("网络请求失败: {0}")]
+    Network(#[from] reqwest::Error),
+    /// The endpoint is cooling down after repeated failures
+    #[error(transparent)]
+    CircuitOpen(#[from] CircuitOpen),
+    /// The vendor answered with an error status or data we cannot use
+    #[error("{0}")]
+    Vendor(String),
+    /// The vendor answered, but in none of the known response schemas
+    #[error(transparent)]
+    Schema(#[from] SchemaMismatch),
+    /// cli.js could not be patched
+    #[error("补丁失败: {0}")]
+    Patch(String),
+    /// No Claude Code executable or cli.js at the expected place
+    #[error("未找到 Claude Code: {0}")]
+    ClaudeNotFound(String),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+impl UucodeError {
+    /// Neither `$HOME` nor the platform equivalent is set
+    pub fn no_home() -> Self {
+        UucodeError::Io(io::Error::new(
+            io::ErrorKind::NotFound,
+            "Could not find home directory",
+        ))
+    }
+
+    /// Whether trying again later may succeed without user action
+    pub fn is_transient(&self) -> bool {
+        matches!(self, UucodeError::Network(_) | UucodeError::CircuitOpen(_))
+    }
+
+    /// Process exit code for this category, as listed in the module docs
+    ///
+    /// ```
+    /// use mycode::error::UucodeError;
+    ///
+    /// assert_eq!(UucodeError::Config("No segments configured".into()).exit_code(), 2);
+    /// assert_eq!(UucodeError::Patch("anchor not found".into()).exit_code(), 4);
+    /// assert_eq!(UucodeError::ClaudeNotFound("cli.js".into()).exit_code(), 5);
+    /// ```
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            UucodeError::Io(_) => 1,
+            UucodeError::Config(_) => 2,
+            UucodeError::Vendor(_) | UucodeError::Schema(_) => 3,
+            UucodeError::Patch(_) => 4,
+            UucodeError::ClaudeNotFound(_) => 5,
+            UucodeError::Network(_) | UucodeError::CircuitOpen(_) => 6,
+        }
+    }
+}
+
+impl From<toml::de::Error> for UucodeError {
+    fn from(error: toml::de::Error) -> Self {
+        UucodeError::Config(error.to_string())
+    }
+}
+
+/// Exit code for an error that reached `main`, 1 unless it is a [`UucodeError`]
+pub fn exit_code(error: &(dyn std::error::Error + 'static)) -> i32 {
+    error
+        .downcast_ref::<UucodeError>()
+        .map_or(1, UucodeError::exit_code)
+}
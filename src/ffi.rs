@@ -0,0 +1,82 @@
+//! C interface to [`crate::render`] for editor plugins (`--features ffi`).
+//!
+//! Build the shared library with
+//! `cargo rustc --lib --profile release-ffi --features ffi --crate-type cdylib`;
+//! the declarations are in `include/uucode.h`. The `release-ffi` profile
+//! keeps panics unwinding, so a panicking render comes back as an error
+//! instead of aborting the host (`release` builds with `panic = "abort"`). Plugins load it through
+//! LuaJIT's `ffi` or a Node FFI module and render without spawning a
+//! process per refresh.
+//!
+//! The library stays loaded across renders: settings.json and the detected
+//! vendor are re-read when the file changes, and the Claude config dir is
+//! resolved again on every call.
+//!
+//! Strings cross the boundary as NUL-terminated UTF-8. Every string
+//! returned here is owned by the caller and must go back through
+//! [`uucode_free_string`].
+
+use crate::config::{Config, ConfigLoader, InputData};
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// Render Claude Code's JSON payload `input` and return the result as JSON:
+/// `{"ansi": ..., "text": ..., "spans": [...]}`, or `{"error": ...}` when
+/// the payload or the config cannot be read. `config_path` may be null to
+/// use `~/.claude/uucode/config.toml`.
+///
+/// Returns null only if `input` is null.
+///
+/// # Safety
+///
+/// `input` and a non-null `config_path` must point to NUL-terminated
+/// strings that stay valid for the duration of the call.
+#[no_mangle]
+pub unsafe extern "C" fn uucode_render_json(
+    input: *const c_char,
+    config_path: *const c_char,
+) -> *mut c_char {
+    if input.is_null() {
+        return std::ptr::null_mut();
+    }
+    let input = CStr::from_ptr(input).to_string_lossy().into_owned();
+    let config_path = (!config_path.is_null())
+        .then(|| CStr::from_ptr(config_path).to_string_lossy().into_owned());
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        render_json(&input, config_path.as_deref())
+    }))
+    .unwrap_or_else(|_| Err("render panicked".into()));
+    let json = match result {
+        Ok(json) => json,
+        Err(e) => serde_json::json!({ "error": e.to_string() }).to_string(),
+    };
+
+    // serde_json escapes control characters, so there is no interior NUL
+    CString::new(json).map_or(std::ptr::null_mut(), CString::into_raw)
+}
+
+/// Free a string returned by this library. Null is ignored.
+///
+/// # Safety
+///
+/// `s` must come from a function of this library and not be freed twice.
+#[no_mangle]
+pub unsafe extern "C" fn uucode_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+fn render_json(
+    input: &str,
+    config_path: Option<&str>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    crate::utils::paths::reload();
+    let input: InputData = serde_json::from_str(input)?;
+    let config = match config_path {
+        Some(path) => ConfigLoader::load_from_path(path)?,
+        None => Config::load()?,
+    };
+    Ok(serde_json::to_string(&crate::render(&config, &input))?)
+}
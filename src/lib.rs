@@ -1,11 +1,42 @@
+//! uucode statusline engine.
+//!
+//! The binary reads Claude Code's payload from stdin; other tools can
+//! render the same statusline in-process with [`render`], building the
+//! payload with [`config::InputData::new`]. [`config`], [`core`] and
+//! [`error`] are the supported surface; the other modules serve the binary and may
+//! change without notice.
+//!
+//! Those modules are `#[doc(hidden)] pub` rather than `pub(crate)`: the
+//! `uucode` binary is a separate crate target that drives them directly,
+//! and their doctests run against the public library.
+
+#[doc(hidden)]
 pub mod api;
+#[doc(hidden)]
 pub mod auto_config;
+#[doc(hidden)]
 pub mod cli;
+#[doc(hidden)]
+pub mod commands;
 pub mod config;
 pub mod core;
+#[doc(hidden)]
+pub mod daemon;
+pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod render;
+#[doc(hidden)]
 pub mod ui;
+#[doc(hidden)]
 pub mod utils;
+#[doc(hidden)]
 pub mod wrapper;
 
 #[cfg(feature = "self-update")]
+#[doc(hidden)]
 pub mod updater;
+
+pub use core::render_log::RenderedSegment;
+pub use error::UucodeError;
+pub use render::{render, RenderedStatusline};
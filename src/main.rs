@@ -1,23 +1,130 @@
-use mycode::cli::Cli;
-use mycode::config::{Config, InputData};
+use mycode::cli::{Cli, Commands};
+use mycode::config::{Config, InputData, OutputFormat};
 use mycode::core::{collect_all_segments, StatusLineGenerator};
+#[cfg(feature = "patcher")]
+use mycode::error::UucodeError;
+#[cfg(feature = "wrapper")]
 use mycode::wrapper::{find_claude_code, injector::ClaudeCodeInjector};
-use std::io::{self, IsTerminal};
+use std::io::{self, IsTerminal, Read};
+use std::path::Path;
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Migrate legacy config directory if needed
-    migrate_legacy_config()?;
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("Error: {}", e);
+        std::process::exit(mycode::error::exit_code(e.as_ref()));
+    }
+}
+
+fn run() -> Result<(), Box<dyn std::error::Error>> {
+    mycode::utils::crash::install_hook();
 
     let cli = Cli::parse_args();
 
+    if cli.debug {
+        mycode::utils::debug::enable();
+    }
+    if cli.no_write {
+        mycode::utils::readonly::enable();
+    }
+    if cli.deterministic {
+        mycode::utils::clock::enable();
+    }
+    if cli.dry_run {
+        mycode::auto_config::settings::enable_dry_run();
+    }
+    if cli.non_interactive {
+        mycode::utils::prompt::non_interactive();
+    }
+    if cli.yes {
+        mycode::utils::prompt::assume_yes();
+    }
+
+    // Migrate legacy config directory if needed
+    if !mycode::utils::readonly::is_enabled() {
+        migrate_legacy_config()?;
+    }
+
+    if let Some(command) = &cli.command {
+        return match command {
+            Commands::Doctor { reset } => mycode::commands::doctor::run(*reset),
+            Commands::Explain => mycode::commands::explain::run(),
+            Commands::Focus { action } => mycode::commands::focus::run(action.as_ref()),
+            #[cfg(feature = "tui")]
+            Commands::Top { interval } => mycode::commands::top::run(*interval),
+            #[cfg(not(feature = "tui"))]
+            Commands::Top { .. } => {
+                eprintln!("TUI feature is not enabled. Please install with --features tui");
+                std::process::exit(1);
+            }
+            Commands::Serve { port, bind } => mycode::commands::serve::run(bind, *port),
+            Commands::Vendor { action } => mycode::commands::vendor::run(action),
+            Commands::Perf { action } => mycode::commands::perf::run(action),
+            Commands::Simulate {
+                dir,
+                rate,
+                count,
+                latency,
+            } => mycode::commands::simulate::run(dir, *rate, *count, *latency),
+            Commands::Stats { action } => mycode::commands::stats::run(action),
+            Commands::Config { action } => mycode::commands::config::run(action),
+            Commands::Segment { action } => mycode::commands::segment::run(action),
+            Commands::Mute { segment, duration } => {
+                mycode::commands::mute::run(segment, duration.as_deref())
+            }
+            Commands::Unmute { segment } => mycode::commands::mute::unmute(segment),
+            Commands::Hooks { action } => mycode::commands::hooks::run(action),
+            #[cfg(feature = "cubence")]
+            Commands::Cubence { action } => mycode::commands::cubence::run(action),
+            #[cfg(not(feature = "cubence"))]
+            Commands::Cubence { .. } => {
+                eprintln!("Cubence feature is not enabled. Please install with --features cubence");
+                std::process::exit(1);
+            }
+            Commands::Daemon { action } => mycode::commands::daemon::run(action.as_ref()),
+            Commands::Theme { action } => mycode::commands::theme::run(action),
+            Commands::Font { action } => mycode::commands::font::run(action),
+            Commands::Trust { path, list, revoke } => {
+                mycode::commands::trust::run(path.as_deref(), *list, *revoke)
+            }
+        };
+    }
+
+    #[cfg(not(feature = "wrapper"))]
+    if cli.wrap || cli.self_test {
+        eprintln!("Wrapper feature is not enabled. Please install with --features wrapper");
+        std::process::exit(1);
+    }
+    #[cfg(feature = "wrapper")]
+    if cli.self_test {
+        match mycode::wrapper::self_test::render_once() {
+            Ok(statusline) => println!("{}", statusline),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
     // Handle wrapper mode - inject into Claude Code
+    #[cfg(feature = "wrapper")]
     if cli.wrap {
         return run_wrapper_mode(&cli);
     }
 
     // Handle configuration commands
     if cli.init {
-        Config::init()?;
+        if cli.dry_run {
+            println!(
+                "--dry-run：跳过重建 {}",
+                Config::get_config_path()
+                    .parent()
+                    .unwrap_or(Path::new("."))
+                    .display()
+            );
+        } else {
+            Config::init()?;
+        }
 
         // 自动配置 Claude Code settings.json
         println!("\n正在配置 Claude Code settings.json...");
@@ -37,7 +144,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         // Apply theme override if provided
         if let Some(theme) = cli.theme {
-            config = mycode::ui::themes::ThemePresets::get_theme(&theme);
+            config.apply_theme(mycode::ui::themes::ThemePresets::get_theme(&theme));
         }
 
         config.print()?;
@@ -55,13 +162,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         #[cfg(feature = "tui")]
         {
             mycode::ui::run_configurator()?;
+            return Ok(());
         }
         #[cfg(not(feature = "tui"))]
         {
             eprintln!("TUI feature is not enabled. Please install with --features tui");
             std::process::exit(1);
         }
-        return Ok(());
     }
 
     if cli.update {
@@ -77,11 +184,32 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     // Handle Claude Code patcher
+    #[cfg(not(feature = "patcher"))]
+    if cli.patch.is_some() || cli.unpatch_spinner.is_some() {
+        eprintln!("Patcher feature is not enabled. Please install with --features patcher");
+        std::process::exit(1);
+    }
+    #[cfg(feature = "patcher")]
     if let Some(claude_path) = cli.patch {
-        use mycode::utils::ClaudeCodePatcher;
+        use mycode::utils::patch_ledger::{self, PatchRun};
+        use mycode::utils::{ClaudeCodePatcher, PatchAnchors};
 
         println!("🔧 Claude Code Context Warning Disabler");
         println!("Target file: {}", claude_path);
+        if !std::path::Path::new(&claude_path).is_file() {
+            return Err(
+                UucodeError::ClaudeNotFound(format!("{} does not exist", claude_path)).into(),
+            );
+        }
+
+        if mycode::utils::readonly::intercept("修改 cli.js", || {
+            format!(
+                "  请在允许修改 Claude Code 安装目录的环境中运行: uucode --patch {}",
+                claude_path
+            )
+        }) {
+            return Ok(());
+        }
 
         // Create backup in same directory
         let backup_path = format!("{}.backup", claude_path);
@@ -89,48 +217,144 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("📦 Created backup: {}", backup_path);
 
         // Load and patch
-        let mut patcher = ClaudeCodePatcher::new(&claude_path)?;
-
-        // Apply all modifications
+        let mut patcher =
+            ClaudeCodePatcher::new(&claude_path).map_err(|e| UucodeError::Patch(e.to_string()))?;
+        let version = patcher.version().map(str::to_string);
+        println!(
+            "Claude Code version: {}",
+            version.as_deref().unwrap_or("unknown")
+        );
+        let mut run = PatchRun::new(&claude_path, version.as_deref());
+
+        // Apply all modifications, recording which ones still work on this version
         println!("\n🔄 Applying patches...");
 
         // 1. Set verbose property to true
-        if let Err(e) = patcher.write_verbose_property(true) {
-            println!("⚠️ Could not modify verbose property: {}", e);
-        }
+        let result = patcher.write_verbose_property(true);
+        run.record("verbose", &result);
 
         // 2. Disable context low warnings
-        patcher.disable_context_low_warnings()?;
+        let result = patcher.disable_context_low_warnings();
+        run.record("context_low", &result);
 
         // 3. Disable ESC interrupt display
-        if let Err(e) = patcher.disable_esc_interrupt_display() {
-            println!("⚠️ Could not disable esc/interrupt display: {}", e);
-        }
+        let result = patcher.disable_esc_interrupt_display();
+        run.record("esc_interrupt", &result);
 
         // 4. Add statusline auto-refresh (30 seconds interval)
-        if let Err(e) = patcher.add_statusline_refresh_interval(30000) {
-            println!("⚠️ Could not add statusline auto-refresh: {}", e);
+        let result = patcher.add_statusline_refresh_interval(30000);
+        run.record("statusline_refresh", &result);
+
+        // 5. Custom spinner tip, only when [patcher.spinner] is configured
+        let spinner = Config::load()
+            .unwrap_or_else(|_| Config::default())
+            .patcher
+            .spinner;
+        if spinner.is_enabled() {
+            let result = patcher.customize_spinner_tip(&spinner);
+            run.record("spinner_tip", &result);
+        }
+
+        if run.any_applied() {
+            patcher
+                .save()
+                .map_err(|e| UucodeError::Patch(e.to_string()))?;
         }
 
-        patcher.save()?;
+        let history = patch_ledger::load();
+        if let Err(e) = run.append() {
+            println!("⚠️ Could not update patch ledger: {}", e);
+        }
+
+        println!();
+        for outcome in &run.patches {
+            match &outcome.error {
+                None => println!("✅ {}", outcome.name),
+                Some(error) => {
+                    let last = patch_ledger::last_working_version(&history, &outcome.name)
+                        .map(|v| format!(", last applied on {}", v))
+                        .unwrap_or_default();
+                    println!("❌ {}: {}{}", outcome.name, error, last);
+                }
+            }
+        }
+
+        if run.failed().next().is_some() {
+            println!(
+                "\n⚠️ Some patches no longer apply to Claude Code {}.",
+                version.as_deref().unwrap_or("(unknown version)")
+            );
+            if let Some(path) = PatchAnchors::overrides_path() {
+                println!(
+                    "   Anchors can be overridden per version in {}",
+                    path.display()
+                );
+            }
+        }
+        if !run.any_applied() {
+            return Err(UucodeError::Patch(
+                "No patch could be applied, cli.js was left unchanged".to_string(),
+            )
+            .into());
+        }
 
-        println!("✅ All patches applied successfully!");
         println!("💡 To restore warnings, replace your cli.js with the backup file:");
         println!("   cp {} {}", backup_path, claude_path);
+        if run
+            .patches
+            .iter()
+            .any(|p| p.name == "spinner_tip" && p.applied)
+        {
+            println!(
+                "   The spinner tip alone can be removed with: uucode --unpatch-spinner {}",
+                claude_path
+            );
+        }
 
         return Ok(());
     }
 
-    // Load configuration
-    let mut config = Config::load().unwrap_or_else(|_| Config::default());
+    #[cfg(feature = "patcher")]
+    if let Some(claude_path) = cli.unpatch_spinner {
+        if mycode::utils::readonly::intercept("修改 cli.js", || {
+            format!(
+                "  请在允许修改 Claude Code 安装目录的环境中运行: uucode --unpatch-spinner {}",
+                claude_path
+            )
+        }) {
+            return Ok(());
+        }
+
+        if !std::path::Path::new(&claude_path).is_file() {
+            return Err(
+                UucodeError::ClaudeNotFound(format!("{} does not exist", claude_path)).into(),
+            );
+        }
 
-    // Apply theme override if provided
-    if let Some(theme) = cli.theme {
-        config = mycode::ui::themes::ThemePresets::get_theme(&theme);
+        let mut patcher = mycode::utils::ClaudeCodePatcher::new(&claude_path)
+            .map_err(|e| UucodeError::Patch(e.to_string()))?;
+        patcher
+            .remove_spinner_tip()
+            .map_err(|e| UucodeError::Patch(e.to_string()))?;
+        patcher
+            .save()
+            .map_err(|e| UucodeError::Patch(e.to_string()))?;
+        println!("✅ Spinner tip restored");
+        return Ok(());
     }
 
-    // Check if stdin has data
-    if io::stdin().is_terminal() {
+    if cli.follow || cli.watch {
+        return follow(cli.theme.as_deref(), cli.output, cli.watch);
+    }
+
+    // Check if stdin has data; bar modules fall back to the last payload
+    if io::stdin().is_terminal() && cli.output.is_none() {
+        if !mycode::utils::prompt::is_interactive() {
+            eprintln!("No input data provided.");
+            eprintln!("Usage: echo '{{...}}' | uucode");
+            return Ok(());
+        }
+
         // No input data available, show main menu
         #[cfg(feature = "tui")]
         {
@@ -166,62 +390,213 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     // Read Claude Code data from stdin
-    let stdin = io::stdin();
-    let input: InputData = serde_json::from_reader(stdin.lock())?;
+    let mut raw_input = String::new();
+    if !io::stdin().is_terminal() {
+        io::stdin().read_to_string(&mut raw_input)?;
+    }
+
+    if raw_input.trim().is_empty() && cli.output.is_some() {
+        raw_input = mycode::api::cache::load_last_input()
+            .ok_or("no statusline data yet, let Claude Code render the statusline once")?;
+    } else if let Err(e) = mycode::api::cache::save_last_input(&raw_input) {
+        // Keep the latest payload for standalone views such as `uucode top`
+        mycode::debug_log!("failed to save last input: {}", e);
+    }
+    let input: InputData = serde_json::from_str(&raw_input)?;
+
+    // Unfinished renders (crashes, hangs) pile up until safe mode takes over
+    if mycode::core::safe_mode::begin() {
+        println!("{}", mycode::core::safe_mode::render(&input, cli.output));
+        return Ok(());
+    }
+    let config = load_config(cli.theme.as_deref());
+    let themed = theme_override(cli.theme.as_deref()).is_some();
+    println!("{}", render(config, &input, cli.output, themed));
+    mycode::core::safe_mode::finish();
+
+    Ok(())
+}
+
+/// Collect, render and post-process one statusline for `output`
+fn render(
+    mut config: Config,
+    input: &InputData,
+    output: Option<OutputFormat>,
+    theme_override: bool,
+) -> String {
+    // Follow vendor switches (theme auto-selection)
+    let vendor = mycode::api::detect_vendor_from_claude_settings();
+    mycode::core::vendor::on_vendor_detected(&mut config, vendor, theme_override);
+
+    // Directory profiles override the theme for this render only
+    mycode::core::profiles::apply(&mut config, &input.workspace.current_dir);
+    let focus = mycode::core::focus::apply(&mut config);
+
+    // Resolve the exchange rate once so every segment formats with the same value
+    mycode::api::exchange::resolve_rate(&mut config.format);
 
     // Collect segment data
-    let segments_data = collect_all_segments(&config, &input);
+    let segments_data = collect_all_segments(&config, input);
+
+    // Feed the history store used by `uucode serve`
+    let sample = mycode::core::history::Sample::from_render(input, &segments_data);
+    if let Err(e) = mycode::core::history::record(&sample) {
+        mycode::debug_log!("failed to record history: {}", e);
+    }
+    if focus.is_some_and(|focus| focus.mute_alerts) {
+        mycode::wrapper::reminder::publish(&[]);
+    } else {
+        mycode::core::alerts::evaluate(&config.alerts, &sample);
+        mycode::wrapper::reminder::publish(&segments_data);
+    }
 
     // Render statusline
-    let generator = StatusLineGenerator::new(config);
-    let statusline = generator.generate(segments_data);
+    let render_log = mycode::core::render_log::RenderLog::new(&config, &segments_data);
+    let output_file = config.output_file.clone();
+    let waybar = config.waybar.clone();
+    let generator = StatusLineGenerator::new(config).with_available_width(input.available_width());
+    let statusline = generator.generate(segments_data.clone());
+
+    // Keep what was shown for `uucode explain`
+    if let Err(e) = render_log.with_text(&statusline).save() {
+        mycode::debug_log!("failed to save render log: {}", e);
+    }
 
-    println!("{}", statusline);
+    if let Some(output_file) = &output_file {
+        if let Err(e) =
+            mycode::core::output::write_file(output_file, &waybar, &statusline, &segments_data)
+        {
+            mycode::debug_log!("failed to write output file: {}", e);
+        }
+    }
 
-    Ok(())
+    match output {
+        Some(format) => mycode::core::output::render(format, &waybar, &statusline, &segments_data),
+        None => statusline,
+    }
+}
+
+/// Load the configuration, applying a theme override while keeping
+/// non-theme settings
+fn load_config(theme: Option<&str>) -> Config {
+    let mut config = Config::load().unwrap_or_else(|_| Config::default());
+    if let Some(theme) = theme_override(theme) {
+        config.apply_theme(mycode::ui::themes::ThemePresets::get_theme(&theme));
+    }
+    config
+}
+
+/// Theme chosen for this run by `--theme` or passed through by the wrapper
+fn theme_override(theme: Option<&str>) -> Option<String> {
+    theme
+        .map(str::to_string)
+        .or_else(|| mycode::wrapper::passed_through(mycode::wrapper::THEME_ENV))
+}
+
+/// Re-render from the last Claude Code payload whenever the cache, config
+/// or transcript changes, printing only output that differs from the
+/// previous one: as new lines (`--follow`) or redrawn in place (`--watch`)
+fn follow(
+    theme: Option<&str>,
+    output: Option<OutputFormat>,
+    in_place: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use mycode::core::watch::ChangeWatcher;
+    use std::io::Write;
+    use std::time::Duration;
+
+    // Usage caches only refresh when something renders, so re-render at
+    // least this often even if nothing changed on disk
+    const MAX_WAIT: Duration = Duration::from_secs(30);
+
+    let mut watcher = ChangeWatcher::new();
+    let mut last_line = String::new();
+    loop {
+        if let Some(input) = mycode::api::cache::load_last_input()
+            .and_then(|raw| serde_json::from_str::<InputData>(&raw).ok())
+        {
+            watcher.watch_transcript(std::path::Path::new(&input.transcript_path));
+
+            let themed = theme_override(theme).is_some();
+            let line = render(load_config(theme), &input, output, themed);
+            if line != last_line {
+                if in_place {
+                    print!("\r\x1b[2K{}", line);
+                } else {
+                    println!("{}", line);
+                }
+                io::stdout().flush()?;
+                last_line = line;
+            }
+        }
+
+        // Rendering itself may write to the cache, skip those changes
+        watcher.clear();
+        watcher.wait(MAX_WAIT);
+    }
 }
 
-fn run_wrapper_mode(_cli: &Cli) -> Result<(), Box<dyn std::error::Error>> {
+#[cfg(feature = "wrapper")]
+fn run_wrapper_mode(cli: &Cli) -> Result<(), Box<dyn std::error::Error>> {
     // Find Claude Code executable
     let claude_path = find_claude_code()?;
     println!("✓ Found Claude Code at: {}", claude_path.display());
 
     // Load API keys from config
-    let home = dirs::home_dir().ok_or("Could not find home directory")?;
-    let keys_path = home.join(".claude").join("uucode").join("api_keys.toml");
+    let keys = mycode::config::ApiKeys::load()?;
+    let (_api_key, _glm_key) = (keys.uucode_api_key, keys.glm_api_key);
 
-    let (_api_key, _glm_key) = if keys_path.exists() {
-        use serde::Deserialize;
+    let mut injector = ClaudeCodeInjector::new(claude_path, None)?;
 
-        #[derive(Deserialize)]
-        struct ApiKeys {
-            #[serde(default)]
-            uucode_api_key: Option<String>,
-            #[serde(default)]
-            glm_api_key: Option<String>,
+    // Claude Code inherits our environment, and with it every statusline
+    // command it runs
+    if let Some(theme) = &cli.theme {
+        std::env::set_var(mycode::wrapper::THEME_ENV, theme);
+    }
+    if let Some(profile) = &cli.profile {
+        let config = Config::load().unwrap_or_else(|_| Config::default());
+        if mycode::core::profiles::named(&config, profile).is_none() {
+            println!("⚠ 没有名为 {} 的 directory_profiles，将按目录匹配", profile);
         }
+        std::env::set_var(mycode::wrapper::PROFILE_ENV, profile);
+    }
 
-        let content = std::fs::read_to_string(&keys_path)?;
-        let keys: ApiKeys = toml::from_str(&content)?;
-        (keys.uucode_api_key, keys.glm_api_key)
-    } else {
-        (None, None)
-    };
-
-    let mut injector = ClaudeCodeInjector::new(claude_path, None)?;
+    // Catch a broken config or a hanging segment before the session starts
+    mycode::wrapper::self_test::report();
 
     // Get remaining args to pass to Claude Code
-    let claude_args: Vec<String> = std::env::args()
-        .skip(1)
-        .filter(|arg| arg != "--wrap")
-        .collect();
+    let claude_args = mycode::wrapper::claude_args(std::env::args().skip(1));
 
     println!("\n🚀 启动 Claude Code...");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("😊 感谢您使用 uucode！");
-    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    let config = load_config(cli.theme.as_deref());
+    if let Some(motd) = mycode::wrapper::motd::render(&config, cli.profile.as_deref()) {
+        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("{}", motd);
+        println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
+    }
+
+    // Renders inside the session report the context advisor back to us
+    let reminder = mycode::wrapper::reminder::Reminder::start(&config.alerts.context);
+
+    let started = std::time::SystemTime::now();
+    let result = injector.run_with_interception(claude_args);
+    drop(reminder);
+
+    // Print a receipt for the session and keep it in the ledger
+    if let Some(record) = mycode::core::ledger::SessionRecord::collect(started) {
+        let config = Config::load().unwrap_or_else(|_| Config::default());
+        println!("\n{}", record.summary(&config));
+        if let Err(e) = record.append() {
+            eprintln!("⚠ 保存会话记录失败: {}", e);
+        }
+        if let Some(url) = &config.wrapper.session_webhook {
+            if let Err(e) = record.post(url, &config) {
+                eprintln!("⚠ 会话记录推送失败: {}", e);
+            }
+        }
+    }
 
-    injector.run_with_interception(claude_args)
+    Ok(result?)
 }
 
 fn migrate_legacy_config() -> Result<(), Box<dyn std::error::Error>> {
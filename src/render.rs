@@ -0,0 +1,55 @@
+//! Rendering entry point for tools that embed the statusline.
+
+use crate::config::{Config, InputData};
+use crate::core::render_log::{RenderLog, RenderedSegment};
+use crate::core::{collect_all_segments, StatusLineGenerator};
+use serde::Serialize;
+
+/// A rendered statusline
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderedStatusline {
+    /// The line with ANSI escapes, as Claude Code shows it
+    pub ansi: String,
+    /// The line without escapes
+    pub text: String,
+    /// Enabled segments that produced data, in display order, for callers
+    /// that draw the line themselves. A narrow width can still drop some
+    /// of them from `ansi`.
+    pub spans: Vec<RenderedSegment>,
+}
+
+/// Render `input` with `config`.
+///
+/// Segments are collected as for Claude Code: vendor segments may hit the
+/// network or their caches, and segment timings (`[perf]`) and peaks are
+/// saved as in any render. Nothing else is recorded: no history, alerts or
+/// `uucode explain` log, and the config is not saved.
+///
+/// ```no_run
+/// use mycode::config::{Config, InputData};
+///
+/// let config = Config::load().unwrap_or_default();
+/// let input = InputData::new("claude-sonnet-4-5", "/home/me/project").with_width(100);
+/// let line = mycode::render(&config, &input);
+/// for span in &line.spans {
+///     println!("{:?}: {}", span.id, span.primary);
+/// }
+/// ```
+pub fn render(config: &Config, input: &InputData) -> RenderedStatusline {
+    let mut config = config.clone();
+    crate::core::profiles::apply(&mut config, &input.workspace.current_dir);
+    crate::api::exchange::resolve_rate(&mut config.format);
+
+    let segments = collect_all_segments(&config, input);
+    let log = RenderLog::new(&config, &segments);
+    let ansi = StatusLineGenerator::new(config)
+        .with_available_width(input.available_width())
+        .generate(segments);
+    let log = log.with_text(&ansi);
+
+    RenderedStatusline {
+        ansi,
+        text: log.text,
+        spans: log.segments,
+    }
+}
@@ -1,10 +1,12 @@
 use crate::config::{Config, SegmentId, StyleMode};
 use crate::ui::components::{
     color_picker::{ColorPickerComponent, NavDirection},
+    health::HealthComponent,
     help::HelpComponent,
     icon_selector::IconSelectorComponent,
     name_input::NameInputComponent,
     preview::PreviewComponent,
+    save_confirm::SaveConfirmComponent,
     segment_list::{FieldSelection, Panel, SegmentListComponent},
     separator_editor::SeparatorEditorComponent,
     settings::SettingsComponent,
@@ -31,9 +33,11 @@ pub struct App {
     selected_field: FieldSelection,
     should_quit: bool,
     color_picker: ColorPickerComponent,
+    health: HealthComponent,
     icon_selector: IconSelectorComponent,
     name_input: NameInputComponent,
     preview: PreviewComponent,
+    save_confirm: SaveConfirmComponent,
     segment_list: SegmentListComponent,
     separator_editor: SeparatorEditorComponent,
     settings: SettingsComponent,
@@ -51,9 +55,11 @@ impl App {
             selected_field: FieldSelection::Enabled,
             should_quit: false,
             color_picker: ColorPickerComponent::new(),
+            health: HealthComponent::new(),
             icon_selector: IconSelectorComponent::new(),
             name_input: NameInputComponent::new(),
             preview: PreviewComponent::new(),
+            save_confirm: SaveConfirmComponent::new(),
             segment_list: SegmentListComponent::new(),
             separator_editor: SeparatorEditorComponent::new(),
             settings: SettingsComponent::new(),
@@ -103,7 +109,40 @@ impl App {
                 }
 
                 // Handle popup events first
-                if app.name_input.is_open {
+                if app.save_confirm.is_open {
+                    match key.code {
+                        KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+                            app.save_confirm.close();
+                            app.status_message = Some("Save cancelled".to_string());
+                        }
+                        KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                            app.save_confirm.close();
+                            if let Err(e) = app.save_config() {
+                                app.status_message = Some(format!("Failed to save config: {}", e));
+                            } else {
+                                app.status_message =
+                                    Some("Configuration saved to config.toml!".to_string());
+                            }
+                        }
+                        KeyCode::Up => app.save_confirm.scroll(-1),
+                        KeyCode::Down => app.save_confirm.scroll(1),
+                        KeyCode::PageUp => app.save_confirm.scroll(-10),
+                        KeyCode::PageDown => app.save_confirm.scroll(10),
+                        _ => {}
+                    }
+                } else if app.health.is_open {
+                    match key.code {
+                        KeyCode::Esc | KeyCode::Char('h') | KeyCode::Char('H') => {
+                            app.health.close()
+                        }
+                        KeyCode::Char('r') | KeyCode::Char('R') => app.health.refresh(&app.config),
+                        KeyCode::Up => app.health.scroll(-1),
+                        KeyCode::Down => app.health.scroll(1),
+                        KeyCode::PageUp => app.health.scroll(-10),
+                        KeyCode::PageDown => app.health.scroll(10),
+                        _ => {}
+                    }
+                } else if app.name_input.is_open {
                     match key.code {
                         KeyCode::Esc => app.name_input.close(),
                         KeyCode::Enter => {
@@ -189,14 +228,8 @@ impl App {
                                 // Ctrl+S: Save as new theme with name input
                                 app.name_input.open("Save as New Theme", "Enter theme name");
                             } else {
-                                // s: Save config to config.toml
-                                if let Err(e) = app.save_config() {
-                                    app.status_message =
-                                        Some(format!("Failed to save config: {}", e));
-                                } else {
-                                    app.status_message =
-                                        Some("Configuration saved to config.toml!".to_string());
-                                }
+                                // s: Review changes, then save config to config.toml
+                                app.request_save();
                             }
                         }
                         KeyCode::Char('w') | KeyCode::Char('W') => {
@@ -226,6 +259,9 @@ impl App {
                         KeyCode::Char('p') => app.cycle_theme(),
                         KeyCode::Char('r') => app.reset_to_theme_defaults(),
                         KeyCode::Char('e') | KeyCode::Char('E') => app.open_separator_editor(),
+                        KeyCode::Char('c') | KeyCode::Char('C') => app.cycle_preview_width(),
+                        KeyCode::Char('i') | KeyCode::Char('I') => app.cycle_icon_set(),
+                        KeyCode::Char('h') | KeyCode::Char('H') => app.health.open(&app.config),
                         _ => {}
                     }
                 }
@@ -281,6 +317,32 @@ impl App {
         line_count + 2
     }
 
+    fn cycle_preview_width(&mut self) {
+        self.status_message = Some(match self.preview.cycle_simulated_width() {
+            Some(width) => format!("Previewing at {} columns", width),
+            None => "Previewing at the window width".to_string(),
+        });
+        self.preview.update_preview(&self.config);
+    }
+
+    fn cycle_icon_set(&mut self) {
+        use crate::config::IconSet;
+        let next = match self.config.style.icon_set {
+            IconSet::Auto => IconSet::Emoji,
+            IconSet::Emoji => IconSet::NerdV3,
+            IconSet::NerdV3 => IconSet::Ascii,
+            IconSet::Ascii => IconSet::None,
+            IconSet::None => IconSet::Auto,
+        };
+        self.config.style.icon_set = next;
+        let name = serde_json::to_value(next)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default();
+        self.status_message = Some(format!("Icon set: {}", name));
+        self.preview.update_preview(&self.config);
+    }
+
     fn calculate_help_height(&self, total_width: u16) -> u16 {
         // Use same help_items as in help.render
         let help_items = if self.color_picker.is_open {
@@ -307,6 +369,9 @@ impl App {
                 "[P] Switch Theme",
                 "[R] Reset",
                 "[E] Edit Separator",
+                "[C] Simulate Width",
+                "[I] Icon Set",
+                "[H] Segment Health",
                 "[S] Save Config",
                 "[W] Write Theme",
                 "[Ctrl+S] Save Theme",
@@ -450,6 +515,12 @@ impl App {
         if self.separator_editor.is_open {
             self.separator_editor.render(f, f.area());
         }
+        if self.save_confirm.is_open {
+            self.save_confirm.render(f, f.area());
+        }
+        if self.health.is_open {
+            self.health.render(f, f.area());
+        }
     }
 
     fn move_selection(&mut self, delta: i32) {
@@ -498,10 +569,17 @@ impl App {
                         SegmentId::Directory => "Directory",
                         SegmentId::Git => "Git",
                         SegmentId::ContextWindow => "Context Window",
+                        SegmentId::ContextAdvisor => "Context Advisor",
+                        SegmentId::ApiErrors => "API Errors",
+                        SegmentId::Summary => "Summary",
+                        SegmentId::QuotaPacing => "Quota Pacing",
                         SegmentId::Usage => "Usage",
                         SegmentId::Cost => "Cost",
+                        SegmentId::DailyCost => "Daily Cost",
+                        SegmentId::BlockUsage => "Block Usage",
                         SegmentId::Session => "Session",
                         SegmentId::OutputStyle => "Output Style",
+                        SegmentId::Runtime => "Runtime",
                         SegmentId::Update => "Update",
                         SegmentId::UucodeUsage => "uucode Usage",
                         SegmentId::UucodeSubscription => "uucode Subscription",
@@ -515,6 +593,9 @@ impl App {
                         SegmentId::CubenceLatency => "Cubence Latency",
                         SegmentId::CubenceSubscription => "Cubence Subscription",
                         SegmentId::CubenceMultiplier => "Cubence Multiplier",
+                        SegmentId::OneApiUsage => "one-api Usage",
+                        SegmentId::VendorUsage => "Vendor Usage",
+                        SegmentId::Custom => "Custom Command",
                     };
                     let is_enabled = segment.enabled;
                     self.status_message = Some(format!(
@@ -537,10 +618,17 @@ impl App {
                                 SegmentId::Directory => "Directory",
                                 SegmentId::Git => "Git",
                                 SegmentId::ContextWindow => "Context Window",
+                                SegmentId::ContextAdvisor => "Context Advisor",
+                                SegmentId::ApiErrors => "API Errors",
+                                SegmentId::Summary => "Summary",
+                                SegmentId::QuotaPacing => "Quota Pacing",
                                 SegmentId::Usage => "Usage",
                                 SegmentId::Cost => "Cost",
+                                SegmentId::DailyCost => "Daily Cost",
+                                SegmentId::BlockUsage => "Block Usage",
                                 SegmentId::Session => "Session",
                                 SegmentId::OutputStyle => "Output Style",
+                                SegmentId::Runtime => "Runtime",
                                 SegmentId::Update => "Update",
                                 SegmentId::UucodeUsage => "uucode Usage",
                                 SegmentId::UucodeSubscription => "uucode Subscription",
@@ -554,6 +642,9 @@ impl App {
                                 SegmentId::CubenceLatency => "Cubence Latency",
                                 SegmentId::CubenceSubscription => "Cubence Subscription",
                                 SegmentId::CubenceMultiplier => "Cubence Multiplier",
+                                SegmentId::OneApiUsage => "one-api Usage",
+                                SegmentId::VendorUsage => "Vendor Usage",
+                                SegmentId::Custom => "Custom Command",
                             };
                             let is_enabled = segment.enabled;
                             self.status_message = Some(format!(
@@ -702,6 +793,22 @@ impl App {
         self.status_message = Some(format!("Reset {} theme to defaults", current_theme));
     }
 
+    /// Show what saving would change in config.toml and ask for confirmation
+    fn request_save(&mut self) {
+        if crate::utils::readonly::is_enabled() {
+            self.status_message =
+                Some("Read-only mode (--no-write): config.toml not written".to_string());
+            return;
+        }
+        match crate::config::diff::ConfigDiff::against_disk(&self.config) {
+            Ok(diff) if !diff.has_changes() => {
+                self.status_message = Some("No changes to save".to_string());
+            }
+            Ok(diff) => self.save_confirm.open(&diff, &self.config),
+            Err(e) => self.status_message = Some(format!("Failed to compare config: {}", e)),
+        }
+    }
+
     fn save_config(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         self.config.save()?;
         Ok(())
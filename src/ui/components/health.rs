@@ -0,0 +1,168 @@
+use crate::config::Config;
+use crate::core::health::{self, SegmentHealth, Status};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// Latency, cache age, last error and breaker state of every enabled
+/// segment, read from what earlier renders recorded
+pub struct HealthComponent {
+    pub is_open: bool,
+    rows: Vec<Line<'static>>,
+    problems: usize,
+    scroll: u16,
+}
+
+impl Default for HealthComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HealthComponent {
+    pub fn new() -> Self {
+        Self {
+            is_open: false,
+            rows: Vec::new(),
+            problems: 0,
+            scroll: 0,
+        }
+    }
+
+    pub fn open(&mut self, config: &Config) {
+        self.refresh(config);
+        self.scroll = 0;
+        self.is_open = true;
+    }
+
+    /// Re-read the stores, e.g. after Claude Code rendered meanwhile
+    pub fn refresh(&mut self, config: &Config) {
+        let segments = health::collect(config);
+        let budget_ms = config.perf.budget_ms;
+        self.problems = segments
+            .iter()
+            .filter(|h| h.status(budget_ms).is_problem())
+            .count();
+        self.rows = segments
+            .iter()
+            .flat_map(|h| health_rows(h, budget_ms))
+            .collect();
+        let max = self.rows.len().saturating_sub(1) as u16;
+        self.scroll = self.scroll.min(max);
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+        self.rows.clear();
+    }
+
+    pub fn scroll(&mut self, delta: i16) {
+        let max = self.rows.len().saturating_sub(1) as u16;
+        self.scroll = self.scroll.saturating_add_signed(delta).min(max);
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect) {
+        if !self.is_open {
+            return;
+        }
+
+        let popup_area = Rect {
+            x: area.width / 10,
+            y: area.height / 10,
+            width: area.width - area.width / 5,
+            height: area.height - area.height / 5,
+        };
+        f.render_widget(Clear, popup_area);
+
+        let title = match self.problems {
+            0 => "Segment Health".to_string(),
+            n => format!("Segment Health - {} need attention", n),
+        };
+        let popup_block = Block::default().borders(Borders::ALL).title(title);
+        let inner = popup_block.inner(popup_area);
+        f.render_widget(popup_block, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(3),    // Segments
+                Constraint::Length(1), // Actions
+            ])
+            .split(inner);
+
+        if self.rows.is_empty() {
+            f.render_widget(
+                Paragraph::new("(no enabled segments)").style(Style::default().fg(Color::DarkGray)),
+                chunks[0],
+            );
+        } else {
+            f.render_widget(
+                Paragraph::new(self.rows.clone()).scroll((self.scroll, 0)),
+                chunks[0],
+            );
+        }
+
+        f.render_widget(
+            Paragraph::new("[R] Refresh  [↑↓] Scroll  [Esc/H] Close"),
+            chunks[1],
+        );
+    }
+}
+
+fn status_color(status: Status) -> Color {
+    match status {
+        Status::Ok => Color::Green,
+        Status::Unmeasured | Status::Muted => Color::DarkGray,
+        Status::Slow | Status::Skipped | Status::Failing => Color::Yellow,
+        Status::CircuitOpen => Color::Red,
+    }
+}
+
+/// One line per segment, plus an indented line for its last error
+fn health_rows(health: &SegmentHealth, budget_ms: u64) -> Vec<Line<'static>> {
+    let status = health.status(budget_ms);
+    let dim = Style::default().fg(Color::DarkGray);
+    let name = serde_json::to_value(health.id)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default();
+
+    let latency = match &health.timing {
+        Some(t) => format!("{:>6.0}ms avg {:>6.0}ms last", t.average_ms, t.last_ms),
+        None => format!("{:>27}", "-"),
+    };
+    let cache = match health.cache_age {
+        Some(age) => format!("cache {}s", age),
+        None => String::new(),
+    };
+    let breaker = match &health.breaker {
+        Some(b) if b.failures > 0 => format!("{} ({} failures)", b.state.label(), b.failures),
+        Some(b) => b.state.label().to_string(),
+        None if health.endpoint.is_some() => "closed".to_string(),
+        None => String::new(),
+    };
+
+    let mut rows = vec![Line::from(vec![
+        Span::styled(
+            format!("{:<14}", status.label()),
+            Style::default()
+                .fg(status_color(status))
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(format!("{:<22}", name)),
+        Span::raw(latency),
+        Span::styled(format!("  {:<12}", cache), dim),
+        Span::styled(breaker, dim),
+    ])];
+    if let Some(error) = health.last_error() {
+        rows.push(Line::styled(
+            format!("{:14}└ {}", "", error),
+            Style::default().fg(status_color(status)),
+        ));
+    }
+    rows
+}
@@ -46,6 +46,9 @@ impl HelpComponent {
                 ("[P]", "切换主题"),
                 ("[R]", "重置"),
                 ("[E]", "编辑分隔符"),
+                ("[C]", "模拟列宽"),
+                ("[I]", "图标集"),
+                ("[H]", "段健康"),
                 ("[S]", "保存配置"),
                 ("[W]", "写入主题"),
                 ("[Ctrl+S]", "保存主题"),
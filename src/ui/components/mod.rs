@@ -1,9 +1,11 @@
 pub mod color_picker;
 pub mod editor;
+pub mod health;
 pub mod help;
 pub mod icon_selector;
 pub mod name_input;
 pub mod preview;
+pub mod save_confirm;
 pub mod segment_list;
 pub mod separator_editor;
 pub mod settings;
@@ -1,4 +1,6 @@
 use crate::config::{Config, SegmentId};
+use crate::core::icons::{self, Glyph};
+use crate::core::palette::{self, Role};
 use crate::core::segments::SegmentData;
 use crate::core::StatusLineGenerator;
 use ratatui::{
@@ -9,9 +11,14 @@ use ratatui::{
 };
 use std::collections::HashMap;
 
+/// Terminal widths the preview can simulate, cycled with `C`
+const SIMULATED_WIDTHS: [u16; 4] = [60, 80, 120, 160];
+
 pub struct PreviewComponent {
     preview_cache: String,
     preview_text: Text<'static>,
+    /// Render as Claude Code would at this many columns instead of the frame width
+    simulated_width: Option<u16>,
 }
 
 impl Default for PreviewComponent {
@@ -25,9 +32,19 @@ impl PreviewComponent {
         Self {
             preview_cache: String::new(),
             preview_text: Text::default(),
+            simulated_width: None,
         }
     }
 
+    /// Step through the simulated widths, then back to the live frame width
+    pub fn cycle_simulated_width(&mut self) -> Option<u16> {
+        self.simulated_width = match self.simulated_width {
+            None => Some(SIMULATED_WIDTHS[0]),
+            Some(width) => SIMULATED_WIDTHS.iter().copied().find(|w| *w > width),
+        };
+        self.simulated_width
+    }
+
     pub fn update_preview(&mut self, config: &Config) {
         self.update_preview_with_width(config, 80); // Default width
     }
@@ -36,15 +53,19 @@ impl PreviewComponent {
         // Generate mock segments data directly for preview
         let segments_data = self.generate_mock_segments_data(config);
 
-        // Generate both string and TUI text versions
-        let renderer = StatusLineGenerator::new(config.clone());
+        // Generate both string and TUI text versions; a simulated width also
+        // applies the same width fitting Claude Code's reported width would
+        let renderer = StatusLineGenerator::new(config.clone())
+            .with_available_width(self.simulated_width.map(usize::from));
 
         // Keep string version for compatibility (if needed elsewhere)
         self.preview_cache = renderer.generate(segments_data.clone());
 
         // Generate TUI-optimized text with smart segment wrapping for preview display
         // Use actual available width minus borders
-        let content_width = width.saturating_sub(2);
+        let content_width = self
+            .simulated_width
+            .unwrap_or_else(|| width.saturating_sub(2));
         let preview_result = renderer.generate_for_tui_preview(segments_data, content_width);
 
         // Convert to owned text by cloning the spans
@@ -71,12 +92,20 @@ impl PreviewComponent {
     }
 
     pub fn render(&self, f: &mut Frame, area: Rect) {
+        let title = match self.simulated_width {
+            Some(width) => format!("预览 · 模拟 {} 列", width),
+            None => "预览".to_string(),
+        };
         let preview = Paragraph::new(self.preview_text.clone())
-            .block(Block::default().borders(Borders::ALL).title("预览"))
+            .block(Block::default().borders(Borders::ALL).title(title))
             .wrap(ratatui::widgets::Wrap { trim: false });
         f.render_widget(preview, area);
     }
 
+    pub fn preview_text(&self) -> &Text<'static> {
+        &self.preview_text
+    }
+
     pub fn get_preview_cache(&self) -> &str {
         &self.preview_cache
     }
@@ -88,6 +117,7 @@ impl PreviewComponent {
         config: &Config,
     ) -> Vec<(crate::config::SegmentConfig, SegmentData)> {
         let mut segments_data = Vec::new();
+        let prefix = |id, glyph| icons::prefix(config, id, glyph);
 
         for segment_config in &config.segments {
             if !segment_config.enabled {
@@ -96,8 +126,8 @@ impl PreviewComponent {
 
             let mock_data = match segment_config.id {
                 SegmentId::Model => SegmentData {
-                    primary: "Sonnet 4".to_string(),
-                    secondary: "".to_string(),
+                    primary: "Sonnet 4".into(),
+                    secondary: "".into(),
                     metadata: {
                         let mut map = HashMap::new();
                         map.insert("model".to_string(), "claude-4-sonnet-20250512".to_string());
@@ -105,8 +135,8 @@ impl PreviewComponent {
                     },
                 },
                 SegmentId::Directory => SegmentData {
-                    primary: "CCometixLine".to_string(),
-                    secondary: "".to_string(),
+                    primary: "CCometixLine".into(),
+                    secondary: "".into(),
                     metadata: {
                         let mut map = HashMap::new();
                         map.insert("current_dir".to_string(), "~/CCometixLine".to_string());
@@ -114,8 +144,8 @@ impl PreviewComponent {
                     },
                 },
                 SegmentId::Git => SegmentData {
-                    primary: "master".to_string(),
-                    secondary: "✓".to_string(),
+                    primary: "master".into(),
+                    secondary: "✓".into(),
                     metadata: {
                         let mut map = HashMap::new();
                         map.insert("branch".to_string(), "master".to_string());
@@ -126,8 +156,8 @@ impl PreviewComponent {
                     },
                 },
                 SegmentId::ContextWindow => SegmentData {
-                    primary: "78.2%".to_string(),
-                    secondary: "· 156.4k".to_string(),
+                    primary: "78.2%".into(),
+                    secondary: "· 156.4k".into(),
                     metadata: {
                         let mut map = HashMap::new();
                         map.insert("total_tokens".to_string(), "156400".to_string());
@@ -136,23 +166,81 @@ impl PreviewComponent {
                         map
                     },
                 },
+                SegmentId::ContextAdvisor => SegmentData {
+                    primary: "/compact soon".into(),
+                    secondary: "context 78%".into(),
+                    metadata: {
+                        let mut map = HashMap::new();
+                        map.insert("advice".to_string(), "compact".to_string());
+                        map
+                    },
+                },
+                SegmentId::ApiErrors => SegmentData {
+                    primary: "3 errors/10min".into(),
+                    secondary: "529×2 429×1".into(),
+                    metadata: {
+                        let mut map = HashMap::new();
+                        map.insert("count".to_string(), "3".to_string());
+                        map.insert("level".to_string(), "critical".to_string());
+                        map
+                    },
+                },
+                SegmentId::Summary => SegmentData {
+                    primary: "⚡️ 78.2% · 156.4k tokens".into(),
+                    secondary: "".into(),
+                    metadata: {
+                        let mut map = HashMap::new();
+                        map.insert("source".to_string(), "context_window".to_string());
+                        map.insert("level".to_string(), "warning".to_string());
+                        map
+                    },
+                },
+                SegmentId::QuotaPacing => SegmentData {
+                    primary: "62% used @ 40% time ⚠".into(),
+                    secondary: "5h".into(),
+                    metadata: {
+                        let mut map = HashMap::new();
+                        map.insert("pace".to_string(), "warning".to_string());
+                        map
+                    },
+                },
                 SegmentId::Usage => SegmentData {
-                    primary: "24%".to_string(),
-                    secondary: "· 10-7-2".to_string(),
+                    primary: "24%".into(),
+                    secondary: "· 10-7-2".into(),
                     metadata: HashMap::new(),
                 },
                 SegmentId::Cost => SegmentData {
-                    primary: "$0.02".to_string(),
-                    secondary: "".to_string(),
+                    primary: "$0.02".into(),
+                    secondary: "".into(),
                     metadata: {
                         let mut map = HashMap::new();
                         map.insert("cost".to_string(), "0.01234".to_string());
                         map
                     },
                 },
+                SegmentId::DailyCost => SegmentData {
+                    primary: "今日 $3.21".into(),
+                    secondary: "本周 $18.40".into(),
+                    metadata: {
+                        let mut map = HashMap::new();
+                        map.insert("today_cost".to_string(), "3.21".to_string());
+                        map.insert("week_cost".to_string(), "18.40".to_string());
+                        map
+                    },
+                },
+                SegmentId::BlockUsage => SegmentData {
+                    primary: "5h ████░░░░ 1.2M $3.40 (2h10m)".into(),
+                    secondary: "".into(),
+                    metadata: {
+                        let mut map = HashMap::new();
+                        map.insert("block_tokens".to_string(), "1200000".to_string());
+                        map.insert("used_percentage".to_string(), "48.0".to_string());
+                        map
+                    },
+                },
                 SegmentId::Session => SegmentData {
-                    primary: "3m45s".to_string(),
-                    secondary: "+156 -23".to_string(),
+                    primary: "3m45s".into(),
+                    secondary: "+156 -23".into(),
                     metadata: {
                         let mut map = HashMap::new();
                         map.insert("duration_ms".to_string(), "225000".to_string());
@@ -162,17 +250,27 @@ impl PreviewComponent {
                     },
                 },
                 SegmentId::OutputStyle => SegmentData {
-                    primary: "default".to_string(),
-                    secondary: "".to_string(),
+                    primary: "default".into(),
+                    secondary: "".into(),
                     metadata: {
                         let mut map = HashMap::new();
                         map.insert("style_name".to_string(), "default".to_string());
                         map
                     },
                 },
+                SegmentId::Runtime => SegmentData {
+                    primary: "WSL: Ubuntu".into(),
+                    secondary: "".into(),
+                    metadata: {
+                        let mut map = HashMap::new();
+                        map.insert("kind".to_string(), "wsl".to_string());
+                        map.insert("name".to_string(), "Ubuntu".to_string());
+                        map
+                    },
+                },
                 SegmentId::Update => SegmentData {
-                    primary: format!("v{}", env!("CARGO_PKG_VERSION")),
-                    secondary: "".to_string(),
+                    primary: format!("v{}", env!("CARGO_PKG_VERSION")).into(),
+                    secondary: "".into(),
                     metadata: {
                         let mut map = HashMap::new();
                         map.insert(
@@ -184,29 +282,39 @@ impl PreviewComponent {
                     },
                 },
                 SegmentId::UucodeUsage => SegmentData {
-                    primary: "$10.38 / $30".to_string(),
-                    secondary: "专业版 - 剩余17天 | payg余额 $0.12 | 欢迎使用uucode".to_string(),
+                    primary: format!(
+                        "{}$10.38 / $30",
+                        prefix(SegmentId::UucodeUsage, Glyph::Card)
+                    ).into(),
+                    secondary: format!(
+                        "{}专业版 - 周期第14/30天 · 预计月末 $24 | {}payg余额 $0.12 | 欢迎使用uucode",
+                        prefix(SegmentId::UucodeUsage, Glyph::Package),
+                        prefix(SegmentId::UucodeUsage, Glyph::Money)
+                    ).into(),
                     metadata: {
                         let mut map = HashMap::new();
                         map.insert("used".to_string(), "10.38".to_string());
                         map.insert("total".to_string(), "30.00".to_string());
                         map.insert("remaining".to_string(), "19.62".to_string());
+                        map.insert("cycle_day".to_string(), "14".to_string());
+                        map.insert("cycle_days".to_string(), "30".to_string());
+                        map.insert("projected_usd".to_string(), "23.95".to_string());
                         map
                     },
                 },
                 SegmentId::UucodeSubscription => SegmentData {
-                    primary: "专业版 - 剩余17天".to_string(),
-                    secondary: "".to_string(),
+                    primary: "专业版 - 剩余17天".into(),
+                    secondary: "".into(),
                     metadata: HashMap::new(),
                 },
                 SegmentId::UucodeStatus => SegmentData {
-                    primary: "".to_string(),
-                    secondary: "".to_string(),
+                    primary: "".into(),
+                    secondary: "".into(),
                     metadata: HashMap::new(),
                 },
                 SegmentId::CubenceBalance => SegmentData {
-                    primary: "$74.02".to_string(),
-                    secondary: "".to_string(),
+                    primary: "$74.02".into(),
+                    secondary: "".into(),
                     metadata: {
                         let mut map = HashMap::new();
                         map.insert("balance_usd".to_string(), "74.02".to_string());
@@ -215,8 +323,14 @@ impl PreviewComponent {
                     },
                 },
                 SegmentId::CubenceUsage => SegmentData {
-                    primary: "⏱ 18.4M/80M (23%)".to_string(),
-                    secondary: "📅 103.4M/200M (52%) | 5h重置: 3h12m | 周重置: 5天8h".to_string(),
+                    primary: format!(
+                        "{}18.4M/80M (23%)",
+                        prefix(SegmentId::CubenceUsage, Glyph::Timer)
+                    ).into(),
+                    secondary: format!(
+                        "{}103.4M/200M (52%) | 5h重置: 3h12m | 周重置: 5天8h",
+                        prefix(SegmentId::CubenceUsage, Glyph::Calendar)
+                    ).into(),
                     metadata: {
                         let mut map = HashMap::new();
                         map.insert("five_hour_used".to_string(), "18436683".to_string());
@@ -228,8 +342,8 @@ impl PreviewComponent {
                     },
                 },
                 SegmentId::CubenceStatus => SegmentData {
-                    primary: "Cubence".to_string(),
-                    secondary: "".to_string(),
+                    primary: "Cubence".into(),
+                    secondary: "".into(),
                     metadata: {
                         let mut map = HashMap::new();
                         map.insert("service".to_string(), "cubence".to_string());
@@ -237,8 +351,8 @@ impl PreviewComponent {
                     },
                 },
                 SegmentId::CubenceFiveHour => SegmentData {
-                    primary: "5h ████░░░░ 36.1M/80M (3h12m)".to_string(),
-                    secondary: "".to_string(),
+                    primary: "5h ████░░░░ 36.1M/80M (3h12m)".into(),
+                    secondary: "".into(),
                     metadata: {
                         let mut map = HashMap::new();
                         map.insert("five_hour_used".to_string(), "36100000".to_string());
@@ -249,8 +363,8 @@ impl PreviewComponent {
                     },
                 },
                 SegmentId::CubenceWeekly => SegmentData {
-                    primary: "周 █████░░░ 121M/200M (3d5h)".to_string(),
-                    secondary: "".to_string(),
+                    primary: "周 █████░░░ 121M/200M (3d5h)".into(),
+                    secondary: "".into(),
                     metadata: {
                         let mut map = HashMap::new();
                         map.insert("weekly_used".to_string(), "121000000".to_string());
@@ -261,8 +375,12 @@ impl PreviewComponent {
                     },
                 },
                 SegmentId::CubenceLoadStatus => SegmentData {
-                    primary: "🚴 负载：23%[轻点蹬🚲]".to_string(),
-                    secondary: "".to_string(),
+                    primary: format!(
+                        "{}负载[23%-轻点蹬{}]",
+                        prefix(SegmentId::CubenceLoadStatus, Glyph::Bike),
+                        icons::glyph(config, SegmentId::CubenceLoadStatus, Glyph::Strained)
+                    ).into(),
+                    secondary: "".into(),
                     metadata: {
                         let mut map = HashMap::new();
                         map.insert("load_percentage".to_string(), "23.0".to_string());
@@ -272,8 +390,8 @@ impl PreviewComponent {
                     },
                 },
                 SegmentId::CubenceLatency => SegmentData {
-                    primary: "🟢 延迟: 256ms".to_string(),
-                    secondary: "".to_string(),
+                    primary: "🟢 延迟: 256ms".into(),
+                    secondary: "".into(),
                     metadata: {
                         let mut map = HashMap::new();
                         map.insert("latency_ms".to_string(), "256".to_string());
@@ -283,8 +401,11 @@ impl PreviewComponent {
                     },
                 },
                 SegmentId::CubenceSubscription => SegmentData {
-                    primary: "💎 Prism-剩余5d 12h".to_string(),
-                    secondary: "".to_string(),
+                    primary: format!(
+                        "{}Prism-剩余5天12h",
+                        prefix(SegmentId::CubenceSubscription, Glyph::Gem)
+                    ).into(),
+                    secondary: "".into(),
                     metadata: {
                         let mut map = HashMap::new();
                         map.insert("plan_name".to_string(), "Prism".to_string());
@@ -293,8 +414,8 @@ impl PreviewComponent {
                     },
                 },
                 SegmentId::CubenceMultiplier => SegmentData {
-                    primary: "\x1b[38;5;220m(x0.75)\x1b[0m".to_string(),
-                    secondary: "".to_string(),
+                    primary: palette::paint(&config.style, Role::Accent, "(x0.75)").into(),
+                    secondary: "".into(),
                     metadata: {
                         let mut map = HashMap::new();
                         map.insert("multiplier".to_string(), "0.75".to_string());
@@ -302,6 +423,46 @@ impl PreviewComponent {
                         map
                     },
                 },
+                SegmentId::OneApiUsage => SegmentData {
+                    primary: format!(
+                        "{}$2.50 / $10 (25%)",
+                        prefix(SegmentId::OneApiUsage, Glyph::Card)
+                    )
+                    .into(),
+                    secondary: format!(
+                        "{}剩余 $7.50 | default | 42 次请求",
+                        prefix(SegmentId::OneApiUsage, Glyph::Money)
+                    )
+                    .into(),
+                    metadata: {
+                        let mut map = HashMap::new();
+                        map.insert("used".to_string(), "2.50".to_string());
+                        map.insert("total".to_string(), "10.00".to_string());
+                        map.insert("remaining".to_string(), "7.50".to_string());
+                        map.insert("used_percentage".to_string(), "25.0".to_string());
+                        map
+                    },
+                },
+                SegmentId::VendorUsage => SegmentData {
+                    primary: format!(
+                        "{}$12.50 / $50 (25%)",
+                        prefix(SegmentId::VendorUsage, Glyph::Card)
+                    )
+                    .into(),
+                    secondary: "Pro | 2h15m".into(),
+                    metadata: {
+                        let mut map = HashMap::new();
+                        map.insert("used".to_string(), "12.50".to_string());
+                        map.insert("total".to_string(), "50.00".to_string());
+                        map.insert("used_percentage".to_string(), "25.0".to_string());
+                        map
+                    },
+                },
+                SegmentId::Custom => SegmentData {
+                    primary: "k8s: prod".into(),
+                    secondary: "".into(),
+                    metadata: HashMap::new(),
+                },
             };
 
             segments_data.push((segment_config.clone(), mock_data));
@@ -0,0 +1,141 @@
+use crate::config::diff::{ConfigDiff, DiffLine, DiffTag};
+use crate::config::Config;
+use crate::ui::components::preview::PreviewComponent;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// Unchanged lines shown around each change
+const DIFF_CONTEXT: usize = 2;
+
+/// Confirmation shown before config.toml is overwritten: the TOML diff
+/// against the file on disk plus the statusline before and after
+pub struct SaveConfirmComponent {
+    pub is_open: bool,
+    rows: Vec<Line<'static>>,
+    scroll: u16,
+    has_current: bool,
+    before: PreviewComponent,
+    after: PreviewComponent,
+}
+
+impl Default for SaveConfirmComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SaveConfirmComponent {
+    pub fn new() -> Self {
+        Self {
+            is_open: false,
+            rows: Vec::new(),
+            scroll: 0,
+            has_current: false,
+            before: PreviewComponent::new(),
+            after: PreviewComponent::new(),
+        }
+    }
+
+    pub fn open(&mut self, diff: &ConfigDiff, new: &Config) {
+        self.rows = diff
+            .hunks(DIFF_CONTEXT)
+            .into_iter()
+            .map(|row| match row {
+                Some(line) => diff_row(line),
+                None => Line::styled("  …", Style::default().fg(Color::DarkGray)),
+            })
+            .collect();
+        self.scroll = 0;
+        self.has_current = diff.current.is_some();
+        if let Some(current) = &diff.current {
+            self.before.update_preview(current);
+        }
+        self.after.update_preview(new);
+        self.is_open = true;
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+        self.rows.clear();
+    }
+
+    pub fn scroll(&mut self, delta: i16) {
+        let max = self.rows.len().saturating_sub(1) as u16;
+        self.scroll = self.scroll.saturating_add_signed(delta).min(max);
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect) {
+        if !self.is_open {
+            return;
+        }
+
+        let popup_area = Rect {
+            x: area.width / 10,
+            y: area.height / 10,
+            width: area.width - area.width / 5,
+            height: area.height - area.height / 5,
+        };
+        f.render_widget(Clear, popup_area);
+
+        let popup_block = Block::default()
+            .borders(Borders::ALL)
+            .title("Save to config.toml?");
+        let inner = popup_block.inner(popup_area);
+        f.render_widget(popup_block, popup_area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(5),    // Diff
+                Constraint::Length(5), // Before
+                Constraint::Length(5), // After
+                Constraint::Length(1), // Actions
+            ])
+            .split(inner);
+
+        f.render_widget(
+            Paragraph::new(self.rows.clone())
+                .scroll((self.scroll, 0))
+                .block(Block::default().borders(Borders::ALL).title("Changes")),
+            chunks[0],
+        );
+
+        let before = if self.has_current {
+            Paragraph::new(self.before.preview_text().clone())
+        } else {
+            Paragraph::new("(no readable config.toml yet)")
+                .style(Style::default().fg(Color::DarkGray))
+        };
+        f.render_widget(
+            before
+                .wrap(ratatui::widgets::Wrap { trim: false })
+                .block(Block::default().borders(Borders::ALL).title("Before")),
+            chunks[1],
+        );
+        f.render_widget(
+            Paragraph::new(self.after.preview_text().clone())
+                .wrap(ratatui::widgets::Wrap { trim: false })
+                .block(Block::default().borders(Borders::ALL).title("After")),
+            chunks[2],
+        );
+
+        f.render_widget(
+            Paragraph::new("[Y/Enter] Save  [N/Esc] Cancel  [↑↓] Scroll"),
+            chunks[3],
+        );
+    }
+}
+
+fn diff_row(line: &DiffLine) -> Line<'static> {
+    let (sign, style) = match line.tag {
+        DiffTag::Same => (' ', Style::default()),
+        DiffTag::Added => ('+', Style::default().fg(Color::Green)),
+        DiffTag::Removed => ('-', Style::default().fg(Color::Red)),
+    };
+    Line::from(Span::styled(format!("{} {}", sign, line.text), style))
+}
@@ -31,10 +31,17 @@ impl SettingsComponent {
                 SegmentId::Directory => "目录",
                 SegmentId::Git => "Git",
                 SegmentId::ContextWindow => "上下文窗口",
+                SegmentId::ContextAdvisor => "上下文建议",
+                SegmentId::ApiErrors => "API 错误",
+                SegmentId::Summary => "智能摘要",
+                SegmentId::QuotaPacing => "额度节奏",
                 SegmentId::Usage => "用量",
                 SegmentId::Cost => "费用",
+                SegmentId::DailyCost => "今日花费",
+                SegmentId::BlockUsage => "5小时窗口",
                 SegmentId::Session => "会话",
                 SegmentId::OutputStyle => "输出样式",
+                SegmentId::Runtime => "运行环境",
                 SegmentId::Update => "更新",
                 SegmentId::UucodeUsage => "uucode 用量",
                 SegmentId::UucodeSubscription => "uucode 订阅",
@@ -48,6 +55,9 @@ impl SettingsComponent {
                 SegmentId::CubenceLatency => "Cubence 延迟",
                 SegmentId::CubenceSubscription => "Cubence 订阅",
                 SegmentId::CubenceMultiplier => "Cubence 倍率",
+                SegmentId::OneApiUsage => "one-api 用量",
+                SegmentId::VendorUsage => "服务商用量",
+                SegmentId::Custom => "自定义命令",
             };
             let current_icon = match config.style.mode {
                 StyleMode::Plain => &segment.icon.plain,
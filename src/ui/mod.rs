@@ -8,7 +8,6 @@ pub mod events;
 pub mod layout;
 #[cfg(feature = "tui")]
 pub mod main_menu;
-#[cfg(feature = "tui")]
 pub mod themes;
 
 #[cfg(feature = "tui")]
@@ -61,6 +61,7 @@ impl ThemePresets {
 
     /// Save current config as a new theme
     pub fn save_theme(theme_name: &str, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+        crate::utils::readonly::ensure_writable("writing the theme")?;
         let themes_dir = Self::get_themes_path();
         let theme_path = themes_dir.join(format!("{}.toml", theme_name));
 
@@ -72,7 +73,7 @@ impl ThemePresets {
         theme_config.theme = theme_name.to_string();
 
         let content = toml::to_string_pretty(&theme_config)?;
-        std::fs::write(&theme_path, content)?;
+        crate::utils::atomic_write(&theme_path, content)?;
 
         Ok(())
     }
@@ -123,12 +124,13 @@ impl ThemePresets {
     }
 
     pub fn get_cometix() -> Config {
-        Config {
-            style: StyleConfig {
+        Config::from_theme(
+            StyleConfig {
                 mode: StyleMode::NerdFont,
                 separator: " | ".to_string(),
+                ..Default::default()
             },
-            segments: vec![
+            vec![
                 // cubence segments (放在最前面)
                 theme_cometix::cubence_status_segment(),
                 theme_cometix::cubence_five_hour_segment(),
@@ -141,29 +143,42 @@ impl ThemePresets {
                 theme_cometix::directory_segment(),
                 theme_cometix::git_segment(),
                 theme_cometix::context_window_segment(),
+                theme_cometix::context_advisor_segment(),
+                theme_cometix::api_errors_segment(),
+                theme_cometix::summary_segment(),
                 theme_cometix::usage_segment(),
                 theme_cometix::cost_segment(),
+                theme_cometix::daily_cost_segment(),
+                theme_cometix::block_usage_segment(),
                 theme_cometix::session_segment(),
                 theme_cometix::output_style_segment(),
+                theme_cometix::runtime_segment(),
+                theme_cometix::custom_segment(),
                 // uucode segments
                 theme_cometix::uucode_usage_segment(),
                 theme_cometix::uucode_subscription_segment(),
+                // one-api 网关和 vendors.d 中的服务商
+                theme_cometix::one_api_usage_segment(),
+                theme_cometix::vendor_usage_segment(),
+                // 读取上面用量段刚写入的缓存
+                theme_cometix::quota_pacing_segment(),
                 // 负载状态放最后
                 theme_cometix::cubence_load_status_segment(),
                 // 延迟放在最末尾
                 theme_cometix::cubence_latency_segment(),
             ],
-            theme: "cometix".to_string(),
-        }
+            "cometix",
+        )
     }
 
     pub fn get_default() -> Config {
-        Config {
-            style: StyleConfig {
+        Config::from_theme(
+            StyleConfig {
                 mode: StyleMode::Plain,
                 separator: " | ".to_string(),
+                ..Default::default()
             },
-            segments: vec![
+            vec![
                 // cubence segments (放在最前面)
                 theme_cometix::cubence_status_segment(),
                 theme_cometix::cubence_five_hour_segment(),
@@ -176,30 +191,43 @@ impl ThemePresets {
                 theme_default::directory_segment(),
                 theme_default::git_segment(),
                 theme_default::context_window_segment(),
+                theme_cometix::context_advisor_segment(),
+                theme_cometix::api_errors_segment(),
+                theme_cometix::summary_segment(),
                 theme_default::usage_segment(),
                 theme_default::cost_segment(),
+                theme_cometix::daily_cost_segment(),
+                theme_cometix::block_usage_segment(),
                 theme_default::session_segment(),
                 theme_default::output_style_segment(),
+                theme_cometix::runtime_segment(),
+                theme_cometix::custom_segment(),
                 // uucode segments
                 theme_default::uucode_usage_segment(),
                 theme_default::uucode_subscription_segment(),
                 theme_default::uucode_status_segment(),
+                // one-api 网关和 vendors.d 中的服务商
+                theme_cometix::one_api_usage_segment(),
+                theme_cometix::vendor_usage_segment(),
+                // 读取上面用量段刚写入的缓存
+                theme_cometix::quota_pacing_segment(),
                 // 负载状态放最后
                 theme_cometix::cubence_load_status_segment(),
                 // 延迟放在最末尾
                 theme_cometix::cubence_latency_segment(),
             ],
-            theme: "default".to_string(),
-        }
+            "default",
+        )
     }
 
     pub fn get_minimal() -> Config {
-        Config {
-            style: StyleConfig {
+        Config::from_theme(
+            StyleConfig {
                 mode: StyleMode::Plain,
                 separator: " │ ".to_string(),
+                ..Default::default()
             },
-            segments: vec![
+            vec![
                 // cubence segments (放在最前面)
                 theme_cometix::cubence_status_segment(),
                 theme_cometix::cubence_five_hour_segment(),
@@ -224,17 +252,19 @@ impl ThemePresets {
                 // 延迟放在最末尾
                 theme_cometix::cubence_latency_segment(),
             ],
-            theme: "minimal".to_string(),
-        }
+            "minimal",
+        )
     }
 
     pub fn get_gruvbox() -> Config {
-        Config {
-            style: StyleConfig {
+        Config::from_theme(
+            StyleConfig {
                 mode: StyleMode::NerdFont,
                 separator: " | ".to_string(),
+                roles: theme_gruvbox::roles(),
+                ..Default::default()
             },
-            segments: vec![
+            vec![
                 // cubence segments (放在最前面)
                 theme_cometix::cubence_status_segment(),
                 theme_cometix::cubence_five_hour_segment(),
@@ -259,17 +289,19 @@ impl ThemePresets {
                 // 延迟放在最末尾
                 theme_cometix::cubence_latency_segment(),
             ],
-            theme: "gruvbox".to_string(),
-        }
+            "gruvbox",
+        )
     }
 
     pub fn get_nord() -> Config {
-        Config {
-            style: StyleConfig {
+        Config::from_theme(
+            StyleConfig {
                 mode: StyleMode::NerdFont,
                 separator: "".to_string(),
+                roles: theme_nord::roles(),
+                ..Default::default()
             },
-            segments: vec![
+            vec![
                 // cubence segments (放在最前面)
                 theme_cometix::cubence_status_segment(),
                 theme_cometix::cubence_five_hour_segment(),
@@ -294,17 +326,18 @@ impl ThemePresets {
                 // 延迟放在最末尾
                 theme_cometix::cubence_latency_segment(),
             ],
-            theme: "nord".to_string(),
-        }
+            "nord",
+        )
     }
 
     pub fn get_powerline_dark() -> Config {
-        Config {
-            style: StyleConfig {
+        Config::from_theme(
+            StyleConfig {
                 mode: StyleMode::NerdFont,
                 separator: "".to_string(),
+                ..Default::default()
             },
-            segments: vec![
+            vec![
                 // cubence segments (放在最前面)
                 theme_cometix::cubence_status_segment(),
                 theme_cometix::cubence_five_hour_segment(),
@@ -329,17 +362,19 @@ impl ThemePresets {
                 // 延迟放在最末尾
                 theme_cometix::cubence_latency_segment(),
             ],
-            theme: "powerline-dark".to_string(),
-        }
+            "powerline-dark",
+        )
     }
 
     pub fn get_powerline_light() -> Config {
-        Config {
-            style: StyleConfig {
+        Config::from_theme(
+            StyleConfig {
                 mode: StyleMode::NerdFont,
                 separator: "".to_string(),
+                roles: theme_powerline_light::roles(),
+                ..Default::default()
             },
-            segments: vec![
+            vec![
                 // cubence segments (放在最前面)
                 theme_cometix::cubence_status_segment(),
                 theme_cometix::cubence_five_hour_segment(),
@@ -364,17 +399,19 @@ impl ThemePresets {
                 // 延迟放在最末尾
                 theme_cometix::cubence_latency_segment(),
             ],
-            theme: "powerline-light".to_string(),
-        }
+            "powerline-light",
+        )
     }
 
     pub fn get_powerline_rose_pine() -> Config {
-        Config {
-            style: StyleConfig {
+        Config::from_theme(
+            StyleConfig {
                 mode: StyleMode::NerdFont,
                 separator: "".to_string(),
+                roles: theme_powerline_rose_pine::roles(),
+                ..Default::default()
             },
-            segments: vec![
+            vec![
                 // cubence segments (放在最前面)
                 theme_cometix::cubence_status_segment(),
                 theme_cometix::cubence_five_hour_segment(),
@@ -399,17 +436,19 @@ impl ThemePresets {
                 // 延迟放在最末尾
                 theme_cometix::cubence_latency_segment(),
             ],
-            theme: "powerline-rose-pine".to_string(),
-        }
+            "powerline-rose-pine",
+        )
     }
 
     pub fn get_powerline_tokyo_night() -> Config {
-        Config {
-            style: StyleConfig {
+        Config::from_theme(
+            StyleConfig {
                 mode: StyleMode::NerdFont,
                 separator: "".to_string(),
+                roles: theme_powerline_tokyo_night::roles(),
+                ..Default::default()
             },
-            segments: vec![
+            vec![
                 // cubence segments (放在最前面)
                 theme_cometix::cubence_status_segment(),
                 theme_cometix::cubence_five_hour_segment(),
@@ -434,7 +473,7 @@ impl ThemePresets {
                 // 延迟放在最末尾
                 theme_cometix::cubence_latency_segment(),
             ],
-            theme: "powerline-tokyo-night".to_string(),
-        }
+            "powerline-tokyo-night",
+        )
     }
 }
@@ -17,6 +17,8 @@ pub fn model_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: true },
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -35,6 +37,8 @@ pub fn directory_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: true },
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -53,6 +57,8 @@ pub fn git_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: true },
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert("show_sha".to_string(), serde_json::Value::Bool(false));
@@ -75,6 +81,8 @@ pub fn context_window_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: true },
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -93,6 +101,48 @@ pub fn cost_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: true },
+        priority: None,
+        icon_rules: Vec::new(),
+        options: HashMap::new(),
+    }
+}
+
+pub fn daily_cost_segment() -> SegmentConfig {
+    SegmentConfig {
+        id: SegmentId::DailyCost,
+        enabled: false,
+        icon: IconConfig {
+            plain: "📅".to_string(),
+            nerd_font: "\u{f00f0}".to_string(), // nf-md-calendar_today
+        },
+        colors: ColorConfig {
+            icon: Some(AnsiColor::Color16 { c16: 3 }),
+            text: Some(AnsiColor::Color16 { c16: 3 }),
+            background: None,
+        },
+        styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
+        options: HashMap::new(),
+    }
+}
+
+pub fn block_usage_segment() -> SegmentConfig {
+    SegmentConfig {
+        id: SegmentId::BlockUsage,
+        enabled: false,
+        icon: IconConfig {
+            plain: "⏳".to_string(),
+            nerd_font: "\u{f051f}".to_string(), // nf-md-timer_sand
+        },
+        colors: ColorConfig {
+            icon: Some(AnsiColor::Color16 { c16: 14 }),
+            text: Some(AnsiColor::Color16 { c16: 14 }),
+            background: None,
+        },
+        styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -111,6 +161,8 @@ pub fn session_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: true },
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -129,6 +181,189 @@ pub fn output_style_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: true },
+        priority: None,
+        icon_rules: Vec::new(),
+        options: HashMap::new(),
+    }
+}
+
+pub fn custom_segment() -> SegmentConfig {
+    SegmentConfig {
+        id: SegmentId::Custom,
+        enabled: false,
+        icon: IconConfig {
+            plain: "⚙️".to_string(),
+            nerd_font: "\u{f013}".to_string(),
+        },
+        colors: ColorConfig {
+            icon: Some(AnsiColor::Color16 { c16: 7 }),
+            text: Some(AnsiColor::Color16 { c16: 7 }),
+            background: None,
+        },
+        styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
+        options: {
+            let mut opts = HashMap::new();
+            opts.insert(
+                "command".to_string(),
+                serde_json::Value::String(String::new()),
+            );
+            opts.insert("timeout_ms".to_string(), serde_json::Value::from(1000));
+            opts.insert("sandbox".to_string(), serde_json::Value::Bool(false));
+            opts
+        },
+    }
+}
+
+pub fn context_advisor_segment() -> SegmentConfig {
+    SegmentConfig {
+        id: SegmentId::ContextAdvisor,
+        enabled: true,
+        icon: IconConfig {
+            plain: "💡".to_string(),
+            nerd_font: "\u{f0335}".to_string(),
+        },
+        colors: ColorConfig {
+            icon: Some(AnsiColor::Color16 { c16: 11 }),
+            text: Some(AnsiColor::Color16 { c16: 11 }),
+            background: None,
+        },
+        styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
+        options: HashMap::new(),
+    }
+}
+
+pub fn api_errors_segment() -> SegmentConfig {
+    SegmentConfig {
+        id: SegmentId::ApiErrors,
+        enabled: true,
+        icon: IconConfig {
+            plain: "❗".to_string(),
+            nerd_font: "\u{f0028}".to_string(),
+        },
+        colors: ColorConfig {
+            icon: Some(AnsiColor::Color16 { c16: 9 }),
+            text: Some(AnsiColor::Color16 { c16: 9 }),
+            background: None,
+        },
+        styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
+        options: {
+            let mut opts = HashMap::new();
+            opts.insert("window_minutes".to_string(), serde_json::Value::from(10));
+            opts.insert("critical_at".to_string(), serde_json::Value::from(3));
+            opts
+        },
+    }
+}
+
+pub fn summary_segment() -> SegmentConfig {
+    SegmentConfig {
+        id: SegmentId::Summary,
+        enabled: false,
+        icon: IconConfig {
+            plain: "".to_string(), // 图标取自被选中的段
+            nerd_font: "".to_string(),
+        },
+        colors: ColorConfig {
+            icon: None,
+            text: Some(AnsiColor::Color16 { c16: 15 }),
+            background: None,
+        },
+        styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
+        options: HashMap::new(),
+    }
+}
+
+pub fn runtime_segment() -> SegmentConfig {
+    SegmentConfig {
+        id: SegmentId::Runtime,
+        enabled: false,
+        icon: IconConfig {
+            plain: "🖥️".to_string(),
+            nerd_font: "\u{f108}".to_string(),
+        },
+        colors: ColorConfig {
+            icon: Some(AnsiColor::Color16 { c16: 6 }),
+            text: Some(AnsiColor::Color16 { c16: 6 }),
+            background: None,
+        },
+        styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
+        options: {
+            let mut opts = HashMap::new();
+            // 本机运行时也显示 "local"
+            opts.insert("show_local".to_string(), serde_json::Value::Bool(false));
+            opts
+        },
+    }
+}
+
+pub fn quota_pacing_segment() -> SegmentConfig {
+    SegmentConfig {
+        id: SegmentId::QuotaPacing,
+        enabled: false,
+        icon: IconConfig {
+            plain: "⏱️".to_string(),
+            nerd_font: "\u{f051f}".to_string(),
+        },
+        colors: ColorConfig {
+            icon: Some(AnsiColor::Color16 { c16: 13 }),
+            text: Some(AnsiColor::Color16 { c16: 13 }),
+            background: None,
+        },
+        styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
+        options: HashMap::new(),
+    }
+}
+
+pub fn one_api_usage_segment() -> SegmentConfig {
+    SegmentConfig {
+        id: SegmentId::OneApiUsage,
+        // 只在 ANTHROPIC_BASE_URL 指向 [oneapi] base_url 时显示
+        enabled: true,
+        icon: IconConfig {
+            plain: "🔑".to_string(),
+            nerd_font: "\u{f0690}".to_string(), // nf-md-gauge
+        },
+        colors: ColorConfig {
+            icon: Some(AnsiColor::Color256 { c256: 214 }),
+            text: Some(AnsiColor::Color256 { c256: 255 }),
+            background: None,
+        },
+        styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
+        options: HashMap::new(),
+    }
+}
+
+pub fn vendor_usage_segment() -> SegmentConfig {
+    SegmentConfig {
+        id: SegmentId::VendorUsage,
+        // 只在当前服务商来自 vendors.d 时显示
+        enabled: true,
+        icon: IconConfig {
+            plain: "🔌".to_string(),
+            nerd_font: "\u{f0690}".to_string(), // nf-md-gauge
+        },
+        colors: ColorConfig {
+            icon: Some(AnsiColor::Color256 { c256: 214 }),
+            text: Some(AnsiColor::Color256 { c256: 255 }),
+            background: None,
+        },
+        styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -147,6 +382,8 @@ pub fn usage_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert(
@@ -177,6 +414,8 @@ pub fn uucode_usage_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: false },
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert(
@@ -202,6 +441,8 @@ pub fn uucode_subscription_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: false },
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert(
@@ -227,6 +468,8 @@ pub fn uucode_status_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -247,6 +490,8 @@ pub fn cubence_balance_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: false },
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -265,6 +510,8 @@ pub fn cubence_usage_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: false },
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -283,6 +530,8 @@ pub fn cubence_status_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: true },
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -301,6 +550,8 @@ pub fn cubence_five_hour_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: false },
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -319,6 +570,8 @@ pub fn cubence_weekly_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: false },
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -337,6 +590,8 @@ pub fn cubence_load_status_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: false },
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -355,7 +610,17 @@ pub fn cubence_latency_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: false },
-        options: HashMap::new(),
+        priority: None,
+        icon_rules: Vec::new(),
+        options: {
+            let mut opts = HashMap::new();
+            // 在延迟后显示近 24 小时的 p50/p95
+            opts.insert(
+                "show_percentiles".to_string(),
+                serde_json::Value::Bool(false),
+            );
+            opts
+        },
     }
 }
 
@@ -373,6 +638,8 @@ pub fn cubence_subscription_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: false },
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -391,6 +658,8 @@ pub fn cubence_multiplier_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: false },
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -17,6 +17,8 @@ pub fn model_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -35,6 +37,8 @@ pub fn directory_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -53,6 +57,8 @@ pub fn git_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert("show_sha".to_string(), serde_json::Value::Bool(false));
@@ -75,6 +81,8 @@ pub fn context_window_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -93,6 +101,8 @@ pub fn usage_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert(
@@ -123,6 +133,8 @@ pub fn cost_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -141,6 +153,8 @@ pub fn session_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -159,6 +173,8 @@ pub fn output_style_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -177,6 +193,8 @@ pub fn uucode_usage_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert(
@@ -202,6 +220,8 @@ pub fn uucode_subscription_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert(
@@ -227,6 +247,8 @@ pub fn uucode_status_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -1,5 +1,5 @@
 use crate::config::{
-    AnsiColor, ColorConfig, IconConfig, SegmentConfig, SegmentId, TextStyleConfig,
+    AnsiColor, ColorConfig, IconConfig, RoleColors, SegmentConfig, SegmentId, TextStyleConfig,
 };
 use std::collections::HashMap;
 
@@ -17,6 +17,8 @@ pub fn model_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: true },
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -35,6 +37,8 @@ pub fn directory_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: true },
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -53,6 +57,8 @@ pub fn git_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: true },
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert("show_sha".to_string(), serde_json::Value::Bool(false));
@@ -75,6 +81,8 @@ pub fn context_window_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: true },
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -93,6 +101,8 @@ pub fn cost_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: true },
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -111,6 +121,8 @@ pub fn session_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: true },
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -129,6 +141,8 @@ pub fn output_style_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig { text_bold: true },
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -147,6 +161,8 @@ pub fn usage_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert(
@@ -177,6 +193,8 @@ pub fn uucode_usage_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert(
@@ -202,6 +220,8 @@ pub fn uucode_subscription_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert(
@@ -227,6 +247,19 @@ pub fn uucode_status_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
+
+/// State and highlight colors for segment text
+pub fn roles() -> RoleColors {
+    RoleColors {
+        ok: Some(AnsiColor::Color256 { c256: 142 }), // Gruvbox green
+        warn: Some(AnsiColor::Color256 { c256: 214 }), // Gruvbox yellow
+        critical: Some(AnsiColor::Color256 { c256: 167 }), // Gruvbox red
+        accent: Some(AnsiColor::Color256 { c256: 208 }), // Gruvbox orange
+        muted: Some(AnsiColor::Color256 { c256: 245 }), // Gruvbox gray
+    }
+}
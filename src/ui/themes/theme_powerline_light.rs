@@ -1,5 +1,5 @@
 use crate::config::{
-    AnsiColor, ColorConfig, IconConfig, SegmentConfig, SegmentId, TextStyleConfig,
+    AnsiColor, ColorConfig, IconConfig, RoleColors, SegmentConfig, SegmentId, TextStyleConfig,
 };
 use std::collections::HashMap;
 
@@ -21,6 +21,8 @@ pub fn model_segment() -> SegmentConfig {
             }),
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -51,6 +53,8 @@ pub fn directory_segment() -> SegmentConfig {
             }),
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -81,6 +85,8 @@ pub fn git_segment() -> SegmentConfig {
             }),
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert("show_sha".to_string(), serde_json::Value::Bool(false));
@@ -115,6 +121,8 @@ pub fn context_window_segment() -> SegmentConfig {
             }),
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -145,6 +153,8 @@ pub fn cost_segment() -> SegmentConfig {
             }),
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -175,6 +185,8 @@ pub fn session_segment() -> SegmentConfig {
             }),
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -205,6 +217,8 @@ pub fn output_style_segment() -> SegmentConfig {
             }),
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -223,6 +237,8 @@ pub fn usage_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert(
@@ -253,6 +269,8 @@ pub fn uucode_usage_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert(
@@ -278,6 +296,8 @@ pub fn uucode_subscription_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert(
@@ -303,6 +323,35 @@ pub fn uucode_status_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
+
+/// State and highlight colors for segment text
+pub fn roles() -> RoleColors {
+    RoleColors {
+        ok: Some(AnsiColor::Rgb {
+            r: 56,
+            g: 118,
+            b: 29,
+        }),
+        warn: Some(AnsiColor::Rgb {
+            r: 181,
+            g: 118,
+            b: 20,
+        }),
+        critical: Some(AnsiColor::Rgb {
+            r: 180,
+            g: 40,
+            b: 40,
+        }),
+        accent: Some(AnsiColor::Rgb {
+            r: 150,
+            g: 90,
+            b: 0,
+        }),
+        ..Default::default()
+    }
+}
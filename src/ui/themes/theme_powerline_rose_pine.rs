@@ -1,5 +1,5 @@
 use crate::config::{
-    AnsiColor, ColorConfig, IconConfig, SegmentConfig, SegmentId, TextStyleConfig,
+    AnsiColor, ColorConfig, IconConfig, RoleColors, SegmentConfig, SegmentId, TextStyleConfig,
 };
 use std::collections::HashMap;
 
@@ -29,6 +29,8 @@ pub fn model_segment() -> SegmentConfig {
             }),
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -59,6 +61,8 @@ pub fn directory_segment() -> SegmentConfig {
             }),
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -89,6 +93,8 @@ pub fn git_segment() -> SegmentConfig {
             }),
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert("show_sha".to_string(), serde_json::Value::Bool(false));
@@ -123,6 +129,8 @@ pub fn context_window_segment() -> SegmentConfig {
             }),
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -153,6 +161,8 @@ pub fn cost_segment() -> SegmentConfig {
             }), // Rose Pine dark background
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -183,6 +193,8 @@ pub fn session_segment() -> SegmentConfig {
             }), // Rose Pine darker background
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -213,6 +225,8 @@ pub fn output_style_segment() -> SegmentConfig {
             }), // Rose Pine darkest background
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -231,6 +245,8 @@ pub fn usage_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert(
@@ -261,6 +277,8 @@ pub fn uucode_usage_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert(
@@ -286,6 +304,8 @@ pub fn uucode_subscription_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert(
@@ -311,6 +331,39 @@ pub fn uucode_status_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
+
+/// State and highlight colors for segment text
+pub fn roles() -> RoleColors {
+    RoleColors {
+        ok: Some(AnsiColor::Rgb {
+            r: 156,
+            g: 207,
+            b: 216,
+        }),
+        warn: Some(AnsiColor::Rgb {
+            r: 246,
+            g: 193,
+            b: 119,
+        }),
+        critical: Some(AnsiColor::Rgb {
+            r: 235,
+            g: 111,
+            b: 146,
+        }),
+        accent: Some(AnsiColor::Rgb {
+            r: 196,
+            g: 167,
+            b: 231,
+        }),
+        muted: Some(AnsiColor::Rgb {
+            r: 110,
+            g: 106,
+            b: 134,
+        }),
+    }
+}
@@ -1,5 +1,5 @@
 use crate::config::{
-    AnsiColor, ColorConfig, IconConfig, SegmentConfig, SegmentId, TextStyleConfig,
+    AnsiColor, ColorConfig, IconConfig, RoleColors, SegmentConfig, SegmentId, TextStyleConfig,
 };
 use std::collections::HashMap;
 
@@ -29,6 +29,8 @@ pub fn model_segment() -> SegmentConfig {
             }),
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -59,6 +61,8 @@ pub fn directory_segment() -> SegmentConfig {
             }),
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -89,6 +93,8 @@ pub fn git_segment() -> SegmentConfig {
             }),
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert("show_sha".to_string(), serde_json::Value::Bool(false));
@@ -123,6 +129,8 @@ pub fn context_window_segment() -> SegmentConfig {
             }),
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -153,6 +161,8 @@ pub fn cost_segment() -> SegmentConfig {
             }), // Tokyo Night dark background
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -183,6 +193,8 @@ pub fn session_segment() -> SegmentConfig {
             }), // Tokyo Night darker background
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -213,6 +225,8 @@ pub fn output_style_segment() -> SegmentConfig {
             }), // Tokyo Night darkest background
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
@@ -231,6 +245,8 @@ pub fn usage_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert(
@@ -261,6 +277,8 @@ pub fn uucode_usage_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert(
@@ -286,6 +304,8 @@ pub fn uucode_subscription_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: {
             let mut opts = HashMap::new();
             opts.insert(
@@ -311,6 +331,39 @@ pub fn uucode_status_segment() -> SegmentConfig {
             background: None,
         },
         styles: TextStyleConfig::default(),
+        priority: None,
+        icon_rules: Vec::new(),
         options: HashMap::new(),
     }
 }
+
+/// State and highlight colors for segment text
+pub fn roles() -> RoleColors {
+    RoleColors {
+        ok: Some(AnsiColor::Rgb {
+            r: 158,
+            g: 206,
+            b: 106,
+        }),
+        warn: Some(AnsiColor::Rgb {
+            r: 224,
+            g: 175,
+            b: 104,
+        }),
+        critical: Some(AnsiColor::Rgb {
+            r: 247,
+            g: 118,
+            b: 142,
+        }),
+        accent: Some(AnsiColor::Rgb {
+            r: 187,
+            g: 154,
+            b: 247,
+        }),
+        muted: Some(AnsiColor::Rgb {
+            r: 86,
+            g: 95,
+            b: 137,
+        }),
+    }
+}
@@ -191,7 +191,7 @@ impl UpdateState {
     /// Save update state to config directory
     pub fn save(&self) -> Result<(), std::io::Error> {
         #[cfg(feature = "self-update")]
-        {
+        if !crate::utils::readonly::is_enabled() {
             let config_dir = dirs::home_dir()
                 .unwrap_or_default()
                 .join(".claude")
@@ -201,7 +201,7 @@ impl UpdateState {
             let state_file = config_dir.join(".update_state.json");
 
             let content = serde_json::to_string_pretty(self)?;
-            std::fs::write(&state_file, content)?;
+            crate::utils::atomic_write(&state_file, content)?;
         }
 
         Ok(())
@@ -237,7 +237,17 @@ impl UpdateState {
 /// GitHub Release API response structures
 #[cfg(feature = "self-update")]
 pub mod github {
+    use crate::utils::download::{self, checksum_for, Integrity};
     use serde::{Deserialize, Serialize};
+    use std::time::Duration;
+
+    /// Release archives are a few MB; anything far larger is not ours
+    const MAX_ASSET_BYTES: u64 = 64 * 1024 * 1024;
+
+    const ASSET_TIMEOUT: Duration = Duration::from_secs(300);
+
+    /// Checksum lists a release may publish for all of its assets
+    const CHECKSUM_LISTS: &[&str] = &["SHA256SUMS", "SHA256SUMS.txt", "checksums.txt"];
 
     #[derive(Debug, Deserialize, Serialize, Clone)]
     pub struct GitHubRelease {
@@ -277,6 +287,52 @@ pub mod github {
                 .iter()
                 .find(|asset| asset.name.contains(&platform_suffix))
         }
+
+        /// Download `asset` and check it against the SHA-256 published with
+        /// the release (`<asset>.sha256` or a checksum list), plus its
+        /// minisign signature when `minisign_key` is given
+        pub fn download_asset(
+            &self,
+            asset: &ReleaseAsset,
+            minisign_key: Option<&str>,
+        ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+            let own = format!("{}.sha256", asset.name);
+            let sums_asset = self
+                .assets
+                .iter()
+                .find(|a| a.name == own)
+                .or_else(|| {
+                    self.assets
+                        .iter()
+                        .find(|a| CHECKSUM_LISTS.contains(&a.name.as_str()))
+                })
+                .ok_or_else(|| format!("release {} publishes no checksums", self.tag_name))?;
+            let sums = download::fetch(
+                &sums_asset.browser_download_url,
+                64 * 1024,
+                download::DEFAULT_TIMEOUT,
+            )?;
+            let sums = String::from_utf8_lossy(&sums);
+            // `<asset>.sha256` may hold the bare digest without a file name
+            let expected = checksum_for(&sums, &asset.name)
+                .or_else(|| {
+                    sums.split_whitespace()
+                        .next()
+                        .filter(|_| sums_asset.name == own)
+                })
+                .ok_or_else(|| format!("{} has no checksum for {}", sums_asset.name, asset.name))?
+                .to_string();
+
+            download::fetch_verified(
+                &asset.browser_download_url,
+                MAX_ASSET_BYTES.max(asset.size),
+                ASSET_TIMEOUT,
+                &Integrity {
+                    sha256: Some(&expected),
+                    minisign_key,
+                },
+            )
+        }
     }
 
     /// Get the expected asset name suffix for current platform
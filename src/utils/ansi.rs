@@ -0,0 +1,247 @@
+//! Helpers for measuring and cutting text that contains ANSI escape sequences.
+
+use crate::config::EmojiWidth;
+use unicode_width::UnicodeWidthChar;
+
+/// Whether `ch` is drawn as an emoji pictograph
+fn is_emoji(ch: char) -> bool {
+    matches!(
+        ch as u32,
+        0x2300..=0x23FF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0x1F000..=0x1FAFF
+    )
+}
+
+/// Terminal columns taken by a single character under the given policy
+///
+/// ```
+/// use mycode::config::EmojiWidth;
+/// use mycode::utils::ansi::char_width;
+///
+/// assert_eq!(char_width('a', EmojiWidth::Wide), 1);
+/// assert_eq!(char_width('用', EmojiWidth::Narrow), 2);
+/// assert_eq!(char_width('⏱', EmojiWidth::Wide), 2);
+/// assert_eq!(char_width('💳', EmojiWidth::Narrow), 1);
+/// ```
+pub fn char_width(ch: char, policy: EmojiWidth) -> usize {
+    if ch.is_control() {
+        return 0;
+    }
+    if is_emoji(ch) {
+        return match policy.resolve() {
+            EmojiWidth::Narrow => 1,
+            _ => 2,
+        };
+    }
+    ch.width().unwrap_or(0)
+}
+
+/// Strip ANSI escape sequences and return the visible width in columns
+pub fn visible_width(text: &str, policy: EmojiWidth) -> usize {
+    let policy = policy.resolve();
+    let mut width = 0;
+    let mut in_escape = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch == '\x1b' {
+            // Start of ANSI escape sequence
+            in_escape = true;
+            // Skip the [ character
+            if chars.peek() == Some(&'[') {
+                chars.next();
+            }
+        } else if in_escape {
+            // Skip until we find the end of the escape sequence (letter)
+            if ch.is_alphabetic() {
+                in_escape = false;
+            }
+        } else {
+            width += char_width(ch, policy);
+        }
+    }
+
+    width
+}
+
+/// Cut `text` to at most `max_width` visible columns without splitting an
+/// escape sequence. A trailing `…` marks the cut and styling is reset so the
+/// rest of the terminal line is not colored.
+///
+/// ```
+/// use mycode::config::EmojiWidth;
+/// use mycode::utils::ansi::{truncate_visible, visible_width};
+///
+/// let text = "\x1b[32mhello\x1b[0m world";
+/// assert_eq!(truncate_visible(text, 20, EmojiWidth::Wide), text);
+///
+/// let cut = truncate_visible(text, 4, EmojiWidth::Wide);
+/// assert_eq!(cut, "\x1b[32mhel…\x1b[0m");
+/// assert_eq!(visible_width(&cut, EmojiWidth::Wide), 4);
+/// ```
+pub fn truncate_visible(text: &str, max_width: usize, policy: EmojiWidth) -> String {
+    let policy = policy.resolve();
+    if visible_width(text, policy) <= max_width {
+        return text.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+
+    let mut out = String::new();
+    let mut width = 0;
+    let mut in_escape = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch == '\x1b' {
+            in_escape = true;
+            out.push(ch);
+            if chars.peek() == Some(&'[') {
+                out.push('[');
+                chars.next();
+            }
+        } else if in_escape {
+            out.push(ch);
+            if ch.is_alphabetic() {
+                in_escape = false;
+            }
+        } else {
+            // Reserve the last column for the ellipsis
+            let ch_width = char_width(ch, policy);
+            if width + ch_width >= max_width {
+                break;
+            }
+            out.push(ch);
+            width += ch_width;
+        }
+    }
+
+    out.push('…');
+    out.push_str("\x1b[0m");
+    out
+}
+
+/// Remove control characters and escape sequences that could corrupt the
+/// terminal, keeping only SGR styling (`ESC [ ... m`).
+///
+/// Whitespace controls become plain spaces so multi-line values stay on one
+/// line; OSC sequences (titles, hyperlinks), cursor movement and bidi
+/// overrides are dropped.
+///
+/// ```
+/// use mycode::utils::ansi::sanitize;
+///
+/// assert_eq!(sanitize("\x1b[32mok\x1b[0m"), "\x1b[32mok\x1b[0m");
+/// assert_eq!(sanitize("feat\x1b[2J/\x07x"), "feat/x");
+/// assert_eq!(sanitize("a\x1b]0;title\x07b"), "ab");
+/// assert_eq!(sanitize("line1\nline2"), "line1 line2");
+/// ```
+pub fn sanitize(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\x1b' => match chars.next() {
+                Some('[') => {
+                    // CSI: parameter/intermediate bytes then a final byte
+                    let mut params = String::new();
+                    let mut final_byte = None;
+                    for c in chars.by_ref() {
+                        if ('\x40'..='\x7e').contains(&c) {
+                            final_byte = Some(c);
+                            break;
+                        }
+                        if !('\x20'..='\x3f').contains(&c) {
+                            break;
+                        }
+                        params.push(c);
+                    }
+                    if final_byte == Some('m')
+                        && params.chars().all(|c| c.is_ascii_digit() || c == ';')
+                    {
+                        out.push_str("\x1b[");
+                        out.push_str(&params);
+                        out.push('m');
+                    }
+                }
+                Some(']') => {
+                    // OSC: terminated by BEL or ST (ESC \)
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' {
+                            break;
+                        }
+                        if c == '\x1b' {
+                            if chars.peek() == Some(&'\\') {
+                                chars.next();
+                            }
+                            break;
+                        }
+                    }
+                }
+                // Other two-byte escapes are dropped along with their argument
+                _ => {}
+            },
+            '\n' | '\r' | '\t' => out.push(' '),
+            '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}' => {}
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+/// Remove every escape sequence, leaving only the visible text
+///
+/// ```
+/// use mycode::utils::ansi::strip;
+///
+/// assert_eq!(strip("\x1b[1;32mok\x1b[0m | \x1b[38;5;214m$1\x1b[0m"), "ok | $1");
+/// ```
+pub fn strip(text: &str) -> String {
+    let sanitized = sanitize(text);
+    let mut out = String::with_capacity(sanitized.len());
+    let mut chars = sanitized.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch == '\x1b' {
+            // sanitize only leaves SGR sequences behind
+            for c in chars.by_ref() {
+                if c == 'm' {
+                    break;
+                }
+            }
+        } else {
+            out.push(ch);
+        }
+    }
+
+    out
+}
+
+/// Remove emoji pictographs (and the joiners and variation selectors that
+/// build them), keeping text symbols such as ✓ or ⚠
+///
+/// ```
+/// use mycode::utils::ansi::strip_emoji;
+///
+/// assert_eq!(strip_emoji("🟢 延迟[120ms]"), "延迟[120ms]");
+/// assert_eq!(strip_emoji("master ✓"), "master ✓");
+/// assert_eq!(strip_emoji("⚠️ 负载: 配置文件错误"), "⚠ 负载: 配置文件错误");
+/// ```
+pub fn strip_emoji(text: &str) -> String {
+    let kept: String = text
+        .chars()
+        .filter(|&c| !matches!(c as u32, 0x1F000..=0x1FAFF | 0xFE0F | 0x200D))
+        .collect();
+    // Collapse the gaps left where an emoji stood between spaces
+    let mut out = String::with_capacity(kept.len());
+    for c in kept.chars() {
+        if c == ' ' && (out.is_empty() || out.ends_with(' ')) {
+            continue;
+        }
+        out.push(c);
+    }
+    out.trim_end().to_string()
+}
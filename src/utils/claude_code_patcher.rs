@@ -1,6 +1,152 @@
+use crate::config::SpinnerPatchConfig;
+use memmap2::Mmap;
+use once_cell::sync::Lazy;
 use regex::Regex;
+use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+// Patterns are compiled on first use and shared by every patcher call
+static CREATE_ELEMENT_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"createElement\([$\w]+,\{[^}]+spinnerTip[^}]+overrideMessage[^}]+\}").unwrap()
+});
+static VERBOSE_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"verbose:[^,}]+").unwrap());
+static SPINNER_TIP_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"spinnerTip:([^,}]+)").unwrap());
+/// An installed spinner patch: the injected expression followed by the
+/// original one, so it can be taken out again
+static SPINNER_PATCH_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(&format!(
+        r"{}.*?{}(.*?)\*/",
+        regex::escape(SPINNER_MARKER),
+        regex::escape(SPINNER_ORIGINAL_MARKER)
+    ))
+    .unwrap()
+});
+static CONTEXT_LOW_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#""Context low \(",([^,]+),"% remaining\) · Run /compact to compact & continue""#)
+        .unwrap()
+});
+static RETURN_NULL_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"if\([^)]+\)return null").unwrap());
+static STATUSLINE_EXECUTION_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
+    [
+        r"execSync\([^)]*statusLine",
+        r"spawn\([^)]*statusLine",
+        r"\.command\s*&&\s*execSync",
+    ]
+    .iter()
+    .map(|pattern| Regex::new(pattern).unwrap())
+    .collect()
+});
+static FUNCTION_END_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\}\s*(async|function|[A-Z])").unwrap());
+static VAR_DECLARATION_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"var\s+([a-zA-Z0-9_]+)\s*=").unwrap());
+static STATUSLINE_NA_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"async function ([a-zA-Z0-9_]+)\([^)]*\)\{[^}]*nA\(\)\?\.statusLine").unwrap()
+});
+static STATUSLINE_HOOK_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"async function ([a-zA-Z0-9_]+)\([^)]*\)\{[^}]{0,500}statusLine[^}]{0,500}Ye1")
+        .unwrap()
+});
+static STATUSLINE_NEAR_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"async function ([a-zA-Z0-9_]+)\([^)]*\)\{[^}]{0,200}statusLine").unwrap()
+});
+static ASYNC_FUNCTION_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"async function ([a-zA-Z0-9_]+)\(").unwrap());
+static TRY_CATCH_END_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"\}\}\);").unwrap());
+static VERSION_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
+    [
+        r"(?m)^// Version: (\d+\.\d+\.\d+\S*)",
+        r#"VERSION:"(\d+\.\d+\.\d+[^"]*)""#,
+    ]
+    .iter()
+    .map(|pattern| Regex::new(pattern).unwrap())
+    .collect()
+});
+
+const SPINNER_MARKER: &str = "/*uucode:spinner*/";
+const SPINNER_ORIGINAL_MARKER: &str = "/*uucode:was:";
+
+/// Stable strings the locators search for.
+///
+/// Defaults match current Claude Code releases. When a release renames one
+/// of them, `~/.claude/uucode/patch_anchors.toml` can override it for that
+/// version without a new uucode build:
+///
+/// ```toml
+/// [versions."2.1"]
+/// interrupt = '"to cancel"'
+/// ```
+///
+/// Keys are version prefixes; the longest matching prefix wins.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct PatchAnchors {
+    pub context_low: String,
+    /// Text that must appear between the function start and `context_low`
+    pub context_function_marker: String,
+    pub esc_key: String,
+    pub interrupt: String,
+    pub statusline: String,
+}
+
+impl Default for PatchAnchors {
+    fn default() -> Self {
+        Self {
+            context_low: "Context low (".to_string(),
+            context_function_marker: "tokenUsage:".to_string(),
+            esc_key: r#"{key:"esc"}"#.to_string(),
+            interrupt: r#""to interrupt""#.to_string(),
+            statusline: "statusLine".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct AnchorOverrides {
+    #[serde(default)]
+    versions: BTreeMap<String, toml::Table>,
+}
+
+impl PatchAnchors {
+    pub fn overrides_path() -> Option<PathBuf> {
+        crate::utils::paths::uucode_dir().map(|dir| dir.join("patch_anchors.toml"))
+    }
+
+    /// Defaults with the overrides for `version` applied
+    pub fn for_version(version: Option<&str>) -> Self {
+        let overrides = Self::overrides_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| toml::from_str::<AnchorOverrides>(&content).ok())
+            .unwrap_or_default();
+
+        let best = version.and_then(|version| {
+            overrides
+                .versions
+                .iter()
+                .filter(|(prefix, _)| version.starts_with(prefix.as_str()))
+                .max_by_key(|(prefix, _)| prefix.len())
+        });
+        let Some((prefix, table)) = best else {
+            return Self::default();
+        };
+
+        // Fields the table leaves out keep their defaults
+        match toml::Value::Table(table.clone()).try_into() {
+            Ok(anchors) => {
+                println!("Using anchor overrides for Claude Code {}", prefix);
+                anchors
+            }
+            Err(e) => {
+                println!("⚠️ Ignoring invalid anchor overrides for {}: {}", prefix, e);
+                Self::default()
+            }
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct LocationResult {
@@ -9,34 +155,122 @@ pub struct LocationResult {
     pub variable_name: Option<String>,
 }
 
+/// A pending replacement of `start..end` in the original file
+#[derive(Debug, Clone)]
+struct Edit {
+    start: usize,
+    end: usize,
+    replacement: String,
+}
+
+/// Patches Claude Code's cli.js.
+///
+/// The file is memory mapped and never modified in memory: patches only
+/// queue edits against the original offsets, and `save` streams the
+/// original with all edits applied into a new file that replaces it.
 #[derive(Debug)]
 pub struct ClaudeCodePatcher {
-    file_content: String,
-    file_path: String,
+    source: Mmap,
+    edits: Vec<Edit>,
+    file_path: PathBuf,
+    version: Option<String>,
+    anchors: PatchAnchors,
 }
 
 impl ClaudeCodePatcher {
     pub fn new<P: AsRef<Path>>(file_path: P) -> Result<Self, Box<dyn std::error::Error>> {
         let path = file_path.as_ref();
-        let content = fs::read_to_string(path)?;
+        let file = fs::File::open(path)?;
+        // SAFETY: the mapping is read-only and the patched file is written
+        // under a new name; Claude Code must not be updated while patching
+        let source = unsafe { Mmap::map(&file)? };
+        std::str::from_utf8(&source)?;
+
+        let mut patcher = Self {
+            source,
+            edits: Vec::new(),
+            file_path: path.to_path_buf(),
+            version: None,
+            anchors: PatchAnchors::default(),
+        };
+        patcher.version = patcher.detect_version();
+        patcher.anchors = PatchAnchors::for_version(patcher.version.as_deref());
+        Ok(patcher)
+    }
+
+    /// Claude Code version, from the package.json installed next to cli.js
+    /// or else from the version string embedded in cli.js
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
 
-        Ok(Self {
-            file_content: content,
-            file_path: path.to_string_lossy().to_string(),
+    fn detect_version(&self) -> Option<String> {
+        let from_package = self
+            .file_path
+            .parent()
+            .map(|dir| dir.join("package.json"))
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .filter(|package| {
+                package.get("name").and_then(|n| n.as_str()) == Some("@anthropic-ai/claude-code")
+            })
+            .and_then(|package| {
+                package
+                    .get("version")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+            });
+        if from_package.is_some() {
+            return from_package;
+        }
+
+        // The version header sits at the top of the bundle
+        let head = &self.content()[..self.content().len().min(64 * 1024)];
+        VERSION_PATTERNS.iter().find_map(|pattern| {
+            pattern
+                .captures(head)
+                .or_else(|| pattern.captures(self.content()))
+                .map(|captures| captures[1].to_string())
         })
     }
 
+    fn content(&self) -> &str {
+        // SAFETY: validated as UTF-8 in `new` and never modified since
+        unsafe { std::str::from_utf8_unchecked(&self.source) }
+    }
+
+    /// Queue the replacement of `start..end`, refusing overlapping edits
+    fn replace_range(
+        &mut self,
+        start: usize,
+        end: usize,
+        replacement: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let overlaps = |edit: &Edit| {
+            start < edit.end && edit.start < end || (start == end && start == edit.start)
+        };
+        if let Some(edit) = self.edits.iter().find(|edit| overlaps(edit)) {
+            return Err(format!(
+                "Edit at {}-{} overlaps an earlier patch at {}-{}",
+                start, end, edit.start, edit.end
+            )
+            .into());
+        }
+        self.edits.push(Edit {
+            start,
+            end,
+            replacement: replacement.to_string(),
+        });
+        Ok(())
+    }
+
     /// Find the verbose property location in Claude Code's cli.js
     /// Based on the pattern from patching.ts getVerbosePropertyLocation function
     pub fn get_verbose_property_location(&self) -> Option<LocationResult> {
         // Step 1: Find createElement pattern with spinnerTip and overrideMessage
-        let create_element_pattern =
-            Regex::new(r"createElement\([$\w]+,\{[^}]+spinnerTip[^}]+overrideMessage[^}]+\}")
-                .ok()?;
-
-        let create_element_match = create_element_pattern.find(&self.file_content)?;
+        let create_element_match = CREATE_ELEMENT_PATTERN.find(self.content())?;
         let extracted_string =
-            &self.file_content[create_element_match.start()..create_element_match.end()];
+            &self.content()[create_element_match.start()..create_element_match.end()];
 
         println!(
             "Found createElement match at: {}-{}",
@@ -49,8 +283,7 @@ impl ClaudeCodePatcher {
         );
 
         // Step 2: Find verbose property within the createElement match
-        let verbose_pattern = Regex::new(r"verbose:[^,}]+").ok()?;
-        let verbose_match = verbose_pattern.find(extracted_string)?;
+        let verbose_match = VERBOSE_PATTERN.find(extracted_string)?;
 
         println!(
             "Found verbose match at: {}-{}",
@@ -81,38 +314,77 @@ impl ClaudeCodePatcher {
 
         let new_code = format!("verbose:{}", value);
 
-        let new_content = format!(
-            "{}{}{}",
-            &self.file_content[..location.start_index],
-            new_code,
-            &self.file_content[location.end_index..]
-        );
-
         self.show_diff(&new_code, location.start_index, location.end_index);
-        self.file_content = new_content;
+        self.replace_range(location.start_index, location.end_index, &new_code)
+    }
+
+    /// Write the patched file next to the original and rename it into place,
+    /// so an interrupted save never leaves a half-written cli.js behind
+    pub fn save(mut self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.edits.is_empty() {
+            return Ok(());
+        }
+        self.edits.sort_by_key(|edit| edit.start);
+
+        let file_name = self
+            .file_path
+            .file_name()
+            .ok_or("Invalid cli.js path")?
+            .to_string_lossy()
+            .to_string();
+        let temp_path = self
+            .file_path
+            .with_file_name(format!(".{}.uucode-tmp", file_name));
+
+        let result = self.write_patched(&temp_path);
+        if let Err(e) = result {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e);
+        }
 
+        let permissions = fs::metadata(&self.file_path)?.permissions();
+        fs::set_permissions(&temp_path, permissions)?;
+        // Windows cannot replace a file that is still mapped
+        let file_path = self.file_path.clone();
+        drop(self);
+        fs::rename(&temp_path, &file_path)?;
         Ok(())
     }
 
-    /// Save the modified content back to file
-    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
-        fs::write(&self.file_path, &self.file_content)?;
+    /// Stream the original with all edits applied into `path`
+    fn write_patched(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let file = fs::File::create(path)?;
+        let mut writer = BufWriter::with_capacity(1 << 20, file);
+        let source = self.content().as_bytes();
+
+        let mut position = 0;
+        for edit in &self.edits {
+            writer.write_all(&source[position..edit.start])?;
+            writer.write_all(edit.replacement.as_bytes())?;
+            position = edit.end;
+        }
+        writer.write_all(&source[position..])?;
+
+        writer
+            .into_inner()
+            .map_err(|e| e.into_error())?
+            .sync_all()?;
         Ok(())
     }
 
-    /// Get a reference to the file content (for testing purposes)
+    /// The original file content; queued edits are applied on `save`
     pub fn get_file_content(&self) -> &str {
-        &self.file_content
+        self.content()
     }
 
     /// Show a diff of the changes (for debugging)
     fn show_diff(&self, injected_text: &str, start_index: usize, end_index: usize) {
         let context_start = start_index.saturating_sub(50);
-        let context_end_old = std::cmp::min(self.file_content.len(), end_index + 50);
+        let context_end_old = std::cmp::min(self.content().len(), end_index + 50);
 
-        let old_before = &self.file_content[context_start..start_index];
-        let old_changed = &self.file_content[start_index..end_index];
-        let old_after = &self.file_content[end_index..context_end_old];
+        let old_before = &self.content()[context_start..start_index];
+        let old_changed = &self.content()[start_index..end_index];
+        let old_after = &self.content()[end_index..context_end_old];
 
         println!("\n--- Verbose Property Diff ---");
         println!(
@@ -131,12 +403,7 @@ impl ClaudeCodePatcher {
     /// where B is a variable name
     pub fn get_context_low_message_location(&self) -> Option<LocationResult> {
         // Pattern to match: "Context low (",{variable},"% remaining) · Run /compact to compact & continue"
-        let context_low_pattern = Regex::new(
-            r#""Context low \(",([^,]+),"% remaining\) · Run /compact to compact & continue""#,
-        )
-        .ok()?;
-
-        let context_low_match = context_low_pattern.find(&self.file_content)?;
+        let context_low_match = CONTEXT_LOW_PATTERN.find(self.content())?;
 
         println!(
             "Found context low match at: {}-{}",
@@ -146,7 +413,7 @@ impl ClaudeCodePatcher {
         println!("Context low string: {}", context_low_match.as_str());
 
         // Extract the variable name from the capture group
-        let captures = context_low_pattern.captures(&self.file_content)?;
+        let captures = CONTEXT_LOW_PATTERN.captures(self.content())?;
         let variable_name = captures.get(1)?.as_str();
 
         println!("Variable name: {}", variable_name);
@@ -162,13 +429,12 @@ impl ClaudeCodePatcher {
     /// Uses stable text patterns to survive Claude Code version updates
     pub fn find_context_low_function_robust(&self) -> Option<LocationResult> {
         // Step 1: Locate stable anchor text that survives obfuscation
-        let primary_anchor = "Context low (";
-        let anchor_pos = self.file_content.find(primary_anchor)?;
+        let anchor_pos = self.content().find(&self.anchors.context_low)?;
 
         // Step 2: Search backward within reasonable range to find function declarations
         let search_range = 800; // Optimized range based on actual function size (~466 chars)
         let search_start = anchor_pos.saturating_sub(search_range);
-        let backward_text = &self.file_content[search_start..anchor_pos];
+        let backward_text = &self.content()[search_start..anchor_pos];
 
         // Find the function declaration that contains our anchor
         let mut function_candidates = Vec::new();
@@ -178,9 +444,9 @@ impl ClaudeCodePatcher {
             let absolute_func_pos = search_start + start + func_pos;
 
             // Check if this function contains the expected stable patterns
-            let func_to_anchor_text = &self.file_content[absolute_func_pos..anchor_pos + 100];
+            let func_to_anchor_text = &self.content()[absolute_func_pos..anchor_pos + 100];
 
-            if func_to_anchor_text.contains("tokenUsage:") {
+            if func_to_anchor_text.contains(&self.anchors.context_function_marker) {
                 function_candidates.push(absolute_func_pos);
                 println!("Found function candidate at: {}", absolute_func_pos);
             }
@@ -213,12 +479,10 @@ impl ClaudeCodePatcher {
         // Find the function using stable patterns
         let function_location = self.find_context_low_function_robust()?;
         let function_content =
-            &self.file_content[function_location.start_index..function_location.end_index];
+            &self.content()[function_location.start_index..function_location.end_index];
 
         // Look for if condition pattern using regex - match any condition that returns null
-        let if_pattern = Regex::new(r"if\([^)]+\)return null").ok()?;
-
-        if let Some(if_match) = if_pattern.find(function_content) {
+        if let Some(if_match) = RETURN_NULL_PATTERN.find(function_content) {
             let absolute_start = function_location.start_index + if_match.start();
             let absolute_end = function_location.start_index + if_match.end();
 
@@ -241,19 +505,16 @@ impl ClaudeCodePatcher {
         if let Some(location) = self.get_context_low_condition_location_robust() {
             let replacement_condition = "if(true)return null";
 
-            let new_content = format!(
-                "{}{}{}",
-                &self.file_content[..location.start_index],
-                replacement_condition,
-                &self.file_content[location.end_index..]
-            );
-
             self.show_diff(
                 replacement_condition,
                 location.start_index,
                 location.end_index,
             );
-            self.file_content = new_content;
+            self.replace_range(
+                location.start_index,
+                location.end_index,
+                replacement_condition,
+            )?;
 
             println!("✅ Context low warnings disabled successfully");
             Ok(())
@@ -279,52 +540,43 @@ impl ClaudeCodePatcher {
             new_message.split(',').nth(1).unwrap_or("")
         );
 
-        let new_content = format!(
-            "{}{}{}",
-            &self.file_content[..location.start_index],
-            new_code,
-            &self.file_content[location.end_index..]
-        );
-
         self.show_diff(&new_code, location.start_index, location.end_index);
-        self.file_content = new_content;
-
-        Ok(())
+        self.replace_range(location.start_index, location.end_index, &new_code)
     }
 
     /// Find the ternary condition for esc/interrupt display
     /// Pattern: ...CONDITION?[...{key:"esc"}...,"to interrupt"...]:[]
     /// Returns the position of CONDITION that needs to be replaced with (false)
     fn find_esc_interrupt_condition(&self) -> Option<LocationResult> {
-        let anchor1 = r#"{key:"esc"}"#;
-        let anchor2 = r#""to interrupt""#;
+        let anchor1 = self.anchors.esc_key.as_str();
+        let anchor2 = self.anchors.interrupt.as_str();
 
         let mut search_start = 0;
-        while let Some(anchor1_offset) = self.file_content[search_start..].find(anchor1) {
+        while let Some(anchor1_offset) = self.content()[search_start..].find(anchor1) {
             let anchor1_pos = search_start + anchor1_offset;
 
-            let search_window_end = (anchor1_pos + 200).min(self.file_content.len());
-            let window = &self.file_content[anchor1_pos..search_window_end];
+            let search_window_end = (anchor1_pos + 200).min(self.content().len());
+            let window = &self.content()[anchor1_pos..search_window_end];
 
             if window.contains(anchor2) {
                 println!(
-                    "Found both anchors: {{key:\"esc\"}} at {} and \"to interrupt\" nearby",
-                    anchor1_pos
+                    "Found both anchors: {} at {} and {} nearby",
+                    anchor1, anchor1_pos, anchor2
                 );
 
-                let before_anchor = &self.file_content[..anchor1_pos];
+                let before_anchor = &self.content()[..anchor1_pos];
                 if let Some(spread_offset) = before_anchor.rfind("...") {
                     let spread_pos = spread_offset;
                     println!("  Found spread operator at: {}", spread_pos);
 
-                    let between_spread_and_anchor = &self.file_content[spread_pos..anchor1_pos];
+                    let between_spread_and_anchor = &self.content()[spread_pos..anchor1_pos];
                     if let Some(question_offset) = between_spread_and_anchor.find('?') {
                         let question_pos = spread_pos + question_offset;
 
                         let condition_start = spread_pos + 3;
                         let condition_end = question_pos;
 
-                        let condition = &self.file_content[condition_start..condition_end];
+                        let condition = &self.content()[condition_start..condition_end];
                         println!(
                             "  Found condition '{}' at {}-{}",
                             condition.trim(),
@@ -366,13 +618,7 @@ impl ClaudeCodePatcher {
 
         self.show_diff("(false)", location.start_index, location.end_index);
 
-        let new_content = format!(
-            "{}(false){}",
-            &self.file_content[..location.start_index],
-            &self.file_content[location.end_index..]
-        );
-
-        self.file_content = new_content;
+        self.replace_range(location.start_index, location.end_index, "(false)")?;
         println!("✅ ESC interrupt display disabled successfully");
 
         Ok(())
@@ -383,55 +629,45 @@ impl ClaudeCodePatcher {
     fn find_statusline_execution_location(&self) -> Option<LocationResult> {
         // Look for patterns that indicate statusline execution
         // Pattern 1: execSync or spawn with statusline command
-        let patterns = vec![
-            r"execSync\([^)]*statusLine",
-            r"spawn\([^)]*statusLine",
-            r"\.command\s*&&\s*execSync",
-        ];
-
-        for pattern_str in patterns {
-            if let Ok(pattern) = Regex::new(pattern_str) {
-                if let Some(match_result) = pattern.find(&self.file_content) {
-                    println!("Found statusline execution pattern: {}", pattern_str);
-                    println!("Match: {}", match_result.as_str());
-
-                    return Some(LocationResult {
-                        start_index: match_result.start(),
-                        end_index: match_result.end(),
-                        variable_name: Some(match_result.as_str().to_string()),
-                    });
-                }
+        for pattern in STATUSLINE_EXECUTION_PATTERNS.iter() {
+            if let Some(match_result) = pattern.find(self.content()) {
+                println!("Found statusline execution pattern: {}", pattern.as_str());
+                println!("Match: {}", match_result.as_str());
+
+                return Some(LocationResult {
+                    start_index: match_result.start(),
+                    end_index: match_result.end(),
+                    variable_name: Some(match_result.as_str().to_string()),
+                });
             }
         }
 
         // Fallback: search for any function that contains "statusLine"
-        if let Some(statusline_pos) = self.file_content.find("statusLine") {
+        if let Some(statusline_pos) = self.content().find(&self.anchors.statusline) {
             println!("Found statusLine reference at position: {}", statusline_pos);
 
             // Find the async function definition before this reference
             let search_start = statusline_pos.saturating_sub(300);
-            let search_text = &self.file_content[search_start..statusline_pos];
+            let search_text = &self.content()[search_start..statusline_pos];
 
             if let Some(func_pos) = search_text.rfind("async function ") {
                 let absolute_func_pos = search_start + func_pos;
                 println!("Found async function at: {}", absolute_func_pos);
 
                 // Find the END of this function - look for pattern: }async or }function
-                let search_end = (statusline_pos + 3000).min(self.file_content.len());
-                let remaining_text = &self.file_content[statusline_pos..search_end];
+                let search_end = (statusline_pos + 3000).min(self.content().len());
+                let remaining_text = &self.content()[statusline_pos..search_end];
 
                 // Look for function end: } followed by 'async' or 'function' or capital letter
-                if let Ok(end_pattern) = Regex::new(r"\}\s*(async|function|[A-Z])") {
-                    if let Some(end_match) = end_pattern.find(remaining_text) {
-                        let injection_pos = statusline_pos + end_match.start() + 1; // After '}'
-                        println!("Found function end at position: {}", injection_pos);
+                if let Some(end_match) = FUNCTION_END_PATTERN.find(remaining_text) {
+                    let injection_pos = statusline_pos + end_match.start() + 1; // After '}'
+                    println!("Found function end at position: {}", injection_pos);
 
-                        return Some(LocationResult {
-                            start_index: absolute_func_pos,
-                            end_index: injection_pos,
-                            variable_name: Some("statusline_function_end".to_string()),
-                        });
-                    }
+                    return Some(LocationResult {
+                        start_index: absolute_func_pos,
+                        end_index: injection_pos,
+                        variable_name: Some("statusline_function_end".to_string()),
+                    });
                 }
             }
         }
@@ -445,25 +681,23 @@ impl ClaudeCodePatcher {
     fn extract_signal_handler_init_function(&self) -> Option<String> {
         // Strategy 1: Look for SIGINT followed by SIGTERM within reasonable distance
         // More lenient pattern to handle nested parentheses and different formatting
-        if let Some(sigint_pos) = self.file_content.find(r#"process.on("SIGINT""#) {
+        if let Some(sigint_pos) = self.content().find(r#"process.on("SIGINT""#) {
             // Search backward for variable declaration
             let search_start = sigint_pos.saturating_sub(500);
-            let before_text = &self.file_content[search_start..sigint_pos];
+            let before_text = &self.content()[search_start..sigint_pos];
 
             // Find the last variable declaration before SIGINT
-            if let Ok(var_pattern) = Regex::new(r"var\s+([a-zA-Z0-9_]+)\s*=") {
-                if let Some(captures_iter) = var_pattern.captures_iter(before_text).last() {
-                    let func_name = captures_iter.get(1)?.as_str();
-
-                    // Verify this is the right function by checking if SIGTERM appears nearby
-                    let check_end = (sigint_pos + 200).min(self.file_content.len());
-                    let check_text = &self.file_content[sigint_pos..check_end];
-
-                    if check_text.contains(r#"process.on("SIGTERM""#) {
-                        println!("🎯 Found signal handler init function: {}", func_name);
-                        println!("   Located via SIGINT/SIGTERM pattern");
-                        return Some(func_name.to_string());
-                    }
+            if let Some(captures_iter) = VAR_DECLARATION_PATTERN.captures_iter(before_text).last() {
+                let func_name = captures_iter.get(1)?.as_str();
+
+                // Verify this is the right function by checking if SIGTERM appears nearby
+                let check_end = (sigint_pos + 200).min(self.content().len());
+                let check_text = &self.content()[sigint_pos..check_end];
+
+                if check_text.contains(r#"process.on("SIGTERM""#) {
+                    println!("🎯 Found signal handler init function: {}", func_name);
+                    println!("   Located via SIGINT/SIGTERM pattern");
+                    return Some(func_name.to_string());
                 }
             }
         }
@@ -478,10 +712,7 @@ impl ClaudeCodePatcher {
     fn extract_statusline_function_name(&self, _from_pos: usize) -> Option<String> {
         // Strategy 1: Look for the exact pattern with nA()?.statusLine
         // This is the most specific pattern found in current versions
-        let specific_pattern =
-            Regex::new(r"async function ([a-zA-Z0-9_]+)\([^)]*\)\{[^}]*nA\(\)\?\.statusLine")
-                .ok()?;
-        if let Some(capture) = specific_pattern.captures(&self.file_content) {
+        if let Some(capture) = STATUSLINE_NA_PATTERN.captures(self.content()) {
             let func_name = capture.get(1)?.as_str();
             println!(
                 "🎯 Found statusline function (strategy 1 - nA pattern): {}",
@@ -491,11 +722,7 @@ impl ClaudeCodePatcher {
         }
 
         // Strategy 2: Look for function that contains both statusLine and Ye1 (hook executor)
-        let hook_pattern = Regex::new(
-            r"async function ([a-zA-Z0-9_]+)\([^)]*\)\{[^}]{0,500}statusLine[^}]{0,500}Ye1",
-        )
-        .ok()?;
-        if let Some(capture) = hook_pattern.captures(&self.file_content) {
+        if let Some(capture) = STATUSLINE_HOOK_PATTERN.captures(self.content()) {
             let func_name = capture.get(1)?.as_str();
             println!(
                 "🎯 Found statusline function (strategy 2 - hook pattern): {}",
@@ -505,9 +732,7 @@ impl ClaudeCodePatcher {
         }
 
         // Strategy 3: Original pattern - function with statusLine close to definition
-        let pattern =
-            Regex::new(r"async function ([a-zA-Z0-9_]+)\([^)]*\)\{[^}]{0,200}statusLine").ok()?;
-        if let Some(capture) = pattern.captures(&self.file_content) {
+        if let Some(capture) = STATUSLINE_NEAR_PATTERN.captures(self.content()) {
             let func_name = capture.get(1)?.as_str();
             println!(
                 "🎯 Found statusline function (strategy 3 - close proximity): {}",
@@ -517,24 +742,17 @@ impl ClaudeCodePatcher {
         }
 
         // Strategy 4: Broader search - last async function before statusLine reference
-        if let Some(statusline_pos) = self.file_content.find("statusLine") {
+        if let Some(statusline_pos) = self.content().find(&self.anchors.statusline) {
             let search_start = statusline_pos.saturating_sub(500); // Search further back
-            let search_text = &self.file_content[search_start..statusline_pos];
-
-            if let Ok(func_pattern) = Regex::new(r"async function ([a-zA-Z0-9_]+)\(") {
-                let mut last_match = None;
-                for capture in func_pattern.captures_iter(search_text) {
-                    last_match = Some(capture);
-                }
+            let search_text = &self.content()[search_start..statusline_pos];
 
-                if let Some(capture) = last_match {
-                    let func_name = capture.get(1)?.as_str();
-                    println!(
-                        "🎯 Found statusline function (strategy 4 - last async func): {}",
-                        func_name
-                    );
-                    return Some(func_name.to_string());
-                }
+            if let Some(capture) = ASYNC_FUNCTION_PATTERN.captures_iter(search_text).last() {
+                let func_name = capture.get(1)?.as_str();
+                println!(
+                    "🎯 Found statusline function (strategy 4 - last async func): {}",
+                    func_name
+                );
+                return Some(func_name.to_string());
             }
         }
 
@@ -549,9 +767,8 @@ impl ClaudeCodePatcher {
         interval_ms: u32,
     ) -> Result<(), Box<dyn std::error::Error>> {
         // Check if already patched
-        if self.file_content.contains("setInterval(function(){try{")
-            && (self.file_content.contains("VZA({})")
-                || self.file_content.contains("refreshStatusLine"))
+        if self.content().contains("setInterval(function(){try{")
+            && (self.content().contains("VZA({})") || self.content().contains("refreshStatusLine"))
         {
             println!("⚠️  Statusline auto-refresh already patched, skipping...");
             return Ok(());
@@ -566,13 +783,13 @@ impl ClaudeCodePatcher {
 
             // Now find where this function is called
             let call_pattern = format!("{}()", init_func_name);
-            if let Some(call_pos) = self.file_content.find(&call_pattern) {
+            if let Some(call_pos) = self.content().find(&call_pattern) {
                 println!("✅ Found {} call at position: {}", call_pattern, call_pos);
 
                 // Find the try-catch block that contains this call
                 // Look backward to find 'try{'
                 let search_back_start = call_pos.saturating_sub(500);
-                let before_text = &self.file_content[search_back_start..call_pos];
+                let before_text = &self.content()[search_back_start..call_pos];
 
                 if let Some(try_offset) = before_text.rfind("try{") {
                     let try_pos = search_back_start + try_offset;
@@ -581,22 +798,17 @@ impl ClaudeCodePatcher {
                     // Now find the end of the try-catch block
                     // Look for pattern: }catch(...){...}
                     let search_forward_start = call_pos;
-                    let remaining = &self.file_content[search_forward_start..];
+                    let remaining = &self.content()[search_forward_start..];
 
                     // Find the matching closing brace for the try-catch
                     // Look for "}});" pattern which typically ends the function
-                    if let Ok(end_pattern) = Regex::new(r"\}\}\);") {
-                        if let Some(end_match) = end_pattern.find(remaining) {
-                            let pos = search_forward_start + end_match.end();
-                            println!("✅ Injecting after try-catch block at position: {}", pos);
-                            pos
-                        } else {
-                            println!("⚠️  Could not find try-catch end, using fallback");
-                            return Err("Could not find try-catch block end".into());
-                        }
+                    if let Some(end_match) = TRY_CATCH_END_PATTERN.find(remaining) {
+                        let pos = search_forward_start + end_match.end();
+                        println!("✅ Injecting after try-catch block at position: {}", pos);
+                        pos
                     } else {
-                        println!("⚠️  Regex error, using fallback");
-                        return Err("Regex compilation failed".into());
+                        println!("⚠️  Could not find try-catch end, using fallback");
+                        return Err("Could not find try-catch block end".into());
                     }
                 } else {
                     println!("⚠️  Could not find try block, using fallback");
@@ -622,9 +834,9 @@ impl ClaudeCodePatcher {
             let mut injection_point = None;
 
             for pattern in init_patterns {
-                if let Some(pos) = self.file_content.rfind(pattern) {
+                if let Some(pos) = self.content().rfind(pattern) {
                     let search_start = pos + pattern.len();
-                    let remaining = &self.file_content[search_start..];
+                    let remaining = &self.content()[search_start..];
 
                     if let Some(semicolon_offset) = remaining.find(';') {
                         injection_point = Some(search_start + semicolon_offset + 1);
@@ -666,28 +878,101 @@ impl ClaudeCodePatcher {
 
         // Show context around injection point
         let context_start = injection_pos.saturating_sub(100);
-        let context_end = (injection_pos + 100).min(self.file_content.len());
+        let context_end = (injection_pos + 100).min(self.content().len());
 
         println!("\n--- Injection Context ---");
-        println!(
-            "BEFORE: {}",
-            &self.file_content[context_start..injection_pos]
-        );
+        println!("BEFORE: {}", &self.content()[context_start..injection_pos]);
         println!(">>> INJECT: \x1b[32m{}\x1b[0m", refresh_code);
-        println!("AFTER: {}", &self.file_content[injection_pos..context_end]);
+        println!("AFTER: {}", &self.content()[injection_pos..context_end]);
         println!("--- End Context ---\n");
 
         // Inject the code
-        let new_content = format!(
-            "{}{}{}",
-            &self.file_content[..injection_pos],
-            refresh_code,
-            &self.file_content[injection_pos..]
-        );
-
-        self.file_content = new_content;
+        self.replace_range(injection_pos, injection_pos, &refresh_code)?;
         println!("✅ Statusline auto-refresh interval added successfully");
 
         Ok(())
     }
+
+    /// Show the configured message or reminders as the spinner tip. An
+    /// earlier spinner patch is replaced; `remove_spinner_tip` undoes it.
+    pub fn customize_spinner_tip(
+        &mut self,
+        config: &SpinnerPatchConfig,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if !config.is_enabled() {
+            return Err("[patcher.spinner] has no message or reminders".into());
+        }
+
+        let (start, end, original) = match SPINNER_PATCH_PATTERN.captures(self.content()) {
+            Some(captures) => {
+                let whole = captures.get(0).ok_or("Invalid spinner patch")?;
+                println!("Replacing the spinner tip patched earlier");
+                (whole.start(), whole.end(), captures[1].to_string())
+            }
+            None => {
+                let create_element = CREATE_ELEMENT_PATTERN
+                    .find(self.content())
+                    .ok_or("Failed to find the spinner createElement call")?;
+                let captures = SPINNER_TIP_PATTERN
+                    .captures(create_element.as_str())
+                    .ok_or("Failed to find spinnerTip")?;
+                let value = captures.get(1).ok_or("Failed to find spinnerTip")?;
+                (
+                    create_element.start() + value.start(),
+                    create_element.start() + value.end(),
+                    value.as_str().to_string(),
+                )
+            }
+        };
+
+        let new_code = format!(
+            "{}{}{}{}*/",
+            SPINNER_MARKER,
+            spinner_tip_expression(config, &original),
+            SPINNER_ORIGINAL_MARKER,
+            original
+        );
+        self.show_diff(&new_code, start, end);
+        self.replace_range(start, end, &new_code)
+    }
+
+    /// Put back the spinner tip replaced by `customize_spinner_tip`
+    pub fn remove_spinner_tip(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let captures = SPINNER_PATCH_PATTERN
+            .captures(self.content())
+            .ok_or("The spinner tip is not patched")?;
+        let whole = captures.get(0).ok_or("Invalid spinner patch")?;
+        let original = captures[1].to_string();
+        self.show_diff(&original, whole.start(), whole.end());
+        self.replace_range(whole.start(), whole.end(), &original)
+    }
+}
+
+/// JavaScript expression for the spinner tip, falling back to `original`
+fn spinner_tip_expression(config: &SpinnerPatchConfig, original: &str) -> String {
+    let projects: serde_json::Map<String, serde_json::Value> = config
+        .projects
+        .iter()
+        .filter(|(_, reminders)| !reminders.is_empty())
+        .map(|(dir, reminders)| {
+            let dir = crate::utils::paths::expand_home(Path::new(dir))
+                .to_string_lossy()
+                .trim_end_matches(['/', '\\'])
+                .to_string();
+            (dir, serde_json::json!(reminders))
+        })
+        .collect();
+    let fallback = match config.message.as_deref().filter(|m| !m.is_empty()) {
+        Some(message) => serde_json::json!(message).to_string(),
+        None => format!("({})", original),
+    };
+    // Longest project directory containing the working directory, else
+    // the global reminders; rotate through them by wall clock
+    format!(
+        "(function(){{var p={},c=process.cwd(),k=Object.keys(p).filter(function(k){{return c===k||c.indexOf(k+\"/\")===0||c.indexOf(k+\"\\\\\")===0}}).sort(function(a,b){{return b.length-a.length}})[0],r=k?p[k]:{};return r.length?r[Math.floor(Date.now()/{})%r.length]:{}}})()",
+        serde_json::Value::Object(projects),
+        serde_json::json!(config.reminders),
+        config.interval_secs.max(1) * 1000,
+        fallback
+    )
 }
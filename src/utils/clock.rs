@@ -0,0 +1,77 @@
+//! Wall clock for rendering, frozen by `--deterministic`.
+//!
+//! Countdowns, time windows and the focus schedule read the time through
+//! here. In deterministic mode (`--deterministic` or `UUCODE_DETERMINISTIC=1`)
+//! the clock stands still at the newest transcript entry of the payload,
+//! or at [`EPOCH`] without one, and local time is UTC, so the same payload
+//! and cached data always render the same line: for documentation
+//! screenshots and golden tests.
+
+use chrono::{DateTime, FixedOffset, Local, Utc};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Frozen time for payloads without a transcript: 2025-01-01T00:00:00Z
+pub const EPOCH: i64 = 1_735_689_600;
+
+static FORCED: AtomicBool = AtomicBool::new(false);
+static FROZEN: Mutex<Option<DateTime<Utc>>> = Mutex::new(None);
+
+/// Turn on deterministic mode for the rest of the process
+pub fn enable() {
+    FORCED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_deterministic() -> bool {
+    FORCED.load(Ordering::Relaxed)
+        || std::env::var("UUCODE_DETERMINISTIC").is_ok_and(|v| !v.is_empty() && v != "0")
+}
+
+/// In deterministic mode, stop the clock at the time of `input`
+pub fn freeze(input: &crate::config::InputData) {
+    if !is_deterministic() {
+        return;
+    }
+    let at = crate::core::transcript::last_timestamp(Path::new(&input.transcript_path))
+        .map(|t| t.to_utc())
+        .unwrap_or_else(epoch);
+    if let Ok(mut frozen) = FROZEN.lock() {
+        *frozen = Some(at);
+    }
+}
+
+fn epoch() -> DateTime<Utc> {
+    DateTime::from_timestamp(EPOCH, 0).unwrap_or_default()
+}
+
+/// Current time, or the frozen time in deterministic mode
+pub fn now() -> DateTime<Utc> {
+    if !is_deterministic() {
+        return Utc::now();
+    }
+    FROZEN
+        .lock()
+        .ok()
+        .and_then(|frozen| *frozen)
+        .unwrap_or_else(epoch)
+}
+
+/// [`now`] in the local time zone, UTC in deterministic mode
+pub fn local_now() -> DateTime<FixedOffset> {
+    to_local(now())
+}
+
+/// `time` in the local time zone, UTC in deterministic mode
+pub fn to_local<Tz: chrono::TimeZone>(time: DateTime<Tz>) -> DateTime<FixedOffset> {
+    if is_deterministic() {
+        time.to_utc().fixed_offset()
+    } else {
+        time.with_timezone(&Local).fixed_offset()
+    }
+}
+
+/// [`now`] as Unix seconds
+pub fn unix_now() -> i64 {
+    now().timestamp()
+}
@@ -0,0 +1,195 @@
+//! Local crash reports.
+//!
+//! A panic hook writes a plain-text report (version, OS, panic message,
+//! backtrace and a config summary without secrets) to
+//! `~/.claude/uucode/crashes` and prints one line on stderr pointing to it.
+//! Nothing is ever sent over the network; the report is meant to be
+//! attached to a bug report by hand.
+
+use crate::config::{Config, ConfigLoader};
+use std::backtrace::Backtrace;
+use std::fmt::Write as _;
+use std::fs;
+use std::panic::PanicHookInfo;
+use std::path::PathBuf;
+
+/// Reports kept; older ones are deleted when a new one is written
+const MAX_REPORTS: usize = 20;
+
+/// Crash report directory; under the cache in read-only mode
+pub fn crashes_dir() -> Option<PathBuf> {
+    if crate::utils::readonly::is_enabled() {
+        crate::utils::paths::cache_dir().map(|dir| dir.join("crashes"))
+    } else {
+        crate::utils::paths::uucode_dir().map(|dir| dir.join("crashes"))
+    }
+}
+
+/// Replace the default panic output with a crash report. With `--debug`
+/// the default message is printed as well.
+pub fn install_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // stdout closed early, e.g. `uucode --print | head`; not a crash
+        if panic_message(info).contains("Broken pipe") {
+            std::process::exit(0);
+        }
+        if crate::utils::debug::is_enabled() {
+            default_hook(info);
+        }
+        let report = build_report(info);
+        match write_report(&report) {
+            Some(path) => eprintln!(
+                "uucode crashed: {}. Crash report saved to {}",
+                panic_message(info),
+                path.display()
+            ),
+            None => eprintln!("uucode crashed: {}\n{}", panic_message(info), report),
+        }
+    }));
+}
+
+/// Saved reports, newest first
+pub fn list() -> Vec<PathBuf> {
+    let Some(entries) = crashes_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    // Names embed a sortable timestamp
+    reports.sort_by(|a, b| b.cmp(a));
+    reports
+}
+
+fn panic_message(info: &PanicHookInfo) -> String {
+    let payload = info.payload();
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+fn build_report(info: &PanicHookInfo) -> String {
+    let mut report = String::new();
+    let _ = writeln!(report, "uucode crash report");
+    let _ = writeln!(report, "version:  {}", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(report, "time:     {}", chrono::Local::now().to_rfc3339());
+    let _ = writeln!(
+        report,
+        "os:       {} {} ({})",
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        std::env::consts::FAMILY
+    );
+    let _ = writeln!(report, "command:  {}", command_line());
+    let _ = writeln!(
+        report,
+        "thread:   {}",
+        std::thread::current().name().unwrap_or("unnamed")
+    );
+    let _ = writeln!(report, "panic:    {}", panic_message(info));
+    if let Some(location) = info.location() {
+        let _ = writeln!(
+            report,
+            "location: {}:{}:{}",
+            location.file(),
+            location.line(),
+            location.column()
+        );
+    }
+
+    let _ = writeln!(report, "\n[config]");
+    report.push_str(&config_summary());
+
+    let _ = writeln!(report, "\n[backtrace]");
+    let _ = writeln!(report, "{}", Backtrace::force_capture());
+    report
+}
+
+/// Binary name, subcommand names and flag names. Positional arguments and
+/// flag values are replaced with `<redacted>`, since they can be cookies,
+/// tokens or paths (`uucode cubence set-cookie <cookie>`)
+fn command_line() -> String {
+    use clap::CommandFactory;
+
+    let mut args = std::env::args();
+    let program = args
+        .next()
+        .map(|arg| {
+            PathBuf::from(&arg)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or(arg)
+        })
+        .unwrap_or_default();
+
+    let mut command = crate::cli::Cli::command();
+    let mut words = vec![program];
+    for arg in args {
+        if arg.starts_with('-') && arg.len() > 1 {
+            words.push(match arg.split_once('=') {
+                Some((flag, _)) => format!("{}=<redacted>", flag),
+                None => arg,
+            });
+        } else if let Some(sub) = command.find_subcommand(&arg).cloned() {
+            words.push(arg);
+            command = sub;
+        } else {
+            words.push("<redacted>".to_string());
+        }
+    }
+    words.join(" ")
+}
+
+/// Shape of the config without option values, which may hold API keys,
+/// cookies or URLs with tokens
+fn config_summary() -> String {
+    let path = Config::get_config_path();
+    if !path.exists() {
+        return "no config.toml, defaults in use\n".to_string();
+    }
+    let config = match ConfigLoader::load_from_path(&path) {
+        Ok(config) => config,
+        Err(e) => return format!("config.toml does not parse: {}\n", e),
+    };
+
+    let mut summary = String::new();
+    let _ = writeln!(summary, "theme:     {}", config.theme);
+    let _ = writeln!(summary, "mode:      {:?}", config.style.mode);
+    let _ = writeln!(summary, "read_only: {}", config.read_only);
+    let _ = writeln!(
+        summary,
+        "perf:      auto_skip={} budget_ms={} deadline_ms={}",
+        config.perf.auto_skip, config.perf.budget_ms, config.perf.deadline_ms
+    );
+    let _ = writeln!(summary, "segments:");
+    for segment in &config.segments {
+        let mut keys: Vec<&str> = segment.options.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        let _ = writeln!(
+            summary,
+            "  {} {:?} options=[{}]",
+            if segment.enabled { "+" } else { "-" },
+            segment.id,
+            keys.join(", ")
+        );
+    }
+    summary
+}
+
+fn write_report(report: &str) -> Option<PathBuf> {
+    let dir = crashes_dir()?;
+    fs::create_dir_all(&dir).ok()?;
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S%.3f");
+    let path = dir.join(format!("crash-{}.txt", stamp));
+    crate::utils::atomic_write(&path, report).ok()?;
+
+    for old in list().into_iter().skip(MAX_REPORTS) {
+        let _ = fs::remove_file(old);
+    }
+    Some(path)
+}
@@ -1,5 +1,10 @@
+use super::subprocess::Subprocess;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::time::Duration;
+
+/// Keychain lookups that take longer give up
+const KEYCHAIN_TIMEOUT: Duration = Duration::from_secs(3);
 
 #[derive(Debug, Deserialize, Serialize)]
 struct OAuthCredentials {
@@ -29,11 +34,10 @@ pub fn get_oauth_token() -> Option<String> {
 }
 
 fn get_oauth_token_macos() -> Option<String> {
-    use std::process::Command;
-
     let user = std::env::var("USER").unwrap_or_else(|_| "user".to_string());
 
-    let output = Command::new("security")
+    // A locked keychain can wait for the user; don't hold up the render
+    let output = Subprocess::new("security")
         .args([
             "find-generic-password",
             "-a",
@@ -42,11 +46,12 @@ fn get_oauth_token_macos() -> Option<String> {
             "-s",
             "Claude Code-credentials",
         ])
-        .output();
+        .with_timeout(KEYCHAIN_TIMEOUT)
+        .run();
 
     match output {
-        Ok(output) if output.status.success() => {
-            let json_str = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(output) if output.success() => {
+            let json_str = output.stdout.trim().to_string();
             if !json_str.is_empty() {
                 if let Ok(creds_file) = serde_json::from_str::<CredentialsFile>(&json_str) {
                     return creds_file.claude_ai_oauth.map(|oauth| oauth.access_token);
@@ -75,6 +80,5 @@ fn get_oauth_token_file() -> Option<String> {
 }
 
 fn get_credentials_path() -> Option<PathBuf> {
-    let home = dirs::home_dir()?;
-    Some(home.join(".claude").join(".credentials.json"))
+    crate::utils::paths::claude_credentials_path()
 }
@@ -0,0 +1,27 @@
+//! Opt-in diagnostics for `--debug` / `UUCODE_DEBUG=1`.
+//!
+//! Messages go to stderr because Claude Code only reads the statusline from
+//! stdout.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turn on debug output for the rest of the process
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed) || std::env::var_os("UUCODE_DEBUG").is_some()
+}
+
+/// Print a debug message to stderr when debugging is enabled
+#[macro_export]
+macro_rules! debug_log {
+    ($($arg:tt)*) => {
+        if $crate::utils::debug::is_enabled() {
+            eprintln!("[uucode debug] {}", format_args!($($arg)*));
+        }
+    };
+}
@@ -0,0 +1,239 @@
+//! Verified downloads shared by `uucode segment install`, `config import`
+//! from a URL, `font install` and self-update.
+//!
+//! Every download is HTTPS-only and size-capped. On top of that a body can
+//! be checked against a SHA-256 published somewhere we already trust (the
+//! signed marketplace index, a release checksum list, the command line)
+//! and against a detached [minisign] signature (`<url>.minisig`) when a
+//! minisign public key is configured. Both the legacy and the prehashed
+//! (BLAKE2b-512) signature formats are accepted.
+//!
+//! [minisign]: https://jedisct1.github.io/minisign/
+
+use base64::Engine;
+use blake2::Blake2b512;
+use ed25519_dalek::{Signature, VerifyingKey};
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::time::Duration;
+
+/// Timeout for the small files (indexes, themes, scripts, signatures)
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Signatures are a few hundred bytes
+const MAX_SIGNATURE_BYTES: u64 = 4 * 1024;
+
+/// What a downloaded body must match before it is used
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Integrity<'a> {
+    /// Expected hex SHA-256 of the body
+    pub sha256: Option<&'a str>,
+    /// Minisign public key; `<url>.minisig` must verify against it
+    pub minisign_key: Option<&'a str>,
+}
+
+/// Download `url` over HTTPS, failing when the body exceeds `max_bytes`
+pub fn fetch(
+    url: &str,
+    max_bytes: u64,
+    timeout: Duration,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    if !url.starts_with("https://") {
+        return Err(format!("refusing non-HTTPS URL: {}", url).into());
+    }
+    let client = crate::api::http::client_for_url(url, timeout)?;
+    let response = client.get(url).send()?.error_for_status()?;
+    let mut body = Vec::new();
+    response.take(max_bytes + 1).read_to_end(&mut body)?;
+    if body.len() as u64 > max_bytes {
+        return Err(format!("{} is larger than {} bytes", url, max_bytes).into());
+    }
+    Ok(body)
+}
+
+/// Download `url` and check it against everything `integrity` asks for
+pub fn fetch_verified(
+    url: &str,
+    max_bytes: u64,
+    timeout: Duration,
+    integrity: &Integrity,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let body = fetch(url, max_bytes, timeout)?;
+    if let Some(expected) = integrity.sha256 {
+        verify_sha256(&body, expected).map_err(|e| format!("{}: {}", url, e))?;
+    }
+    if let Some(public_key) = integrity.minisign_key {
+        let signature = fetch(
+            &format!("{}.minisig", url),
+            MAX_SIGNATURE_BYTES,
+            DEFAULT_TIMEOUT,
+        )?;
+        verify_minisign(&body, &String::from_utf8_lossy(&signature), public_key)
+            .map_err(|e| format!("{}: {}", url, e))?;
+    }
+    Ok(body)
+}
+
+/// Lowercase hex SHA-256 digest
+///
+/// ```
+/// assert_eq!(
+///     mycode::utils::download::sha256_hex(b"abc"),
+///     "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+/// );
+/// ```
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Compare the SHA-256 of `body` with a hex digest, ignoring case
+///
+/// ```
+/// use mycode::utils::download::verify_sha256;
+///
+/// let digest = "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD";
+/// assert!(verify_sha256(b"abc", digest).is_ok());
+/// assert!(verify_sha256(b"abd", digest).is_err());
+/// ```
+pub fn verify_sha256(body: &[u8], expected: &str) -> Result<(), String> {
+    let actual = sha256_hex(body);
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        Ok(())
+    } else {
+        Err(format!(
+            "checksum mismatch: expected {}, got {}",
+            expected.trim(),
+            actual
+        ))
+    }
+}
+
+/// Digest listed for `file` in a `SHA-256.txt` / `sha256sum` style list
+///
+/// ```
+/// use mycode::utils::download::checksum_for;
+///
+/// let list = "0a1b  Hack.tar.xz\n\
+///             9f8e *JetBrainsMono.tar.xz\n";
+/// assert_eq!(checksum_for(list, "JetBrainsMono.tar.xz"), Some("9f8e"));
+/// assert_eq!(checksum_for(list, "Mono.tar.xz"), None);
+/// ```
+pub fn checksum_for<'a>(list: &'a str, file: &str) -> Option<&'a str> {
+    list.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let digest = parts.next()?;
+        let name = parts.next()?.trim_start_matches('*');
+        let name = name.strip_prefix("./").unwrap_or(name);
+        (name == file).then_some(digest)
+    })
+}
+
+/// Check a minisign signature file over `body`
+///
+/// `public_key` is either the `.pub` file or just its base64 line, as
+/// printed by `minisign -G`. The trusted comment is covered by the global
+/// signature and checked as well.
+///
+/// ```
+/// use base64::Engine;
+/// use ed25519_dalek::{Signer, SigningKey};
+/// use blake2::{Blake2b512, Digest};
+/// use mycode::utils::download::verify_minisign;
+///
+/// let b64 = base64::engine::general_purpose::STANDARD;
+/// let key = SigningKey::from_bytes(&[9; 32]);
+/// let key_id = [1, 2, 3, 4, 5, 6, 7, 8];
+/// let public = b64.encode([&b"Ed"[..], &key_id, key.verifying_key().as_bytes()].concat());
+///
+/// let body = b"[style]\nmode = \"nerd_font\"\n";
+/// let signature = key.sign(&Blake2b512::digest(body)).to_bytes();
+/// let comment = "timestamp:1760000000\tfile:theme.toml";
+/// let global = key.sign(&[&signature[..], comment.as_bytes()].concat()).to_bytes();
+/// let minisig = format!(
+///     "untrusted comment: signature from minisign secret key\n{}\ntrusted comment: {}\n{}\n",
+///     b64.encode([&b"ED"[..], &key_id, &signature].concat()),
+///     comment,
+///     b64.encode(global),
+/// );
+///
+/// assert!(verify_minisign(body, &minisig, &public).is_ok());
+/// assert!(verify_minisign(b"[style]\n", &minisig, &public).is_err());
+/// // A tampered trusted comment fails the global signature
+/// let tampered = minisig.replace("theme.toml", "other.toml");
+/// assert!(verify_minisign(body, &tampered, &public).is_err());
+/// ```
+///
+/// A prehashed signature made by minisign itself:
+///
+/// ```
+/// use mycode::utils::download::verify_minisign;
+///
+/// let public = "RWRcuC3cO/MWA/vlXPE8Som0k3PYwOb4euhPP5OX06uNoxxDHEJ8mX2V";
+/// let minisig = "untrusted comment: signature from minisign secret key\n\
+///     RURcuC3cO/MWA3sotx5xxxKk28xxShrjPO1dR15n75Ygm+u2mAlaTcbJ3AMKXe+O4Y+Zl60yBoShUXIaqnOEyvViPn3rUj8xtQI=\n\
+///     trusted comment: timestamp:1760000000\tfile:theme.toml\n\
+///     pOxB1Y53hz89rkw/+4tdk6GlXdIGS8cq0c7vQi1zFopvv7iLeYauSkzz96C9UWXkMiDGSWS0GqYSfdLqIIHaAw==\n";
+///
+/// assert!(verify_minisign(b"[style]\nmode = \"nerd_font\"\n", minisig, public).is_ok());
+/// assert!(verify_minisign(b"[style]\n", minisig, public).is_err());
+/// ```
+pub fn verify_minisign(body: &[u8], signature: &str, public_key: &str) -> Result<(), String> {
+    let engine = base64::engine::general_purpose::STANDARD;
+
+    let key_line = public_key
+        .lines()
+        .map(str::trim)
+        .rfind(|line| !line.is_empty() && !line.starts_with("untrusted comment:"))
+        .ok_or("empty minisign public key")?;
+    let key = engine
+        .decode(key_line)
+        .map_err(|_| "minisign public key is not valid base64")?;
+    if key.len() != 42 || &key[..2] != b"Ed" {
+        return Err("not a minisign public key".to_string());
+    }
+    let key_id = &key[2..10];
+    let verifying_key = VerifyingKey::from_bytes(key[10..].try_into().unwrap_or(&[0; 32]))
+        .map_err(|_| "invalid minisign public key")?;
+
+    let mut lines = signature.lines().map(|line| line.trim_end_matches('\r'));
+    let (Some(_untrusted), Some(sig_line), Some(comment_line), Some(global_line)) =
+        (lines.next(), lines.next(), lines.next(), lines.next())
+    else {
+        return Err("truncated minisign signature".to_string());
+    };
+    let sig = engine
+        .decode(sig_line.trim())
+        .map_err(|_| "minisign signature is not valid base64")?;
+    if sig.len() != 74 {
+        return Err("not a minisign signature".to_string());
+    }
+    if &sig[2..10] != key_id {
+        return Err("signed with a different minisign key".to_string());
+    }
+    let message = match &sig[..2] {
+        b"Ed" => body.to_vec(),
+        b"ED" => Blake2b512::digest(body).to_vec(),
+        _ => return Err("unsupported minisign signature algorithm".to_string()),
+    };
+    let signature_bytes = &sig[10..];
+    let bad_signature = || "signature does not match the minisign public key".to_string();
+    let detached = Signature::from_slice(signature_bytes).map_err(|_| bad_signature())?;
+    verifying_key
+        .verify_strict(&message, &detached)
+        .map_err(|_| bad_signature())?;
+
+    let comment = comment_line
+        .strip_prefix("trusted comment: ")
+        .ok_or("minisign signature has no trusted comment")?;
+    let global = engine
+        .decode(global_line.trim())
+        .ok()
+        .and_then(|bytes| Signature::from_slice(&bytes).ok())
+        .ok_or("invalid minisign global signature")?;
+    verifying_key
+        .verify_strict(&[signature_bytes, comment.as_bytes()].concat(), &global)
+        .map_err(|_| "trusted comment does not match its signature".to_string())
+}
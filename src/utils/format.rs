@@ -0,0 +1,156 @@
+//! Shared number and currency formatting.
+//!
+//! Every segment that prints an amount or a count goes through these helpers
+//! so that the `[format]` section of the config applies consistently.
+
+use crate::config::{Currency, FormatConfig};
+
+/// Format a plain number with the configured separators.
+///
+/// `decimals` is the segment's preferred precision; it is only used when
+/// `format.decimal_places` is not set.
+///
+/// ```
+/// use mycode::config::FormatConfig;
+/// use mycode::utils::format::number;
+///
+/// let fmt = FormatConfig {
+///     thousands_separator: ",".to_string(),
+///     ..FormatConfig::default()
+/// };
+/// assert_eq!(number(1234567.891, 2, &fmt), "1,234,567.89");
+/// assert_eq!(number(-1000.0, 0, &FormatConfig::default()), "-1000");
+/// ```
+pub fn number(value: f64, decimals: usize, format: &FormatConfig) -> String {
+    let decimals = format.decimal_places.unwrap_or(decimals);
+    let rendered = format!("{:.*}", decimals, value.abs());
+    let (int_part, frac_part) = match rendered.split_once('.') {
+        Some((i, f)) => (i, Some(f)),
+        None => (rendered.as_str(), None),
+    };
+
+    let mut out = String::new();
+    if value < 0.0 && rendered.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        out.push('-');
+    }
+    out.push_str(&group_thousands(int_part, &format.thousands_separator));
+    if let Some(frac) = frac_part {
+        out.push_str(&format.decimal_separator);
+        out.push_str(frac);
+    }
+    out
+}
+
+/// Format a USD amount in the configured display currency.
+///
+/// ```
+/// use mycode::config::{Currency, FormatConfig};
+/// use mycode::utils::format::money;
+///
+/// assert_eq!(money(10.378, 2, &FormatConfig::default()), "$10.38");
+///
+/// let cny = FormatConfig {
+///     currency: Currency::Cny,
+///     cny_rate: 7.0,
+///     ..FormatConfig::default()
+/// };
+/// assert_eq!(money(2.0, 2, &cny), "¥14.00");
+/// ```
+pub fn money(usd: f64, decimals: usize, format: &FormatConfig) -> String {
+    format!(
+        "{}{}",
+        currency_symbol(format),
+        number(convert_usd(usd, format), decimals, format)
+    )
+}
+
+/// Convert a USD amount into the configured display currency.
+pub fn convert_usd(usd: f64, format: &FormatConfig) -> f64 {
+    convert(usd, Currency::Usd, format.currency, format)
+}
+
+/// Convert between currencies using the configured USD -> CNY rate.
+///
+/// Lets mixed-vendor figures (uucode plans are priced in ¥, Cubence balances
+/// in $) be summed in a single currency.
+///
+/// ```
+/// use mycode::config::{Currency, FormatConfig};
+/// use mycode::utils::format::convert;
+///
+/// let fmt = FormatConfig {
+///     cny_rate: 8.0,
+///     ..FormatConfig::default()
+/// };
+/// assert_eq!(convert(80.0, Currency::Cny, Currency::Usd, &fmt), 10.0);
+/// assert_eq!(convert(10.0, Currency::Usd, Currency::Cny, &fmt), 80.0);
+/// ```
+pub fn convert(amount: f64, from: Currency, to: Currency, format: &FormatConfig) -> f64 {
+    match (from, to) {
+        (Currency::Usd, Currency::Cny) => amount * format.cny_rate,
+        (Currency::Cny, Currency::Usd) if format.cny_rate > 0.0 => amount / format.cny_rate,
+        _ => amount,
+    }
+}
+
+/// Symbol shown in front of amounts, honoring `currency_symbol` overrides.
+pub fn currency_symbol(format: &FormatConfig) -> String {
+    format
+        .currency_symbol
+        .clone()
+        .unwrap_or_else(|| format.currency.symbol().to_string())
+}
+
+/// Format a token count as money, where 1M tokens = $1 (Cubence convention).
+///
+/// ```
+/// use mycode::config::FormatConfig;
+/// use mycode::utils::format::tokens_as_money;
+///
+/// let fmt = FormatConfig::default();
+/// assert_eq!(tokens_as_money(18_400_000, &fmt), "$18.4");
+/// assert_eq!(tokens_as_money(250_000, &fmt), "$0.25");
+/// assert_eq!(tokens_as_money(900, &fmt), "$0.001");
+/// ```
+pub fn tokens_as_money(tokens: i64, format: &FormatConfig) -> String {
+    let decimals = if tokens >= 1_000_000 {
+        1
+    } else if tokens >= 1_000 {
+        2
+    } else {
+        3
+    };
+    money(tokens as f64 / 1_000_000.0, decimals, format)
+}
+
+/// Abbreviate a token count.
+///
+/// ```
+/// use mycode::utils::format::token_count;
+///
+/// assert_eq!(token_count(950), "950");
+/// assert_eq!(token_count(12_345), "12.3k");
+/// assert_eq!(token_count(4_560_000), "4.6M");
+/// ```
+pub fn token_count(tokens: u64) -> String {
+    match tokens {
+        0..=999 => tokens.to_string(),
+        1_000..=999_999 => format!("{:.1}k", tokens as f64 / 1_000.0),
+        _ => format!("{:.1}M", tokens as f64 / 1_000_000.0),
+    }
+}
+
+fn group_thousands(digits: &str, separator: &str) -> String {
+    if separator.is_empty() || digits.len() <= 3 {
+        return digits.to_string();
+    }
+
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3 * separator.len());
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push_str(separator);
+        }
+        out.push(c);
+    }
+    out
+}
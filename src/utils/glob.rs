@@ -0,0 +1,48 @@
+//! Path globs for config keys.
+//!
+//! `*` and `?` match within a single path component, `**` matches any
+//! number of components (including none). `/` and `\` both separate
+//! components, so patterns work unchanged on Windows paths.
+
+/// Whether `path` matches `pattern`
+///
+/// ```
+/// use mycode::utils::glob::matches;
+///
+/// assert!(matches("/home/me/work/**", "/home/me/work/api/src"));
+/// assert!(matches("/home/me/work/**", "/home/me/work"));
+/// assert!(matches("/home/*/oss/*", "/home/me/oss/tool"));
+/// assert!(!matches("/home/*/oss/*", "/home/me/oss/tool/src"));
+/// assert!(matches("C:\\src\\client-?", "C:/src/client-a"));
+/// ```
+pub fn matches(pattern: &str, path: &str) -> bool {
+    match_components(&components(pattern), &components(path))
+}
+
+fn components(path: &str) -> Vec<&str> {
+    path.split(['/', '\\']).filter(|c| !c.is_empty()).collect()
+}
+
+fn match_components(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| match_components(rest, &path[skip..])),
+        Some((first, rest)) => match path.split_first() {
+            Some((component, path_rest)) => {
+                let pattern: Vec<char> = first.chars().collect();
+                let name: Vec<char> = component.chars().collect();
+                match_component(&pattern, &name) && match_components(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+fn match_component(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|skip| match_component(rest, &name[skip..])),
+        Some(('?', rest)) => !name.is_empty() && match_component(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && match_component(rest, &name[1..]),
+    }
+}
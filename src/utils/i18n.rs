@@ -0,0 +1,170 @@
+//! Locale-dependent wording of durations.
+//!
+//! Remaining subscription days, reset countdowns and session lengths all go
+//! through these helpers so that `format.locale` switches them together.
+//! Chinese has no plural forms; English picks between singular and plural.
+
+use crate::config::Locale;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl Unit {
+    const SECONDS: [(Unit, u64); 4] = [
+        (Unit::Day, 86_400),
+        (Unit::Hour, 3_600),
+        (Unit::Minute, 60),
+        (Unit::Second, 1),
+    ];
+
+    /// Unit name after a count, e.g. `天` or `days`
+    fn name(self, n: u64, locale: Locale) -> &'static str {
+        match locale {
+            Locale::Zh => match self {
+                Unit::Day => "天",
+                Unit::Hour => "小时",
+                Unit::Minute => "分钟",
+                Unit::Second => "秒",
+            },
+            Locale::En => match self {
+                Unit::Day => plural(n, "day", "days"),
+                Unit::Hour => plural(n, "hour", "hours"),
+                Unit::Minute => plural(n, "minute", "minutes"),
+                Unit::Second => plural(n, "second", "seconds"),
+            },
+        }
+    }
+
+    /// Suffix in compact spans; Chinese spells out days only
+    fn suffix(self, locale: Locale) -> &'static str {
+        match (self, locale) {
+            (Unit::Day, Locale::Zh) => "天",
+            (Unit::Day, Locale::En) => "d",
+            (Unit::Hour, _) => "h",
+            (Unit::Minute, _) => "m",
+            (Unit::Second, _) => "s",
+        }
+    }
+}
+
+/// Fixed phrases shown in place of a duration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phrase {
+    /// A subscription that has ended
+    Expired,
+    /// A quota window that has ended
+    Due,
+    /// A reset that is already due but not yet reflected by the API
+    ResettingSoon,
+}
+
+impl Phrase {
+    pub fn text(self, locale: Locale) -> &'static str {
+        match (self, locale) {
+            (Phrase::Expired, Locale::Zh) => "已过期",
+            (Phrase::Expired, Locale::En) => "expired",
+            (Phrase::Due, Locale::Zh) => "已到期",
+            (Phrase::Due, Locale::En) => "due",
+            (Phrase::ResettingSoon, Locale::Zh) => "即将重置",
+            (Phrase::ResettingSoon, Locale::En) => "resetting soon",
+        }
+    }
+}
+
+/// English plural rule: only exactly one is singular
+///
+/// ```
+/// use mycode::utils::i18n::plural;
+///
+/// assert_eq!(plural(1, "day", "days"), "day");
+/// assert_eq!(plural(0, "day", "days"), "days");
+/// assert_eq!(plural(17, "day", "days"), "days");
+/// ```
+pub fn plural<'a>(n: u64, one: &'a str, other: &'a str) -> &'a str {
+    if n == 1 {
+        one
+    } else {
+        other
+    }
+}
+
+/// A count with its unit spelled out
+///
+/// ```
+/// use mycode::config::Locale;
+/// use mycode::utils::i18n::{count, Unit};
+///
+/// assert_eq!(count(17, Unit::Day, Locale::Zh), "17天");
+/// assert_eq!(count(1, Unit::Day, Locale::En), "1 day");
+/// assert_eq!(count(3, Unit::Hour, Locale::En), "3 hours");
+/// ```
+pub fn count(n: u64, unit: Unit, locale: Locale) -> String {
+    match locale {
+        Locale::Zh => format!("{}{}", n, unit.name(n, locale)),
+        Locale::En => format!("{} {}", n, unit.name(n, locale)),
+    }
+}
+
+/// Remaining whole days, e.g. `剩余17天` / `17 days left`
+///
+/// ```
+/// use mycode::config::Locale;
+/// use mycode::utils::i18n::days_left;
+///
+/// assert_eq!(days_left(17, Locale::Zh), "剩余17天");
+/// assert_eq!(days_left(17, Locale::En), "17 days left");
+/// assert_eq!(days_left(1, Locale::En), "1 day left");
+/// ```
+pub fn days_left(days: u64, locale: Locale) -> String {
+    left(&count(days, Unit::Day, locale), locale)
+}
+
+/// Wrap an already formatted amount as what remains, e.g. `剩余5天12h`
+pub fn left(amount: &str, locale: Locale) -> String {
+    match locale {
+        Locale::Zh => format!("剩余{}", amount),
+        Locale::En => format!("{} left", amount),
+    }
+}
+
+/// Compact span made of its two largest units, dropping a trailing zero
+///
+/// ```
+/// use mycode::config::Locale;
+/// use mycode::utils::i18n::span;
+///
+/// assert_eq!(span(3 * 86_400 + 5 * 3_600 + 59, Locale::Zh), "3天5h");
+/// assert_eq!(span(3 * 86_400 + 5 * 3_600, Locale::En), "3d5h");
+/// assert_eq!(span(5 * 3_600 + 20 * 60, Locale::Zh), "5h20m");
+/// assert_eq!(span(2 * 3_600, Locale::En), "2h");
+/// assert_eq!(span(90, Locale::En), "1m30s");
+/// assert_eq!(span(0, Locale::Zh), "0s");
+/// ```
+pub fn span(secs: u64, locale: Locale) -> String {
+    let Some(index) = Unit::SECONDS.iter().position(|(_, size)| secs >= *size) else {
+        return format!("0{}", Unit::Second.suffix(locale));
+    };
+    let (unit, size) = Unit::SECONDS[index];
+    let mut text = format!("{}{}", secs / size, unit.suffix(locale));
+    if let Some(&(next, next_size)) = Unit::SECONDS.get(index + 1) {
+        let rest = secs % size / next_size;
+        if rest > 0 {
+            text.push_str(&format!("{}{}", rest, next.suffix(locale)));
+        }
+    }
+    text
+}
+
+/// Countdown to a reset: the span until then, or `phrase` once it is due
+pub fn countdown(secs: i64, phrase: Phrase, locale: Locale) -> String {
+    if secs <= 0 {
+        phrase.text(locale).to_string()
+    } else {
+        span(secs as u64, locale)
+    }
+}
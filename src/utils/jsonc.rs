@@ -0,0 +1,93 @@
+//! Lenient JSON parsing for hand-edited files such as Claude's settings.json:
+//! `//` and `/* */` comments and trailing commas are accepted.
+
+/// Remove comments and trailing commas, leaving string contents untouched.
+///
+/// ```
+/// use mycode::utils::jsonc::strip;
+///
+/// let src = "{\n  // base url\n  \"a\": \"http://x\", /* note */\n  \"b\": [1, 2,],\n}";
+/// let value: serde_json::Value = serde_json::from_str(&strip(src)).unwrap();
+/// assert_eq!(value["a"], "http://x");
+/// assert_eq!(value["b"][1], 2);
+/// ```
+pub fn strip(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(ch) = chars.next() {
+        if in_string {
+            out.push(ch);
+            match ch {
+                '\\' => {
+                    if let Some(next) = chars.next() {
+                        out.push(next);
+                    }
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match ch {
+            '"' => {
+                in_string = true;
+                out.push(ch);
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = '\0';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    // Keep line numbers stable for error messages
+                    if c == '\n' {
+                        out.push('\n');
+                    }
+                    prev = c;
+                }
+            }
+            ']' | '}' => {
+                // Drop a trailing comma before the closing bracket
+                let trimmed_len = out.trim_end().len();
+                if out[..trimmed_len].ends_with(',') {
+                    out.remove(trimmed_len - 1);
+                }
+                out.push(ch);
+            }
+            _ => out.push(ch),
+        }
+    }
+
+    out
+}
+
+/// Parse JSON with comments / trailing commas
+pub fn parse(input: &str) -> Result<serde_json::Value, serde_json::Error> {
+    serde_json::from_str(&strip(input))
+}
+
+/// Whether `input` uses comments or trailing commas, which a round trip
+/// through [`parse`] and `serde_json` would drop.
+///
+/// ```
+/// use mycode::utils::jsonc::has_extensions;
+///
+/// assert!(has_extensions("{\n  // base url\n  \"a\": 1\n}"));
+/// assert!(has_extensions("{\"a\": [1, 2,]}"));
+/// assert!(!has_extensions("{\"url\": \"http://x/*y*/\"}"));
+/// ```
+pub fn has_extensions(input: &str) -> bool {
+    strip(input) != input
+}
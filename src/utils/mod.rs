@@ -1,4 +1,25 @@
+pub mod ansi;
+pub mod atomic;
+#[cfg(feature = "patcher")]
 pub mod claude_code_patcher;
+pub mod clock;
+pub mod crash;
 pub mod credentials;
+pub mod debug;
+pub mod download;
+pub mod format;
+pub mod glob;
+pub mod i18n;
+pub mod jsonc;
+#[cfg(feature = "patcher")]
+pub mod patch_ledger;
+pub mod paths;
+pub mod prompt;
+pub mod readonly;
+pub mod subprocess;
+pub mod trust;
+pub mod wsl;
 
-pub use claude_code_patcher::{ClaudeCodePatcher, LocationResult};
+pub use atomic::write as atomic_write;
+#[cfg(feature = "patcher")]
+pub use claude_code_patcher::{ClaudeCodePatcher, LocationResult, PatchAnchors};
@@ -0,0 +1,93 @@
+//! Record of every `--patch` run (`~/.claude/uucode/patch_ledger.jsonl`).
+//!
+//! Keeping the Claude Code version next to the outcome of each patch tells
+//! which release broke a locator and which patches still apply to it.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatchOutcome {
+    pub name: String,
+    pub applied: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatchRun {
+    /// Unix timestamp in seconds
+    pub timestamp: u64,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    pub patches: Vec<PatchOutcome>,
+}
+
+pub fn ledger_path() -> Option<PathBuf> {
+    crate::utils::paths::uucode_dir().map(|dir| dir.join("patch_ledger.jsonl"))
+}
+
+impl PatchRun {
+    pub fn new(path: &str, version: Option<&str>) -> Self {
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            path: path.to_string(),
+            version: version.map(str::to_string),
+            patches: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, name: &str, result: &Result<(), Box<dyn std::error::Error>>) {
+        self.patches.push(PatchOutcome {
+            name: name.to_string(),
+            applied: result.is_ok(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+        });
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = &PatchOutcome> {
+        self.patches.iter().filter(|p| !p.applied)
+    }
+
+    pub fn any_applied(&self) -> bool {
+        self.patches.iter().any(|p| p.applied)
+    }
+
+    /// Append this run to the ledger
+    pub fn append(&self) -> Result<(), Box<dyn std::error::Error>> {
+        crate::utils::readonly::ensure_writable("updating the patch ledger")?;
+        let path = ledger_path().ok_or("Could not find home directory")?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+/// All recorded runs, oldest first
+pub fn load() -> Vec<PatchRun> {
+    let Some(content) = ledger_path().and_then(|path| fs::read_to_string(path).ok()) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Most recent Claude Code version on which `patch` applied
+pub fn last_working_version(runs: &[PatchRun], patch: &str) -> Option<String> {
+    runs.iter()
+        .rev()
+        .filter(|run| run.patches.iter().any(|p| p.name == patch && p.applied))
+        .find_map(|run| run.version.clone())
+}
@@ -0,0 +1,94 @@
+//! Well-known file locations.
+//!
+//! Claude Code's own files (settings.json, .credentials.json) live in the
+//! Claude config dir, which honors `CLAUDE_CONFIG_DIR` and the
+//! `claude_config_dir` key in config.toml. uucode's own files always live in
+//! `~/.claude/uucode` and `~/.claude/mycode` so every Claude profile shares
+//! them. Across WSL and Windows the Claude config dir goes through the
+//! [`wsl`](super::wsl) bridge.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Resolved Claude config dir, `None` until first asked for
+static CLAUDE_DIR: Mutex<Option<Option<PathBuf>>> = Mutex::new(None);
+
+/// Directory holding Claude Code's settings.json
+pub fn claude_dir() -> Option<PathBuf> {
+    match CLAUDE_DIR.lock() {
+        Ok(mut cached) => cached.get_or_insert_with(resolve_claude_dir).clone(),
+        Err(_) => resolve_claude_dir(),
+    }
+}
+
+/// Forget the resolved Claude config dir, so the next [`claude_dir`] picks
+/// up a changed `CLAUDE_CONFIG_DIR` or `claude_config_dir`. For long-lived
+/// hosts such as the FFI library; a statusline render resolves it once.
+pub fn reload() {
+    if let Ok(mut cached) = CLAUDE_DIR.lock() {
+        *cached = None;
+    }
+}
+
+fn resolve_claude_dir() -> Option<PathBuf> {
+    let wsl = super::wsl::config();
+    std::env::var_os("CLAUDE_CONFIG_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(config_override)
+        .map(|dir| super::wsl::translate(&expand_home(&dir), &wsl))
+        .or_else(|| {
+            let dir = dirs::home_dir()?.join(".claude");
+            Some(super::wsl::bridged_claude_dir(&dir, &wsl).unwrap_or(dir))
+        })
+}
+
+/// Claude Code settings.json
+pub fn claude_settings_path() -> Option<PathBuf> {
+    claude_dir().map(|dir| dir.join("settings.json"))
+}
+
+/// Claude Code OAuth credentials file
+pub fn claude_credentials_path() -> Option<PathBuf> {
+    claude_dir().map(|dir| dir.join(".credentials.json"))
+}
+
+/// uucode config directory (~/.claude/uucode)
+pub fn uucode_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".claude").join("uucode"))
+}
+
+/// Community segment scripts installed by `uucode segment` (~/.claude/uucode/plugins)
+pub fn plugins_dir() -> Option<PathBuf> {
+    uucode_dir().map(|dir| dir.join("plugins"))
+}
+
+/// uucode cache directory (~/.claude/mycode/cache)
+pub fn cache_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".claude").join("mycode").join("cache"))
+}
+
+/// Top-level config.toml key, read without loading the full config
+pub(crate) fn raw_config_value(key: &str) -> Option<toml::Value> {
+    let content = std::fs::read_to_string(uucode_dir()?.join("config.toml")).ok()?;
+    let mut value: toml::Value = toml::from_str(&content).ok()?;
+    value.as_table_mut()?.remove(key)
+}
+
+/// `claude_config_dir` from config.toml
+fn config_override() -> Option<PathBuf> {
+    raw_config_value("claude_config_dir")?
+        .as_str()
+        .filter(|s| !s.is_empty())
+        .map(PathBuf::from)
+}
+
+/// `path` with a leading `~` replaced by the home directory
+pub(crate) fn expand_home(path: &std::path::Path) -> PathBuf {
+    match path.strip_prefix("~") {
+        Ok(rest) => dirs::home_dir()
+            .map(|home| home.join(rest))
+            .unwrap_or_else(|| path.to_path_buf()),
+        Err(_) => path.to_path_buf(),
+    }
+}
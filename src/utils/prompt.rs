@@ -0,0 +1,87 @@
+//! Non-interactive mode for scripts and CI.
+//!
+//! `--non-interactive` or `UUCODE_NON_INTERACTIVE=1` answers every prompt
+//! with its safe default (no), `--yes` or `UUCODE_NON_INTERACTIVE=yes`
+//! answers yes. Either way nothing waits on stdin.
+
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+/// How prompts are answered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    /// Ask on stdin
+    Ask,
+    /// Take the safe default without asking
+    Default,
+    /// Answer yes without asking
+    Yes,
+}
+
+static FORCED: AtomicU8 = AtomicU8::new(0);
+static CONFIGURED: OnceLock<Answer> = OnceLock::new();
+
+/// Answer every prompt with its safe default for the rest of the process
+pub fn non_interactive() {
+    FORCED.fetch_max(1, Ordering::Relaxed);
+}
+
+/// Answer yes to every prompt for the rest of the process
+pub fn assume_yes() {
+    FORCED.store(2, Ordering::Relaxed);
+}
+
+/// Parse `UUCODE_NON_INTERACTIVE`
+///
+/// ```
+/// use mycode::utils::prompt::{parse_env, Answer};
+///
+/// assert_eq!(parse_env("1"), Answer::Default);
+/// assert_eq!(parse_env("yes"), Answer::Yes);
+/// assert_eq!(parse_env("0"), Answer::Ask);
+/// ```
+pub fn parse_env(value: &str) -> Answer {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "" | "0" | "false" => Answer::Ask,
+        "yes" | "y" => Answer::Yes,
+        _ => Answer::Default,
+    }
+}
+
+pub fn answer() -> Answer {
+    match FORCED.load(Ordering::Relaxed) {
+        2 => Answer::Yes,
+        1 => Answer::Default,
+        _ => *CONFIGURED.get_or_init(|| {
+            std::env::var("UUCODE_NON_INTERACTIVE")
+                .map(|v| parse_env(&v))
+                .unwrap_or(Answer::Ask)
+        }),
+    }
+}
+
+pub fn is_interactive() -> bool {
+    answer() == Answer::Ask
+}
+
+/// Ask a yes/no question that defaults to no; only "y" counts as yes
+pub fn confirm(prompt: &str) -> io::Result<bool> {
+    print!("{}", prompt);
+    match answer() {
+        Answer::Yes => {
+            println!("y（自动确认）");
+            Ok(true)
+        }
+        Answer::Default => {
+            println!("n（非交互模式）");
+            Ok(false)
+        }
+        Answer::Ask => {
+            io::stdout().flush()?;
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+            Ok(input.trim().eq_ignore_ascii_case("y"))
+        }
+    }
+}
@@ -0,0 +1,52 @@
+//! Read-only mode for shared or locked-down machines.
+//!
+//! Turned on by `--no-write`, `read_only = true` in config.toml or
+//! `UUCODE_NO_WRITE=1`. Only the cache directory stays writable: commands
+//! that would edit settings.json, config.toml, themes or cli.js print what
+//! to change by hand instead, and background writes outside the cache are
+//! skipped.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+static FORCED: AtomicBool = AtomicBool::new(false);
+static CONFIGURED: OnceLock<bool> = OnceLock::new();
+
+/// Turn on read-only mode for the rest of the process
+pub fn enable() {
+    FORCED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    FORCED.load(Ordering::Relaxed)
+        || *CONFIGURED.get_or_init(|| {
+            std::env::var("UUCODE_NO_WRITE").is_ok_and(|v| !v.is_empty() && v != "0")
+                || crate::utils::paths::raw_config_value("read_only")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false)
+        })
+}
+
+/// In read-only mode, print that `action` was skipped followed by
+/// `instructions` for doing it by hand, and return true so the caller
+/// skips the write
+pub fn intercept(action: &str, instructions: impl FnOnce() -> String) -> bool {
+    if !is_enabled() {
+        return false;
+    }
+    println!("🔒 只读模式：已跳过{}", action);
+    let instructions = instructions();
+    if !instructions.is_empty() {
+        println!("{}", instructions);
+    }
+    true
+}
+
+/// Error for writes refused in read-only mode where printing is not an
+/// option (TUI, background work)
+pub fn ensure_writable(action: &str) -> Result<(), Box<dyn std::error::Error>> {
+    if is_enabled() {
+        return Err(format!("read-only mode, {} skipped", action).into());
+    }
+    Ok(())
+}
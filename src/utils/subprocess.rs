@@ -0,0 +1,266 @@
+//! Shared runner for the external commands segments depend on.
+//!
+//! Every segment that shells out (git, custom commands, version lookups)
+//! goes through [`Subprocess`] so a hung or slow tool cannot stall the
+//! statusline: each run has a hard wall-clock timeout, after which the
+//! whole process group is killed, and stderr is captured instead of leaking
+//! into Claude Code's terminal.
+//!
+//! On Unix a run can also be sandboxed: the environment is cleared down to
+//! a few harmless variables (no API keys or tokens), and on Linux the
+//! command is put in a fresh network namespace with `unshare` when
+//! unprivileged user namespaces are available, so it has no network.
+
+use std::ffi::{OsStr, OsString};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// Timeout used when the caller does not set one
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Variables kept in a sandboxed environment
+#[cfg(unix)]
+const SANDBOX_ENV: &[&str] = &["PATH", "HOME", "USER", "LANG", "LC_ALL", "TERM", "TMPDIR"];
+
+#[derive(Debug)]
+pub struct Output {
+    pub status: ExitStatus,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl Output {
+    pub fn success(&self) -> bool {
+        self.status.success()
+    }
+
+    /// Stdout without surrounding whitespace, `None` if the command failed
+    /// or printed nothing
+    pub fn stdout_trimmed(&self) -> Option<&str> {
+        let text = self.stdout.trim();
+        (self.success() && !text.is_empty()).then_some(text)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Subprocess {
+    program: OsString,
+    args: Vec<OsString>,
+    dir: Option<PathBuf>,
+    timeout: Duration,
+    sandbox: bool,
+}
+
+impl Subprocess {
+    pub fn new(program: impl AsRef<OsStr>) -> Self {
+        Self {
+            program: program.as_ref().to_os_string(),
+            args: Vec::new(),
+            dir: None,
+            timeout: DEFAULT_TIMEOUT,
+            sandbox: false,
+        }
+    }
+
+    /// A command line run through the platform shell (`sh -c` / `cmd /C`)
+    pub fn shell(line: &str) -> Self {
+        if cfg!(windows) {
+            Self::new("cmd").args(["/C", line])
+        } else {
+            Self::new("sh").args(["-c", line])
+        }
+    }
+
+    pub fn arg(mut self, arg: impl AsRef<OsStr>) -> Self {
+        self.args.push(arg.as_ref().to_os_string());
+        self
+    }
+
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.args
+            .extend(args.into_iter().map(|a| a.as_ref().to_os_string()));
+        self
+    }
+
+    /// Working directory; ignored if it does not exist
+    pub fn current_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Run with a cleared environment and, where possible, no network.
+    /// Has no effect outside Unix.
+    pub fn with_sandbox(mut self, sandbox: bool) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    /// Run to completion. A non-zero exit is not an error, check
+    /// [`Output::success`]; failing to start and hitting the timeout are.
+    pub fn run(&self) -> Result<Output, Box<dyn std::error::Error>> {
+        let started = Instant::now();
+        let mut command = self.build();
+        if let Some(dir) = self.dir.as_deref().filter(|d| d.is_dir()) {
+            command.current_dir(dir);
+        }
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        #[cfg(unix)]
+        {
+            // Own process group, so a timeout also kills what a shell spawned
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+
+        let mut child = command
+            .spawn()
+            .map_err(|e| format!("failed to start {}: {}", self.display(), e))?;
+
+        // Drain both pipes on threads so a chatty command cannot block on a
+        // full pipe while we wait for it
+        let stdout = drain(child.stdout.take());
+        let stderr = drain(child.stderr.take());
+
+        let deadline = started + self.timeout;
+        let status = loop {
+            match child.try_wait()? {
+                Some(status) => break status,
+                None if Instant::now() >= deadline => {
+                    kill_tree(&mut child);
+                    crate::debug_log!("{} timed out after {:?}", self.display(), self.timeout);
+                    return Err(format!(
+                        "{} timed out after {}ms",
+                        self.display(),
+                        self.timeout.as_millis()
+                    )
+                    .into());
+                }
+                None => std::thread::sleep(Duration::from_millis(5)),
+            }
+        };
+
+        // Background processes the command left behind would keep the pipes
+        // open and outlive the statusline; they go with the group
+        #[cfg(unix)]
+        kill_group(&child);
+        let remaining = || deadline.saturating_duration_since(Instant::now());
+        let stdout = stdout.recv_timeout(remaining()).unwrap_or_default();
+        let stderr = stderr.recv_timeout(remaining()).unwrap_or_default();
+        let output = Output {
+            status,
+            stdout: String::from_utf8_lossy(&stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&stderr).into_owned(),
+        };
+        if !output.success() {
+            crate::debug_log!(
+                "{} exited with {}: {}",
+                self.display(),
+                output.status,
+                output.stderr.trim()
+            );
+        }
+        Ok(output)
+    }
+
+    fn build(&self) -> Command {
+        #[cfg(unix)]
+        if self.sandbox {
+            #[cfg(target_os = "linux")]
+            let mut command = if network_namespaces_available() {
+                let mut c = Command::new("unshare");
+                c.args(["--map-root-user", "--net", "--"])
+                    .arg(&self.program)
+                    .args(&self.args);
+                c
+            } else {
+                let mut c = Command::new(&self.program);
+                c.args(&self.args);
+                c
+            };
+            #[cfg(not(target_os = "linux"))]
+            let mut command = {
+                let mut c = Command::new(&self.program);
+                c.args(&self.args);
+                c
+            };
+            command.env_clear();
+            for key in SANDBOX_ENV {
+                if let Some(value) = std::env::var_os(key) {
+                    command.env(key, value);
+                }
+            }
+            return command;
+        }
+
+        let mut command = Command::new(&self.program);
+        command.args(&self.args);
+        command
+    }
+
+    fn display(&self) -> String {
+        Path::new(&self.program)
+            .file_name()
+            .unwrap_or(&self.program)
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+fn drain(pipe: Option<impl Read + Send + 'static>) -> mpsc::Receiver<Vec<u8>> {
+    let (tx, rx) = mpsc::channel();
+    if let Some(mut pipe) = pipe {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = pipe.read_to_end(&mut buf);
+            let _ = tx.send(buf);
+        });
+    }
+    rx
+}
+
+fn kill_tree(child: &mut std::process::Child) {
+    #[cfg(unix)]
+    kill_group(child);
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[cfg(unix)]
+fn kill_group(child: &std::process::Child) {
+    if let Ok(pid) = i32::try_from(child.id()) {
+        // SAFETY: plain syscall; the group id is the child's own pid
+        unsafe {
+            libc::kill(-pid, libc::SIGKILL);
+        }
+    }
+}
+
+/// Whether `unshare` can create a user + network namespace here; checked
+/// once per process
+#[cfg(target_os = "linux")]
+fn network_namespaces_available() -> bool {
+    static AVAILABLE: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+    *AVAILABLE.get_or_init(|| {
+        Command::new("unshare")
+            .args(["--map-root-user", "--net", "true"])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    })
+}
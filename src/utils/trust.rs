@@ -0,0 +1,101 @@
+//! Workspace trust for project `.uucode.toml` files.
+//!
+//! A cloned repository can ship a `.uucode.toml` that tells the statusline
+//! to run a command. Like VS Code's workspace trust, such a file is ignored
+//! until the user approves it with `uucode trust`. Approval is pinned to the
+//! file's SHA-256, so any later edit (a `git pull`, a checkout) needs to be
+//! trusted again.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trust {
+    Trusted,
+    /// Never trusted, or revoked
+    Untrusted,
+    /// Trusted once, but the file changed since
+    Changed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustedWorkspace {
+    /// Canonical path of the `.uucode.toml`
+    pub path: PathBuf,
+    /// Hex SHA-256 of the file when it was trusted
+    pub sha256: String,
+    pub trusted_at: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrustStore {
+    #[serde(default)]
+    pub workspaces: Vec<TrustedWorkspace>,
+}
+
+/// Allowlist location (~/.claude/uucode/trusted_workspaces.json)
+pub fn store_path() -> Option<PathBuf> {
+    crate::utils::paths::uucode_dir().map(|dir| dir.join("trusted_workspaces.json"))
+}
+
+impl TrustStore {
+    pub fn load() -> Self {
+        store_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        crate::utils::readonly::ensure_writable("trusted_workspaces.json")?;
+        let path = store_path().ok_or("Could not find home directory")?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        crate::utils::atomic_write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn check(&self, file: &Path) -> Trust {
+        let Ok(content) = fs::read(file) else {
+            return Trust::Untrusted;
+        };
+        let path = canonical(file);
+        match self.workspaces.iter().find(|w| w.path == path) {
+            Some(w) if w.sha256 == crate::utils::download::sha256_hex(&content) => Trust::Trusted,
+            Some(_) => Trust::Changed,
+            None => Trust::Untrusted,
+        }
+    }
+
+    /// Trust the current contents of `file`, replacing an older approval
+    pub fn trust(&mut self, file: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let content = fs::read(file)?;
+        let path = canonical(file);
+        self.workspaces.retain(|w| w.path != path);
+        self.workspaces.push(TrustedWorkspace {
+            path,
+            sha256: crate::utils::download::sha256_hex(&content),
+            trusted_at: chrono::Local::now().to_rfc3339(),
+        });
+        Ok(())
+    }
+
+    /// Returns false if `file` was not trusted
+    pub fn revoke(&mut self, file: &Path) -> bool {
+        let path = canonical(file);
+        let before = self.workspaces.len();
+        self.workspaces.retain(|w| w.path != path);
+        self.workspaces.len() != before
+    }
+}
+
+/// Trust state of `file` according to the saved allowlist
+pub fn check(file: &Path) -> Trust {
+    TrustStore::load().check(file)
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
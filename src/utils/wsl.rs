@@ -0,0 +1,133 @@
+//! Paths across the WSL / Windows boundary (`[wsl]` in config.toml).
+//!
+//! Claude Code is often installed on one side while uucode runs on the
+//! other, so `~/.claude` resolves to the wrong home directory. Configured
+//! paths are translated to the current side, and inside WSL the Windows
+//! `~/.claude` can stand in for a missing Linux one.
+
+use crate::config::{WslBridge, WslConfig};
+use std::path::{Path, PathBuf};
+
+/// `[wsl]` from config.toml, read without loading the full config
+pub fn config() -> WslConfig {
+    super::paths::raw_config_value("wsl")
+        .and_then(|value| value.try_into().ok())
+        .unwrap_or_default()
+}
+
+/// A Windows path as seen from WSL, `None` for anything else. Paths into
+/// a distro (`\\wsl.localhost\<distro>\...`) become Linux paths again.
+///
+/// ```
+/// use mycode::utils::wsl::to_wsl;
+///
+/// assert_eq!(to_wsl(r"C:\Users\me\.claude", "/mnt").as_deref(), Some("/mnt/c/Users/me/.claude"));
+/// assert_eq!(to_wsl("D:/work", "/mnt/").as_deref(), Some("/mnt/d/work"));
+/// assert_eq!(to_wsl(r"\\wsl$\Ubuntu\home\me", "/mnt").as_deref(), Some("/home/me"));
+/// assert_eq!(to_wsl("/home/me", "/mnt"), None);
+/// ```
+pub fn to_wsl(path: &str, mount_root: &str) -> Option<String> {
+    for prefix in [r"\\wsl.localhost\", r"\\wsl$\"] {
+        if let Some(rest) = path.strip_prefix(prefix) {
+            let (_distro, rest) = rest.split_once('\\')?;
+            return Some(format!("/{}", rest.replace('\\', "/")));
+        }
+    }
+    let mut chars = path.chars();
+    let drive = chars.next().filter(char::is_ascii_alphabetic)?;
+    if chars.next() != Some(':') {
+        return None;
+    }
+    let rest = chars.as_str().replace('\\', "/");
+    Some(format!(
+        "{}/{}{}",
+        mount_root.trim_end_matches('/'),
+        drive.to_ascii_lowercase(),
+        rest.trim_end_matches('/')
+    ))
+}
+
+/// A WSL path as seen from Windows: drive mounts map back to their drive,
+/// other paths go through `\\wsl.localhost\<distro>`; `None` when that
+/// needs a distro and none is given
+///
+/// ```
+/// use mycode::utils::wsl::to_windows;
+///
+/// assert_eq!(to_windows("/mnt/c/Users/me", "/mnt", None).as_deref(), Some(r"C:\Users\me"));
+/// assert_eq!(
+///     to_windows("/home/me/.claude", "/mnt", Some("Ubuntu")).as_deref(),
+///     Some(r"\\wsl.localhost\Ubuntu\home\me\.claude")
+/// );
+/// assert_eq!(to_windows("/home/me", "/mnt", None), None);
+/// ```
+pub fn to_windows(path: &str, mount_root: &str, distro: Option<&str>) -> Option<String> {
+    if !path.starts_with('/') {
+        return None;
+    }
+    let root = format!("{}/", mount_root.trim_end_matches('/'));
+    if let Some(rest) = path.strip_prefix(&root) {
+        let (drive, rest) = rest.split_once('/').unwrap_or((rest, ""));
+        if drive.len() == 1 && drive.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Some(format!(
+                "{}:\\{}",
+                drive.to_ascii_uppercase(),
+                rest.replace('/', "\\")
+            ));
+        }
+    }
+    Some(format!(
+        r"\\wsl.localhost\{}{}",
+        distro?,
+        path.replace('/', "\\")
+    ))
+}
+
+/// `path` translated to the side uucode runs on, unchanged when it
+/// already belongs here
+pub fn translate(path: &Path, config: &WslConfig) -> PathBuf {
+    let text = path.to_string_lossy();
+    let translated = if cfg!(windows) {
+        to_windows(&text, &config.mount_root, config.distro.as_deref())
+    } else if crate::core::runtime::is_wsl() {
+        to_wsl(&text, &config.mount_root)
+    } else {
+        None
+    };
+    translated
+        .map(PathBuf::from)
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Windows profile directory as seen from WSL
+pub fn windows_home(config: &WslConfig) -> Option<PathBuf> {
+    if let Some(home) = &config.windows_home {
+        return Some(translate(Path::new(home), config));
+    }
+    // Only set when WSLENV passes it through
+    if let Some(profile) = std::env::var("USERPROFILE").ok().filter(|p| !p.is_empty()) {
+        return Some(translate(Path::new(&profile), config));
+    }
+    let users = Path::new(&config.mount_root).join("c").join("Users");
+    let user = std::env::var("USER").ok()?;
+    Some(users.join(user)).filter(|home| home.is_dir())
+}
+
+/// Inside WSL, the Windows `~/.claude` when `bridge` says to use it
+/// instead of `linux_dir`
+pub fn bridged_claude_dir(linux_dir: &Path, config: &WslConfig) -> Option<PathBuf> {
+    if cfg!(windows) || config.bridge == WslBridge::Off || !crate::core::runtime::is_wsl() {
+        return None;
+    }
+    let windows_dir = windows_home(config)?.join(".claude");
+    let use_windows = match config.bridge {
+        WslBridge::Windows => windows_dir.is_dir(),
+        _ => {
+            !linux_dir.join("settings.json").exists() && windows_dir.join("settings.json").exists()
+        }
+    };
+    if use_windows {
+        crate::debug_log!("using Claude settings from {}", windows_dir.display());
+    }
+    use_windows.then_some(windows_dir)
+}
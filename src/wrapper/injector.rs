@@ -1,4 +1,5 @@
-use std::io::{BufRead, BufReader, Write};
+use crate::error::UucodeError;
+use std::io::{self, BufRead, BufReader, Write};
 use std::path::PathBuf;
 use std::process::{Child, Command, Stdio};
 
@@ -8,17 +9,14 @@ pub struct ClaudeCodeInjector {
 }
 
 impl ClaudeCodeInjector {
-    pub fn new(
-        claude_path: PathBuf,
-        _translation_config: Option<()>,
-    ) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn new(claude_path: PathBuf, _translation_config: Option<()>) -> Result<Self, UucodeError> {
         Ok(Self {
             claude_path,
             translation_enabled: false,
         })
     }
 
-    pub fn start(&self, args: Vec<String>) -> Result<Child, Box<dyn std::error::Error>> {
+    pub fn start(&self, args: Vec<String>) -> Result<Child, UucodeError> {
         let mut cmd = if cfg!(target_os = "windows")
             && self.claude_path.extension().is_some_and(|ext| ext == "cmd")
         {
@@ -44,18 +42,15 @@ impl ClaudeCodeInjector {
         Ok(child)
     }
 
-    pub fn intercept_input(&self, input: &str) -> Result<String, Box<dyn std::error::Error>> {
+    pub fn intercept_input(&self, input: &str) -> Result<String, UucodeError> {
         Ok(input.to_string())
     }
 
-    pub fn intercept_output(&self, output: &str) -> Result<String, Box<dyn std::error::Error>> {
+    pub fn intercept_output(&self, output: &str) -> Result<String, UucodeError> {
         Ok(output.to_string())
     }
 
-    pub fn run_with_interception(
-        &mut self,
-        args: Vec<String>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn run_with_interception(&mut self, args: Vec<String>) -> Result<(), UucodeError> {
         // 默认情况下直接运行 Claude Code
         if !self.translation_enabled {
             let mut cmd = if cfg!(target_os = "windows")
@@ -83,7 +78,11 @@ impl ClaudeCodeInjector {
             let status = cmd.status()?;
 
             if !status.success() {
-                return Err(format!("Claude Code exited with status: {}", status).into());
+                return Err(io::Error::other(format!(
+                    "Claude Code exited with status: {}",
+                    status
+                ))
+                .into());
             }
 
             return Ok(());
@@ -92,9 +91,18 @@ impl ClaudeCodeInjector {
         // Translation enabled - intercept I/O
         let mut child = self.start(args)?;
 
-        let stdin = child.stdin.take().ok_or("Failed to capture stdin")?;
-        let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
-        let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| io::Error::other("Failed to capture stdin"))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| io::Error::other("Failed to capture stdout"))?;
+        let stderr = child
+            .stderr
+            .take()
+            .ok_or_else(|| io::Error::other("Failed to capture stderr"))?;
 
         // Spawn thread to handle stdout
         let stdout_handle = std::thread::spawn(move || {
@@ -133,7 +141,9 @@ impl ClaudeCodeInjector {
         let _ = stdin_handle.join();
 
         if !status.success() {
-            return Err(format!("Claude Code exited with status: {}", status).into());
+            return Err(
+                io::Error::other(format!("Claude Code exited with status: {}", status)).into(),
+            );
         }
 
         Ok(())
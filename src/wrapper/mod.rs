@@ -1,14 +1,70 @@
+#[cfg(feature = "wrapper")]
 pub mod injector;
+#[cfg(feature = "wrapper")]
 pub mod io_interceptor;
+#[cfg(feature = "wrapper")]
+pub mod motd;
+pub mod reminder;
+#[cfg(feature = "wrapper")]
+pub mod self_test;
 
-use std::path::PathBuf;
+/// Theme chosen with `--wrap --theme`, inherited by the statusline
+/// commands Claude Code runs
+pub const THEME_ENV: &str = "UUCODE_THEME";
+/// Directory profile chosen with `--wrap --profile`
+pub const PROFILE_ENV: &str = "UUCODE_PROFILE";
+
+/// Value the wrapper passed down in `key`, `None` when unset or empty
+pub fn passed_through(key: &str) -> Option<String> {
+    std::env::var(key).ok().filter(|value| !value.is_empty())
+}
+
+/// Arguments for Claude Code: ours (`--wrap`, `--theme`, `--profile`)
+/// taken out of the command line
+///
+/// ```
+/// use mycode::wrapper::claude_args;
+///
+/// let args = ["--wrap", "-t", "nord", "--profile=work", "--resume"];
+/// assert_eq!(claude_args(args.map(String::from)), ["--resume"]);
+/// ```
+pub fn claude_args(args: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut args = args.into_iter();
+    let mut kept = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--wrap" => {}
+            "--theme" | "-t" | "--profile" => {
+                args.next();
+            }
+            _ if arg.starts_with("--theme=") || arg.starts_with("--profile=") => {}
+            _ => kept.push(arg),
+        }
+    }
+    kept
+}
 
 /// Find Claude Code executable from PATH environment variable
-pub fn find_claude_code() -> Result<PathBuf, Box<dyn std::error::Error>> {
+#[cfg(feature = "wrapper")]
+pub fn find_claude_code() -> Result<std::path::PathBuf, crate::error::UucodeError> {
+    use std::path::PathBuf;
+
     // Try to find 'claude' command in PATH
     match which::which("claude") {
         Ok(path) => Ok(path),
         Err(_) => {
+            // Local install created by `claude migrate-installer`
+            if let Some(claude_dir) = crate::utils::paths::claude_dir() {
+                let local = claude_dir.join("local").join(if cfg!(windows) {
+                    "claude.cmd"
+                } else {
+                    "claude"
+                });
+                if local.exists() {
+                    return Ok(local);
+                }
+            }
+
             // Try common installation paths on different platforms
             #[cfg(target_os = "windows")]
             {
@@ -45,7 +101,9 @@ pub fn find_claude_code() -> Result<PathBuf, Box<dyn std::error::Error>> {
                 }
             }
 
-            Err("Claude Code executable not found in PATH or common locations".into())
+            Err(crate::error::UucodeError::ClaudeNotFound(
+                "executable not found in PATH or common locations".to_string(),
+            ))
         }
     }
 }
@@ -0,0 +1,61 @@
+//! Banner `--wrap` prints before Claude Code starts (`[wrapper] motd`).
+
+use crate::config::Config;
+use crate::core::render_log::RenderLog;
+
+/// Quota older than this is shown with its age
+const STALE_AFTER_MINS: i64 = 10;
+
+/// Banner text, `None` when `motd` is empty or every line ends up empty
+pub fn render(config: &Config, profile: Option<&str>) -> Option<String> {
+    let motd = &config.wrapper.motd;
+    if motd.trim().is_empty() {
+        return None;
+    }
+
+    let quota = if motd.contains("{quota}") {
+        quota_summary().unwrap_or_default()
+    } else {
+        String::new()
+    };
+    let lines: Vec<String> = motd
+        .lines()
+        .map(|line| {
+            line.replace("{quota}", &quota)
+                .replace("{theme}", &config.theme)
+                .replace("{profile}", profile.unwrap_or(""))
+        })
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// Remaining quota as of the last render, e.g. `额度 uucode 剩余 62%`
+pub fn quota_summary() -> Option<String> {
+    let log = RenderLog::load()?;
+    let quotas = log.quotas();
+    if quotas.is_empty() {
+        return None;
+    }
+
+    let parts: Vec<String> = quotas
+        .iter()
+        .map(|(label, used)| format!("{} 剩余 {:.0}%", label, (100.0 - used).max(0.0)))
+        .collect();
+    let mut line = format!("额度 {}", parts.join(" · "));
+    if let Ok(rendered_at) = chrono::DateTime::parse_from_rfc3339(&log.rendered_at) {
+        let mins = (chrono::Local::now().fixed_offset() - rendered_at).num_minutes();
+        if mins >= STALE_AFTER_MINS {
+            line.push_str(&format!("（{}前的数据）", age(mins)));
+        }
+    }
+    Some(line)
+}
+
+fn age(mins: i64) -> String {
+    match mins {
+        0..=59 => format!("{} 分钟", mins),
+        60..=1439 => format!("{} 小时", mins / 60),
+        _ => format!("{} 天", mins / 1440),
+    }
+}
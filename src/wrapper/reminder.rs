@@ -0,0 +1,143 @@
+//! Terminal reminders for the context advisor (`[alerts.context]`).
+//!
+//! Statusline renders run as children of Claude Code, so they cannot reach
+//! the terminal themselves. The wrapper names a state file in
+//! [`STATE_ENV`]; each render inside the session writes the advisor's
+//! verdict there and a wrapper thread polls it, printing a line or ringing
+//! the bell once per crossing. Nothing is written to Claude Code's input.
+
+use crate::config::{ContextReminder, ContextReminderConfig, SegmentConfig, SegmentId};
+use crate::core::palette::Level;
+use crate::core::segments::SegmentData;
+use serde::{Deserialize, Serialize};
+use std::io::{IsTerminal, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// State file of the wrapped session, set by the wrapper for the renders
+pub const STATE_ENV: &str = "UUCODE_WRAPPER_STATE";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The advisor's verdict in the latest render, `advice` empty when healthy
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AdvisorState {
+    advice: String,
+    reason: String,
+}
+
+impl AdvisorState {
+    fn level(&self) -> Level {
+        match self.advice.as_str() {
+            "clear" => Level::Critical,
+            "compact" => Level::Warning,
+            _ => Level::Ok,
+        }
+    }
+
+    fn message(&self) -> String {
+        let command = if self.advice == "clear" {
+            "/clear"
+        } else {
+            "/compact"
+        };
+        format!("⚠ uucode: {}，建议运行 {}", self.reason, command)
+    }
+}
+
+/// Write the advisor's verdict of this render for the wrapper, if any
+pub fn publish(segments: &[(SegmentConfig, SegmentData)]) {
+    let Some(path) = super::passed_through(STATE_ENV) else {
+        return;
+    };
+    let state = segments
+        .iter()
+        .find(|(segment, _)| segment.id == SegmentId::ContextAdvisor)
+        .map(|(_, data)| AdvisorState {
+            advice: data.metadata.get("advice").cloned().unwrap_or_default(),
+            reason: data.secondary.to_string(),
+        })
+        .unwrap_or_default();
+    let result = serde_json::to_string(&state)
+        .map_err(|e| e.to_string())
+        .and_then(|json| crate::utils::atomic_write(&path, json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        crate::debug_log!("failed to publish advisor state: {}", e);
+    }
+}
+
+/// Polls the session's state file while Claude Code runs; stops and
+/// removes the file when dropped
+pub struct Reminder {
+    path: PathBuf,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Reminder {
+    /// Start reminding, `None` when turned off or stderr is not a terminal
+    pub fn start(config: &ContextReminderConfig) -> Option<Self> {
+        if config.remind == ContextReminder::Off || !std::io::stderr().is_terminal() {
+            return None;
+        }
+        let threshold = Level::from_key(&config.level).unwrap_or(Level::Critical);
+        let dir = crate::utils::paths::cache_dir()?.join("wrapper");
+        std::fs::create_dir_all(&dir).ok()?;
+        let path = dir.join(format!("{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        std::env::set_var(STATE_ENV, &path);
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let (path, stop, remind) = (path.clone(), stop.clone(), config.remind);
+            std::thread::spawn(move || {
+                let mut fired = false;
+                while !stop.load(Ordering::Relaxed) {
+                    std::thread::park_timeout(POLL_INTERVAL);
+                    let Some(state) = std::fs::read_to_string(&path)
+                        .ok()
+                        .and_then(|content| serde_json::from_str::<AdvisorState>(&content).ok())
+                    else {
+                        continue;
+                    };
+                    let reached = state.level() >= threshold;
+                    if reached && !fired {
+                        notify(remind, &state);
+                    }
+                    fired = reached;
+                }
+            })
+        };
+
+        Some(Self {
+            path,
+            stop,
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for Reminder {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            let _ = thread.join();
+        }
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn notify(remind: ContextReminder, state: &AdvisorState) {
+    let mut stderr = std::io::stderr().lock();
+    if matches!(remind, ContextReminder::Line | ContextReminder::LineAndBell) {
+        let _ = write!(stderr, "\r\n{}\r\n", state.message());
+    }
+    if matches!(remind, ContextReminder::Bell | ContextReminder::LineAndBell) {
+        let _ = write!(stderr, "\x07");
+    }
+    let _ = stderr.flush();
+}
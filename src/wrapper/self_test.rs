@@ -0,0 +1,108 @@
+//! Statusline self-test run by `--wrap` before Claude Code starts.
+//!
+//! The statusline is rendered once from a synthetic payload with the
+//! user's real config, so a broken config or a hanging segment shows up in
+//! the terminal instead of as an empty bar halfway through a session. The
+//! render happens in a child process (`mycode --self-test`): release builds
+//! abort on panic, and a crash there must not take the wrapper down with it.
+
+use crate::config::{Config, InputData};
+use crate::utils::subprocess::Subprocess;
+use std::time::{Duration, Instant};
+
+/// Renders slower than this get a warning; Claude Code redraws the
+/// statusline on every message
+pub const LATENCY_BUDGET: Duration = Duration::from_millis(1000);
+
+/// The child is killed after this long
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug)]
+pub enum Outcome {
+    Passed(Duration),
+    Slow(Duration),
+    Failed(String),
+}
+
+/// Claude Code payload used for the test render
+pub fn synthetic_input() -> InputData {
+    let current_dir = std::env::current_dir()
+        .map(|dir| dir.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| ".".to_string());
+    InputData::new("claude-sonnet-4-5", &current_dir).with_display_name("Sonnet 4.5")
+}
+
+/// Render the statusline once for `--self-test`. Unlike a normal render
+/// nothing is recorded: no history, alerts, render log, output file,
+/// segment timings or peaks, so the cold synthetic render does not count
+/// towards `[perf] auto_skip`. Vendor caches may still be refreshed.
+pub fn render_once() -> Result<String, Box<dyn std::error::Error>> {
+    crate::core::statusline::disable_recording();
+    let mut config = Config::load()?;
+    config.check()?;
+    if let Some(theme) = super::passed_through(super::THEME_ENV) {
+        config.apply_theme(crate::ui::themes::ThemePresets::get_theme(&theme));
+    }
+
+    let statusline = crate::render(&config, &synthetic_input()).ansi;
+    if statusline.trim().is_empty() {
+        return Err("statusline rendered empty".into());
+    }
+    Ok(statusline)
+}
+
+/// Run the self-test in a child process and time it
+pub fn run() -> Outcome {
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => return Outcome::Failed(e.to_string()),
+    };
+
+    let started = Instant::now();
+    let output = match Subprocess::new(exe)
+        .arg("--self-test")
+        .with_timeout(TIMEOUT)
+        .run()
+    {
+        Ok(output) => output,
+        Err(e) => return Outcome::Failed(e.to_string()),
+    };
+    let elapsed = started.elapsed();
+
+    if !output.success() {
+        let reason = match output.stderr.trim() {
+            "" => format!("exited with {}", output.status),
+            stderr => stderr.to_string(),
+        };
+        return Outcome::Failed(reason);
+    }
+    if elapsed > LATENCY_BUDGET {
+        Outcome::Slow(elapsed)
+    } else {
+        Outcome::Passed(elapsed)
+    }
+}
+
+/// Run the self-test and print the result with what to do about it
+pub fn report() {
+    match run() {
+        Outcome::Passed(elapsed) => {
+            println!("✓ 状态栏自检通过（{} ms）", elapsed.as_millis());
+        }
+        Outcome::Slow(elapsed) => {
+            println!(
+                "⚠ 状态栏渲染耗时 {} ms，超过 {} ms 的预算",
+                elapsed.as_millis(),
+                LATENCY_BUDGET.as_millis()
+            );
+            println!("  运行 uucode perf show 找出慢的段，或 uucode doctor 检查网络和配置");
+        }
+        Outcome::Failed(reason) => {
+            println!("⚠ 状态栏自检失败:");
+            for line in reason.lines() {
+                println!("  {}", line);
+            }
+            println!("  Claude Code 里的状态栏可能显示异常，运行 uucode doctor 查看原因");
+        }
+    }
+}